@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
 
@@ -7,7 +9,9 @@ impl Plugin for ActionPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(InputManagerPlugin::<GameAction>::default())
             .init_resource::<ActionState<GameAction>>()
-            .insert_resource(GameAction::default_input_map());
+            .init_resource::<InputBindings>()
+            .init_resource::<MenuNav>()
+            .insert_resource(InputBindings::default().to_input_map());
     }
 }
 
@@ -20,37 +24,104 @@ pub enum GameAction {
     Down,
     Left,
     Right,
+    /// Replay the song just finished, from the results screen.
+    Retry,
+    /// Move on to the next song in the list, from the results screen.
+    Next,
+    /// Raw left-stick vector, used for analog slide flicks.
+    #[actionlike(DualAxis)]
+    LeftStick,
+    /// Raw right-stick vector, used for analog slide flicks.
+    #[actionlike(DualAxis)]
+    RightStick,
+}
+
+/// Actions the menus can't be driven without, so their bindings can be
+/// replaced but never cleared.
+pub const MENU_ACTIONS: [GameAction; 6] = [
+    GameAction::Up,
+    GameAction::Down,
+    GameAction::Left,
+    GameAction::Right,
+    GameAction::Confirm,
+    GameAction::Back,
+];
+
+/// Player-editable button bindings. The live `InputMap` is rebuilt from this so
+/// keyboard and gamepad bindings can be changed independently.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct InputBindings {
+    pub keys: HashMap<GameAction, Vec<KeyCode>>,
+    pub buttons: HashMap<GameAction, Vec<GamepadButton>>,
 }
 
-impl GameAction {
-    fn default_input_map() -> InputMap<Self> {
+impl Default for InputBindings {
+    fn default() -> Self {
         use GameAction::*;
-        let mut map = InputMap::default();
+        let keys = HashMap::from([
+            (Tap, vec![KeyCode::Space]),
+            (Confirm, vec![KeyCode::Space, KeyCode::Enter]),
+            (Back, vec![KeyCode::Escape]),
+            (Up, vec![KeyCode::ArrowUp]),
+            (Down, vec![KeyCode::ArrowDown]),
+            (Left, vec![KeyCode::ArrowLeft]),
+            (Right, vec![KeyCode::ArrowRight]),
+            (Retry, vec![KeyCode::KeyR]),
+            (Next, vec![KeyCode::KeyN]),
+        ]);
+        let buttons = HashMap::from([
+            (Tap, vec![GamepadButton::South]),
+            (Confirm, vec![GamepadButton::South]),
+            (Back, vec![GamepadButton::East]),
+            (Up, vec![GamepadButton::DPadUp]),
+            (Down, vec![GamepadButton::DPadDown]),
+            (Left, vec![GamepadButton::DPadLeft]),
+            (Right, vec![GamepadButton::DPadRight]),
+            (Retry, vec![GamepadButton::West]),
+            (Next, vec![GamepadButton::North]),
+        ]);
+        Self { keys, buttons }
+    }
+}
 
-        // Gameplay
-        map.insert(Tap, KeyCode::Space);
-        map.insert(Tap, GamepadButton::South);
+impl InputBindings {
+    /// Replace the keyboard binding for `action`, leaving gamepad bindings intact.
+    pub fn bind_key(&mut self, action: GameAction, key: KeyCode) {
+        self.keys.insert(action, vec![key]);
+    }
 
-        // Menu confirm
-        map.insert(Confirm, KeyCode::Space);
-        map.insert(Confirm, KeyCode::Enter);
-        map.insert(Confirm, GamepadButton::South);
+    /// Replace the gamepad button binding for `action`, leaving keyboard bindings intact.
+    pub fn bind_button(&mut self, action: GameAction, button: GamepadButton) {
+        self.buttons.insert(action, vec![button]);
+    }
 
-        // Menu back
-        map.insert(Back, KeyCode::Escape);
-        map.insert(Back, GamepadButton::East);
+    /// Remove the keyboard binding for `action`, leaving gamepad bindings intact.
+    /// Menu actions keep theirs; returns whether anything was cleared.
+    pub fn clear_keys(&mut self, action: GameAction) -> bool {
+        !MENU_ACTIONS.contains(&action) && self.keys.remove(&action).is_some()
+    }
 
-        // Navigation — keyboard
-        map.insert(Up, KeyCode::ArrowUp);
-        map.insert(Down, KeyCode::ArrowDown);
-        map.insert(Left, KeyCode::ArrowLeft);
-        map.insert(Right, KeyCode::ArrowRight);
+    /// Remove the gamepad button binding for `action`, leaving keyboard bindings intact.
+    /// Menu actions keep theirs; returns whether anything was cleared.
+    pub fn clear_buttons(&mut self, action: GameAction) -> bool {
+        !MENU_ACTIONS.contains(&action) && self.buttons.remove(&action).is_some()
+    }
+
+    /// Build the leafwing input map. Stick directions are fixed and always included.
+    pub fn to_input_map(&self) -> InputMap<GameAction> {
+        use GameAction::*;
+        let mut map = InputMap::default();
 
-        // Navigation — gamepad d-pad
-        map.insert(Up, GamepadButton::DPadUp);
-        map.insert(Down, GamepadButton::DPadDown);
-        map.insert(Left, GamepadButton::DPadLeft);
-        map.insert(Right, GamepadButton::DPadRight);
+        for (action, keys) in &self.keys {
+            for key in keys {
+                map.insert(*action, *key);
+            }
+        }
+        for (action, buttons) in &self.buttons {
+            for button in buttons {
+                map.insert(*action, *button);
+            }
+        }
 
         // Navigation — gamepad left stick
         map.insert(Up, GamepadControlDirection::LEFT_UP);
@@ -58,6 +129,187 @@ impl GameAction {
         map.insert(Left, GamepadControlDirection::LEFT_LEFT);
         map.insert(Right, GamepadControlDirection::LEFT_RIGHT);
 
+        // Analog sticks — raw vectors for slide flicks
+        map.insert_dual_axis(LeftStick, GamepadStick::LEFT);
+        map.insert_dual_axis(RightStick, GamepadStick::RIGHT);
+
         map
     }
 }
+
+// --- Menu navigation repeat ---
+
+/// Delay before a held direction starts repeating.
+const NAV_REPEAT_DELAY: f32 = 0.4;
+/// Time between steps once a held direction is repeating.
+const NAV_REPEAT_INTERVAL: f32 = 0.1;
+/// A release shorter than this is stick jitter around the deadzone, not a new press.
+const NAV_RELEASE_DEBOUNCE: f32 = 0.06;
+
+const NAV_DIRECTIONS: [GameAction; 4] =
+    [GameAction::Up, GameAction::Down, GameAction::Left, GameAction::Right];
+
+#[derive(Debug, Clone, Copy, Default)]
+struct HeldDirection {
+    down: bool,
+    held: f32,
+    next_repeat: f32,
+    released: f32,
+}
+
+impl HeldDirection {
+    /// Advance by `dt` seconds. Returns true when this frame should take a step.
+    fn tick(&mut self, pressed: bool, dt: f32) -> bool {
+        if !pressed {
+            self.released += dt;
+            if self.released >= NAV_RELEASE_DEBOUNCE {
+                self.down = false;
+            }
+            return false;
+        }
+        self.released = 0.0;
+        if !self.down {
+            *self = Self {
+                down: true,
+                held: 0.0,
+                next_repeat: NAV_REPEAT_DELAY,
+                released: 0.0,
+            };
+            return true;
+        }
+        self.held += dt;
+        if self.held >= self.next_repeat {
+            self.next_repeat += NAV_REPEAT_INTERVAL;
+            return true;
+        }
+        false
+    }
+}
+
+/// Steady menu stepping for Up/Down/Left/Right: one step on press, then
+/// repeats after a delay while held. Analog sticks flicker across the press
+/// threshold, so brief releases don't count as new presses.
+///
+/// Menus call `tick` once per frame from their navigation system and read
+/// `fired` in place of `just_pressed`. Insert a fresh one when a menu opens so
+/// a direction still held from the previous screen doesn't step.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct MenuNav {
+    directions: [HeldDirection; 4],
+    fired: [bool; 4],
+    /// False until the first tick, which only records what is already held.
+    primed: bool,
+}
+
+impl MenuNav {
+    pub fn tick(&mut self, action: &ActionState<GameAction>, dt: f32) {
+        self.tick_pressed(NAV_DIRECTIONS.map(|a| action.pressed(&a)), dt);
+    }
+
+    fn tick_pressed(&mut self, pressed: [bool; 4], dt: f32) {
+        let primed = std::mem::replace(&mut self.primed, true);
+        for (i, direction) in self.directions.iter_mut().enumerate() {
+            self.fired[i] = direction.tick(pressed[i], dt) && primed;
+        }
+    }
+
+    /// Whether `direction` steps this frame.
+    pub fn fired(&self, direction: GameAction) -> bool {
+        NAV_DIRECTIONS
+            .iter()
+            .position(|a| *a == direction)
+            .is_some_and(|i| self.fired[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Up only, one frame of `dt` seconds.
+    fn step(nav: &mut MenuNav, up: bool, dt: f32) -> bool {
+        nav.tick_pressed([up, false, false, false], dt);
+        nav.fired(GameAction::Up)
+    }
+
+    #[test]
+    fn menu_nav_repeats_after_delay_and_ignores_jitter() {
+        let dt = 1.0 / 100.0;
+        let mut nav = MenuNav::default();
+        assert!(!step(&mut nav, false, dt));
+
+        // Press steps once, then nothing until the repeat delay
+        assert!(step(&mut nav, true, dt));
+        let mut steps = 0;
+        for _ in 0..35 {
+            steps += step(&mut nav, true, dt) as u32;
+        }
+        assert_eq!(steps, 0);
+
+        // Held to 1.35 s: a step at 0.4 s, then every 0.1 s up to 1.3 s
+        for _ in 0..100 {
+            steps += step(&mut nav, true, dt) as u32;
+        }
+        assert_eq!(steps, 10);
+
+        // A two-frame dropout is jitter, not a release
+        assert!(!step(&mut nav, false, dt));
+        assert!(!step(&mut nav, false, dt));
+        assert!(!step(&mut nav, true, dt));
+
+        // A real release lets the next press step straight away
+        for _ in 0..10 {
+            step(&mut nav, false, dt);
+        }
+        assert!(step(&mut nav, true, dt));
+        assert!(!nav.fired(GameAction::Down));
+    }
+
+    #[test]
+    fn menu_nav_ignores_direction_held_on_open() {
+        let mut nav = MenuNav::default();
+        assert!(!step(&mut nav, true, 0.01));
+        assert!(!step(&mut nav, true, 0.01));
+    }
+
+    #[test]
+    fn rebinding_one_device_keeps_the_other() {
+        let mut bindings = InputBindings::default();
+        bindings.bind_button(GameAction::Tap, GamepadButton::West);
+        bindings.bind_key(GameAction::Tap, KeyCode::KeyJ);
+
+        assert_eq!(bindings.keys[&GameAction::Tap], vec![KeyCode::KeyJ]);
+        assert_eq!(bindings.buttons[&GameAction::Tap], vec![GamepadButton::West]);
+        // Other actions untouched
+        assert_eq!(bindings.keys[&GameAction::Up], vec![KeyCode::ArrowUp]);
+        assert_eq!(bindings.buttons[&GameAction::Up], vec![GamepadButton::DPadUp]);
+    }
+
+    #[test]
+    fn clearing_one_device_keeps_the_other() {
+        let mut bindings = InputBindings::default();
+        bindings.clear_keys(GameAction::Tap);
+
+        let map = bindings.to_input_map();
+        let tap = map.get_buttonlike(&GameAction::Tap).unwrap();
+        assert_eq!(tap.len(), 1);
+        assert_eq!(bindings.buttons[&GameAction::Tap], vec![GamepadButton::South]);
+
+        bindings.clear_buttons(GameAction::Tap);
+        assert!(bindings.to_input_map().get_buttonlike(&GameAction::Tap).is_none_or(|b| b.is_empty()));
+    }
+
+    #[test]
+    fn menu_actions_cannot_be_cleared() {
+        let mut bindings = InputBindings::default();
+        for action in MENU_ACTIONS {
+            assert!(!bindings.clear_keys(action));
+            assert!(!bindings.clear_buttons(action));
+        }
+        assert_eq!(bindings, InputBindings::default());
+
+        assert!(bindings.clear_keys(GameAction::Retry));
+        // Already cleared
+        assert!(!bindings.clear_keys(GameAction::Retry));
+    }
+}
@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::time::Duration;
 
 use bevy::prelude::*;
@@ -5,6 +6,7 @@ use kira::{
     AudioManager, AudioManagerSettings, DefaultBackend, Tween,
     clock::{ClockHandle, ClockSpeed},
     sound::static_sound::{StaticSoundData, StaticSoundHandle, StaticSoundSettings},
+    sound::streaming::StreamingSoundData,
 };
 
 pub struct KiraPlugin;
@@ -19,7 +21,11 @@ impl Plugin for KiraPlugin {
             clock: None,
             sound: None,
             preview: None,
+            preview_loop: None,
+            music_amplitude: 1.0,
+            music_fade: None,
         });
+        app.add_systems(Update, (tick_music_fade, tick_preview_loop));
     }
 }
 
@@ -28,6 +34,72 @@ pub struct KiraContext {
     pub clock: Option<ClockHandle>,
     pub sound: Option<StaticSoundHandle>,
     pub preview: Option<StaticSoundHandle>,
+    /// Window the current preview fades around and loops back over.
+    pub preview_loop: Option<PreviewLoop>,
+    /// Current song volume as a 0.0–1.0 amplitude.
+    pub music_amplitude: f64,
+    pub music_fade: Option<MusicFade>,
+}
+
+/// An in-progress song volume fade, advanced each frame by `tick_music_fade`.
+pub struct MusicFade {
+    from: f64,
+    to: f64,
+    elapsed: f64,
+    duration: f64,
+}
+
+/// Fade in at the start of a preview and out before it loops.
+const PREVIEW_FADE_SECS: f64 = 0.5;
+
+/// The slice of a song a preview plays, and where its fade-out has got to.
+pub struct PreviewLoop {
+    start_secs: f64,
+    duration_secs: f64,
+    db: f32,
+    fading_out: bool,
+}
+
+/// What a looping preview should do at a playback position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PreviewCue {
+    Play,
+    /// Inside the final fade; silence is reached at the end of the window.
+    FadeOut { remaining_secs: f64 },
+    /// Past the end of the window: seek back to the start and fade in again.
+    Restart,
+}
+
+/// Where a preview of `duration_secs` starting at `start_secs` is at
+/// `position`. Short previews shrink the fade so it never takes more than half.
+pub fn preview_cue(position: f64, start_secs: f64, duration_secs: f64) -> PreviewCue {
+    let end = start_secs + duration_secs;
+    let fade = PREVIEW_FADE_SECS.min(duration_secs / 2.0);
+    if position >= end {
+        PreviewCue::Restart
+    } else if position >= end - fade {
+        PreviewCue::FadeOut { remaining_secs: end - position }
+    } else {
+        PreviewCue::Play
+    }
+}
+
+/// Length of a preview window from `start_secs`, clipped to finish a fade
+/// length before the end of a `file_secs` long file. The sound stops when the
+/// file runs out, so a window reaching the very end would go silent before
+/// `tick_preview_loop` could see it end and loop. `None` if nothing is left.
+pub fn preview_window_secs(start_secs: f64, requested_secs: f64, file_secs: f64) -> Option<f64> {
+    let duration = requested_secs.min(file_secs - PREVIEW_FADE_SECS - start_secs);
+    (duration > 0.0).then_some(duration)
+}
+
+/// Amplitude of a linear fade from `from` to `to` after `elapsed` seconds.
+pub fn fade_amplitude(from: f64, to: f64, elapsed: f64, duration: f64) -> f64 {
+    if duration <= 0.0 {
+        return to;
+    }
+    let t = (elapsed / duration).clamp(0.0, 1.0);
+    from + (to - from) * t
 }
 
 /// Convert a 0.0–1.0 amplitude to decibels (f32).
@@ -39,20 +111,34 @@ fn amplitude_to_db(amp: f64) -> f32 {
     }
 }
 
-pub fn play_song(ctx: &mut KiraContext, path: &str, bpm: f64) {
+pub fn play_song(ctx: &mut KiraContext, path: &Path, bpm: f64) -> Result<(), String> {
+    let sound_data = load_song_audio(path)?;
+    play_song_data(ctx, sound_data, bpm);
+    Ok(())
+}
+
+/// Decode a song's audio up front, so it can happen off the main thread.
+pub fn load_song_audio(path: &Path) -> Result<StaticSoundData, String> {
+    StaticSoundData::from_file(path)
+        .map_err(|e| format!("audio load error for {}: {e}", path.display()))
+}
+
+/// Length of an audio file, read from its headers without decoding it.
+pub fn audio_duration(path: &Path) -> Option<Duration> {
+    StreamingSoundData::from_file(path).ok().map(|data| data.duration())
+}
+
+/// Start already-decoded song audio on a fresh clock.
+pub fn play_song_data(ctx: &mut KiraContext, sound_data: StaticSoundData, bpm: f64) {
     let mut clock = ctx
         .manager
         .add_clock(ClockSpeed::TicksPerMinute(bpm))
         .expect("failed to create clock");
     clock.start();
 
-    let sound_data = StaticSoundData::from_file(path)
-        .expect("failed to load audio file")
-        .start_time(clock.time());
-
     let sound = ctx
         .manager
-        .play(sound_data)
+        .play(sound_data.start_time(clock.time()))
         .expect("failed to play sound");
 
     ctx.clock = Some(clock);
@@ -65,34 +151,42 @@ pub fn stop_song(ctx: &mut KiraContext) {
     }
     ctx.sound = None;
     ctx.clock = None;
+    ctx.music_fade = None;
 }
 
-pub fn play_preview(ctx: &mut KiraContext, path: &str, start_ms: u64, duration_ms: u64, volume: f64) {
+pub fn play_preview(ctx: &mut KiraContext, path: &Path, start_ms: u64, duration_ms: u64, volume: f64) {
     stop_preview(ctx);
 
     let Ok(sound_data) = StaticSoundData::from_file(path) else {
-        warn!("Failed to load preview audio: {}", path);
+        warn!("Failed to load preview audio: {}", path.display());
         return;
     };
 
     let start_secs = start_ms as f64 / 1000.0;
-    let end_secs = start_secs + duration_ms as f64 / 1000.0;
+    let file_secs = sound_data.duration().as_secs_f64();
+    let requested_secs = duration_ms as f64 / 1000.0;
+    let Some(duration_secs) = preview_window_secs(start_secs, requested_secs, file_secs) else {
+        warn!("Preview starts past the end of {}", path.display());
+        return;
+    };
     let db = amplitude_to_db(volume);
 
     let settings = StaticSoundSettings::new()
         .start_position(start_secs)
-        .loop_region(start_secs..end_secs)
         .volume(db)
-        .fade_in_tween(Tween {
-            duration: Duration::from_millis(500),
-            ..default()
-        });
+        .fade_in_tween(preview_fade_in());
 
     let sound_data = sound_data.with_settings(settings);
 
     match ctx.manager.play(sound_data) {
         Ok(handle) => {
             ctx.preview = Some(handle);
+            ctx.preview_loop = Some(PreviewLoop {
+                start_secs,
+                duration_secs,
+                db,
+                fading_out: false,
+            });
         }
         Err(e) => {
             warn!("Failed to play preview: {}", e);
@@ -100,6 +194,71 @@ pub fn play_preview(ctx: &mut KiraContext, path: &str, start_ms: u64, duration_m
     }
 }
 
+/// Play a short slice of already-decoded audio, e.g. an editor scrub grain.
+pub fn play_grain(
+    ctx: &mut KiraContext,
+    data: StaticSoundData,
+    start_secs: f64,
+    duration_ms: u64,
+    volume: f64,
+) -> Option<StaticSoundHandle> {
+    if start_secs < 0.0 || start_secs >= data.duration().as_secs_f64() {
+        return None;
+    }
+    let end_secs = start_secs + duration_ms as f64 / 1000.0;
+    let grain = data
+        .slice(start_secs..end_secs)
+        .volume(amplitude_to_db(volume))
+        .fade_in_tween(Tween {
+            duration: Duration::from_millis(5),
+            ..default()
+        });
+
+    match ctx.manager.play(grain) {
+        Ok(handle) => Some(handle),
+        Err(e) => {
+            warn!("Failed to play grain: {}", e);
+            None
+        }
+    }
+}
+
+fn preview_fade_in() -> Tween {
+    Tween {
+        duration: Duration::from_secs_f64(PREVIEW_FADE_SECS),
+        ..default()
+    }
+}
+
+/// Fade the preview out as it nears the end of its window, then jump back to
+/// the start and fade in again rather than going silent.
+fn tick_preview_loop(mut ctx: NonSendMut<KiraContext>) {
+    let ctx = &mut *ctx;
+    let (Some(preview), Some(window)) = (ctx.preview.as_mut(), ctx.preview_loop.as_mut()) else {
+        return;
+    };
+    match preview_cue(preview.position(), window.start_secs, window.duration_secs) {
+        PreviewCue::Play => {}
+        PreviewCue::FadeOut { remaining_secs } => {
+            if !window.fading_out {
+                window.fading_out = true;
+                preview.set_volume(
+                    amplitude_to_db(0.0),
+                    Tween {
+                        duration: Duration::from_secs_f64(remaining_secs),
+                        ..default()
+                    },
+                );
+            }
+        }
+        PreviewCue::Restart => {
+            window.fading_out = false;
+            preview.seek_to(window.start_secs);
+            preview.set_volume(window.db, preview_fade_in());
+        }
+    }
+}
+
 pub fn stop_preview(ctx: &mut KiraContext) {
     if let Some(ref mut preview) = ctx.preview {
         let _ = preview.stop(Tween {
@@ -108,10 +267,84 @@ pub fn stop_preview(ctx: &mut KiraContext) {
         });
     }
     ctx.preview = None;
+    ctx.preview_loop = None;
 }
 
 pub fn set_song_volume(ctx: &mut KiraContext, amplitude: f64) {
-    // StaticSoundHandle in Kira 0.11 doesn't expose set_volume.
-    // Volume is set at construction time or via tracks. This is a no-op for now.
-    let _ = (ctx, amplitude);
+    ctx.music_fade = None;
+    apply_music_amplitude(ctx, amplitude);
+}
+
+/// Fade the song volume to `target` amplitude over `duration`, starting
+/// from wherever the volume currently is.
+pub fn fade_music_to(ctx: &mut KiraContext, target: f64, duration: Duration) {
+    ctx.music_fade = Some(MusicFade {
+        from: ctx.music_amplitude,
+        to: target,
+        elapsed: 0.0,
+        duration: duration.as_secs_f64(),
+    });
+}
+
+fn apply_music_amplitude(ctx: &mut KiraContext, amplitude: f64) {
+    ctx.music_amplitude = amplitude;
+    if let Some(ref mut sound) = ctx.sound {
+        sound.set_volume(amplitude_to_db(amplitude), Tween::default());
+    }
+}
+
+/// Real time keeps running while paused, so fades finish in the pause menu.
+fn tick_music_fade(time: Res<Time<Real>>, mut ctx: NonSendMut<KiraContext>) {
+    let Some(ref mut fade) = ctx.music_fade else {
+        return;
+    };
+    fade.elapsed += time.delta_secs_f64();
+    let amplitude = fade_amplitude(fade.from, fade.to, fade.elapsed, fade.duration);
+    if fade.elapsed >= fade.duration {
+        ctx.music_fade = None;
+    }
+    apply_music_amplitude(&mut ctx, amplitude);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fade_reaches_target_at_end_time() {
+        assert_eq!(fade_amplitude(1.0, 0.0, 0.0, 0.15), 1.0);
+        assert!((fade_amplitude(1.0, 0.0, 0.075, 0.15) - 0.5).abs() < 1e-9);
+        assert_eq!(fade_amplitude(1.0, 0.0, 0.15, 0.15), 0.0);
+        // Overshooting the end time holds the target
+        assert_eq!(fade_amplitude(0.0, 0.8, 0.5, 0.15), 0.8);
+        // Zero-length fades jump straight to the target
+        assert_eq!(fade_amplitude(0.3, 0.7, 0.0, 0.0), 0.7);
+    }
+
+    #[test]
+    fn preview_fades_out_then_restarts_at_the_window_end() {
+        // 10 s preview from 30 s: plays, fades over the last half second, loops
+        assert_eq!(preview_cue(30.0, 30.0, 10.0), PreviewCue::Play);
+        assert_eq!(preview_cue(39.4, 30.0, 10.0), PreviewCue::Play);
+        match preview_cue(39.75, 30.0, 10.0) {
+            PreviewCue::FadeOut { remaining_secs } => assert!((remaining_secs - 0.25).abs() < 1e-9),
+            other => panic!("expected a fade-out, got {other:?}"),
+        }
+        assert_eq!(preview_cue(40.0, 30.0, 10.0), PreviewCue::Restart);
+        assert_eq!(preview_cue(40.02, 30.0, 10.0), PreviewCue::Restart);
+
+        // A one-second preview only spends half of it fading
+        assert_eq!(preview_cue(0.4, 0.0, 1.0), PreviewCue::Play);
+        assert!(matches!(preview_cue(0.6, 0.0, 1.0), PreviewCue::FadeOut { .. }));
+    }
+
+    #[test]
+    fn preview_window_stops_short_of_the_file_end() {
+        // Fits with room to spare
+        assert_eq!(preview_window_secs(30.0, 10.0, 120.0), Some(10.0));
+        // Would run to the end of a 35 s file: ends a fade early so it can still loop
+        assert_eq!(preview_window_secs(30.0, 10.0, 35.0), Some(4.5));
+        assert_eq!(preview_window_secs(34.5, 10.0, 35.0), None);
+        assert_eq!(preview_window_secs(40.0, 10.0, 35.0), None);
+    }
 }
@@ -1,21 +1,24 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::audio::{KiraContext, play_song};
+use crate::audio::{KiraContext, audio_duration, play_song, play_song_data};
 use crate::conductor::{SongConductor, TimingPoint};
 use crate::config::GameSettings;
-use crate::notes::{ChartNote, NoteKind, NoteQueue, Playhead};
+use crate::loading::{LoadError, PreloadedSong};
+use crate::notes::{ChartNote, NoteKind, NoteQueue, Playhead, scaled_approach_beats};
 use crate::path::SplinePath;
 use crate::results::SongComplete;
-use crate::state::GameScreen;
+use crate::state::{GameScreen, InGame};
 
 pub struct BeatMapPlugin;
 
 impl Plugin for BeatMapPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameScreen::Playing), setup_playing);
+        app.add_systems(OnEnter(InGame), setup_playing);
     }
 }
 
@@ -35,6 +38,10 @@ pub struct SongMetadata {
     pub source: String,
     #[serde(default)]
     pub difficulties: Vec<Difficulty>,
+    /// Added to the global audio offset for this song only, for files with
+    /// encoder delay or other quirks.
+    #[serde(default)]
+    pub offset_ms: i32,
 }
 
 fn default_preview_duration() -> u64 {
@@ -94,7 +101,7 @@ fn default_look_ahead() -> f64 {
 }
 
 /// Extract the beat range covered by path segments.
-fn beat_range_from_segments(segments: &[PathSegment]) -> (f64, f64) {
+pub(crate) fn beat_range_from_segments(segments: &[PathSegment]) -> (f64, f64) {
     let mut min_beat = f64::MAX;
     let mut max_beat = f64::MIN;
     for seg in segments {
@@ -154,6 +161,71 @@ pub enum PathSegment {
     },
 }
 
+/// Samples taken along a non-CatmullRom segment when building the gameplay spline.
+const SEGMENT_SAMPLES: usize = 8;
+
+impl PathSegment {
+    /// Points this segment contributes to the gameplay spline. CatmullRom
+    /// control points pass through as-is; other shapes are sampled so the
+    /// Catmull-Rom spline through them follows the intended curve.
+    pub fn spline_points(&self) -> Vec<(f32, f32)> {
+        match self {
+            PathSegment::CatmullRom { points, .. } => points.clone(),
+            PathSegment::Linear { start, end, .. } => {
+                let (a, b) = (Vec2::from(*start), Vec2::from(*end));
+                (0..=SEGMENT_SAMPLES)
+                    .map(|i| a.lerp(b, i as f32 / SEGMENT_SAMPLES as f32).into())
+                    .collect()
+            }
+            PathSegment::Bezier { control_points, .. } => {
+                if control_points.is_empty() {
+                    return Vec::new();
+                }
+                let cps: Vec<Vec2> = control_points.iter().map(|&p| Vec2::from(p)).collect();
+                (0..=SEGMENT_SAMPLES)
+                    .map(|i| de_casteljau(&cps, i as f32 / SEGMENT_SAMPLES as f32).into())
+                    .collect()
+            }
+            PathSegment::Arc { center, radius, start_angle, end_angle, .. } => {
+                (0..=SEGMENT_SAMPLES)
+                    .map(|i| {
+                        let t = i as f32 / SEGMENT_SAMPLES as f32;
+                        let a = start_angle + (end_angle - start_angle) * t;
+                        (center.0 + radius * a.cos(), center.1 + radius * a.sin())
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Evaluate a Bezier curve of any degree at `t`.
+fn de_casteljau(points: &[Vec2], t: f32) -> Vec2 {
+    let mut pts = points.to_vec();
+    for level in (1..pts.len()).rev() {
+        for i in 0..level {
+            pts[i] = pts[i].lerp(pts[i + 1], t);
+        }
+    }
+    pts[0]
+}
+
+/// All segments' spline points in order, dropping the duplicate where one
+/// segment ends exactly where the next begins.
+pub fn spline_points_for(segments: &[PathSegment]) -> Vec<Vec2> {
+    let mut all: Vec<Vec2> = Vec::new();
+    for seg in segments {
+        for (x, y) in seg.spline_points() {
+            let p = Vec2::new(x, y);
+            if all.last().is_some_and(|last| last.distance(p) < 1e-3) {
+                continue;
+            }
+            all.push(p);
+        }
+    }
+    all
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChartNoteEntry {
     pub beat: f64,
@@ -171,6 +243,8 @@ pub enum ChartNoteType {
     Critical,
     CriticalHold { duration_beats: f64 },
     DualSlide { left: SlideDirection, right: SlideDirection },
+    /// Hidden bonus note: hit like a Tap, never drawn, never missed.
+    AdLib,
     Rest,
 }
 
@@ -195,6 +269,20 @@ impl SlideDirection {
         }
     }
 
+    /// The direction pointing the other way (N↔S, NE↔SW, ...).
+    pub fn opposite(self) -> Self {
+        match self {
+            SlideDirection::N => SlideDirection::S,
+            SlideDirection::NE => SlideDirection::SW,
+            SlideDirection::E => SlideDirection::W,
+            SlideDirection::SE => SlideDirection::NW,
+            SlideDirection::S => SlideDirection::N,
+            SlideDirection::SW => SlideDirection::NE,
+            SlideDirection::W => SlideDirection::E,
+            SlideDirection::NW => SlideDirection::SE,
+        }
+    }
+
     /// Quantize an input vector to one of 8 directions.
     /// Returns `None` for near-zero vectors (dead zone).
     pub fn from_vec2(v: Vec2) -> Option<Self> {
@@ -246,18 +334,78 @@ pub struct SelectedSong {
     pub chart: ChartFile,
 }
 
+impl SelectedSong {
+    /// The song's audio file, or why it can't be played.
+    pub fn audio_path(&self) -> Result<PathBuf, String> {
+        song_audio_path(&self.song_dir, &self.metadata)
+    }
+}
+
+/// Path of a song's audio file, checked to exist so a bad pick is caught
+/// before gameplay starts rather than when kira tries to decode it.
+pub fn song_audio_path(song_dir: &Path, metadata: &SongMetadata) -> Result<PathBuf, String> {
+    let path = song_dir.join(&metadata.audio_file);
+    if path.is_file() {
+        Ok(path)
+    } else {
+        Err(format!("audio file missing: {}", path.display()))
+    }
+}
+
+/// The song list as it was shown when the current song was picked, so the
+/// results screen can move straight on to the next one.
+#[derive(Resource, Default)]
+pub struct SongPlaylist {
+    pub songs: Vec<DiscoveredSong>,
+}
+
+impl SongPlaylist {
+    /// The song after `current` (wrapping), played at `difficulty` when it has
+    /// that chart and at its first difficulty otherwise.
+    pub fn next_after(&self, current: &Path, difficulty: Difficulty) -> Option<(&DiscoveredSong, Difficulty)> {
+        let pos = self.songs.iter().position(|s| s.dir == current)?;
+        let next = &self.songs[(pos + 1) % self.songs.len()];
+        let diffs = &next.metadata.difficulties;
+        let difficulty = if diffs.contains(&difficulty) {
+            difficulty
+        } else {
+            *diffs.first()?
+        };
+        Some((next, difficulty))
+    }
+}
+
 // --- Song discovery & loading ---
 
+#[derive(Clone)]
 pub struct DiscoveredSong {
     pub dir: PathBuf,
     pub metadata: SongMetadata,
+    /// Length of the song's audio, read once at discovery.
+    pub duration: Option<Duration>,
 }
 
-pub fn discover_songs(songs_root: &Path) -> Vec<DiscoveredSong> {
+/// Song lengths already probed, by audio file, so coming back to song select
+/// doesn't reopen every song's audio.
+#[derive(Resource, Default)]
+pub struct SongDurations(HashMap<PathBuf, Option<Duration>>);
+
+impl SongDurations {
+    fn get_or_probe(&mut self, audio_path: PathBuf) -> Option<Duration> {
+        *self.0.entry(audio_path).or_insert_with_key(|path| audio_duration(path))
+    }
+}
+
+pub fn discover_songs(songs_root: &Path, durations: &mut SongDurations) -> Vec<DiscoveredSong> {
     let mut songs = Vec::new();
 
     let entries = match std::fs::read_dir(songs_root) {
         Ok(e) => e,
+        // A fresh install has no songs folder; song select explains what to do
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            info!("No songs directory at {:?}", songs_root);
+            return songs;
+        }
         Err(err) => {
             warn!("Failed to read songs directory {:?}: {}", songs_root, err);
             return songs;
@@ -275,9 +423,11 @@ pub fn discover_songs(songs_root: &Path) -> Vec<DiscoveredSong> {
         }
         match load_metadata(&meta_path) {
             Ok(metadata) => {
+                let duration = durations.get_or_probe(path.join(&metadata.audio_file));
                 songs.push(DiscoveredSong {
                     dir: path,
                     metadata,
+                    duration,
                 });
             }
             Err(err) => {
@@ -290,6 +440,37 @@ pub fn discover_songs(songs_root: &Path) -> Vec<DiscoveredSong> {
     songs
 }
 
+/// File extensions the song loader can play.
+pub const AUDIO_EXTENSIONS: &[&str] = &["ogg", "mp3", "wav", "flac"];
+
+/// The first song folder (by name) holding an audio file but no `metadata.ron`,
+/// with that audio file: a song someone dropped in but hasn't charted yet.
+pub fn find_uncharted_audio(songs_root: &Path) -> Option<(PathBuf, String)> {
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(songs_root)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && !path.join("metadata.ron").exists())
+        .collect();
+    dirs.sort();
+    dirs.into_iter().find_map(|dir| {
+        let mut audio: Vec<String> = std::fs::read_dir(&dir)
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| AUDIO_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+            })
+            .filter_map(|path| path.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .collect();
+        audio.sort();
+        let file = audio.into_iter().next()?;
+        Some((dir, file))
+    })
+}
+
 pub fn load_metadata(path: &Path) -> Result<SongMetadata, String> {
     let contents = std::fs::read_to_string(path)
         .map_err(|e| format!("read error: {e}"))?;
@@ -305,40 +486,98 @@ pub fn load_chart(song_dir: &Path, difficulty: Difficulty) -> Result<ChartFile,
         .map_err(|e| format!("parse error for {:?}: {e}", chart_path))
 }
 
-// --- OnEnter(Playing) setup ---
+/// Control points a Catmull-Rom spline needs before it can be built.
+pub const MIN_PATH_POINTS: usize = 4;
 
-fn setup_playing(
-    mut commands: Commands,
-    mut ctx: NonSendMut<KiraContext>,
-    selected: Res<SelectedSong>,
-    settings: Res<GameSettings>,
-) {
-    // 1. Build SplinePath from CatmullRom segments
-    let mut all_points: Vec<Vec2> = Vec::new();
-    for seg in &selected.chart.path_segments {
-        match seg {
-            PathSegment::CatmullRom { points, .. } => {
-                for &(x, y) in points {
-                    all_points.push(Vec2::new(x, y));
-                }
+/// A chart that parses but can't be played as written.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChartError {
+    NoTimingPoints,
+    TooFewPathPoints { count: usize },
+    NegativeBeat { index: usize, beat: f64 },
+    NonFiniteBeat { index: usize },
+    NonPositiveHold { index: usize, beat: f64, duration: f64 },
+    PastSongEnd { index: usize, beat: f64, end_beat: f64 },
+}
+
+impl std::fmt::Display for ChartError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChartError::NoTimingPoints => write!(f, "no timing points"),
+            ChartError::TooFewPathPoints { count } => {
+                write!(f, "path has {count} control points, need at least {MIN_PATH_POINTS}")
+            }
+            ChartError::NegativeBeat { index, beat } => {
+                write!(f, "note {index} is at negative beat {beat:.2}")
+            }
+            ChartError::NonFiniteBeat { index } => write!(f, "note {index} has no valid beat"),
+            ChartError::NonPositiveHold { index, beat, duration } => {
+                write!(f, "note {index} at beat {beat:.2} has hold duration {duration:.2}")
             }
-            other => {
-                warn!("Unsupported path segment type {:?}, skipping", std::mem::discriminant(other));
+            ChartError::PastSongEnd { index, beat, end_beat } => {
+                write!(f, "note {index} at beat {beat:.2} is past the path end at beat {end_beat:.2}")
             }
         }
     }
+}
 
-    if all_points.len() < 4 {
-        error!("Need at least 4 control points for CatmullRom spline, got {}", all_points.len());
-        return;
+/// Every reason the chart can't be played, in chart order.
+pub fn validate_chart(chart: &ChartFile) -> Result<(), Vec<ChartError>> {
+    let mut errors = Vec::new();
+    if chart.timing_points.is_empty() {
+        errors.push(ChartError::NoTimingPoints);
+    }
+    let count = spline_points_for(&chart.path_segments).len();
+    if count < MIN_PATH_POINTS {
+        errors.push(ChartError::TooFewPathPoints { count });
     }
 
-    let spline_path = SplinePath::from_catmull_rom_points(all_points);
-    commands.insert_resource(spline_path);
+    let end_beat = (!chart.path_segments.is_empty())
+        .then(|| beat_range_from_segments(&chart.path_segments).1);
+    for (index, note) in chart.notes.iter().enumerate() {
+        let beat = note.beat;
+        if !beat.is_finite() {
+            // NaN compares false against everything, so nothing below can judge it
+            errors.push(ChartError::NonFiniteBeat { index });
+            continue;
+        }
+        if beat < 0.0 {
+            errors.push(ChartError::NegativeBeat { index, beat });
+        }
+        let duration = match note.note_type {
+            ChartNoteType::Hold { duration_beats }
+            | ChartNoteType::SlideHold { duration_beats, .. }
+            | ChartNoteType::CriticalHold { duration_beats } => Some(duration_beats),
+            _ => None,
+        };
+        if let Some(duration) = duration.filter(|d| *d <= 0.0) {
+            errors.push(ChartError::NonPositiveHold { index, beat, duration });
+        }
+        if let Some(end_beat) = end_beat.filter(|end| beat > *end) {
+            errors.push(ChartError::PastSongEnd { index, beat, end_beat });
+        }
+    }
+
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
 
-    // 2. Build NoteQueue (Tap notes only for now)
+/// `validate_chart`'s errors as one message, for `Result<_, String>` callers.
+pub fn format_chart_errors(errors: &[ChartError]) -> String {
+    errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
+/// Load a chart for play, rejecting it if `validate_chart` finds problems.
+pub fn load_playable_chart(song_dir: &Path, difficulty: Difficulty) -> Result<ChartFile, String> {
+    let chart = load_chart(song_dir, difficulty)?;
+    validate_chart(&chart).map_err(|errors| format_chart_errors(&errors))?;
+    Ok(chart)
+}
+
+/// Gameplay notes for a chart, sorted by beat and then by kind. Unsupported
+/// types are skipped and exact duplicates (same beat and kind) are merged into one.
+pub fn chart_notes(chart: &ChartFile) -> Vec<ChartNote> {
     let mut notes = Vec::new();
-    for entry in &selected.chart.notes {
+    for entry in &chart.notes {
         match entry.note_type {
             ChartNoteType::Tap => {
                 notes.push(ChartNote {
@@ -367,12 +606,21 @@ fn setup_playing(
                 }
             }
             ChartNoteType::Rest => {
+                // Timing anchor only: marks an intentional gap, never judged
+            }
+            ChartNoteType::Scratch => {
                 notes.push(ChartNote {
                     target_beat: entry.beat,
-                    kind: NoteKind::Rest,
+                    kind: NoteKind::Scratch,
                 });
             }
-            ChartNoteType::Beat | ChartNoteType::Scratch | ChartNoteType::DualSlide { .. } => {
+            ChartNoteType::DualSlide { left, right } => {
+                notes.push(ChartNote {
+                    target_beat: entry.beat,
+                    kind: NoteKind::DualSlide { left, right },
+                });
+            }
+            ChartNoteType::Beat => {
                 warn!("Deprecated note type {:?} at beat {:.1}, skipping", entry.note_type, entry.beat);
             }
             ChartNoteType::Critical => {
@@ -381,17 +629,92 @@ fn setup_playing(
                     kind: NoteKind::Critical,
                 });
             }
+            ChartNoteType::AdLib => {
+                notes.push(ChartNote {
+                    target_beat: entry.beat,
+                    kind: NoteKind::AdLib,
+                });
+            }
             ref other => {
                 warn!("Unsupported note type {:?}, skipping", std::mem::discriminant(other));
             }
         }
     }
-    notes.sort_by(|a, b| a.target_beat.partial_cmp(&b.target_beat).unwrap());
+    // Stable and total, so equal beats keep a fixed order and NaN can't panic
+    notes.sort_by(|a, b| {
+        a.target_beat
+            .total_cmp(&b.target_beat)
+            .then(a.kind.sort_rank().cmp(&b.kind.sort_rank()))
+    });
+    merge_duplicate_notes(&mut notes);
+    notes
+}
+
+/// Drop notes identical to an earlier one at the same beat. A single input
+/// only ever consumes one of them, so the copies would always auto-miss.
+/// Expects `notes` sorted by beat.
+fn merge_duplicate_notes(notes: &mut Vec<ChartNote>) {
+    let mut kept: Vec<ChartNote> = Vec::with_capacity(notes.len());
+    for note in notes.drain(..) {
+        let duplicate = kept
+            .iter()
+            .rev()
+            .take_while(|k| k.target_beat == note.target_beat)
+            .any(|k| k.kind == note.kind);
+        if duplicate {
+            warn!("Duplicate {:?} note at beat {:.2}, merging", note.kind, note.target_beat);
+            continue;
+        }
+        kept.push(note);
+    }
+    *notes = kept;
+}
+
+// --- OnEnter(Playing) setup ---
+
+fn setup_playing(
+    mut commands: Commands,
+    mut ctx: NonSendMut<KiraContext>,
+    selected: Res<SelectedSong>,
+    settings: Res<GameSettings>,
+    preloaded: Option<Res<PreloadedSong>>,
+    mut next_state: ResMut<NextState<GameScreen>>,
+) {
+    // A retry or "next" can land here without the loading screen checking the audio
+    let audio_path = match selected.audio_path() {
+        Ok(path) => path,
+        Err(err) => {
+            error!("Can't play {}: {}", selected.metadata.title, err);
+            commands.insert_resource(LoadError(format!("Can't play this chart: {err}")));
+            next_state.set(GameScreen::SongSelect);
+            return;
+        }
+    };
+
+    // 1. Build SplinePath from all segments (non-CatmullRom shapes are sampled)
+    let all_points = spline_points_for(&selected.chart.path_segments);
+
+    // Song select validates first; this only catches charts that slipped past it
+    if all_points.len() < MIN_PATH_POINTS {
+        error!(
+            "Need at least {MIN_PATH_POINTS} control points for CatmullRom spline, got {}",
+            all_points.len()
+        );
+        next_state.set(GameScreen::SongSelect);
+        return;
+    }
+
+    let spline_path = SplinePath::from_catmull_rom_points(all_points);
+    commands.insert_resource(spline_path);
+
+    // 2. Build NoteQueue
+    let notes = chart_notes(&selected.chart);
 
-    commands.insert_resource(NoteQueue {
+    commands.insert_resource(NoteQueue::new(
         notes,
-        next_index: 0,
-    });
+        scaled_approach_beats(selected.chart.travel_beats, settings.note_speed),
+        scaled_approach_beats(selected.chart.look_ahead_beats, settings.note_speed),
+    ));
 
     // Extract beat range from path segments and insert Playhead
     let (song_start_beat, song_end_beat) = beat_range_from_segments(&selected.chart.path_segments);
@@ -401,23 +724,26 @@ fn setup_playing(
     });
 
     // 3. Build SongConductor
-    let first_tp = selected.chart.timing_points.first()
-        .expect("Chart must have at least one timing point");
-    let bpm = first_tp.bpm;
-
-    let remaining_timing_points: Vec<TimingPoint> = selected.chart.timing_points.iter()
-        .skip(1)
+    let timing_points: Vec<TimingPoint> = selected.chart.timing_points.iter()
         .map(|tp| TimingPoint { beat: tp.beat, bpm: tp.bpm })
         .collect();
-
-    let mut conductor = SongConductor::new(bpm);
-    conductor.timing_points = remaining_timing_points;
+    let mut conductor = SongConductor::from_timing_points(timing_points);
+    conductor.song_offset_ms = selected.metadata.offset_ms;
+    let bpm = conductor.bpm;
     commands.insert_resource(conductor);
 
-    // 4. Play song
-    let audio_path = selected.song_dir.join(&selected.metadata.audio_file);
-    let audio_str = audio_path.to_str().expect("audio path must be valid UTF-8");
-    play_song(&mut ctx, audio_str, bpm);
+    // 4. Play song, reusing the audio the loading screen decoded when it's this song's
+    match preloaded.filter(|p| p.path == audio_path) {
+        Some(preloaded) => play_song_data(&mut ctx, preloaded.data.clone(), bpm),
+        None => {
+            if let Err(err) = play_song(&mut ctx, &audio_path, bpm) {
+                error!("Failed to start song audio: {}", err);
+                commands.insert_resource(LoadError(format!("Can't play this chart: {err}")));
+                next_state.set(GameScreen::SongSelect);
+                return;
+            }
+        }
+    }
     crate::audio::set_song_volume(&mut ctx, settings.master_amplitude());
 
     // 5. Insert SongComplete
@@ -459,6 +785,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn slide_direction_opposite() {
+        for dir in [
+            SlideDirection::N, SlideDirection::NE, SlideDirection::E, SlideDirection::SE,
+            SlideDirection::S, SlideDirection::SW, SlideDirection::W, SlideDirection::NW,
+        ] {
+            let opp = dir.opposite();
+            assert_ne!(opp, dir);
+            assert_eq!(opp.opposite(), dir);
+            assert!((opp.to_vec2() + dir.to_vec2()).length() < 1e-6, "{dir:?}");
+        }
+    }
+
     #[test]
     fn slide_direction_dead_zone() {
         assert!(SlideDirection::from_vec2(Vec2::ZERO).is_none());
@@ -474,4 +813,253 @@ mod tests {
         let v = Vec2::new(0.2, 0.9); // mostly north
         assert_eq!(SlideDirection::from_vec2(v), Some(SlideDirection::N));
     }
+
+    #[test]
+    fn playlist_next_wraps_and_keeps_difficulty() {
+        let song = |dir: &str, difficulties: Vec<Difficulty>| DiscoveredSong {
+            dir: PathBuf::from(dir),
+            metadata: SongMetadata {
+                title: dir.to_string(),
+                artist: String::new(),
+                charter: String::new(),
+                audio_file: "song.ogg".into(),
+                preview_start_ms: 0,
+                preview_duration_ms: 15000,
+                source: String::new(),
+                difficulties,
+                offset_ms: 0,
+            },
+            duration: None,
+        };
+        let playlist = SongPlaylist {
+            songs: vec![
+                song("a", vec![Difficulty::Easy, Difficulty::Hard]),
+                song("b", vec![Difficulty::Normal]),
+            ],
+        };
+
+        let (next, diff) = playlist.next_after(Path::new("a"), Difficulty::Hard).unwrap();
+        assert_eq!(next.dir, PathBuf::from("b"));
+        assert_eq!(diff, Difficulty::Normal);
+
+        let (next, diff) = playlist.next_after(Path::new("b"), Difficulty::Normal).unwrap();
+        assert_eq!(next.dir, PathBuf::from("a"));
+        assert_eq!(diff, Difficulty::Easy);
+
+        assert!(playlist.next_after(Path::new("missing"), Difficulty::Easy).is_none());
+    }
+
+    fn valid_chart() -> ChartFile {
+        ChartFile {
+            difficulty: Difficulty::Easy,
+            difficulty_rating: 1,
+            timing_points: vec![ChartTimingPoint { beat: 0.0, bpm: 120.0, time_signature: (4, 4) }],
+            path_segments: vec![PathSegment::CatmullRom {
+                points: vec![(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0)],
+                start_beat: 0.0,
+                end_beat: 8.0,
+            }],
+            notes: vec![
+                ChartNoteEntry { beat: 1.0, note_type: ChartNoteType::Tap },
+                ChartNoteEntry { beat: 2.0, note_type: ChartNoteType::Hold { duration_beats: 1.0 } },
+            ],
+            events: Vec::new(),
+            travel_beats: 3.0,
+            look_ahead_beats: 3.0,
+        }
+    }
+
+    #[test]
+    fn stacked_identical_notes_collapse_to_one() {
+        let mut chart = valid_chart();
+        chart.notes = vec![
+            ChartNoteEntry { beat: 1.0, note_type: ChartNoteType::Tap },
+            ChartNoteEntry { beat: 1.0, note_type: ChartNoteType::Critical },
+            ChartNoteEntry { beat: 1.0, note_type: ChartNoteType::Tap },
+            ChartNoteEntry { beat: 2.0, note_type: ChartNoteType::Tap },
+        ];
+        let notes = chart_notes(&chart);
+        let kinds: Vec<(f64, NoteKind)> = notes.iter().map(|n| (n.target_beat, n.kind)).collect();
+        // Different kinds at one beat are left alone; only the second Tap goes
+        assert_eq!(
+            kinds,
+            vec![(1.0, NoteKind::Tap), (1.0, NoteKind::Critical), (2.0, NoteKind::Tap)]
+        );
+    }
+
+    #[test]
+    fn valid_chart_passes() {
+        assert_eq!(validate_chart(&valid_chart()), Ok(()));
+    }
+
+    #[test]
+    fn three_point_path_is_rejected() {
+        let mut chart = valid_chart();
+        chart.path_segments = vec![PathSegment::CatmullRom {
+            points: vec![(0.0, 0.0), (1.0, 0.0), (2.0, 0.0)],
+            start_beat: 0.0,
+            end_beat: 8.0,
+        }];
+        assert_eq!(validate_chart(&chart), Err(vec![ChartError::TooFewPathPoints { count: 3 }]));
+    }
+
+    #[test]
+    fn missing_timing_points_are_rejected() {
+        let mut chart = valid_chart();
+        chart.timing_points.clear();
+        assert_eq!(validate_chart(&chart), Err(vec![ChartError::NoTimingPoints]));
+    }
+
+    #[test]
+    fn negative_beats_name_the_note() {
+        let mut chart = valid_chart();
+        chart.notes[0].beat = -0.5;
+        assert_eq!(
+            validate_chart(&chart),
+            Err(vec![ChartError::NegativeBeat { index: 0, beat: -0.5 }])
+        );
+    }
+
+    #[test]
+    fn nan_beats_are_rejected() {
+        let mut chart = valid_chart();
+        chart.notes[1].beat = f64::NAN;
+        let errors = validate_chart(&chart).unwrap_err();
+        assert_eq!(errors, vec![ChartError::NonFiniteBeat { index: 1 }]);
+        assert_eq!(errors[0].to_string(), "note 1 has no valid beat");
+    }
+
+    #[test]
+    fn same_beat_notes_sort_the_same_whatever_the_file_order() {
+        let mut chart = valid_chart();
+        chart.notes = vec![
+            ChartNoteEntry { beat: 2.0, note_type: ChartNoteType::Critical },
+            ChartNoteEntry { beat: 1.0, note_type: ChartNoteType::AdLib },
+            ChartNoteEntry {
+                beat: 1.0,
+                note_type: ChartNoteType::Slide { direction: SlideDirection::E },
+            },
+            ChartNoteEntry { beat: 1.0, note_type: ChartNoteType::Tap },
+            ChartNoteEntry {
+                beat: 1.0,
+                note_type: ChartNoteType::Slide { direction: SlideDirection::N },
+            },
+        ];
+        let kinds = |chart: &ChartFile| -> Vec<(f64, NoteKind)> {
+            chart_notes(chart).iter().map(|n| (n.target_beat, n.kind)).collect()
+        };
+        let expected = vec![
+            (1.0, NoteKind::Tap),
+            (1.0, NoteKind::Slide(SlideDirection::N)),
+            (1.0, NoteKind::Slide(SlideDirection::E)),
+            (1.0, NoteKind::AdLib),
+            (2.0, NoteKind::Critical),
+        ];
+        assert_eq!(kinds(&chart), expected);
+
+        chart.notes.reverse();
+        assert_eq!(kinds(&chart), expected);
+    }
+
+    #[test]
+    fn empty_holds_are_rejected() {
+        let mut chart = valid_chart();
+        chart.notes[1].note_type = ChartNoteType::Hold { duration_beats: 0.0 };
+        chart.notes.push(ChartNoteEntry {
+            beat: 3.0,
+            note_type: ChartNoteType::CriticalHold { duration_beats: -1.0 },
+        });
+        assert_eq!(
+            validate_chart(&chart),
+            Err(vec![
+                ChartError::NonPositiveHold { index: 1, beat: 2.0, duration: 0.0 },
+                ChartError::NonPositiveHold { index: 2, beat: 3.0, duration: -1.0 },
+            ])
+        );
+    }
+
+    #[test]
+    fn notes_past_the_path_end_are_rejected() {
+        let mut chart = valid_chart();
+        chart.notes.push(ChartNoteEntry { beat: 9.0, note_type: ChartNoteType::Tap });
+        let errors = validate_chart(&chart).unwrap_err();
+        assert_eq!(errors, vec![ChartError::PastSongEnd { index: 2, beat: 9.0, end_beat: 8.0 }]);
+        assert!(errors[0].to_string().contains("note 2 at beat 9.00"));
+    }
+
+    #[test]
+    fn missing_audio_is_caught_before_playing() {
+        let dir = std::env::temp_dir().join(format!("funktrack-{}-no-audio", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let selected = SelectedSong {
+            song_dir: dir.clone(),
+            difficulty: Difficulty::Normal,
+            metadata: SongMetadata {
+                title: "Silent".into(),
+                artist: String::new(),
+                charter: String::new(),
+                audio_file: "missing.ogg".into(),
+                preview_start_ms: 0,
+                preview_duration_ms: 0,
+                source: String::new(),
+                difficulties: vec![Difficulty::Normal],
+                offset_ms: 0,
+            },
+            chart: valid_chart(),
+        };
+        let err = selected.audio_path().unwrap_err();
+        assert!(err.contains("missing.ogg"), "{err}");
+
+        std::fs::write(dir.join("missing.ogg"), b"").unwrap();
+        assert_eq!(selected.audio_path(), Ok(dir.join("missing.ogg")));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn missing_songs_folder_is_just_empty() {
+        let root = std::env::temp_dir().join(format!("funktrack-{}-no-songs", std::process::id()));
+        assert!(!root.exists());
+        assert!(discover_songs(&root, &mut SongDurations::default()).is_empty());
+        assert_eq!(find_uncharted_audio(&root), None);
+    }
+
+    #[test]
+    fn audio_only_folder_is_offered_as_a_new_chart() {
+        let root = std::env::temp_dir().join(format!("funktrack-{}-uncharted", std::process::id()));
+        let song = root.join("fresh_track");
+        std::fs::create_dir_all(&song).unwrap();
+        std::fs::write(song.join("notes.txt"), b"").unwrap();
+        assert_eq!(find_uncharted_audio(&root), None);
+
+        std::fs::write(song.join("Fresh.OGG"), b"").unwrap();
+        assert!(discover_songs(&root, &mut SongDurations::default()).is_empty());
+        assert_eq!(find_uncharted_audio(&root), Some((song.clone(), "Fresh.OGG".to_string())));
+
+        // Once it has metadata it's a song, not an offer
+        std::fs::write(song.join("metadata.ron"), b"").unwrap();
+        assert_eq!(find_uncharted_audio(&root), None);
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn song_lengths_are_probed_once() {
+        let root = std::env::temp_dir().join(format!("funktrack-{}-durations", std::process::id()));
+        let song = root.join("cached_track");
+        std::fs::create_dir_all(&song).unwrap();
+        std::fs::write(
+            song.join("metadata.ron"),
+            r#"(title: "Cached", artist: "", charter: "", audio_file: "song.ogg")"#,
+        )
+        .unwrap();
+        std::fs::write(song.join("song.ogg"), b"").unwrap();
+
+        // The empty file can't be probed, so a length can only come from the cache
+        let mut durations = SongDurations::default();
+        assert_eq!(discover_songs(&root, &mut durations)[0].duration, None);
+        durations.0.insert(song.join("song.ogg"), Some(Duration::from_secs(90)));
+        let songs = discover_songs(&root, &mut durations);
+        assert_eq!(songs[0].duration, Some(Duration::from_secs(90)));
+        assert_eq!(durations.0.len(), 1);
+        std::fs::remove_dir_all(&root).ok();
+    }
 }
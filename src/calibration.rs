@@ -45,12 +45,15 @@ const BEAT_INTERVAL: f64 = 60.0 / CAL_BPM;
 const TAPS_NEEDED: usize = 16;
 /// Flash duration in seconds.
 const FLASH_DURATION: f32 = 0.08;
+/// Where the sweep marker sits along the visual track (percent of width).
+const SWEEP_MARKER_PCT: f32 = 80.0;
 
 // --- Resources ---
 
 #[derive(Resource)]
 struct CalibrationState {
     phase: CalPhase,
+    mode: CalMode,
     elapsed: f64,
     beat_count: u32,
     tap_offsets: Vec<f64>,
@@ -66,6 +69,45 @@ enum CalPhase {
     Results,
 }
 
+/// Which offset is being measured. Audio taps to a flashing beat; visual taps
+/// when a sweeping note crosses a fixed marker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CalMode {
+    Audio,
+    Visual,
+}
+
+impl CalMode {
+    fn label(self) -> &'static str {
+        match self {
+            CalMode::Audio => "AUDIO",
+            CalMode::Visual => "VISUAL",
+        }
+    }
+
+    fn toggle(self) -> Self {
+        match self {
+            CalMode::Audio => CalMode::Visual,
+            CalMode::Visual => CalMode::Audio,
+        }
+    }
+}
+
+/// Median of the collected tap offsets, or `None` if there are none.
+fn median(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mid = sorted.len() / 2;
+    Some(if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    })
+}
+
 // --- Markers ---
 
 #[derive(Component)]
@@ -80,11 +122,18 @@ struct OffsetText;
 #[derive(Component)]
 struct ProgressText;
 
+#[derive(Component)]
+struct SweepTrack;
+
+#[derive(Component)]
+struct SweepNote;
+
 // --- Setup ---
 
 fn setup_calibration(mut commands: Commands) {
     commands.insert_resource(CalibrationState {
         phase: CalPhase::Instructions,
+        mode: CalMode::Audio,
         elapsed: 0.0,
         beat_count: 0,
         tap_offsets: Vec::with_capacity(TAPS_NEEDED),
@@ -147,10 +196,51 @@ fn setup_calibration(mut commands: Commands) {
                     BorderColor::all(BEAT_MARKER_COLOR.with_alpha(0.3)),
                 ));
 
+                // Visual sweep track — note slides to the marker on each beat
+                panel
+                    .spawn((
+                        SweepTrack,
+                        Node {
+                            width: Val::Px(400.0),
+                            height: Val::Px(24.0),
+                            display: Display::None,
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0.1, 0.05, 0.2, 0.5)),
+                    ))
+                    .with_children(|track| {
+                        // Fixed marker
+                        track.spawn((
+                            Node {
+                                position_type: PositionType::Absolute,
+                                left: Val::Percent(SWEEP_MARKER_PCT),
+                                width: Val::Px(2.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            BackgroundColor(FLASH_COLOR),
+                        ));
+                        // Sweeping note
+                        track.spawn((
+                            SweepNote,
+                            Node {
+                                position_type: PositionType::Absolute,
+                                left: Val::Percent(0.0),
+                                top: Val::Px(4.0),
+                                width: Val::Px(16.0),
+                                height: Val::Px(16.0),
+                                margin: UiRect::left(Val::Px(-8.0)),
+                                border_radius: BorderRadius::all(Val::Px(8.0)),
+                                ..default()
+                            },
+                            BackgroundColor(BEAT_MARKER_COLOR),
+                        ));
+                    });
+
                 // Status text
                 panel.spawn((
                     StatusText,
-                    Text::new("Tap along with the flashing beat.\nPress SPACE to start."),
+                    Text::new(instructions_text(CalMode::Audio)),
                     TextFont { font_size: INFO_FONT, ..default() },
                     TextColor(TITLE_COLOR),
                     TextLayout::new_with_justify(Justify::Center),
@@ -181,6 +271,7 @@ fn setup_calibration(mut commands: Commands) {
                 ..default()
             })
             .with_children(|hints| {
+                spawn_hint(hints, "LEFT/RIGHT", "audio / visual");
                 spawn_hint(hints, "SPACE", "tap / start");
                 spawn_hint(hints, "CONFIRM", "apply offset");
                 spawn_hint(hints, "ESC", "back");
@@ -188,6 +279,14 @@ fn setup_calibration(mut commands: Commands) {
         });
 }
 
+fn instructions_text(mode: CalMode) -> String {
+    let how = match mode {
+        CalMode::Audio => "Tap along with the flashing beat.",
+        CalMode::Visual => "Tap when the moving note crosses the line.",
+    };
+    format!("< {} >\n{how}\nPress SPACE to start.", mode.label())
+}
+
 fn spawn_hint(parent: &mut ChildSpawnerCommands, key: &str, action: &str) {
     parent
         .spawn(Node {
@@ -253,6 +352,10 @@ fn handle_calibration_input(
 
     match state.phase {
         CalPhase::Instructions => {
+            if action.just_pressed(&GameAction::Left) || action.just_pressed(&GameAction::Right) {
+                state.mode = state.mode.toggle();
+                state.dirty = true;
+            }
             if keys.just_pressed(KeyCode::Space) || action.just_pressed(&GameAction::Confirm) {
                 state.phase = CalPhase::Tapping;
                 state.elapsed = 0.0;
@@ -279,16 +382,7 @@ fn handle_calibration_input(
                     state.dirty = true;
 
                     if state.tap_offsets.len() >= TAPS_NEEDED {
-                        // Compute median offset
-                        let mut sorted = state.tap_offsets.clone();
-                        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
-                        let median = if sorted.len() % 2 == 0 {
-                            let mid = sorted.len() / 2;
-                            (sorted[mid - 1] + sorted[mid]) / 2.0
-                        } else {
-                            sorted[sorted.len() / 2]
-                        };
-                        state.computed_offset = Some(median);
+                        state.computed_offset = median(&state.tap_offsets);
                         state.phase = CalPhase::Results;
                         state.dirty = true;
                     }
@@ -299,9 +393,13 @@ fn handle_calibration_input(
             if action.just_pressed(&GameAction::Confirm) {
                 // Apply offset
                 if let Some(offset) = state.computed_offset {
-                    settings.audio_offset_ms = offset.round() as i32;
+                    let offset_ms = offset.round() as i32;
+                    match state.mode {
+                        CalMode::Audio => settings.audio_offset_ms = offset_ms,
+                        CalMode::Visual => settings.visual_offset_ms = offset_ms,
+                    }
                     settings.save();
-                    info!("Applied audio offset: {} ms", settings.audio_offset_ms);
+                    info!("Applied {} offset: {} ms", state.mode.label().to_lowercase(), offset_ms);
                 }
                 next_state.set(GameScreen::Settings);
             }
@@ -322,9 +420,29 @@ fn update_calibration_ui(
     mut status: Query<&mut Text, (With<StatusText>, Without<ProgressText>, Without<OffsetText>)>,
     mut progress: Query<&mut Text, (With<ProgressText>, Without<StatusText>, Without<OffsetText>)>,
     mut offset_text: Query<&mut Text, (With<OffsetText>, Without<StatusText>, Without<ProgressText>)>,
+    mut sweep_track: Query<&mut Node, (With<SweepTrack>, Without<SweepNote>, Without<FlashIndicator>)>,
+    mut sweep_note: Query<&mut Node, (With<SweepNote>, Without<SweepTrack>, Without<FlashIndicator>)>,
+    mut flash_node: Query<&mut Node, (With<FlashIndicator>, Without<SweepTrack>, Without<SweepNote>)>,
 ) {
-    // Always update flash (smooth animation)
+    let visual = state.mode == CalMode::Visual;
+
+    // Visual mode: note reaches the marker exactly on each beat
+    if visual {
+        for mut node in &mut sweep_note {
+            let phase = if state.phase == CalPhase::Tapping {
+                (state.elapsed / BEAT_INTERVAL).fract() as f32
+            } else {
+                0.0
+            };
+            node.left = Val::Percent(phase * SWEEP_MARKER_PCT);
+        }
+    }
+
+    // Always update flash (smooth animation); visual mode has no flash
     for (mut bg, mut border) in &mut flash {
+        if visual {
+            continue;
+        }
         if state.flash_timer > 0.0 {
             let intensity = state.flash_timer / FLASH_DURATION;
             *bg = BackgroundColor(FLASH_COLOR.with_alpha(intensity * 0.9));
@@ -340,15 +458,21 @@ fn update_calibration_ui(
     }
     state.dirty = false;
 
+    for mut node in &mut sweep_track {
+        node.display = if visual { Display::Flex } else { Display::None };
+    }
+    for mut node in &mut flash_node {
+        node.display = if visual { Display::None } else { Display::Flex };
+    }
+
     // Update status text
     for mut text in &mut status {
         **text = match state.phase {
-            CalPhase::Instructions => {
-                "Tap along with the flashing beat.\nPress SPACE to start.".to_string()
-            }
-            CalPhase::Tapping => {
-                "Tap SPACE on each flash...".to_string()
-            }
+            CalPhase::Instructions => instructions_text(state.mode),
+            CalPhase::Tapping => match state.mode {
+                CalMode::Audio => "Tap SPACE on each flash...".to_string(),
+                CalMode::Visual => "Tap SPACE as the note hits the line...".to_string(),
+            },
             CalPhase::Results => {
                 if let Some(offset) = state.computed_offset {
                     let quality = if offset.abs() < 10.0 {
@@ -414,3 +538,20 @@ fn update_calibration_ui(
         }
     }
 }
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_of_visual_tap_offsets() {
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&[12.0]), Some(12.0));
+        // Odd count — middle value, unaffected by an outlier
+        assert_eq!(median(&[30.0, -5.0, 18.0, 22.0, 190.0]), Some(22.0));
+        // Even count — mean of the two middle values
+        assert_eq!(median(&[40.0, 10.0, 20.0, 30.0]), Some(25.0));
+    }
+}
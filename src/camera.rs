@@ -3,22 +3,23 @@ use bevy::prelude::*;
 use crate::GameSet;
 use crate::beatmap::{EventType, SelectedSong};
 use crate::conductor::SongConductor;
+use crate::config::GameSettings;
 use crate::notes::Playhead;
 use crate::path::SplinePath;
-use crate::state::GameScreen;
+use crate::state::InGame;
 
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameScreen::Playing), init_camera_state)
+        app.add_systems(OnEnter(InGame), init_camera_state)
             .add_systems(
                 Update,
                 (process_camera_events, update_camera, apply_camera_transform)
                     .chain()
                     .in_set(GameSet::Render),
             )
-            .add_systems(OnExit(GameScreen::Playing), cleanup_camera_state);
+            .add_systems(OnExit(InGame), cleanup_camera_state);
     }
 }
 
@@ -194,7 +195,7 @@ fn init_camera_state(
             })
             .collect();
 
-        events.sort_by(|a, b| a.beat.partial_cmp(&b.beat).unwrap());
+        events.sort_by(|a, b| a.beat.total_cmp(&b.beat));
         state.pending_events = events;
     }
 
@@ -203,12 +204,14 @@ fn init_camera_state(
 
 fn process_camera_events(
     conductor: Option<Res<SongConductor>>,
+    settings: Option<Res<GameSettings>>,
     mut state: Option<ResMut<CameraState>>,
 ) {
     let Some(conductor) = conductor else { return };
     let Some(ref mut state) = state else { return };
 
-    let beat = conductor.current_beat;
+    let visual_offset_ms = settings.map_or(0, |s| s.visual_offset_ms);
+    let beat = conductor.visual_beat(visual_offset_ms);
 
     while state.next_event_index < state.pending_events.len() {
         let event = &state.pending_events[state.next_event_index];
@@ -258,6 +261,7 @@ fn process_camera_events(
 
 fn update_camera(
     conductor: Option<Res<SongConductor>>,
+    settings: Option<Res<GameSettings>>,
     time: Res<Time>,
     playhead: Option<Res<Playhead>>,
     spline: Option<Res<SplinePath>>,
@@ -266,7 +270,8 @@ fn update_camera(
     let Some(conductor) = conductor else { return };
     let Some(ref mut state) = state else { return };
 
-    let beat = conductor.current_beat;
+    let visual_offset_ms = settings.map_or(0, |s| s.visual_offset_ms);
+    let beat = conductor.visual_beat(visual_offset_ms);
     let dt = time.delta_secs();
 
     // --- Update chart-driven animations ---
@@ -316,7 +321,7 @@ fn update_camera(
     // --- Playhead tracking ---
 
     if let (Some(playhead), Some(spline)) = (playhead, spline) {
-        let progress = playhead.progress(beat);
+        let progress = playhead.progress_at_beat(beat);
         let playhead_pos = spline.position_at_progress(progress);
 
         // Look ahead slightly for better note visibility
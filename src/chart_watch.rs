@@ -0,0 +1,261 @@
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use bevy::prelude::*;
+
+use crate::beatmap::{SelectedSong, beat_range_from_segments, chart_notes, load_chart, spline_points_for};
+use crate::conductor::SongConductor;
+use crate::notes::{
+    ChartNote, HoldState, HoldTicks, NoteAlive, NoteKind, NoteQueue, NoteTiming, NoteType, Playhead,
+};
+use crate::path::SplinePath;
+use crate::scoring::{ScoreState, outstanding_pool, pool_weight, total_judgments};
+use crate::state::{GameScreen, InGame};
+use crate::visuals::PathVisual;
+
+/// Dev-only chart hot reload. Run with `--watch` and saving the chart (e.g.
+/// from the editor) swaps the new notes and path into the running song.
+pub struct ChartWatchPlugin;
+
+impl Plugin for ChartWatchPlugin {
+    fn build(&self, app: &mut App) {
+        if !std::env::args().any(|arg| arg == "--watch") {
+            return;
+        }
+        info!("Chart hot reload enabled (--watch)");
+        app.add_systems(OnEnter(InGame), start_watch)
+            .add_systems(OnExit(InGame), stop_watch)
+            .add_systems(
+                Update,
+                poll_chart
+                    .run_if(in_state(GameScreen::Playing))
+                    .run_if(resource_exists::<ChartWatch>),
+            );
+    }
+}
+
+/// How often the chart file's mtime is checked.
+const POLL_INTERVAL_SECS: f32 = 0.5;
+
+#[derive(Resource)]
+struct ChartWatch {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    poll: Timer,
+}
+
+fn modified_time(path: &PathBuf) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn start_watch(mut commands: Commands, selected: Option<Res<SelectedSong>>) {
+    let Some(selected) = selected else { return };
+    let path = selected.song_dir.join(selected.difficulty.filename());
+    commands.insert_resource(ChartWatch {
+        modified: modified_time(&path),
+        path,
+        poll: Timer::from_seconds(POLL_INTERVAL_SECS, TimerMode::Repeating),
+    });
+}
+
+fn stop_watch(mut commands: Commands) {
+    commands.remove_resource::<ChartWatch>();
+}
+
+/// Notes still to come after a reload. Anything at or before `current_beat`
+/// has either been judged already or is still live and kept, so the new
+/// chart's copy must not be queued.
+fn requeue_after(notes: Vec<ChartNote>, current_beat: f64) -> Vec<ChartNote> {
+    notes
+        .into_iter()
+        .filter(|n| n.target_beat > current_beat)
+        .collect()
+}
+
+/// A note in play when the chart is reloaded.
+struct LiveNote {
+    entity: Entity,
+    target_beat: f64,
+    kind: NoteKind,
+    /// A hold's head has already been judged.
+    head_judged: bool,
+    ticks_awarded: u32,
+}
+
+/// What a reload does to the notes in play.
+struct NoteReload {
+    despawn: Vec<Entity>,
+    requeue: Vec<ChartNote>,
+    /// Judgments and pool weight still to come: kept live notes plus the requeue.
+    outstanding_judgments: u32,
+    outstanding_weight: f64,
+}
+
+/// Live notes still ahead of `current_beat` are replaced by the new chart's.
+/// Ones already past their beat are inside their hit window (or mid-hold), so
+/// they stay to be judged.
+fn plan_reload(live: &[LiveNote], new_notes: Vec<ChartNote>, current_beat: f64) -> NoteReload {
+    let requeue = requeue_after(new_notes, current_beat);
+    let mut reload = NoteReload {
+        despawn: Vec::new(),
+        outstanding_judgments: total_judgments(&requeue),
+        outstanding_weight: pool_weight(&requeue),
+        requeue,
+    };
+    for note in live {
+        if note.target_beat > current_beat {
+            reload.despawn.push(note.entity);
+            continue;
+        }
+        let (judgments, weight) =
+            outstanding_pool(note.kind, note.target_beat, note.head_judged, note.ticks_awarded);
+        reload.outstanding_judgments += judgments;
+        reload.outstanding_weight += weight;
+    }
+    reload
+}
+
+#[allow(clippy::too_many_arguments)]
+fn poll_chart(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut watch: ResMut<ChartWatch>,
+    mut selected: ResMut<SelectedSong>,
+    conductor: Option<Res<SongConductor>>,
+    queue: Option<ResMut<NoteQueue>>,
+    score: Option<ResMut<ScoreState>>,
+    live_notes: Query<
+        (Entity, &NoteTiming, &NoteType, Option<&HoldState>, Option<&HoldTicks>),
+        With<NoteAlive>,
+    >,
+    path_visuals: Query<Entity, With<PathVisual>>,
+) {
+    if !watch.poll.tick(time.delta()).just_finished() {
+        return;
+    }
+    let modified = modified_time(&watch.path);
+    if modified == watch.modified {
+        return;
+    }
+    watch.modified = modified;
+
+    let Some(conductor) = conductor else { return };
+    let Some(mut queue) = queue else { return };
+
+    let chart = match load_chart(&selected.song_dir, selected.difficulty) {
+        Ok(chart) => chart,
+        Err(e) => {
+            warn!("Chart reload failed: {e}");
+            return;
+        }
+    };
+
+    let points = spline_points_for(&chart.path_segments);
+    if points.len() < 4 {
+        warn!("Chart reload skipped: need at least 4 path points, got {}", points.len());
+        return;
+    }
+
+    // Path and playhead mapping; the path visual is rebuilt from the new spline
+    commands.insert_resource(SplinePath::from_catmull_rom_points(points));
+    let (song_start_beat, song_end_beat) = beat_range_from_segments(&chart.path_segments);
+    commands.insert_resource(Playhead {
+        song_start_beat,
+        song_end_beat,
+    });
+    for entity in &path_visuals {
+        commands.entity(entity).despawn();
+    }
+
+    // Notes: swap in everything still ahead. The conductor is untouched so
+    // audio stays in sync.
+    let live: Vec<LiveNote> = live_notes
+        .iter()
+        .map(|(entity, timing, note_type, hold_state, ticks)| LiveNote {
+            entity,
+            target_beat: timing.target_beat,
+            kind: note_type.0,
+            head_judged: hold_state.is_some_and(|s| *s != HoldState::Pending),
+            ticks_awarded: ticks.map_or(0, |t| t.0),
+        })
+        .collect();
+    let reload = plan_reload(&live, chart_notes(&chart), conductor.current_beat);
+    for entity in reload.despawn {
+        commands.entity(entity).despawn();
+    }
+    // The song ends once every judgment is in, so the totals must follow the new chart
+    if let Some(mut score) = score {
+        score.rebase(reload.outstanding_judgments, reload.outstanding_weight);
+    }
+    queue.notes = reload.requeue;
+    queue.next_index = 0;
+
+    info!(
+        "Reloaded chart at beat {:.2}: {} notes ahead",
+        conductor.current_beat,
+        queue.notes.len()
+    );
+    selected.chart = chart;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reload_keeps_passed_notes_out() {
+        let notes = [1.0, 2.0, 4.0, 4.5, 8.0]
+            .into_iter()
+            .map(|beat| ChartNote {
+                target_beat: beat,
+                kind: NoteKind::Tap,
+            })
+            .collect();
+        let ahead = requeue_after(notes, 4.0);
+        let beats: Vec<f64> = ahead.iter().map(|n| n.target_beat).collect();
+        assert_eq!(beats, vec![4.5, 8.0]);
+    }
+
+    fn live_tap(entity: Entity, target_beat: f64) -> LiveNote {
+        LiveNote {
+            entity,
+            target_beat,
+            kind: NoteKind::Tap,
+            head_judged: false,
+            ticks_awarded: 0,
+        }
+    }
+
+    #[test]
+    fn reload_keeps_notes_inside_their_hit_window() {
+        let mut world = World::new();
+        let [late_tap, early_tap, hold] = [(); 3].map(|_| world.spawn_empty().id());
+        let live = [
+            // Just past its beat but still hittable
+            live_tap(late_tap, 3.95),
+            // Still ahead: the new chart decides what is there
+            live_tap(early_tap, 4.5),
+            // Mid-hold: head judged, one tick in
+            LiveNote {
+                entity: hold,
+                target_beat: 2.0,
+                kind: NoteKind::Hold { end_beat: 6.0 },
+                head_judged: true,
+                ticks_awarded: 1,
+            },
+        ];
+        let new_notes = [1.0, 3.95, 4.5, 8.0]
+            .into_iter()
+            .map(|beat| ChartNote { target_beat: beat, kind: NoteKind::Tap })
+            .collect();
+
+        let reload = plan_reload(&live, new_notes, 4.0);
+        assert_eq!(reload.despawn, vec![early_tap]);
+        let beats: Vec<f64> = reload.requeue.iter().map(|n| n.target_beat).collect();
+        assert_eq!(beats, vec![4.5, 8.0]);
+        // Two requeued taps, the late tap, and the hold's tail
+        assert_eq!(reload.outstanding_judgments, 4);
+        let hold_owes = outstanding_pool(NoteKind::Hold { end_beat: 6.0 }, 2.0, true, 1).1;
+        assert!((reload.outstanding_weight - (3.0 + hold_owes)).abs() < 1e-9);
+    }
+}
@@ -37,6 +37,9 @@ pub struct SongConductor {
     intercept: f64,
     pub timing_points: Vec<TimingPoint>,
     drift_frames: u32,
+    paused: bool,
+    /// The playing song's own offset, on top of the global audio offset.
+    pub song_offset_ms: i32,
 }
 
 impl SongConductor {
@@ -50,8 +53,85 @@ impl SongConductor {
             intercept: 0.0,
             timing_points: Vec::new(),
             drift_frames: 0,
+            paused: false,
+            song_offset_ms: 0,
         }
     }
+
+    /// Build from a chart's timing points, normalized first: sorted by beat,
+    /// duplicates at the same beat collapsed to the last one, and a point added
+    /// at beat 0 if the chart starts later.
+    pub fn from_timing_points(points: Vec<TimingPoint>) -> Self {
+        let mut points = normalize_timing_points(points);
+        if points.is_empty() {
+            warn!("Chart has no timing points, defaulting to {DEFAULT_BPM} BPM");
+            return Self::new(DEFAULT_BPM);
+        }
+        let first = points.remove(0);
+        let mut conductor = Self::new(first.bpm);
+        conductor.timing_points = points;
+        conductor
+    }
+
+    /// Freeze `current_beat` while the song is paused.
+    pub fn pause(&mut self) {
+        self.paused = true;
+        self.playing = false;
+    }
+
+    /// Continue after a pause. Samples from before the pause span the gap and
+    /// would skew the regression, so drop them and re-seed from the audio clock.
+    pub fn resume(&mut self) {
+        self.paused = false;
+        self.time_samples.clear();
+        self.drift_frames = 0;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Convert a millisecond offset to beats at the current BPM.
+    pub fn offset_beats(&self, offset_ms: i32) -> f64 {
+        offset_ms as f64 * self.bpm / 60_000.0
+    }
+
+    /// Audio offset in beats: the global setting plus this song's override.
+    pub fn audio_offset_beats(&self, global_offset_ms: i32) -> f64 {
+        self.offset_beats(global_offset_ms + self.song_offset_ms)
+    }
+
+    /// Beat used for rendering: `current_beat` shifted by the visual offset so the
+    /// playhead and camera compensate for display lag. Judgment keeps `current_beat`.
+    pub fn visual_beat(&self, visual_offset_ms: i32) -> f64 {
+        self.current_beat + self.offset_beats(visual_offset_ms)
+    }
+}
+
+/// BPM used when a chart has no timing points at all.
+const DEFAULT_BPM: f64 = 120.0;
+
+fn normalize_timing_points(mut points: Vec<TimingPoint>) -> Vec<TimingPoint> {
+    // Stable sort keeps chart order among equal beats, so "last" is well defined
+    points.sort_by(|a, b| a.beat.total_cmp(&b.beat));
+    let mut normalized: Vec<TimingPoint> = Vec::with_capacity(points.len());
+    for point in points {
+        match normalized.last_mut() {
+            Some(last) if (last.beat - point.beat).abs() < 1e-9 => {
+                warn!("Duplicate timing point at beat {}, keeping the later one", point.beat);
+                *last = point;
+            }
+            _ => normalized.push(point),
+        }
+    }
+    if let Some(first) = normalized.first() {
+        if first.beat > 0.0 {
+            warn!("First timing point is at beat {}, not 0; extending its BPM back to 0", first.beat);
+            let bpm = first.bpm;
+            normalized.insert(0, TimingPoint { beat: 0.0, bpm });
+        }
+    }
+    normalized
 }
 
 fn clock_time_to_beats(clock: &kira::clock::ClockHandle) -> f64 {
@@ -115,92 +195,99 @@ fn update_conductor(
         return;
     };
 
-    conductor.playing = true;
+    if conductor.paused {
+        return;
+    }
 
     let game_time = time.elapsed_secs_f64();
     // Apply audio offset: positive offset means audio is late, so shift beats forward
-    let offset_beats = if let Some(ref settings) = settings {
-        settings.audio_offset_ms as f64 * conductor.bpm / 60_000.0
-    } else {
-        0.0
-    };
+    let global_offset_ms = settings.map_or(0, |s| s.audio_offset_ms);
+    let offset_beats = conductor.audio_offset_beats(global_offset_ms);
     let audio_beats = clock_time_to_beats(clock) + offset_beats;
+    conductor.observe(game_time, audio_beats);
+}
 
-    // Push sample into rolling window.
-    if conductor.time_samples.len() >= MAX_SAMPLES {
-        conductor.time_samples.pop_front();
-    }
-    conductor.time_samples.push_back((game_time, audio_beats));
-
-    // During warmup, the audio clock updates in discrete buffer chunks (~10ms)
-    // which makes regression unreliable over short time spans. Track audio beats
-    // directly until we have enough temporal spread.
-    let x_span = game_time - conductor.time_samples.front().unwrap().0;
-    if x_span < MIN_REGRESSION_SPAN {
-        conductor.current_beat = audio_beats.max(conductor.current_beat);
-        conductor.slope = conductor.bpm / 60.0;
-        conductor.intercept = audio_beats - conductor.slope * game_time;
-        return;
-    }
-
-    // Compute linear regression.
-    let (slope, intercept) = linear_regression(&conductor.time_samples);
-    conductor.slope = slope;
-    conductor.intercept = intercept;
-
-    let predicted_beat = slope * game_time + intercept;
-
-    // Drift check: compare predicted vs raw audio beats.
-    let drift_beats = (predicted_beat - audio_beats).abs();
-    let drift_secs = drift_beats / (conductor.bpm / 60.0);
+impl SongConductor {
+    /// Fold one (game time, audio beat) reading into the smoothed beat.
+    fn observe(&mut self, game_time: f64, audio_beats: f64) {
+        self.playing = true;
 
-    if drift_secs > DRIFT_THRESHOLD_SECS {
-        conductor.drift_frames += 1;
-        if conductor.drift_frames >= DRIFT_FRAME_LIMIT {
-            warn!(
-                "Audio drift {drift_secs:.3}s exceeded threshold for {} frames, hard resyncing",
-                conductor.drift_frames
-            );
-            conductor.time_samples.clear();
-            conductor.time_samples.push_back((game_time, audio_beats));
-            conductor.slope = conductor.bpm / 60.0;
-            conductor.intercept = audio_beats - conductor.slope * game_time;
-            conductor.drift_frames = 0;
-            conductor.current_beat = audio_beats;
+        // Push sample into rolling window.
+        if self.time_samples.len() >= MAX_SAMPLES {
+            self.time_samples.pop_front();
+        }
+        self.time_samples.push_back((game_time, audio_beats));
+
+        // During warmup, the audio clock updates in discrete buffer chunks (~10ms)
+        // which makes regression unreliable over short time spans. Track audio beats
+        // directly until we have enough temporal spread.
+        let x_span = game_time - self.time_samples.front().unwrap().0;
+        if x_span < MIN_REGRESSION_SPAN {
+            self.current_beat = audio_beats.max(self.current_beat);
+            self.slope = self.bpm / 60.0;
+            self.intercept = audio_beats - self.slope * game_time;
             return;
         }
-    } else {
-        conductor.drift_frames = 0;
-    }
 
-    // Monotonicity guarantee.
-    conductor.current_beat = predicted_beat.max(conductor.current_beat);
-
-    // Sanity check slope against expected bpm/60.
-    let expected_slope = conductor.bpm / 60.0;
-    if conductor.time_samples.len() >= 5 {
-        let deviation = ((slope - expected_slope) / expected_slope).abs();
-        if deviation > 0.10 {
-            warn!(
-                "Conductor slope {slope:.4} deviates {:.1}% from expected {expected_slope:.4}",
-                deviation * 100.0
-            );
+        // Compute linear regression.
+        let (slope, intercept) = linear_regression(&self.time_samples);
+        self.slope = slope;
+        self.intercept = intercept;
+
+        let predicted_beat = slope * game_time + intercept;
+
+        // Drift check: compare predicted vs raw audio beats.
+        let drift_beats = (predicted_beat - audio_beats).abs();
+        let drift_secs = drift_beats / (self.bpm / 60.0);
+
+        if drift_secs > DRIFT_THRESHOLD_SECS {
+            self.drift_frames += 1;
+            if self.drift_frames >= DRIFT_FRAME_LIMIT {
+                warn!(
+                    "Audio drift {drift_secs:.3}s exceeded threshold for {} frames, hard resyncing",
+                    self.drift_frames
+                );
+                self.time_samples.clear();
+                self.time_samples.push_back((game_time, audio_beats));
+                self.slope = self.bpm / 60.0;
+                self.intercept = audio_beats - self.slope * game_time;
+                self.drift_frames = 0;
+                self.current_beat = audio_beats;
+                return;
+            }
+        } else {
+            self.drift_frames = 0;
         }
-    }
 
-    // Advance timing points if we crossed a BPM change boundary.
-    if conductor
-        .timing_points
-        .first()
-        .is_some_and(|tp| conductor.current_beat >= tp.beat)
-    {
-        let tp = conductor.timing_points.remove(0);
-        conductor.bpm = tp.bpm;
-        conductor.time_samples.clear();
-        conductor.time_samples.push_back((game_time, audio_beats));
-        conductor.slope = conductor.bpm / 60.0;
-        conductor.intercept = audio_beats - conductor.slope * game_time;
-        info!("BPM changed to {}", conductor.bpm);
+        // Monotonicity guarantee.
+        self.current_beat = predicted_beat.max(self.current_beat);
+
+        // Sanity check slope against expected bpm/60.
+        let expected_slope = self.bpm / 60.0;
+        if self.time_samples.len() >= 5 {
+            let deviation = ((slope - expected_slope) / expected_slope).abs();
+            if deviation > 0.10 {
+                warn!(
+                    "Conductor slope {slope:.4} deviates {:.1}% from expected {expected_slope:.4}",
+                    deviation * 100.0
+                );
+            }
+        }
+
+        // Advance timing points if we crossed a BPM change boundary.
+        if self
+            .timing_points
+            .first()
+            .is_some_and(|tp| self.current_beat >= tp.beat)
+        {
+            let tp = self.timing_points.remove(0);
+            self.bpm = tp.bpm;
+            self.time_samples.clear();
+            self.time_samples.push_back((game_time, audio_beats));
+            self.slope = self.bpm / 60.0;
+            self.intercept = audio_beats - self.slope * game_time;
+            info!("BPM changed to {}", self.bpm);
+        }
     }
 }
 
@@ -262,4 +349,87 @@ mod tests {
             "predicted beat at x=100.0 should be ~0.0, got {predicted}"
         );
     }
+
+    #[test]
+    fn visual_offset_converts_ms_to_beats() {
+        let mut conductor = SongConductor::new(120.0);
+        conductor.current_beat = 10.0;
+        assert!((conductor.offset_beats(250) - 0.5).abs() < 1e-10);
+        assert!((conductor.visual_beat(250) - 10.5).abs() < 1e-10);
+        assert!((conductor.visual_beat(0) - 10.0).abs() < 1e-10);
+
+        conductor.bpm = 150.0;
+        assert!((conductor.offset_beats(-100) + 0.25).abs() < 1e-10);
+        assert!((conductor.visual_beat(-100) - 9.75).abs() < 1e-10);
+    }
+
+    #[test]
+    fn song_offset_adds_to_global_offset() {
+        let mut conductor = SongConductor::new(120.0);
+        assert!((conductor.audio_offset_beats(100) - 0.2).abs() < 1e-10);
+
+        // 100 ms global + 25 ms for this song = 125 ms = 0.25 beats at 120 BPM
+        conductor.song_offset_ms = 25;
+        assert!((conductor.audio_offset_beats(100) - 0.25).abs() < 1e-10);
+
+        // A negative override can cancel the global offset entirely
+        conductor.song_offset_ms = -100;
+        assert!(conductor.audio_offset_beats(100).abs() < 1e-10);
+
+        conductor.bpm = 150.0;
+        conductor.song_offset_ms = -40;
+        assert!((conductor.audio_offset_beats(0) + 0.1).abs() < 1e-10);
+    }
+
+    #[test]
+    fn resume_after_pause_has_no_beat_jump() {
+        // 120 BPM: two beats per second, sampled at 60 fps
+        let mut conductor = SongConductor::new(120.0);
+        let frame = 1.0 / 60.0;
+        for i in 0..60 {
+            let t = i as f64 * frame;
+            conductor.observe(t, 2.0 * t);
+        }
+        let beat_at_pause = conductor.current_beat;
+
+        // Ten seconds in the pause menu: the clock stops, game time does not
+        conductor.pause();
+        assert!(conductor.is_paused());
+        conductor.resume();
+
+        let resume_time = 60.0 * frame + 10.0;
+        let mut last = beat_at_pause;
+        for i in 0..60 {
+            let t = resume_time + i as f64 * frame;
+            let audio = beat_at_pause + 2.0 * (t - resume_time);
+            conductor.observe(t, audio);
+            assert!(
+                (conductor.current_beat - audio).abs() < 0.05,
+                "frame {i}: beat {} vs audio {audio}",
+                conductor.current_beat
+            );
+            assert!(conductor.current_beat - last < 0.1, "frame {i} jumped");
+            last = conductor.current_beat;
+        }
+    }
+
+    #[test]
+    fn timing_points_are_sorted_deduped_and_anchored() {
+        let tp = |beat, bpm| TimingPoint { beat, bpm };
+        let conductor = SongConductor::from_timing_points(vec![
+            tp(16.0, 150.0),
+            tp(4.0, 128.0),
+            tp(8.0, 140.0),
+            tp(4.0, 130.0),
+        ]);
+
+        // Beat 4 duplicate keeps the later 130; its BPM is extended back to beat 0
+        assert_eq!(conductor.bpm, 130.0);
+        let rest: Vec<(f64, f64)> = conductor.timing_points.iter().map(|t| (t.beat, t.bpm)).collect();
+        assert_eq!(rest, vec![(4.0, 130.0), (8.0, 140.0), (16.0, 150.0)]);
+
+        let empty = SongConductor::from_timing_points(Vec::new());
+        assert_eq!(empty.bpm, DEFAULT_BPM);
+        assert!(empty.timing_points.is_empty());
+    }
 }
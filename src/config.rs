@@ -1,18 +1,34 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use bevy::prelude::*;
+use bevy::window::{PresentMode, VideoModeSelection, WindowMode};
 use serde::{Deserialize, Serialize};
 
+use crate::beatmap::Difficulty;
+use crate::health::GameplayMods;
+use crate::scoring::ClearBadge;
+
 pub struct ConfigPlugin;
 
 impl Plugin for ConfigPlugin {
     fn build(&self, app: &mut App) {
         let settings = GameSettings::load();
-        app.insert_resource(settings);
+        app.insert_resource(settings)
+            .insert_resource(HighScores::load())
+            .insert_resource(GameplayTuning::load(Path::new(TUNING_PATH)));
     }
 }
 
+/// Path of `name` inside the game's config directory.
+pub(crate) fn config_file(name: &str) -> Option<PathBuf> {
+    let dirs = directories::ProjectDirs::from("", "", "FunkTrack")?;
+    Some(dirs.config_dir().join(name))
+}
+
 #[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GameSettings {
     pub master_volume: f32,
     pub sfx_volume: f32,
@@ -21,7 +37,18 @@ pub struct GameSettings {
     pub visual_offset_ms: i32,
     pub note_speed: f32,
     pub background_dim: f32,
+    pub approach_ring: bool,
+    /// Flash the background faintly on every beat. Off by default for
+    /// photosensitive players.
+    pub beat_pulse: bool,
     pub fullscreen: bool,
+    /// How fullscreen takes the display when `fullscreen` is on.
+    pub fullscreen_mode: FullscreenMode,
+    /// Window size in logical pixels while windowed.
+    pub resolution: (u32, u32),
+    pub vsync: bool,
+    /// Frame-rate cap in frames per second; 0 leaves it uncapped.
+    pub fps_cap: u32,
 }
 
 impl Default for GameSettings {
@@ -34,15 +61,49 @@ impl Default for GameSettings {
             visual_offset_ms: 0,
             note_speed: 1.0,
             background_dim: 0.0,
+            approach_ring: false,
+            beat_pulse: false,
             fullscreen: false,
+            fullscreen_mode: FullscreenMode::default(),
+            resolution: RESOLUTIONS[0],
+            vsync: true,
+            fps_cap: 0,
+        }
+    }
+}
+
+/// Window sizes offered on the Display tab, smallest first.
+pub const RESOLUTIONS: &[(u32, u32)] = &[
+    (1280, 720),
+    (1600, 900),
+    (1920, 1080),
+    (2560, 1440),
+    (3840, 2160),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FullscreenMode {
+    /// A borderless window covering the monitor; quick to alt-tab.
+    #[default]
+    Borderless,
+    /// Exclusive fullscreen at the monitor's current video mode.
+    Exclusive,
+}
+
+impl FullscreenMode {
+    pub const ALL: [FullscreenMode; 2] = [FullscreenMode::Borderless, FullscreenMode::Exclusive];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FullscreenMode::Borderless => "BORDERLESS",
+            FullscreenMode::Exclusive => "EXCLUSIVE",
         }
     }
 }
 
 impl GameSettings {
     fn config_path() -> Option<PathBuf> {
-        let dirs = directories::ProjectDirs::from("", "", "FunkTrack")?;
-        Some(dirs.config_dir().join("settings.json"))
+        config_file("settings.json")
     }
 
     pub fn load() -> Self {
@@ -101,9 +162,265 @@ impl GameSettings {
         (self.master_volume as f64 / 100.0).clamp(0.0, 1.0)
     }
 
+    /// Window present mode for the VSync setting.
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+
+    /// Window mode for the fullscreen settings.
+    pub fn window_mode(&self) -> WindowMode {
+        if !self.fullscreen {
+            return WindowMode::Windowed;
+        }
+        match self.fullscreen_mode {
+            FullscreenMode::Borderless => WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+            FullscreenMode::Exclusive => {
+                WindowMode::Fullscreen(MonitorSelection::Current, VideoModeSelection::Current)
+            }
+        }
+    }
+
+    /// Minimum time per frame under the FPS cap, if there is one.
+    pub fn frame_budget(&self) -> Option<Duration> {
+        (self.fps_cap > 0).then(|| Duration::from_secs_f64(1.0 / self.fps_cap as f64))
+    }
+
     /// Preview volume as a 0.0–1.0 amplitude (scaled by master).
     pub fn preview_amplitude(&self) -> f64 {
         let preview = (self.preview_volume as f64 / 100.0).clamp(0.0, 1.0);
         preview * self.master_amplitude()
     }
 }
+
+/// Balance file designers can edit without rebuilding.
+const TUNING_PATH: &str = "assets/tuning.ron";
+
+/// Timing windows and score constants read by judgment and scoring.
+/// Any field missing from `tuning.ron` keeps its default.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameplayTuning {
+    pub great_window_ms: f64,
+    pub cool_window_ms: f64,
+    pub good_window_ms: f64,
+    /// How long past its beat an unhit note lingers before it's a miss.
+    pub miss_window_ms: f64,
+    /// Points a perfect run earns from judgments and hold ticks.
+    pub play_score_pool: f64,
+    pub max_chain_bonus: u64,
+    pub clear_bonus: u64,
+    /// Chain length where the Fever tier starts.
+    pub fever_threshold: u32,
+    /// Chain length where the Trance tier starts.
+    pub trance_threshold: u32,
+}
+
+impl Default for GameplayTuning {
+    fn default() -> Self {
+        Self {
+            great_window_ms: 20.0,
+            cool_window_ms: 50.0,
+            good_window_ms: 100.0,
+            miss_window_ms: 100.0,
+            play_score_pool: 850_000.0,
+            max_chain_bonus: 100_000,
+            clear_bonus: 50_000,
+            fever_threshold: 10,
+            trance_threshold: 100,
+        }
+    }
+}
+
+impl GameplayTuning {
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => match ron::from_str(&contents) {
+                Ok(tuning) => {
+                    info!("Loaded gameplay tuning from {:?}", path);
+                    tuning
+                }
+                Err(e) => {
+                    warn!("Failed to parse tuning {:?}: {}, using defaults", path, e);
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        }
+    }
+}
+
+/// Best total score per song and difficulty, kept next to the settings file.
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HighScores {
+    /// Keyed by `"<song folder>/<difficulty>"`, plus `"+<mod tag>"` for each
+    /// active mod so modded runs keep their own bests.
+    pub best: HashMap<String, u64>,
+    /// Best clean-run badge ever earned, same keys as `best`.
+    pub badges: HashMap<String, ClearBadge>,
+}
+
+impl HighScores {
+    fn scores_path() -> Option<PathBuf> {
+        config_file("scores.json")
+    }
+
+    fn key(song_dir: &Path, difficulty: Difficulty, mods: &GameplayMods) -> String {
+        let song = song_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut key = format!("{song}/{}", difficulty.label());
+        for tag in mods.tags() {
+            key.push('+');
+            key.push_str(&tag);
+        }
+        key
+    }
+
+    pub fn load() -> Self {
+        let Some(path) = Self::scores_path() else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Failed to parse high scores {:?}: {}", path, e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(path) = Self::scores_path() else {
+            warn!("No config directory available, cannot save high scores");
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                warn!("Failed to create config directory {:?}: {}", parent, e);
+                return;
+            }
+        }
+        match serde_json::to_string_pretty(self) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(&path, json) {
+                    warn!("Failed to write high scores to {:?}: {}", path, e);
+                }
+            }
+            Err(e) => warn!("Failed to serialize high scores: {}", e),
+        }
+    }
+
+    pub fn get(&self, song_dir: &Path, difficulty: Difficulty, mods: &GameplayMods) -> Option<u64> {
+        self.best.get(&Self::key(song_dir, difficulty, mods)).copied()
+    }
+
+    pub fn badge(
+        &self,
+        song_dir: &Path,
+        difficulty: Difficulty,
+        mods: &GameplayMods,
+    ) -> Option<ClearBadge> {
+        self.badges.get(&Self::key(song_dir, difficulty, mods)).copied()
+    }
+
+    /// Keep `badge` if it beats the stored one. Returns whether it did.
+    pub fn record_badge(
+        &mut self,
+        song_dir: &Path,
+        difficulty: Difficulty,
+        mods: &GameplayMods,
+        badge: ClearBadge,
+    ) -> bool {
+        let key = Self::key(song_dir, difficulty, mods);
+        if self.badges.get(&key).is_some_and(|best| *best >= badge) {
+            return false;
+        }
+        self.badges.insert(key, badge);
+        true
+    }
+
+    /// Record a finished run and return the best it is compared against.
+    pub fn record(
+        &mut self,
+        song_dir: &Path,
+        difficulty: Difficulty,
+        mods: &GameplayMods,
+        score: u64,
+    ) -> Option<u64> {
+        let key = Self::key(song_dir, difficulty, mods);
+        let previous = self.best.get(&key).copied();
+        if previous.is_none_or(|best| score > best) {
+            self.best.insert(key, score);
+        }
+        previous
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_settings_round_trip() {
+        let settings = GameSettings {
+            vsync: false,
+            fps_cap: 144,
+            ..default()
+        };
+        let json = serde_json::to_string(&settings).unwrap();
+        let loaded: GameSettings = serde_json::from_str(&json).unwrap();
+        assert!(!loaded.vsync);
+        assert_eq!(loaded.fps_cap, 144);
+        assert_eq!(loaded.present_mode(), PresentMode::AutoNoVsync);
+        assert_eq!(loaded.frame_budget(), Some(Duration::from_secs_f64(1.0 / 144.0)));
+
+        // Settings saved before these existed fall back to VSync on, uncapped
+        let old: GameSettings = serde_json::from_str(r#"{ "master_volume": 50.0 }"#).unwrap();
+        assert!(old.vsync);
+        assert_eq!(old.frame_budget(), None);
+    }
+
+    #[test]
+    fn missing_tuning_file_keeps_current_constants() {
+        let tuning = GameplayTuning::load(Path::new("does/not/exist/tuning.ron"));
+        assert_eq!(tuning, GameplayTuning::default());
+        assert_eq!(tuning.great_window_ms, 20.0);
+        assert_eq!(tuning.cool_window_ms, 50.0);
+        assert_eq!(tuning.good_window_ms, 100.0);
+        assert_eq!(tuning.miss_window_ms, 100.0);
+        assert_eq!(tuning.play_score_pool, 850_000.0);
+        assert_eq!(tuning.max_chain_bonus, 100_000);
+        assert_eq!(tuning.clear_bonus, 50_000);
+        assert_eq!(tuning.fever_threshold, 10);
+        assert_eq!(tuning.trance_threshold, 100);
+
+        // A partial file only overrides what it names
+        let partial: GameplayTuning = ron::from_str("(great_window_ms: 25.0)").unwrap();
+        assert_eq!(partial.great_window_ms, 25.0);
+        assert_eq!(partial.good_window_ms, 100.0);
+    }
+
+    #[test]
+    fn modded_bests_are_kept_apart_from_vanilla() {
+        use crate::health::FailMod;
+
+        let song = Path::new("assets/songs/neon_drive");
+        let vanilla = GameplayMods::default();
+        let sudden_death = GameplayMods {
+            fail: Some(FailMod::SuddenDeath),
+        };
+        let mut scores = HighScores::default();
+        scores.record(song, Difficulty::Hard, &vanilla, 900_000);
+        scores.record(song, Difficulty::Hard, &sudden_death, 950_000);
+
+        assert_eq!(scores.get(song, Difficulty::Hard, &vanilla), Some(900_000));
+        assert_eq!(scores.get(song, Difficulty::Hard, &sudden_death), Some(950_000));
+        assert!(scores.best.contains_key("neon_drive/HARD+SD"));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::beatmap::{ChartEvent, ChartFile, ChartNoteEntry, ChartTimingPoint};
+use crate::beatmap::{ChartEvent, ChartFile, ChartNoteEntry, ChartNoteType, ChartTimingPoint, PathSegment};
 
 /// A reversible editor action for undo/redo.
 #[derive(Debug, Clone)]
@@ -15,6 +15,20 @@ pub enum EditorAction {
         old: ChartNoteEntry,
         new: ChartNoteEntry,
     },
+    /// Batch edit that rewrites the whole note list (e.g. mirroring a selection).
+    ReplaceNotes {
+        old: Vec<ChartNoteEntry>,
+        new: Vec<ChartNoteEntry>,
+    },
+    ChangeNoteType {
+        index: usize,
+        from: ChartNoteType,
+        to: ChartNoteType,
+    },
+    /// Retime notes in place as `(index, old_beat, new_beat)`; indices are unchanged.
+    MoveNotes {
+        moves: Vec<(usize, f64, f64)>,
+    },
     AddPathPoint {
         segment: usize,
         point: (f32, f32),
@@ -24,12 +38,22 @@ pub enum EditorAction {
         index: usize,
         point: (f32, f32),
     },
+    InsertPathPoint {
+        segment: usize,
+        index: usize,
+        point: (f32, f32),
+    },
     MovePathPoint {
         segment: usize,
         index: usize,
         old_pos: (f32, f32),
         new_pos: (f32, f32),
     },
+    ReplaceSegment {
+        index: usize,
+        old: PathSegment,
+        new: PathSegment,
+    },
     AddEvent {
         event: ChartEvent,
     },
@@ -45,6 +69,20 @@ pub enum EditorAction {
 }
 
 impl EditorAction {
+    /// Whether this action can change the chart's difficulty rating.
+    pub fn affects_rating(&self) -> bool {
+        matches!(
+            self,
+            EditorAction::AddNote { .. }
+                | EditorAction::RemoveNote { .. }
+                | EditorAction::ModifyNote { .. }
+                | EditorAction::ReplaceNotes { .. }
+                | EditorAction::ChangeNoteType { .. }
+                | EditorAction::MoveNotes { .. }
+                | EditorAction::ModifyTimingPoint { .. }
+        )
+    }
+
     pub fn apply(&self, chart: &mut ChartFile) {
         match self {
             EditorAction::AddNote { note } => {
@@ -63,6 +101,21 @@ impl EditorAction {
                     chart.notes[*index] = new.clone();
                 }
             }
+            EditorAction::ReplaceNotes { new, .. } => {
+                chart.notes = new.clone();
+            }
+            EditorAction::ChangeNoteType { index, to, .. } => {
+                if let Some(note) = chart.notes.get_mut(*index) {
+                    note.note_type = to.clone();
+                }
+            }
+            EditorAction::MoveNotes { moves } => {
+                for &(index, _, new_beat) in moves {
+                    if let Some(note) = chart.notes.get_mut(index) {
+                        note.beat = new_beat;
+                    }
+                }
+            }
             EditorAction::AddPathPoint { segment, point } => {
                 if let Some(seg) = chart.path_segments.get_mut(*segment) {
                     if let crate::beatmap::PathSegment::CatmullRom { points, .. } = seg {
@@ -81,6 +134,19 @@ impl EditorAction {
                     }
                 }
             }
+            EditorAction::InsertPathPoint {
+                segment,
+                index,
+                point,
+            } => {
+                if let Some(seg) = chart.path_segments.get_mut(*segment) {
+                    if let crate::beatmap::PathSegment::CatmullRom { points, .. } = seg {
+                        if *index <= points.len() {
+                            points.insert(*index, *point);
+                        }
+                    }
+                }
+            }
             EditorAction::MovePathPoint {
                 segment,
                 index,
@@ -95,6 +161,11 @@ impl EditorAction {
                     }
                 }
             }
+            EditorAction::ReplaceSegment { index, new, .. } => {
+                if *index < chart.path_segments.len() {
+                    chart.path_segments[*index] = new.clone();
+                }
+            }
             EditorAction::AddEvent { event } => {
                 let pos = chart
                     .events
@@ -132,6 +203,21 @@ impl EditorAction {
                     chart.notes[*index] = old.clone();
                 }
             }
+            EditorAction::ReplaceNotes { old, .. } => {
+                chart.notes = old.clone();
+            }
+            EditorAction::ChangeNoteType { index, from, .. } => {
+                if let Some(note) = chart.notes.get_mut(*index) {
+                    note.note_type = from.clone();
+                }
+            }
+            EditorAction::MoveNotes { moves } => {
+                for &(index, old_beat, _) in moves {
+                    if let Some(note) = chart.notes.get_mut(index) {
+                        note.beat = old_beat;
+                    }
+                }
+            }
             EditorAction::AddPathPoint { segment, .. } => {
                 if let Some(seg) = chart.path_segments.get_mut(*segment) {
                     if let crate::beatmap::PathSegment::CatmullRom { points, .. } = seg {
@@ -150,6 +236,15 @@ impl EditorAction {
                     }
                 }
             }
+            EditorAction::InsertPathPoint { segment, index, .. } => {
+                if let Some(seg) = chart.path_segments.get_mut(*segment) {
+                    if let crate::beatmap::PathSegment::CatmullRom { points, .. } = seg {
+                        if *index < points.len() {
+                            points.remove(*index);
+                        }
+                    }
+                }
+            }
             EditorAction::MovePathPoint {
                 segment,
                 index,
@@ -164,6 +259,11 @@ impl EditorAction {
                     }
                 }
             }
+            EditorAction::ReplaceSegment { index, old, .. } => {
+                if *index < chart.path_segments.len() {
+                    chart.path_segments[*index] = old.clone();
+                }
+            }
             EditorAction::AddEvent { event } => {
                 if let Some(pos) = chart.events.iter().position(|e| {
                     (e.beat - event.beat).abs() < 1e-6
@@ -0,0 +1,205 @@
+use std::f32::consts::TAU;
+
+use bevy::math::Vec2;
+
+use crate::beatmap::PathSegment;
+
+/// CatmullRom control points generated when converting from another shape.
+const CATMULL_ROM_POINTS: usize = 6;
+
+/// The four path segment shapes, for the Path-mode type selector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentKind {
+    CatmullRom,
+    Bezier,
+    Linear,
+    Arc,
+}
+
+impl SegmentKind {
+    pub const ALL: [SegmentKind; 4] = [
+        SegmentKind::CatmullRom,
+        SegmentKind::Bezier,
+        SegmentKind::Linear,
+        SegmentKind::Arc,
+    ];
+
+    pub fn of(segment: &PathSegment) -> Self {
+        match segment {
+            PathSegment::CatmullRom { .. } => SegmentKind::CatmullRom,
+            PathSegment::Bezier { .. } => SegmentKind::Bezier,
+            PathSegment::Linear { .. } => SegmentKind::Linear,
+            PathSegment::Arc { .. } => SegmentKind::Arc,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SegmentKind::CatmullRom => "SPLINE",
+            SegmentKind::Bezier => "BEZIER",
+            SegmentKind::Linear => "LINE",
+            SegmentKind::Arc => "ARC",
+        }
+    }
+}
+
+fn beat_span(segment: &PathSegment) -> (f64, f64) {
+    match segment {
+        PathSegment::CatmullRom { start_beat, end_beat, .. }
+        | PathSegment::Bezier { start_beat, end_beat, .. }
+        | PathSegment::Arc { start_beat, end_beat, .. }
+        | PathSegment::Linear { start_beat, end_beat, .. } => (*start_beat, *end_beat),
+    }
+}
+
+/// Point at `fraction` (0–1) of the polyline's total length.
+fn point_at_fraction(points: &[Vec2], fraction: f32) -> Vec2 {
+    let total: f32 = points.windows(2).map(|w| w[0].distance(w[1])).sum();
+    if total <= 0.0 {
+        return points[0];
+    }
+    let mut remaining = total * fraction.clamp(0.0, 1.0);
+    for w in points.windows(2) {
+        let len = w[0].distance(w[1]);
+        if remaining <= len && len > 0.0 {
+            return w[0].lerp(w[1], remaining / len);
+        }
+        remaining -= len;
+    }
+    points[points.len() - 1]
+}
+
+/// Center of the circle through three points, or None if they're collinear.
+fn circumcenter(a: Vec2, b: Vec2, c: Vec2) -> Option<Vec2> {
+    let d = 2.0 * (a.x * (b.y - c.y) + b.x * (c.y - a.y) + c.x * (a.y - b.y));
+    if d.abs() < 1e-4 {
+        return None;
+    }
+    let (a2, b2, c2) = (a.length_squared(), b.length_squared(), c.length_squared());
+    Some(Vec2::new(
+        (a2 * (b.y - c.y) + b2 * (c.y - a.y) + c2 * (a.y - b.y)) / d,
+        (a2 * (c.x - b.x) + b2 * (a.x - c.x) + c2 * (b.x - a.x)) / d,
+    ))
+}
+
+/// Convert a segment to another shape, keeping its beat span and endpoints and
+/// approximating the route in between.
+pub fn convert_segment(segment: &PathSegment, kind: SegmentKind) -> Result<PathSegment, String> {
+    if SegmentKind::of(segment) == kind {
+        return Ok(segment.clone());
+    }
+    let (start_beat, end_beat) = beat_span(segment);
+    let points: Vec<Vec2> = segment
+        .spline_points()
+        .into_iter()
+        .map(Vec2::from)
+        .collect();
+    if points.len() < 2 {
+        return Err("Segment needs at least 2 points to convert".to_string());
+    }
+    let first = points[0];
+    let last = points[points.len() - 1];
+
+    match kind {
+        SegmentKind::Linear => Ok(PathSegment::Linear {
+            start: first.into(),
+            end: last.into(),
+            start_beat,
+            end_beat,
+        }),
+        SegmentKind::CatmullRom => {
+            let n = CATMULL_ROM_POINTS;
+            let points = (0..n)
+                .map(|i| point_at_fraction(&points, i as f32 / (n - 1) as f32).into())
+                .collect();
+            Ok(PathSegment::CatmullRom { points, start_beat, end_beat })
+        }
+        SegmentKind::Bezier => {
+            // Cubic through the endpoints that also hits the 1/3 and 2/3 marks
+            let q1 = point_at_fraction(&points, 1.0 / 3.0);
+            let q2 = point_at_fraction(&points, 2.0 / 3.0);
+            let c1 = (-5.0 * first + 18.0 * q1 - 9.0 * q2 + 2.0 * last) / 6.0;
+            let c2 = (2.0 * first - 9.0 * q1 + 18.0 * q2 - 5.0 * last) / 6.0;
+            Ok(PathSegment::Bezier {
+                control_points: vec![first.into(), c1.into(), c2.into(), last.into()],
+                start_beat,
+                end_beat,
+            })
+        }
+        SegmentKind::Arc => {
+            let mid = point_at_fraction(&points, 0.5);
+            let center = circumcenter(first, mid, last)
+                .ok_or_else(|| "Points are collinear, can't fit an arc".to_string())?;
+            let angle = |p: Vec2| (p - center).to_angle();
+            let (a0, am, a1) = (angle(first), angle(mid), angle(last));
+            // Sweep whichever way round passes through the midpoint
+            let ccw = (a1 - a0).rem_euclid(TAU);
+            let end_angle = if (am - a0).rem_euclid(TAU) <= ccw {
+                a0 + ccw
+            } else {
+                a0 - (TAU - ccw)
+            };
+            Ok(PathSegment::Arc {
+                center: center.into(),
+                radius: first.distance(center),
+                start_angle: a0,
+                end_angle,
+                start_beat,
+                end_beat,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spline() -> PathSegment {
+        PathSegment::CatmullRom {
+            points: vec![(0.0, 0.0), (100.0, 80.0), (200.0, 80.0), (300.0, 0.0)],
+            start_beat: 0.0,
+            end_beat: 16.0,
+        }
+    }
+
+    fn endpoints(segment: &PathSegment) -> (Vec2, Vec2) {
+        let pts = segment.spline_points();
+        (Vec2::from(pts[0]), Vec2::from(pts[pts.len() - 1]))
+    }
+
+    #[test]
+    fn catmull_rom_to_linear_keeps_endpoints() {
+        let PathSegment::Linear { start, end, start_beat, end_beat } =
+            convert_segment(&spline(), SegmentKind::Linear).unwrap()
+        else {
+            panic!("expected Linear");
+        };
+        assert_eq!(start, (0.0, 0.0));
+        assert_eq!(end, (300.0, 0.0));
+        assert_eq!((start_beat, end_beat), (0.0, 16.0));
+    }
+
+    #[test]
+    fn bezier_and_arc_keep_endpoints() {
+        for kind in [SegmentKind::Bezier, SegmentKind::Arc, SegmentKind::CatmullRom] {
+            let line = convert_segment(&spline(), SegmentKind::Linear).unwrap();
+            let bent = convert_segment(&spline(), kind).unwrap();
+            let (a, b) = endpoints(&bent);
+            let (la, lb) = endpoints(&line);
+            assert!(a.distance(la) < 1e-2, "{kind:?} start {a}");
+            assert!(b.distance(lb) < 1e-2, "{kind:?} end {b}");
+        }
+    }
+
+    #[test]
+    fn straight_line_cannot_become_arc() {
+        let line = PathSegment::Linear {
+            start: (0.0, 0.0),
+            end: (100.0, 0.0),
+            start_beat: 0.0,
+            end_beat: 4.0,
+        };
+        assert!(convert_segment(&line, SegmentKind::Arc).is_err());
+    }
+}
@@ -1,6 +1,26 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::beatmap::{ChartFile, SongMetadata};
+use serde::Deserialize;
+
+use crate::beatmap::{
+    ChartFile, ChartTimingPoint, Difficulty, PathSegment, SongMetadata, format_chart_errors,
+    validate_chart,
+};
+
+/// Onset dump written by `chart_gen --dump-onsets`, next to the charts.
+pub const ONSETS_FILE: &str = "onsets.ron";
+
+#[derive(Debug, Clone, Deserialize)]
+struct OnsetDump {
+    onsets: Vec<DetectedOnset>,
+}
+
+/// A transient chart_gen found, drawn as a ghost tick on the timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct DetectedOnset {
+    pub beat: f64,
+    pub strength: f32,
+}
 
 /// Save a chart file in RON format.
 pub fn save_chart_ron(chart: &ChartFile, path: &Path) -> Result<(), String> {
@@ -22,6 +42,109 @@ pub fn save_metadata_ron(metadata: &SongMetadata, path: &Path) -> Result<(), Str
     std::fs::write(path, data).map_err(|e| format!("Write error: {e}"))
 }
 
+/// Tempo a new chart starts at until the charter sets the real one.
+const NEW_CHART_BPM: f64 = 120.0;
+/// Length of a new chart's starter path, in beats.
+const NEW_CHART_BEATS: f64 = 64.0;
+
+/// Turn a folder holding only an audio file into a song: write `metadata.ron`
+/// and a Normal chart with a straight starter path, ready for the editor.
+/// Either file already in the folder (say one that failed to load) is left alone.
+pub fn create_song_from_audio(
+    song_dir: &Path,
+    audio_file: &str,
+) -> Result<(SongMetadata, ChartFile), String> {
+    let title = song_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Untitled".to_string());
+    let metadata = SongMetadata {
+        title,
+        artist: "Unknown".to_string(),
+        charter: String::new(),
+        audio_file: audio_file.to_string(),
+        preview_start_ms: 0,
+        preview_duration_ms: 15000,
+        source: String::new(),
+        difficulties: vec![Difficulty::Normal],
+        offset_ms: 0,
+    };
+    let chart = ChartFile {
+        difficulty: Difficulty::Normal,
+        difficulty_rating: 1,
+        timing_points: vec![ChartTimingPoint {
+            beat: 0.0,
+            bpm: NEW_CHART_BPM,
+            time_signature: (4, 4),
+        }],
+        path_segments: vec![PathSegment::CatmullRom {
+            points: vec![(-300.0, 0.0), (-100.0, 0.0), (100.0, 0.0), (300.0, 0.0)],
+            start_beat: 0.0,
+            end_beat: NEW_CHART_BEATS,
+        }],
+        notes: Vec::new(),
+        events: Vec::new(),
+        travel_beats: 3.0,
+        look_ahead_beats: 3.0,
+    };
+    let chart_path = song_dir.join(Difficulty::Normal.filename());
+    if !chart_path.exists() {
+        save_chart_ron(&chart, &chart_path)?;
+    }
+    let metadata_path = song_dir.join("metadata.ron");
+    if !metadata_path.exists() {
+        save_metadata_ron(&metadata, &metadata_path)?;
+    }
+    Ok((metadata, chart))
+}
+
+/// Crash-recovery copy written next to the chart, e.g. `normal.ron.autosave`.
+pub fn autosave_path(song_dir: &Path, difficulty: Difficulty) -> PathBuf {
+    song_dir.join(format!("{}.autosave", difficulty.filename()))
+}
+
+/// Whether `autosave` holds work newer than `chart` (or `chart` was never saved).
+pub fn autosave_is_newer(chart: &Path, autosave: &Path) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(chart), modified(autosave)) {
+        (_, None) => false,
+        (None, Some(_)) => true,
+        (Some(saved), Some(auto)) => auto > saved,
+    }
+}
+
+/// Load an autosave if it is newer than the real chart.
+pub fn load_newer_autosave(song_dir: &Path, difficulty: Difficulty) -> Option<ChartFile> {
+    let autosave = autosave_path(song_dir, difficulty);
+    if !autosave_is_newer(&song_dir.join(difficulty.filename()), &autosave) {
+        return None;
+    }
+    let contents = std::fs::read_to_string(&autosave).ok()?;
+    ron::from_str(&contents).ok()
+}
+
+/// Drop the autosave once the real chart has been saved.
+pub fn clear_autosave(song_dir: &Path, difficulty: Difficulty) {
+    let path = autosave_path(song_dir, difficulty);
+    if path.exists() {
+        std::fs::remove_file(path).ok();
+    }
+}
+
+/// `path` if nothing is there yet, otherwise the first free `name (n).ext`
+/// beside it, so exports never clobber an existing file.
+pub fn unique_export_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    let stem = path.file_stem().map(|s| s.to_string_lossy()).unwrap_or_default();
+    let ext = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (1..)
+        .map(|n| path.with_file_name(format!("{stem} ({n}){ext}")))
+        .find(|candidate| !candidate.exists())
+        .expect("some suffix is free")
+}
+
 /// Export a chart file as JSON.
 pub fn export_chart_json(chart: &ChartFile, path: &Path) -> Result<(), String> {
     let data = serde_json::to_string_pretty(chart)
@@ -33,5 +156,174 @@ pub fn export_chart_json(chart: &ChartFile, path: &Path) -> Result<(), String> {
 pub fn import_chart_json(path: &Path) -> Result<ChartFile, String> {
     let contents =
         std::fs::read_to_string(path).map_err(|e| format!("Read error: {e}"))?;
-    serde_json::from_str(&contents).map_err(|e| format!("JSON parse error: {e}"))
+    let chart: ChartFile =
+        serde_json::from_str(&contents).map_err(|e| format!("JSON parse error: {e}"))?;
+    validate_chart(&chart).map_err(|errors| format_chart_errors(&errors))?;
+    Ok(chart)
+}
+
+/// Load `onsets.ron` from a song folder. `Ok(None)` when there isn't one.
+pub fn load_onsets(song_dir: &Path) -> Result<Option<Vec<DetectedOnset>>, String> {
+    let path = song_dir.join(ONSETS_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = std::fs::read_to_string(&path).map_err(|e| format!("Read error: {e}"))?;
+    parse_onsets(&contents).map(Some)
+}
+
+/// Parse an onset dump, sorted by beat.
+pub fn parse_onsets(contents: &str) -> Result<Vec<DetectedOnset>, String> {
+    let dump: OnsetDump =
+        ron::from_str(contents).map_err(|e| format!("Onset parse error: {e}"))?;
+    let mut onsets = dump.onsets;
+    onsets.sort_by(|a, b| a.beat.total_cmp(&b.beat));
+    Ok(onsets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHART_JSON: &str = r#"{
+        "difficulty": "Normal",
+        "difficulty_rating": 5,
+        "timing_points": [{ "beat": 0.0, "bpm": 128.0, "time_signature": [4, 4] }],
+        "path_segments": [
+            { "CatmullRom": {
+                "points": [[0.0, 0.0], [100.0, 50.0], [200.0, 0.0], [300.0, 50.0]],
+                "start_beat": 0.0,
+                "end_beat": 32.0
+            } }
+        ],
+        "notes": [
+            { "beat": 4.0, "note_type": "Tap" },
+            { "beat": 6.0, "note_type": { "Hold": { "duration_beats": 2.0 } } }
+        ]
+    }"#;
+
+    fn write_temp(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("funktrack-{}-{name}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn import_json_round_trips_chart() {
+        let path = write_temp("import.json", CHART_JSON);
+        let chart = import_chart_json(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(chart.difficulty_rating, 5);
+        assert_eq!(chart.timing_points[0].bpm, 128.0);
+        assert_eq!(chart.notes.len(), 2);
+        assert_eq!(chart.notes[1].note_type, crate::beatmap::ChartNoteType::Hold { duration_beats: 2.0 });
+        // Serde defaults fill in what the JSON left out
+        assert!(chart.events.is_empty());
+        assert_eq!(chart.travel_beats, 3.0);
+
+        // Exporting and re-importing gives the same chart back
+        let out = write_temp("export.json", "");
+        export_chart_json(&chart, &out).unwrap();
+        let again = import_chart_json(&out).unwrap();
+        std::fs::remove_file(&out).ok();
+        assert_eq!(
+            serde_json::to_string(&chart).unwrap(),
+            serde_json::to_string(&again).unwrap()
+        );
+    }
+
+    #[test]
+    fn import_rejects_unplayable_chart() {
+        let short_path = CHART_JSON.replace(
+            "[[0.0, 0.0], [100.0, 50.0], [200.0, 0.0], [300.0, 50.0]]",
+            "[[0.0, 0.0], [100.0, 50.0]]",
+        );
+        let path = write_temp("short.json", &short_path);
+        let err = import_chart_json(&path).unwrap_err();
+        std::fs::remove_file(&path).ok();
+        assert!(err.contains("path has 2 control points"), "{err}");
+    }
+
+    #[test]
+    fn new_song_keeps_files_already_there() {
+        let dir = std::env::temp_dir().join(format!("funktrack-{}-new-song", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("metadata.ron"), "(broken").unwrap();
+
+        let (metadata, _) = create_song_from_audio(&dir, "song.ogg").unwrap();
+        assert_eq!(metadata.audio_file, "song.ogg");
+        assert_eq!(std::fs::read_to_string(dir.join("metadata.ron")).unwrap(), "(broken");
+        assert!(dir.join(Difficulty::Normal.filename()).exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn parses_chart_gen_onset_dump() {
+        // As written by chart_gen --dump-onsets
+        let dump = r#"(
+    onsets: [
+        (beat: 4.02, strength: 0.8),
+        (beat: 1.5, strength: 0.25),
+    ],
+)"#;
+        let onsets = parse_onsets(dump).unwrap();
+        assert_eq!(
+            onsets,
+            vec![
+                DetectedOnset { beat: 1.5, strength: 0.25 },
+                DetectedOnset { beat: 4.02, strength: 0.8 },
+            ]
+        );
+
+        assert!(parse_onsets("(onsets: [(beat: 1.0)])").is_err());
+
+        let dir = std::env::temp_dir().join(format!("funktrack-{}-no-onsets", std::process::id()));
+        assert_eq!(load_onsets(&dir), Ok(None));
+    }
+
+    #[test]
+    fn autosave_newer_than_chart_is_detected() {
+        use std::time::{Duration, SystemTime};
+
+        let chart = write_temp("normal.ron", "()");
+        let autosave = write_temp("normal.ron.autosave", "()");
+        let set_mtime = |path: &Path, time: SystemTime| {
+            std::fs::File::options().write(true).open(path).unwrap().set_modified(time).unwrap();
+        };
+        let earlier = SystemTime::now() - Duration::from_secs(60);
+
+        set_mtime(&chart, earlier);
+        assert!(autosave_is_newer(&chart, &autosave));
+
+        set_mtime(&autosave, earlier - Duration::from_secs(60));
+        assert!(!autosave_is_newer(&chart, &autosave));
+
+        // A chart that was never saved loses to any autosave; no autosave never wins
+        std::fs::remove_file(&chart).ok();
+        assert!(autosave_is_newer(&chart, &autosave));
+        std::fs::remove_file(&autosave).ok();
+        assert!(!autosave_is_newer(&chart, &autosave));
+    }
+
+    #[test]
+    fn export_path_gets_numeric_suffix_when_taken() {
+        let dir = std::env::temp_dir().join(format!("funktrack-{}-export", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("chart.json");
+
+        assert_eq!(unique_export_path(&target), target);
+        std::fs::write(&target, "{}").unwrap();
+        assert_eq!(unique_export_path(&target), dir.join("chart (1).json"));
+        std::fs::write(dir.join("chart (1).json"), "{}").unwrap();
+        assert_eq!(unique_export_path(&target), dir.join("chart (2).json"));
+
+        // Multi-dot names keep everything but the last extension in the stem
+        std::fs::write(dir.join("normal.ron.json"), "{}").unwrap();
+        assert_eq!(
+            unique_export_path(&dir.join("normal.ron.json")),
+            dir.join("normal.ron (1).json")
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
 }
@@ -0,0 +1,212 @@
+//! Soft warnings for charts that load fine but will probably play badly.
+//! Unlike validation these never block saving or playtesting.
+
+use std::fmt;
+
+use super::rating::{RatedKind, RatedNote};
+use crate::beatmap::{ChartFile, ChartNoteType};
+
+/// Hits closer together than this can't be played as separate notes.
+const MIN_NOTE_INTERVAL_MS: f64 = 60.0;
+/// Slides closer together than this count as quick succession.
+const QUICK_SLIDE_MS: f64 = 250.0;
+/// Compass steps (of 8) allowed between quick slides: a right angle.
+const MAX_QUICK_TURN_STEPS: u8 = 2;
+/// Beats without a note before a gap is worth a look. A Rest inside the gap
+/// marks it as intentional.
+const LONG_GAP_BEATS: f64 = 16.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LintKind {
+    NotesTooClose { gap_ms: f64 },
+    SharpSlideTurn { degrees: u32, gap_ms: f64 },
+    ShortHold { length_ms: f64 },
+    LongGap { beats: f64 },
+}
+
+/// One suggestion, anchored to the beat the editor should jump to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartLint {
+    pub beat: f64,
+    pub kind: LintKind,
+}
+
+impl fmt::Display for ChartLint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            LintKind::NotesTooClose { gap_ms } => write!(f, "notes only {gap_ms:.0} ms apart"),
+            LintKind::SharpSlideTurn { degrees, gap_ms } => {
+                write!(f, "slide turns {degrees}° in {gap_ms:.0} ms")
+            }
+            LintKind::ShortHold { length_ms } => {
+                write!(f, "hold lasts {length_ms:.0} ms, inside the timing window")
+            }
+            LintKind::LongGap { beats } => write!(f, "{beats:.0} beats without a note"),
+        }
+    }
+}
+
+/// Every lint for `chart`, sorted by beat. `notes` is the chart's rating
+/// traversal (hittable notes only); holds and rests are read from the chart.
+pub fn lint_chart(
+    chart: &ChartFile,
+    notes: &[RatedNote],
+    bpm: f64,
+    window_ms: f64,
+) -> Vec<ChartLint> {
+    let ms_per_beat = 60_000.0 / bpm;
+    let mut notes = notes.to_vec();
+    notes.sort_by(|a, b| a.beat.total_cmp(&b.beat));
+    let rests: Vec<f64> = chart
+        .notes
+        .iter()
+        .filter(|n| matches!(n.note_type, ChartNoteType::Rest))
+        .map(|n| n.beat)
+        .collect();
+
+    let mut lints = Vec::new();
+    for pair in notes.windows(2) {
+        let (a, b) = (pair[0].beat, pair[1].beat);
+        let gap_ms = (b - a) * ms_per_beat;
+        // Same-beat notes are a chord, not a cramped pair
+        if gap_ms > 0.0 && gap_ms < MIN_NOTE_INTERVAL_MS {
+            lints.push(ChartLint { beat: b, kind: LintKind::NotesTooClose { gap_ms } });
+        }
+        if b - a >= LONG_GAP_BEATS && !rests.iter().any(|r| *r > a && *r < b) {
+            lints.push(ChartLint { beat: a, kind: LintKind::LongGap { beats: b - a } });
+        }
+    }
+
+    let slides: Vec<(f64, u8)> = notes
+        .iter()
+        .filter_map(|n| match n.kind {
+            RatedKind::Slide(dir) => Some((n.beat, dir)),
+            _ => None,
+        })
+        .collect();
+    for pair in slides.windows(2) {
+        let ((a, from), (b, to)) = (pair[0], pair[1]);
+        let gap_ms = (b - a) * ms_per_beat;
+        let steps = turn_steps(from, to);
+        if gap_ms <= QUICK_SLIDE_MS && steps > MAX_QUICK_TURN_STEPS {
+            let degrees = steps as u32 * 45;
+            lints.push(ChartLint { beat: b, kind: LintKind::SharpSlideTurn { degrees, gap_ms } });
+        }
+    }
+
+    for note in &chart.notes {
+        let duration = match note.note_type {
+            ChartNoteType::Hold { duration_beats }
+            | ChartNoteType::SlideHold { duration_beats, .. }
+            | ChartNoteType::CriticalHold { duration_beats } => duration_beats,
+            _ => continue,
+        };
+        let length_ms = duration * ms_per_beat;
+        // Zero and negative lengths are validation errors, not style
+        if length_ms > 0.0 && length_ms < window_ms {
+            lints.push(ChartLint { beat: note.beat, kind: LintKind::ShortHold { length_ms } });
+        }
+    }
+
+    lints.sort_by(|a, b| a.beat.total_cmp(&b.beat));
+    lints
+}
+
+/// Shortest way round the compass between two of the 8 slide directions.
+fn turn_steps(from: u8, to: u8) -> u8 {
+    let diff = (to + 8 - from) % 8;
+    diff.min(8 - diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beatmap::{ChartNoteEntry, Difficulty};
+
+    const BPM: f64 = 120.0;
+    const WINDOW_MS: f64 = 100.0;
+
+    fn chart(notes: Vec<ChartNoteEntry>) -> ChartFile {
+        ChartFile {
+            difficulty: Difficulty::Normal,
+            difficulty_rating: 1,
+            timing_points: Vec::new(),
+            path_segments: Vec::new(),
+            notes,
+            events: Vec::new(),
+            travel_beats: 4.0,
+            look_ahead_beats: 4.0,
+        }
+    }
+
+    fn tap(beat: f64) -> RatedNote {
+        RatedNote { beat, kind: RatedKind::Tap }
+    }
+
+    fn slide(beat: f64, dir: u8) -> RatedNote {
+        RatedNote { beat, kind: RatedKind::Slide(dir) }
+    }
+
+    fn kinds(lints: &[ChartLint]) -> Vec<&LintKind> {
+        lints.iter().map(|l| &l.kind).collect()
+    }
+
+    #[test]
+    fn steady_eighths_raise_nothing() {
+        let notes: Vec<RatedNote> = (0..16).map(|i| tap(i as f64 * 0.5)).collect();
+        assert!(lint_chart(&chart(Vec::new()), &notes, BPM, WINDOW_MS).is_empty());
+    }
+
+    #[test]
+    fn notes_closer_than_the_minimum_interval() {
+        // A 64th apart at 120 BPM is under 8 ms
+        let notes = [tap(1.0), tap(1.0625), tap(2.0)];
+        let lints = lint_chart(&chart(Vec::new()), &notes, BPM, WINDOW_MS);
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].beat, 1.0625);
+        assert!(matches!(lints[0].kind, LintKind::NotesTooClose { gap_ms } if gap_ms < 60.0));
+
+        // A chord on one beat is fine
+        let chord = [tap(1.0), tap(1.0), tap(2.0)];
+        assert!(lint_chart(&chart(Vec::new()), &chord, BPM, WINDOW_MS).is_empty());
+    }
+
+    #[test]
+    fn quick_slide_reversal_is_a_sharp_turn() {
+        // Up then down an eighth (250 ms) apart; a right angle at the same gap is fine
+        let notes = [slide(0.0, 0), slide(0.5, 4), slide(1.0, 6)];
+        let lints = lint_chart(&chart(Vec::new()), &notes, BPM, WINDOW_MS);
+        assert_eq!(
+            kinds(&lints),
+            [&LintKind::SharpSlideTurn { degrees: 180, gap_ms: 250.0 }]
+        );
+        assert_eq!(lints[0].beat, 0.5);
+
+        // The same reversal with time to recover is left alone
+        let slow = [slide(0.0, 0), slide(2.0, 4)];
+        assert!(lint_chart(&chart(Vec::new()), &slow, BPM, WINDOW_MS).is_empty());
+    }
+
+    #[test]
+    fn hold_shorter_than_the_timing_window() {
+        let chart = chart(vec![
+            ChartNoteEntry { beat: 1.0, note_type: ChartNoteType::Hold { duration_beats: 0.125 } },
+            ChartNoteEntry { beat: 2.0, note_type: ChartNoteType::Hold { duration_beats: 1.0 } },
+        ]);
+        let notes = [tap(1.0), tap(2.0)];
+        let lints = lint_chart(&chart, &notes, BPM, WINDOW_MS);
+        assert_eq!(kinds(&lints), [&LintKind::ShortHold { length_ms: 62.5 }]);
+        assert_eq!(lints[0].beat, 1.0);
+    }
+
+    #[test]
+    fn long_gap_unless_a_rest_marks_it() {
+        let notes = [tap(0.0), tap(20.0)];
+        let lints = lint_chart(&chart(Vec::new()), &notes, BPM, WINDOW_MS);
+        assert_eq!(kinds(&lints), [&LintKind::LongGap { beats: 20.0 }]);
+        assert_eq!(lints[0].beat, 0.0);
+
+        let rested = chart(vec![ChartNoteEntry { beat: 8.0, note_type: ChartNoteType::Rest }]);
+        assert!(lint_chart(&rested, &notes, BPM, WINDOW_MS).is_empty());
+    }
+}
@@ -1,6 +1,12 @@
 mod actions;
 mod camera;
+mod convert;
 mod io;
+mod lint;
+#[path = "../../tools/chart_gen/src/rating.rs"]
+mod rating;
+mod scrub;
+mod snapshot;
 mod viewport;
 mod ui;
 
@@ -11,12 +17,17 @@ use bevy::prelude::*;
 use bevy_egui::EguiPlugin;
 
 use crate::beatmap::{
-    ChartFile, ChartNoteEntry, ChartNoteType, Difficulty,
-    PathSegment, SongMetadata,
+    ChartError, ChartFile, ChartNoteEntry, ChartNoteType, ChartTimingPoint, Difficulty,
+    MIN_PATH_POINTS, PathSegment, SongMetadata, spline_points_for, validate_chart,
 };
+use crate::config::GameplayTuning;
+use crate::path::SplinePath;
 use crate::state::GameScreen;
 
 pub use self::actions::EditorAction;
+pub use self::io::{create_song_from_audio, save_metadata_ron};
+use self::lint::ChartLint;
+use self::rating::{DifficultyRating, RatedKind, RatedNote};
 
 pub struct EditorPluginBundle;
 
@@ -29,6 +40,7 @@ impl Plugin for EditorPluginBundle {
             (
                 input_system,
                 ui::editor_ui_system,
+                scrub::scrub_audio_system,
             )
                 .chain()
                 .run_if(in_state(GameScreen::Editor)),
@@ -38,6 +50,7 @@ impl Plugin for EditorPluginBundle {
             (
                 camera::editor_camera_system,
                 viewport::render_viewport,
+                autosave_system,
             )
                 .run_if(in_state(GameScreen::Editor)),
         )
@@ -107,6 +120,9 @@ impl NoteBrush {
     }
 }
 
+/// Swing ratio for a straight grid.
+pub const STRAIGHT_SWING: f64 = 0.5;
+
 /// Beat grid snap resolution.
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum GridSnap {
@@ -142,12 +158,40 @@ impl GridSnap {
         }
     }
 
-    pub fn snap_beat(self, beat: f64) -> f64 {
+    /// Whether `swing_ratio` applies at this division.
+    pub fn swings(self) -> bool {
+        matches!(self, GridSnap::Eighth | GridSnap::Sixteenth)
+    }
+
+    /// Where straight grid line `beat` sits once swung: every second step moves
+    /// to `swing_ratio` of the way through its pair (0.5 = straight, 2/3 = triplet).
+    pub fn swung(self, beat: f64, swing_ratio: f64) -> f64 {
+        if !self.swings() {
+            return beat;
+        }
+        let pair = 2.0 / self.divisor();
+        let step = (beat * self.divisor()).round() as i64;
+        if step.rem_euclid(2) == 0 {
+            return beat;
+        }
+        (step.div_euclid(2) as f64 + swing_ratio) * pair
+    }
+
+    pub fn snap_beat(self, beat: f64, swing_ratio: f64) -> f64 {
         if self == GridSnap::None {
             return beat;
         }
         let d = self.divisor();
-        (beat * d).round() / d
+        if !self.swings() {
+            return (beat * d).round() / d;
+        }
+        // Nearest of this pair's on-beat, its swung off-beat, and the next on-beat
+        let pair = 2.0 / d;
+        let start = (beat / pair).floor() * pair;
+        [start, start + swing_ratio * pair, start + pair]
+            .into_iter()
+            .min_by(|a, b| (a - beat).abs().total_cmp(&(b - beat).abs()))
+            .unwrap_or(beat)
     }
 
     pub fn next(self) -> GridSnap {
@@ -173,6 +217,30 @@ impl GridSnap {
     }
 }
 
+/// Unit for the timeline ruler and cursor readout. Snapping is always in beats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeDisplay {
+    #[default]
+    Beats,
+    Seconds,
+}
+
+impl TimeDisplay {
+    pub fn toggled(self) -> TimeDisplay {
+        match self {
+            TimeDisplay::Beats => TimeDisplay::Seconds,
+            TimeDisplay::Seconds => TimeDisplay::Beats,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TimeDisplay::Beats => "BEATS",
+            TimeDisplay::Seconds => "SECONDS",
+        }
+    }
+}
+
 /// Playback state within the editor.
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum PlaybackState {
@@ -199,14 +267,21 @@ pub struct EditorState {
     pub mode: EditorMode,
     pub note_brush: NoteBrush,
     pub grid_snap: GridSnap,
+    /// Where the off-beat of each 1/8 or 1/16 pair falls: 0.5 straight, ~0.66 triplet swing.
+    pub swing_ratio: f64,
 
     pub cursor_beat: f64,
     pub playback: PlaybackState,
     pub total_beats: f64,
     pub timeline_view_beats: f64,
+    pub time_display: TimeDisplay,
 
     pub selected: HashSet<EditorElement>,
+    /// Note the timeline's right-click menu is open for.
+    pub context_note: Option<usize>,
     pub dragging_cp: Option<(usize, usize)>,
+    /// Hold being resized from the timeline: (note index, live duration).
+    pub dragging_hold: Option<(usize, f64)>,
 
     pub undo_stack: Vec<EditorAction>,
     pub redo_stack: Vec<EditorAction>,
@@ -214,11 +289,41 @@ pub struct EditorState {
 
     pub egui_wants_pointer: bool,
     pub toast: Option<(String, f64)>,
+    /// Deadline for a second Escape to discard unsaved changes and leave.
+    pub pending_exit: Option<f64>,
+    /// Live rating, refreshed only when notes or timing change.
+    pub rating: DifficultyRating,
+    /// Problems that would stop the chart from being played, refreshed on every edit.
+    pub validation: Vec<ChartError>,
+    /// Playable-but-awkward spots (cramped notes, sharp slide turns, ...), refreshed on every edit.
+    pub lints: Vec<ChartLint>,
+    /// Timing windows the lints are judged against: the game's `tuning.ron` once loaded.
+    pub tuning: GameplayTuning,
+    /// The gameplay spline for Chart mode's path mini-map, refreshed on every edit.
+    pub path_preview: Option<SplinePath>,
+    /// Notes per beat around each whole beat, for the timeline's heat strip.
+    pub density: Vec<f32>,
+    /// Onsets from chart_gen's `onsets.ron`, if the song folder has one.
+    pub onsets: Vec<io::DetectedOnset>,
+    /// Draw `onsets` as ghost ticks on the timeline.
+    pub show_onsets: bool,
+    /// Navigation anchors (beat, label), sorted by beat. Session-only, never saved.
+    pub bookmarks: Vec<(f64, String)>,
+    /// Autosave found on open that is newer than the saved chart (Ctrl+R restores it).
+    pub recovery: Option<ChartFile>,
+    /// Time of the last autosave check.
+    pub last_autosave: f64,
 }
 
 impl EditorState {
     pub fn new(chart: ChartFile, metadata: SongMetadata, song_dir: PathBuf) -> Self {
         let total_beats = Self::compute_total_beats(&chart);
+        let rating = Self::compute_rating(&chart);
+        let validation = validate_chart(&chart).err().unwrap_or_default();
+        let tuning = GameplayTuning::default();
+        let lints = Self::compute_lints(&chart, &tuning);
+        let path_preview = Self::compute_path_preview(&chart);
+        let density = note_density(&chart.notes, total_beats, DENSITY_WINDOW_BEATS);
         Self {
             chart,
             metadata,
@@ -226,17 +331,33 @@ impl EditorState {
             mode: EditorMode::default(),
             note_brush: NoteBrush::default(),
             grid_snap: GridSnap::default(),
+            swing_ratio: STRAIGHT_SWING,
             cursor_beat: 0.0,
             playback: PlaybackState::default(),
             total_beats,
             timeline_view_beats: 16.0,
+            time_display: TimeDisplay::default(),
             selected: HashSet::new(),
+            context_note: None,
             dragging_cp: None,
+            dragging_hold: None,
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
             unsaved_changes: false,
             egui_wants_pointer: false,
             toast: None,
+            pending_exit: None,
+            rating,
+            validation,
+            lints,
+            tuning,
+            path_preview,
+            density,
+            onsets: Vec::new(),
+            show_onsets: false,
+            bookmarks: Vec::new(),
+            recovery: None,
+            last_autosave: 0.0,
         }
     }
 
@@ -250,32 +371,119 @@ impl EditorState {
         (last_note.max(last_event) + 8.0).max(32.0)
     }
 
+    fn compute_path_preview(chart: &ChartFile) -> Option<SplinePath> {
+        let points = spline_points_for(&chart.path_segments);
+        (points.len() >= MIN_PATH_POINTS).then(|| SplinePath::from_catmull_rom_points(points))
+    }
+
+    /// Evenly spaced points along the path for the Chart-mode mini-map.
+    pub fn path_preview_points(&self) -> Vec<Vec2> {
+        let Some(path) = &self.path_preview else {
+            return Vec::new();
+        };
+        (0..=PATH_PREVIEW_SAMPLES)
+            .map(|i| path.position_at_progress(i as f32 / PATH_PREVIEW_SAMPLES as f32))
+            .collect()
+    }
+
+    /// Where the cursor sits on the path, using the same beat-to-progress
+    /// mapping as the viewport's note previews.
+    pub fn path_preview_cursor(&self) -> Option<Vec2> {
+        let path = self.path_preview.as_ref()?;
+        let progress = (self.cursor_beat / self.total_beats).clamp(0.0, 1.0) as f32;
+        Some(path.position_at_progress(progress))
+    }
+
+    /// The hittable notes as the difficulty rating sees them.
+    fn rated_notes(chart: &ChartFile) -> Vec<RatedNote> {
+        chart
+            .notes
+            .iter()
+            .filter_map(|n| {
+                // Same mapping chart_gen rates with, so the two can't drift apart
+                let kind = rating::rated_kind!(n.note_type)?;
+                Some(RatedNote { beat: n.beat, kind })
+            })
+            .collect()
+    }
+
+    fn chart_bpm(chart: &ChartFile) -> f64 {
+        chart.timing_points.first().map_or(120.0, |tp| tp.bpm)
+    }
+
+    fn compute_rating(chart: &ChartFile) -> DifficultyRating {
+        rating::rate(&Self::rated_notes(chart), Self::chart_bpm(chart))
+    }
+
+    /// Style warnings, judged against `tuning`'s Good window.
+    fn compute_lints(chart: &ChartFile, tuning: &GameplayTuning) -> Vec<ChartLint> {
+        let window_ms = tuning.good_window_ms;
+        lint::lint_chart(chart, &Self::rated_notes(chart), Self::chart_bpm(chart), window_ms)
+    }
+
+    /// Swap in the game's loaded timing windows and re-lint against them.
+    pub fn set_tuning(&mut self, tuning: GameplayTuning) {
+        self.tuning = tuning;
+        self.lints = Self::compute_lints(&self.chart, &self.tuning);
+    }
+
     pub fn execute(&mut self, action: EditorAction) {
         action.apply(&mut self.chart);
+        if action.affects_rating() {
+            self.rating = Self::compute_rating(&self.chart);
+        }
         self.undo_stack.push(action);
         self.redo_stack.clear();
         self.unsaved_changes = true;
+        self.refresh_derived();
+    }
+
+    /// Recompute what every edit can change.
+    fn refresh_derived(&mut self) {
         self.total_beats = Self::compute_total_beats(&self.chart);
+        self.validation = validate_chart(&self.chart).err().unwrap_or_default();
+        self.lints = Self::compute_lints(&self.chart, &self.tuning);
+        self.path_preview = Self::compute_path_preview(&self.chart);
+        self.density = note_density(&self.chart.notes, self.total_beats, DENSITY_WINDOW_BEATS);
     }
 
     pub fn undo(&mut self) {
         if let Some(action) = self.undo_stack.pop() {
             action.undo(&mut self.chart);
+            if action.affects_rating() {
+                self.rating = Self::compute_rating(&self.chart);
+            }
             self.redo_stack.push(action);
             self.unsaved_changes = true;
-            self.total_beats = Self::compute_total_beats(&self.chart);
+            self.refresh_derived();
         }
     }
 
     pub fn redo(&mut self) {
         if let Some(action) = self.redo_stack.pop() {
             action.apply(&mut self.chart);
+            if action.affects_rating() {
+                self.rating = Self::compute_rating(&self.chart);
+            }
             self.undo_stack.push(action);
             self.unsaved_changes = true;
-            self.total_beats = Self::compute_total_beats(&self.chart);
+            self.refresh_derived();
         }
     }
 
+    /// Swap in a whole new chart (e.g. an import). History no longer applies.
+    pub fn replace_chart(&mut self, chart: ChartFile) {
+        self.chart = chart;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.selected.clear();
+        self.dragging_cp = None;
+        self.dragging_hold = None;
+        self.unsaved_changes = true;
+        self.refresh_derived();
+        self.rating = Self::compute_rating(&self.chart);
+    }
+
     pub fn bpm(&self) -> f64 {
         self.chart
             .timing_points
@@ -288,11 +496,233 @@ impl EditorState {
         beat * 60.0 / self.bpm()
     }
 
+    pub fn time_to_beat(&self, seconds: f64) -> f64 {
+        seconds * self.bpm() / 60.0
+    }
+
+    /// Widen the timeline until it shows the whole chart around the cursor.
+    pub fn zoom_to_fit(&mut self) {
+        // The view centres on the cursor, so it must reach back to beat 0 too
+        let beats = self.total_beats.max(2.0 * self.cursor_beat);
+        self.timeline_view_beats = beats.clamp(MIN_TIMELINE_VIEW_BEATS, MAX_TIMELINE_VIEW_BEATS);
+    }
+
+    /// Show `measures` measures of the time signature at the cursor.
+    pub fn zoom_to_measures(&mut self, measures: u32) {
+        let time_signature = self
+            .chart
+            .timing_points
+            .iter()
+            .take_while(|tp| tp.beat <= self.cursor_beat)
+            .last()
+            .map_or((4, 4), |tp| tp.time_signature);
+        let beats = measures as f64 * measure_length(time_signature);
+        self.timeline_view_beats = beats.clamp(MIN_TIMELINE_VIEW_BEATS, MAX_TIMELINE_VIEW_BEATS);
+    }
+
+    /// Song time of `beat` in whole milliseconds, as metadata stores it.
+    pub fn beat_to_ms(&self, beat: f64) -> u64 {
+        (self.beat_to_time(beat) * 1000.0).round().max(0.0) as u64
+    }
+
+    /// Start the song-select preview at the cursor, keeping its length.
+    pub fn mark_preview_start(&mut self) {
+        self.metadata.preview_start_ms = self.beat_to_ms(self.cursor_beat);
+        self.unsaved_changes = true;
+    }
+
+    /// End the song-select preview at the cursor. Fails if that isn't after
+    /// the current start.
+    pub fn mark_preview_end(&mut self) -> Result<(), String> {
+        let end_ms = self.beat_to_ms(self.cursor_beat);
+        let start_ms = self.metadata.preview_start_ms;
+        if end_ms <= start_ms {
+            return Err("Preview end must be after its start".to_string());
+        }
+        self.metadata.preview_duration_ms = end_ms - start_ms;
+        self.unsaved_changes = true;
+        Ok(())
+    }
+
+    /// The song-select preview window in beats, for the timeline.
+    pub fn preview_beats(&self) -> (f64, f64) {
+        let start_ms = self.metadata.preview_start_ms;
+        let end_ms = start_ms + self.metadata.preview_duration_ms;
+        (
+            self.time_to_beat(start_ms as f64 / 1000.0),
+            self.time_to_beat(end_ms as f64 / 1000.0),
+        )
+    }
+
+    /// Write the chart and `metadata.ron`, then drop the autosave.
+    pub fn save(&mut self) -> Result<(), String> {
+        io::save_chart_ron(&self.chart, &self.song_dir.join(self.chart.difficulty.filename()))?;
+        io::save_metadata_ron(&self.metadata, &self.song_dir.join("metadata.ron"))?;
+        self.unsaved_changes = false;
+        self.recovery = None;
+        io::clear_autosave(&self.song_dir, self.chart.difficulty);
+        Ok(())
+    }
+
+    /// Beats visible in the Chart-mode timeline, which centres on the cursor.
+    pub fn timeline_range(&self) -> (f64, f64) {
+        let start = (self.cursor_beat - self.timeline_view_beats / 2.0).max(0.0);
+        (start, start + self.timeline_view_beats)
+    }
+
+    pub fn select_all_notes(&mut self) {
+        self.selected = (0..self.chart.notes.len())
+            .map(|index| EditorElement::Note { index })
+            .collect();
+    }
+
+    /// Select the notes in `start..=end`, replacing the selection.
+    pub fn select_notes_in(&mut self, start: f64, end: f64) {
+        self.selected = self
+            .chart
+            .notes
+            .iter()
+            .enumerate()
+            .filter(|(_, note)| note.beat >= start && note.beat <= end)
+            .map(|(index, _)| EditorElement::Note { index })
+            .collect();
+    }
+
+    /// Beats spanned by `ms` milliseconds at the chart's BPM.
+    pub fn ms_to_beats(&self, ms: f64) -> f64 {
+        self.time_to_beat(ms / 1000.0)
+    }
+
+    /// Shift every selected note by `ms` (unsnapped) as one undo step.
+    /// Notes stop at beat 0.
+    pub fn nudge_selected(&mut self, ms: f64) {
+        let delta = self.ms_to_beats(ms);
+        let mut moves: Vec<(usize, f64, f64)> = self
+            .selected
+            .iter()
+            .filter_map(|e| match e {
+                EditorElement::Note { index } => self.chart.notes.get(*index).map(|n| (*index, n.beat)),
+                _ => None,
+            })
+            .map(|(index, beat)| (index, beat, (beat + delta).max(0.0)))
+            .filter(|(_, old, new)| old != new)
+            .collect();
+        if moves.is_empty() {
+            return;
+        }
+        moves.sort_by_key(|(index, _, _)| *index);
+        self.execute(EditorAction::MoveNotes { moves });
+    }
+
+    /// `beat` snapped to the current grid, swung if swing is on.
+    pub fn snap(&self, beat: f64) -> f64 {
+        self.grid_snap.snap_beat(beat, self.swing_ratio)
+    }
+
+    /// One grid step in beats (a quarter beat when snapping is off).
+    pub fn grid_step(&self) -> f64 {
+        match self.grid_snap {
+            GridSnap::None => 0.25,
+            other => 1.0 / other.divisor(),
+        }
+    }
+
+    /// Copy note `index` one grid step later. Returns the copy's beat.
+    pub fn duplicate_note(&mut self, index: usize) -> Option<f64> {
+        let mut note = self.chart.notes.get(index)?.clone();
+        note.beat += self.grid_step();
+        let beat = note.beat;
+        self.execute(EditorAction::AddNote { note });
+        Some(beat)
+    }
+
+    /// Duplicate the note at the (snapped) cursor and move the cursor onto the copy.
+    pub fn duplicate_note_at_cursor(&mut self) {
+        let beat = self.snap(self.cursor_beat);
+        let Some(index) = self.chart.notes.iter().position(|n| (n.beat - beat).abs() < 1e-6) else {
+            return;
+        };
+        if let Some(new_beat) = self.duplicate_note(index) {
+            self.cursor_beat = new_beat;
+        }
+    }
+
+    /// Drop a bookmark at `beat`, or remove the one already there.
+    pub fn toggle_bookmark(&mut self, beat: f64) {
+        if let Some(i) = self.bookmarks.iter().position(|(b, _)| (b - beat).abs() < BOOKMARK_EPS) {
+            self.bookmarks.remove(i);
+            return;
+        }
+        let number = self.bookmarks.len() + 1;
+        let pos = self.bookmarks.partition_point(|(b, _)| *b < beat);
+        self.bookmarks.insert(pos, (beat, format!("B{number}")));
+    }
+
+    /// First bookmark after `beat`.
+    pub fn next_bookmark(&self, beat: f64) -> Option<f64> {
+        self.bookmarks
+            .iter()
+            .map(|(b, _)| *b)
+            .find(|b| *b > beat + BOOKMARK_EPS)
+    }
+
+    /// Last bookmark before `beat`.
+    pub fn prev_bookmark(&self, beat: f64) -> Option<f64> {
+        self.bookmarks
+            .iter()
+            .rev()
+            .map(|(b, _)| *b)
+            .find(|b| *b < beat - BOOKMARK_EPS)
+    }
+
+    /// `beat` in the current display unit.
+    pub fn format_position(&self, beat: f64) -> String {
+        match self.time_display {
+            TimeDisplay::Beats => format!("{beat:.2}"),
+            TimeDisplay::Seconds => format!("{:.3}s", self.beat_to_time(beat)),
+        }
+    }
+
     pub fn show_toast(&mut self, msg: impl Into<String>, now: f64) {
-        self.toast = Some((msg.into(), now + 2.5));
+        self.toast = Some((msg.into(), now + TOAST_SECS));
+    }
+
+    /// Whether the editor may leave now. With unsaved changes the first request
+    /// only arms a confirmation; a second one before it expires goes through.
+    pub fn confirm_exit(&mut self, now: f64) -> bool {
+        if !self.unsaved_changes {
+            return true;
+        }
+        if self.pending_exit.is_some_and(|deadline| now <= deadline) {
+            self.pending_exit = None;
+            return true;
+        }
+        self.pending_exit = Some(now + TOAST_SECS);
+        self.show_toast("Unsaved changes. Esc again to discard, Ctrl+S to save", now);
+        false
     }
 }
 
+/// How long toasts (and the exit confirmation they announce) stay up.
+const TOAST_SECS: f64 = 2.5;
+
+/// Seconds between autosaves while there are unsaved changes.
+const AUTOSAVE_SECS: f64 = 30.0;
+/// How long the recovery prompt stays on screen.
+const RECOVERY_PROMPT_SECS: f64 = 10.0;
+
+/// Alt+arrow nudge step for selected notes.
+const NUDGE_MS: f64 = 5.0;
+
+/// Bookmarks closer than this count as the same position.
+const BOOKMARK_EPS: f64 = 1e-3;
+
+/// Narrowest and widest the timeline can zoom, in beats.
+pub const MIN_TIMELINE_VIEW_BEATS: f64 = 4.0;
+pub const MAX_TIMELINE_VIEW_BEATS: f64 = 128.0;
+/// Alt+1..4 zoom presets, in measures.
+const ZOOM_PRESET_MEASURES: [u32; 4] = [1, 2, 4, 8];
+
 /// Marker for entities spawned by the editor (for cleanup).
 #[derive(Component)]
 pub struct EditorEntity;
@@ -321,26 +751,30 @@ fn input_system(
     let ctrl = keys.pressed(KeyCode::ControlLeft) || keys.pressed(KeyCode::ControlRight);
     let now = time.elapsed_secs_f64();
 
-    // Expire toast
+    // Expire toast and exit confirmation
     if let Some((_, deadline)) = &state.toast {
         if now > *deadline {
             state.toast = None;
         }
     }
+    if state.pending_exit.is_some_and(|deadline| now > deadline) {
+        state.pending_exit = None;
+    }
 
     // ── Escape ──
     if keys.just_pressed(KeyCode::Escape) {
-        crate::audio::stop_preview(&mut ctx);
-        next_state.set(GameScreen::SongSelect);
+        if state.confirm_exit(now) {
+            crate::audio::stop_preview(&mut ctx);
+            next_state.set(GameScreen::SongSelect);
+        }
         return;
     }
 
     // ── Ctrl+S save ──
     if ctrl && keys.just_pressed(KeyCode::KeyS) {
-        let path = state.song_dir.join(state.chart.difficulty.filename());
-        match io::save_chart_ron(&state.chart, &path) {
+        match state.save() {
             Ok(()) => {
-                state.unsaved_changes = false;
+                state.pending_exit = None;
                 state.show_toast("Saved", now);
             }
             Err(e) => {
@@ -350,6 +784,15 @@ fn input_system(
         return;
     }
 
+    // ── Ctrl+R: restore the autosave offered on open ──
+    if ctrl && keys.just_pressed(KeyCode::KeyR) {
+        if let Some(chart) = state.recovery.take() {
+            state.replace_chart(chart);
+            state.show_toast("Recovered autosave", now);
+        }
+        return;
+    }
+
     // ── Ctrl+Z / Ctrl+Y ──
     if ctrl && keys.just_pressed(KeyCode::KeyZ) {
         state.undo();
@@ -378,9 +821,7 @@ fn input_system(
                 let total_ms = (state.beat_to_time(state.total_beats) * 1000.0) as u64;
                 let remaining = total_ms.saturating_sub(start_ms).max(1000);
                 let audio_path = state.song_dir.join(&state.metadata.audio_file);
-                if let Some(s) = audio_path.to_str() {
-                    crate::audio::play_preview(&mut ctx, s, start_ms, remaining, 0.7);
-                }
+                crate::audio::play_preview(&mut ctx, &audio_path, start_ms, remaining, 0.7);
             }
             PlaybackState::Playing => {
                 state.playback = PlaybackState::Stopped;
@@ -402,6 +843,33 @@ fn input_system(
         return; // Don't process edit keys during playback
     }
 
+    // ── Ctrl+B: select notes between the bookmarks around the cursor ──
+    if ctrl && keys.just_pressed(KeyCode::KeyB) {
+        let cursor = state.cursor_beat;
+        let start = state
+            .bookmarks
+            .iter()
+            .rev()
+            .map(|(b, _)| *b)
+            .find(|b| *b <= cursor + BOOKMARK_EPS)
+            .unwrap_or(0.0);
+        let end = state.next_bookmark(cursor).unwrap_or(state.total_beats);
+        state.select_notes_in(start, end);
+        return;
+    }
+
+    // ── Ctrl+A: select every note, Ctrl+Shift+A: notes in view ──
+    if ctrl && keys.just_pressed(KeyCode::KeyA) {
+        let shift = keys.pressed(KeyCode::ShiftLeft) || keys.pressed(KeyCode::ShiftRight);
+        if shift {
+            let (start, end) = state.timeline_range();
+            state.select_notes_in(start, end);
+        } else {
+            state.select_all_notes();
+        }
+        return;
+    }
+
     // ── Grid snap cycling: [ / ] ──
     if keys.just_pressed(KeyCode::BracketLeft) {
         state.grid_snap = state.grid_snap.prev();
@@ -410,6 +878,21 @@ fn input_system(
         state.grid_snap = state.grid_snap.next();
     }
 
+    // ── Zoom: F fits the chart, Alt+1..4 show 1/2/4/8 measures ──
+    if keys.just_pressed(KeyCode::KeyF) {
+        state.zoom_to_fit();
+    }
+    let alt = keys.pressed(KeyCode::AltLeft) || keys.pressed(KeyCode::AltRight);
+    if alt {
+        let preset_keys = [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4];
+        for (key, measures) in preset_keys.into_iter().zip(ZOOM_PRESET_MEASURES) {
+            if keys.just_pressed(key) {
+                state.zoom_to_measures(measures);
+                return;
+            }
+        }
+    }
+
     // ── Note brush shortcuts: 1-5 ──
     let brush_keys: [(KeyCode, NoteBrush); 5] = [
         (KeyCode::Digit1, NoteBrush::Tap),
@@ -425,19 +908,33 @@ fn input_system(
     }
 
     // ── Arrow keys: timeline navigation ──
-    let step = if ctrl {
-        1.0
+    let step = if ctrl { 1.0 } else { state.grid_step() };
+    if alt {
+        // ── Alt+Left/Right: nudge selected notes off the grid ──
+        if keys.just_pressed(KeyCode::ArrowRight) {
+            state.nudge_selected(NUDGE_MS);
+        }
+        if keys.just_pressed(KeyCode::ArrowLeft) {
+            state.nudge_selected(-NUDGE_MS);
+        }
     } else {
-        match state.grid_snap {
-            GridSnap::None => 0.25,
-            other => 1.0 / other.divisor(),
+        if keys.just_pressed(KeyCode::ArrowRight) {
+            state.cursor_beat = (state.cursor_beat + step).min(state.total_beats);
+        }
+        if keys.just_pressed(KeyCode::ArrowLeft) {
+            state.cursor_beat = (state.cursor_beat - step).max(0.0);
         }
-    };
-    if keys.just_pressed(KeyCode::ArrowRight) {
-        state.cursor_beat = (state.cursor_beat + step).min(state.total_beats);
     }
-    if keys.just_pressed(KeyCode::ArrowLeft) {
-        state.cursor_beat = (state.cursor_beat - step).max(0.0);
+    // ── D: duplicate the note at the cursor one grid step later ──
+    if keys.just_pressed(KeyCode::KeyD) {
+        state.duplicate_note_at_cursor();
+    }
+
+    // ── N: jump to the nearest note, ignoring the grid ──
+    if keys.just_pressed(KeyCode::KeyN) {
+        if let Some(beat) = nearest_note_beat(&state.chart.notes, state.cursor_beat) {
+            state.cursor_beat = beat;
+        }
     }
     if keys.just_pressed(KeyCode::Home) {
         state.cursor_beat = 0.0;
@@ -446,9 +943,43 @@ fn input_system(
         state.cursor_beat = state.total_beats;
     }
 
+    // ── Preview window: P marks the start, Shift+P the end ──
+    if keys.just_pressed(KeyCode::KeyP) {
+        let shift = keys.pressed(KeyCode::ShiftLeft) || keys.pressed(KeyCode::ShiftRight);
+        if shift {
+            match state.mark_preview_end() {
+                Ok(()) => {
+                    let secs = state.metadata.preview_duration_ms as f64 / 1000.0;
+                    state.show_toast(format!("Preview length {secs:.1}s"), now);
+                }
+                Err(e) => state.show_toast(e, now),
+            }
+        } else {
+            state.mark_preview_start();
+            let secs = state.metadata.preview_start_ms as f64 / 1000.0;
+            state.show_toast(format!("Preview starts at {secs:.1}s"), now);
+        }
+    }
+
+    // ── Bookmarks: B drops/removes, PageUp/PageDown jump ──
+    if keys.just_pressed(KeyCode::KeyB) {
+        let beat = state.cursor_beat;
+        state.toggle_bookmark(beat);
+    }
+    if keys.just_pressed(KeyCode::PageDown) {
+        if let Some(beat) = state.next_bookmark(state.cursor_beat) {
+            state.cursor_beat = beat;
+        }
+    }
+    if keys.just_pressed(KeyCode::PageUp) {
+        if let Some(beat) = state.prev_bookmark(state.cursor_beat) {
+            state.cursor_beat = beat;
+        }
+    }
+
     // ── Delete selected ──
     if keys.just_pressed(KeyCode::Delete) || keys.just_pressed(KeyCode::Backspace) {
-        let mut note_indices: Vec<usize> = state
+        let note_indices: Vec<usize> = state
             .selected
             .iter()
             .filter_map(|e| match e {
@@ -456,20 +987,121 @@ fn input_system(
                 _ => None,
             })
             .collect();
-        note_indices.sort_unstable();
-        note_indices.reverse();
-        for index in note_indices {
+        if note_indices.len() > 1 {
+            // One undo step, however many notes are selected
+            let doomed: HashSet<usize> = note_indices.into_iter().collect();
+            let old = state.chart.notes.clone();
+            let new = old
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !doomed.contains(i))
+                .map(|(_, note)| note.clone())
+                .collect();
+            state.execute(EditorAction::ReplaceNotes { old, new });
+        } else if let Some(&index) = note_indices.first() {
             if index < state.chart.notes.len() {
                 let note = state.chart.notes[index].clone();
                 state.execute(EditorAction::RemoveNote { index, note });
             }
         }
+
+        let mut cp_indices: Vec<(usize, usize)> = state
+            .selected
+            .iter()
+            .filter_map(|e| match e {
+                EditorElement::PathControlPoint { segment, index } => Some((*segment, *index)),
+                _ => None,
+            })
+            .collect();
+        cp_indices.sort_unstable();
+        cp_indices.reverse();
+        let mut next_selection = None;
+        for (segment, index) in cp_indices {
+            if !can_remove_path_point(&state.chart.path_segments) {
+                state.show_toast(
+                    format!("A path needs at least {MIN_PATH_POINTS} control points"),
+                    now,
+                );
+                break;
+            }
+            let point = match state.chart.path_segments.get(segment) {
+                Some(PathSegment::CatmullRom { points, .. }) if index < points.len() => {
+                    points[index]
+                }
+                _ => continue,
+            };
+            state.execute(EditorAction::RemovePathPoint { segment, index, point });
+            next_selection = Some((segment, index.saturating_sub(1)));
+        }
+        state.dragging_cp = None;
+
         state.selected.clear();
+        // Keep a neighbouring point selected so repeated deletes walk the path
+        if let Some((segment, index)) = next_selection {
+            state.selected.insert(EditorElement::PathControlPoint { segment, index });
+        }
+    }
+
+    // ── T: toggle selected notes between their tap and hold forms ──
+    if keys.just_pressed(KeyCode::KeyT) {
+        let mut note_indices: Vec<usize> = state
+            .selected
+            .iter()
+            .filter_map(|e| match e {
+                EditorElement::Note { index } => Some(*index),
+                _ => None,
+            })
+            .collect();
+        note_indices.sort_unstable();
+        for index in note_indices {
+            let Some(from) = state.chart.notes.get(index).map(|n| n.note_type.clone()) else {
+                continue;
+            };
+            if let Some(to) = toggled_note_type(&from) {
+                state.execute(EditorAction::ChangeNoteType { index, from, to });
+            }
+        }
+    }
+
+    // ── M: mirror selected notes in time (Shift+M also flips slides) ──
+    if keys.just_pressed(KeyCode::KeyM) {
+        let shift = keys.pressed(KeyCode::ShiftLeft) || keys.pressed(KeyCode::ShiftRight);
+        let selected: Vec<usize> = state
+            .selected
+            .iter()
+            .filter_map(|e| match e {
+                EditorElement::Note { index } => Some(*index),
+                _ => None,
+            })
+            .collect();
+        if selected.len() >= 2 {
+            let (new, new_indices) = mirror_notes(&state.chart.notes, &selected, shift);
+            let old = state.chart.notes.clone();
+            state.execute(EditorAction::ReplaceNotes { old, new });
+            state.selected = new_indices
+                .into_iter()
+                .map(|index| EditorElement::Note { index })
+                .collect();
+        }
+    }
+
+    // ── U: switch the timeline between beats and seconds ──
+    if keys.just_pressed(KeyCode::KeyU) {
+        state.time_display = state.time_display.toggled();
+    }
+
+    // ── O: toggle the detected-onset overlay ──
+    if keys.just_pressed(KeyCode::KeyO) {
+        if state.onsets.is_empty() {
+            state.show_toast(format!("No {} in song folder", io::ONSETS_FILE), now);
+        } else {
+            state.show_onsets = !state.show_onsets;
+        }
     }
 
     // ── Enter: place note at cursor (Chart mode) ──
     if state.mode == EditorMode::Chart && keys.just_pressed(KeyCode::Enter) {
-        let beat = state.grid_snap.snap_beat(state.cursor_beat);
+        let beat = state.snap(state.cursor_beat);
         let note = ChartNoteEntry {
             beat,
             note_type: state.note_brush.to_chart_note_type(),
@@ -519,6 +1151,21 @@ fn input_system(
                     segment: seg,
                     index: idx,
                 });
+            } else if let Some((seg, idx)) =
+                find_insert_position(&state.chart.path_segments, world_pos, 12.0)
+            {
+                // Clicked on the curve between two points — split it there
+                state.execute(EditorAction::InsertPathPoint {
+                    segment: seg,
+                    index: idx,
+                    point: (world_pos.x, world_pos.y),
+                });
+                state.dragging_cp = Some((seg, idx));
+                state.selected.clear();
+                state.selected.insert(EditorElement::PathControlPoint {
+                    segment: seg,
+                    index: idx,
+                });
             }
         }
 
@@ -535,12 +1182,190 @@ fn input_system(
             }
         }
 
-        if mouse.just_released(MouseButton::Left) {
-            state.dragging_cp = None;
+        // Dragging edits the chart directly, so catch derived state up once it's placed
+        if mouse.just_released(MouseButton::Left) && state.dragging_cp.take().is_some() {
+            state.refresh_derived();
+        }
+    }
+}
+
+/// Width of the sliding window behind the timeline's density strip.
+const DENSITY_WINDOW_BEATS: f64 = 4.0;
+
+/// Notes per beat in a `window_beats`-wide window centred on the middle of
+/// each whole beat from 0 to `total_beats`. Rests and ad-libs aren't hits,
+/// so they don't count.
+pub fn note_density(notes: &[ChartNoteEntry], total_beats: f64, window_beats: f64) -> Vec<f32> {
+    let mut beats: Vec<f64> = notes
+        .iter()
+        .filter(|n| !matches!(n.note_type, ChartNoteType::Rest | ChartNoteType::AdLib))
+        .map(|n| n.beat)
+        .collect();
+    beats.sort_by(f64::total_cmp);
+
+    let buckets = total_beats.ceil().max(0.0) as usize;
+    (0..buckets)
+        .map(|i| {
+            let center = i as f64 + 0.5;
+            let from = beats.partition_point(|&b| b < center - window_beats / 2.0);
+            let to = beats.partition_point(|&b| b < center + window_beats / 2.0);
+            ((to - from) as f64 / window_beats) as f32
+        })
+        .collect()
+}
+
+/// Beats per measure for a time signature, counted in quarter-note beats
+/// (3/4 → 3, 6/8 → 3, 7/8 → 3.5).
+fn measure_length(time_signature: (u32, u32)) -> f64 {
+    let (num, denom) = time_signature;
+    if num == 0 || denom == 0 {
+        return 4.0;
+    }
+    num as f64 * 4.0 / denom as f64
+}
+
+/// Measure (1-based) and beat within that measure (0-based) at `beat`.
+///
+/// Each timing point starts a fresh measure with its own time signature;
+/// a measure cut short by a change still counts. Before any timing point,
+/// 4/4 is assumed.
+pub fn measure_position(timing_points: &[ChartTimingPoint], beat: f64) -> (u32, f64) {
+    const EPS: f64 = 1e-6;
+    let mut measures_before = 0u32;
+    let mut section_start = 0.0;
+    let mut length = 4.0;
+
+    for tp in timing_points {
+        if tp.beat > beat + EPS {
+            break;
+        }
+        if tp.beat > section_start + EPS {
+            measures_before += ((tp.beat - section_start) / length - EPS).ceil() as u32;
+        }
+        section_start = tp.beat;
+        length = measure_length(tp.time_signature);
+    }
+
+    let offset = (beat - section_start).max(0.0);
+    let measure = (offset / length + EPS).floor();
+    let in_measure = (offset - measure * length).max(0.0);
+    (measures_before + measure as u32 + 1, in_measure)
+}
+
+/// Beat of the note closest to `beat` in either direction. `notes` is sorted by beat.
+fn nearest_note_beat(notes: &[ChartNoteEntry], beat: f64) -> Option<f64> {
+    let i = notes.partition_point(|n| n.beat < beat);
+    let after = notes.get(i).map(|n| n.beat);
+    let before = i.checked_sub(1).map(|j| notes[j].beat);
+    match (before, after) {
+        (Some(b), Some(a)) => Some(if beat - b <= a - beat { b } else { a }),
+        (b, a) => b.or(a),
+    }
+}
+
+/// Reflect `beat` across the midpoint of `first..=last`.
+fn mirror_beat(beat: f64, first: f64, last: f64) -> f64 {
+    first + last - beat
+}
+
+fn note_duration(note_type: &ChartNoteType) -> f64 {
+    match note_type {
+        ChartNoteType::Hold { duration_beats }
+        | ChartNoteType::SlideHold { duration_beats, .. }
+        | ChartNoteType::CriticalHold { duration_beats } => *duration_beats,
+        _ => 0.0,
+    }
+}
+
+fn reverse_slide(note_type: &mut ChartNoteType) {
+    match note_type {
+        ChartNoteType::Slide { direction } | ChartNoteType::SlideHold { direction, .. } => {
+            *direction = direction.opposite();
+        }
+        ChartNoteType::DualSlide { left, right } => {
+            *left = left.opposite();
+            *right = right.opposite();
+        }
+        _ => {}
+    }
+}
+
+/// Mirror the selected notes in time across the selection's span, so the
+/// earliest and latest swap. Holds flip too: a hold's tail lands where its
+/// mirrored head would be. Returns the re-sorted note list and the selected
+/// notes' new indices.
+fn mirror_notes(
+    notes: &[ChartNoteEntry],
+    selected: &[usize],
+    reverse_slides: bool,
+) -> (Vec<ChartNoteEntry>, Vec<usize>) {
+    let picked: Vec<usize> = selected.iter().copied().filter(|&i| i < notes.len()).collect();
+    let first = picked.iter().map(|&i| notes[i].beat).fold(f64::INFINITY, f64::min);
+    let last = picked
+        .iter()
+        .map(|&i| notes[i].beat + note_duration(&notes[i].note_type))
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    let mut tagged: Vec<(bool, ChartNoteEntry)> = notes
+        .iter()
+        .enumerate()
+        .map(|(i, note)| {
+            if !picked.contains(&i) {
+                return (false, note.clone());
+            }
+            let mut note = note.clone();
+            let end = note.beat + note_duration(&note.note_type);
+            note.beat = mirror_beat(end, first, last);
+            if reverse_slides {
+                reverse_slide(&mut note.note_type);
+            }
+            (true, note)
+        })
+        .collect();
+    tagged.sort_by(|a, b| a.1.beat.total_cmp(&b.1.beat));
+
+    let new_indices = tagged
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (was_selected, _))| was_selected.then_some(i))
+        .collect();
+    (tagged.into_iter().map(|(_, note)| note).collect(), new_indices)
+}
+
+/// Points drawn along the Chart-mode path mini-map.
+const PATH_PREVIEW_SAMPLES: usize = 64;
+
+/// Hold length given to a note when it's toggled into a hold.
+const DEFAULT_HOLD_BEATS: f64 = 1.0;
+
+/// The tap/hold counterpart of a note type, or None if it has none.
+fn toggled_note_type(note_type: &ChartNoteType) -> Option<ChartNoteType> {
+    match note_type {
+        ChartNoteType::Tap => Some(ChartNoteType::Hold { duration_beats: DEFAULT_HOLD_BEATS }),
+        ChartNoteType::Hold { .. } => Some(ChartNoteType::Tap),
+        ChartNoteType::Slide { direction } => Some(ChartNoteType::SlideHold {
+            direction: *direction,
+            duration_beats: DEFAULT_HOLD_BEATS,
+        }),
+        ChartNoteType::SlideHold { direction, .. } => {
+            Some(ChartNoteType::Slide { direction: *direction })
         }
+        ChartNoteType::Critical => Some(ChartNoteType::CriticalHold {
+            duration_beats: DEFAULT_HOLD_BEATS,
+        }),
+        ChartNoteType::CriticalHold { .. } => Some(ChartNoteType::Critical),
+        _ => None,
     }
 }
 
+/// Whether one more control point can be removed without breaking the spline.
+/// Counts every segment's spline points, the same ones `setup_playing` builds from.
+fn can_remove_path_point(segments: &[PathSegment]) -> bool {
+    spline_points_for(segments).len() > MIN_PATH_POINTS
+}
+
+/// Nearest draggable control point. Only CatmullRom points are editable in
+/// place; other segment shapes are changed through the segment type panel.
 fn find_nearest_cp(
     segments: &[PathSegment],
     pos: Vec2,
@@ -562,19 +1387,143 @@ fn find_nearest_cp(
     best
 }
 
+/// Polyline samples per curve segment when hit-testing the spline.
+const INSERT_SAMPLES_PER_SEGMENT: usize = 16;
+
+/// Find where a click on the spline should insert a control point. The viewport
+/// draws every segment's spline points as one Catmull-Rom spline; the click is
+/// projected onto a sampled polyline of it and the new point goes right after
+/// the curve segment's start point. Clicks on a stretch that starts inside a
+/// non-CatmullRom segment don't insert, since those shapes have no point list.
+fn find_insert_position(
+    segments: &[PathSegment],
+    pos: Vec2,
+    max_dist: f32,
+) -> Option<(usize, usize)> {
+    // Mirrors `spline_points_for`, remembering which CatmullRom point each came from
+    let mut owners: Vec<Option<(usize, usize)>> = Vec::new();
+    let mut points: Vec<Vec2> = Vec::new();
+    for (si, seg) in segments.iter().enumerate() {
+        let editable = matches!(seg, PathSegment::CatmullRom { .. });
+        for (pi, (x, y)) in seg.spline_points().into_iter().enumerate() {
+            let p = Vec2::new(x, y);
+            let owner = editable.then_some((si, pi));
+            if points.last().is_some_and(|last| last.distance(p) < 1e-3) {
+                // A shared joint belongs to whichever side can be edited
+                if let Some(last) = owners.last_mut() {
+                    if last.is_none() {
+                        *last = owner;
+                    }
+                }
+                continue;
+            }
+            owners.push(owner);
+            points.push(p);
+        }
+    }
+    if points.len() < MIN_PATH_POINTS {
+        return None;
+    }
+
+    let curve = CubicCardinalSpline::new_catmull_rom(points).to_curve().ok()?;
+    let num_segments = curve.segments().len();
+    let mut polyline = Vec::with_capacity(num_segments * INSERT_SAMPLES_PER_SEGMENT + 1);
+    for i in 0..=num_segments * INSERT_SAMPLES_PER_SEGMENT {
+        let t = i as f32 / INSERT_SAMPLES_PER_SEGMENT as f32;
+        polyline.push(curve.position(t));
+    }
+
+    let (edge, dist) = nearest_polyline_edge(&polyline, pos)?;
+    if dist > max_dist {
+        return None;
+    }
+    let global = insertion_index(edge, INSERT_SAMPLES_PER_SEGMENT);
+    let (seg, local) = owners[global - 1]?;
+    Some((seg, local + 1))
+}
+
+/// Index of the polyline edge closest to `pos`, with its distance.
+fn nearest_polyline_edge(polyline: &[Vec2], pos: Vec2) -> Option<(usize, f32)> {
+    let mut best: Option<(usize, f32)> = None;
+    for (i, pair) in polyline.windows(2).enumerate() {
+        let (a, b) = (pair[0], pair[1]);
+        let ab = b - a;
+        let len_sq = ab.length_squared();
+        let t = if len_sq > 0.0 {
+            ((pos - a).dot(ab) / len_sq).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let d = pos.distance(a + ab * t);
+        if best.is_none_or(|(_, bd)| d < bd) {
+            best = Some((i, d));
+        }
+    }
+    best
+}
+
+/// Control point index a new point takes when inserted on polyline `edge`:
+/// just after the start point of the curve segment the edge was sampled from.
+fn insertion_index(edge: usize, samples_per_segment: usize) -> usize {
+    edge / samples_per_segment + 1
+}
+
+// ─── Autosave ───────────────────────────────────────────────────────
+
+/// Write unsaved work to the autosave file every `AUTOSAVE_SECS`.
+/// The real chart file is only written by an explicit save.
+fn autosave_system(mut state: ResMut<EditorState>, time: Res<Time>) {
+    let now = time.elapsed_secs_f64();
+    if now - state.last_autosave < AUTOSAVE_SECS {
+        return;
+    }
+    state.last_autosave = now;
+    if !state.unsaved_changes {
+        return;
+    }
+    let path = io::autosave_path(&state.song_dir, state.chart.difficulty);
+    if let Err(e) = io::save_chart_ron(&state.chart, &path) {
+        warn!("Autosave to {:?} failed: {e}", path);
+    }
+}
+
 // ─── Setup / Cleanup ───────────────────────────────────────────────
 
-fn setup_editor(mut commands: Commands, editing: Option<Res<EditingSong>>) {
+fn setup_editor(
+    mut commands: Commands,
+    editing: Option<Res<EditingSong>>,
+    tuning: Res<GameplayTuning>,
+    time: Res<Time>,
+) {
     let Some(editing) = editing else {
         warn!("No song selected for editing, returning to song select");
         commands.insert_resource(NextState::<GameScreen>::Pending(GameScreen::SongSelect));
         return;
     };
-    let state = EditorState::new(
+    let mut state = EditorState::new(
         editing.chart.clone(),
         editing.metadata.clone(),
         editing.song_dir.clone(),
     );
+    state.set_tuning(tuning.clone());
+    match io::load_onsets(&editing.song_dir) {
+        Ok(Some(onsets)) => {
+            state.show_onsets = true;
+            state.onsets = onsets;
+        }
+        Ok(None) => {}
+        Err(e) => warn!("Failed to load {}: {e}", io::ONSETS_FILE),
+    }
+    let now = time.elapsed_secs_f64();
+    state.last_autosave = now;
+    if let Some(chart) = io::load_newer_autosave(&editing.song_dir, editing.difficulty) {
+        state.recovery = Some(chart);
+        state.toast = Some((
+            "Found an autosave newer than this chart. Ctrl+R to recover".to_string(),
+            now + RECOVERY_PROMPT_SECS,
+        ));
+    }
+    commands.insert_resource(scrub::load_scrub_audio(&state));
     commands.insert_resource(state);
 }
 
@@ -583,7 +1532,517 @@ fn cleanup_editor(
     editor_entities: Query<Entity, With<EditorEntity>>,
 ) {
     commands.remove_resource::<EditorState>();
+    commands.remove_resource::<scrub::ScrubAudio>();
     for entity in &editor_entities {
         commands.entity(entity).despawn();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::lint::LintKind;
+    use crate::beatmap::SlideDirection;
+
+    fn test_metadata() -> SongMetadata {
+        SongMetadata {
+            title: "Test".to_string(),
+            artist: "Test".to_string(),
+            charter: "Test".to_string(),
+            audio_file: "song.ogg".to_string(),
+            preview_start_ms: 0,
+            preview_duration_ms: 15000,
+            source: String::new(),
+            difficulties: Vec::new(),
+            offset_ms: 0,
+        }
+    }
+
+    fn empty_chart() -> ChartFile {
+        ChartFile {
+            difficulty: Difficulty::Easy,
+            difficulty_rating: 1,
+            timing_points: Vec::new(),
+            path_segments: Vec::new(),
+            notes: Vec::new(),
+            events: Vec::new(),
+            travel_beats: 3.0,
+            look_ahead_beats: 3.0,
+        }
+    }
+
+    #[test]
+    fn zoom_to_fit_shows_the_whole_chart() {
+        let mut state = EditorState::new(empty_chart(), test_metadata(), PathBuf::new());
+        state.total_beats = 96.0;
+        state.cursor_beat = 10.0;
+        state.zoom_to_fit();
+        assert!(state.timeline_view_beats >= state.total_beats);
+        let (start, end) = state.timeline_range();
+        assert!(start <= 0.0 && end >= state.total_beats);
+
+        // Still inside the scroll-zoom limits on very long charts
+        state.total_beats = 500.0;
+        state.zoom_to_fit();
+        assert_eq!(state.timeline_view_beats, MAX_TIMELINE_VIEW_BEATS);
+    }
+
+    #[test]
+    fn zoom_presets_follow_the_time_signature() {
+        let mut chart = empty_chart();
+        chart.timing_points = vec![
+            ChartTimingPoint { beat: 0.0, bpm: 120.0, time_signature: (4, 4) },
+            ChartTimingPoint { beat: 32.0, bpm: 120.0, time_signature: (3, 4) },
+        ];
+        let mut state = EditorState::new(chart, test_metadata(), PathBuf::new());
+        state.zoom_to_measures(2);
+        assert_eq!(state.timeline_view_beats, 8.0);
+        state.cursor_beat = 40.0;
+        state.zoom_to_measures(8);
+        assert_eq!(state.timeline_view_beats, 24.0);
+        // One measure of 3/4 is narrower than the zoom allows
+        state.zoom_to_measures(1);
+        assert_eq!(state.timeline_view_beats, MIN_TIMELINE_VIEW_BEATS);
+    }
+
+    #[test]
+    fn preview_marks_use_song_time() {
+        let mut chart = empty_chart();
+        chart.timing_points = vec![ChartTimingPoint { beat: 0.0, bpm: 150.0, time_signature: (4, 4) }];
+        let mut state = EditorState::new(chart, test_metadata(), PathBuf::new());
+
+        // 150 BPM: 0.4 s a beat
+        state.cursor_beat = 10.0;
+        state.mark_preview_start();
+        assert_eq!(state.metadata.preview_start_ms, 4000);
+        assert_eq!(state.metadata.preview_duration_ms, 15000);
+
+        state.cursor_beat = 35.0;
+        assert_eq!(state.mark_preview_end(), Ok(()));
+        assert_eq!(state.metadata.preview_duration_ms, 10000);
+        assert_eq!(state.preview_beats(), (10.0, 35.0));
+        assert!(state.unsaved_changes);
+
+        // An end at or before the start is refused and changes nothing
+        state.cursor_beat = 10.0;
+        assert!(state.mark_preview_end().is_err());
+        assert_eq!(state.metadata.preview_duration_ms, 10000);
+    }
+
+    #[test]
+    fn insertion_index_follows_curve_segment() {
+        // Edges 0..16 sample curve segment 0 (points 0→1), so insert at 1
+        assert_eq!(insertion_index(0, 16), 1);
+        assert_eq!(insertion_index(15, 16), 1);
+        assert_eq!(insertion_index(16, 16), 2);
+        assert_eq!(insertion_index(47, 16), 3);
+    }
+
+    #[test]
+    fn nearest_edge_projects_onto_segment() {
+        let line = [Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(10.0, 10.0)];
+        let (edge, d) = nearest_polyline_edge(&line, Vec2::new(5.0, 2.0)).unwrap();
+        assert_eq!(edge, 0);
+        assert!((d - 2.0).abs() < 1e-5);
+        let (edge, _) = nearest_polyline_edge(&line, Vec2::new(12.0, 6.0)).unwrap();
+        assert_eq!(edge, 1);
+    }
+
+    #[test]
+    fn toggled_note_types_pair_up() {
+        let hold = toggled_note_type(&ChartNoteType::Tap).unwrap();
+        assert_eq!(hold, ChartNoteType::Hold { duration_beats: DEFAULT_HOLD_BEATS });
+        assert_eq!(toggled_note_type(&hold), Some(ChartNoteType::Tap));
+        assert_eq!(toggled_note_type(&ChartNoteType::Rest), None);
+    }
+
+    #[test]
+    fn change_note_type_undo_restores_note() {
+        let mut chart = empty_chart();
+        chart.notes.push(ChartNoteEntry { beat: 4.0, note_type: ChartNoteType::Tap });
+        let action = EditorAction::ChangeNoteType {
+            index: 0,
+            from: ChartNoteType::Tap,
+            to: ChartNoteType::Hold { duration_beats: 1.0 },
+        };
+        action.apply(&mut chart);
+        assert_eq!(chart.notes[0].note_type, ChartNoteType::Hold { duration_beats: 1.0 });
+        assert_eq!(chart.notes[0].beat, 4.0);
+        action.undo(&mut chart);
+        assert_eq!(chart.notes[0].note_type, ChartNoteType::Tap);
+        assert_eq!(chart.notes[0].beat, 4.0);
+    }
+
+    #[test]
+    fn exit_needs_confirmation_only_when_unsaved() {
+        let mut state = EditorState::new(empty_chart(), test_metadata(), PathBuf::new());
+        assert!(state.confirm_exit(0.0));
+
+        state.unsaved_changes = true;
+        assert!(!state.confirm_exit(1.0));
+        assert!(state.pending_exit.is_some());
+        // Second Escape inside the window leaves
+        assert!(state.confirm_exit(2.0));
+        assert!(state.pending_exit.is_none());
+
+        // A confirmation that has expired re-arms instead of leaving
+        assert!(!state.confirm_exit(10.0));
+        assert!(!state.confirm_exit(10.0 + TOAST_SECS + 0.1));
+    }
+
+    #[test]
+    fn mirror_reflects_around_midpoint() {
+        assert_eq!(mirror_beat(4.0, 4.0, 8.0), 8.0);
+        assert_eq!(mirror_beat(8.0, 4.0, 8.0), 4.0);
+        assert_eq!(mirror_beat(5.0, 4.0, 8.0), 7.0);
+        assert_eq!(mirror_beat(6.0, 4.0, 8.0), 6.0);
+    }
+
+    #[test]
+    fn mirror_notes_makes_palindrome() {
+        let note = |beat, note_type| ChartNoteEntry { beat, note_type };
+        let notes = vec![
+            note(0.0, ChartNoteType::Tap),
+            note(4.0, ChartNoteType::Tap),
+            note(5.0, ChartNoteType::Slide { direction: SlideDirection::N }),
+            note(8.0, ChartNoteType::Critical),
+        ];
+        let (mirrored, indices) = mirror_notes(&notes, &[1, 2, 3], true);
+        let beats: Vec<f64> = mirrored.iter().map(|n| n.beat).collect();
+        assert_eq!(beats, vec![0.0, 4.0, 7.0, 8.0]);
+        assert_eq!(mirrored[1].note_type, ChartNoteType::Critical);
+        assert_eq!(
+            mirrored[2].note_type,
+            ChartNoteType::Slide { direction: SlideDirection::S }
+        );
+        assert_eq!(indices, vec![1, 2, 3]);
+        // Unselected notes are untouched
+        assert_eq!(mirrored[0].beat, 0.0);
+    }
+
+    #[test]
+    fn path_point_removal_keeps_minimum() {
+        let segment = |n: usize| PathSegment::CatmullRom {
+            points: (0..n).map(|i| (i as f32, 0.0)).collect(),
+            start_beat: 0.0,
+            end_beat: 8.0,
+        };
+        assert!(can_remove_path_point(&[segment(5)]));
+        assert!(!can_remove_path_point(&[segment(4)]));
+        assert!(!can_remove_path_point(&[]));
+        // Counted across all segments, matching how the spline is built
+        assert!(can_remove_path_point(&[segment(3), segment(2)]));
+    }
+
+    #[test]
+    fn insert_position_follows_the_whole_path() {
+        let segments = [
+            PathSegment::Linear {
+                start: (0.0, 0.0),
+                end: (300.0, 0.0),
+                start_beat: 0.0,
+                end_beat: 4.0,
+            },
+            PathSegment::CatmullRom {
+                points: vec![(300.0, 0.0), (400.0, 0.0), (500.0, 0.0), (600.0, 0.0)],
+                start_beat: 4.0,
+                end_beat: 8.0,
+            },
+        ];
+        // The line has no point list to split
+        assert_eq!(find_insert_position(&segments, Vec2::new(150.0, 2.0), 12.0), None);
+        // Between the CatmullRom segment's first two points, past the shared joint
+        assert_eq!(find_insert_position(&segments, Vec2::new(350.0, 2.0), 12.0), Some((1, 1)));
+        assert_eq!(find_insert_position(&segments, Vec2::new(450.0, 2.0), 12.0), Some((1, 2)));
+        // The line's samples count toward the spline's minimum
+        assert!(can_remove_path_point(&segments));
+    }
+
+    #[test]
+    fn insert_path_point_round_trips() {
+        let mut chart = empty_chart();
+        chart.path_segments.push(PathSegment::CatmullRom {
+            points: vec![(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0)],
+            start_beat: 0.0,
+            end_beat: 8.0,
+        });
+        let action = EditorAction::InsertPathPoint { segment: 0, index: 2, point: (1.5, 1.0) };
+        action.apply(&mut chart);
+        let PathSegment::CatmullRom { points, .. } = &chart.path_segments[0] else { panic!() };
+        assert_eq!(points[2], (1.5, 1.0));
+        assert_eq!(points.len(), 5);
+        action.undo(&mut chart);
+        let PathSegment::CatmullRom { points, .. } = &chart.path_segments[0] else { panic!() };
+        assert_eq!(points, &vec![(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0)]);
+    }
+
+    #[test]
+    fn lints_follow_the_loaded_tuning() {
+        let mut state = EditorState::new(empty_chart(), test_metadata(), PathBuf::new());
+        // 62.5 ms at the default 120 BPM: inside the default 100 ms Good window
+        state.execute(EditorAction::AddNote {
+            note: ChartNoteEntry {
+                beat: 0.0,
+                note_type: ChartNoteType::Hold { duration_beats: 0.125 },
+            },
+        });
+        assert!(state.lints.iter().any(|l| matches!(l.kind, LintKind::ShortHold { .. })));
+
+        let tuning = GameplayTuning { good_window_ms: 50.0, ..GameplayTuning::default() };
+        state.set_tuning(tuning);
+        assert!(!state.lints.iter().any(|l| matches!(l.kind, LintKind::ShortHold { .. })));
+    }
+
+    #[test]
+    fn dense_notes_raise_live_rating() {
+        let mut state = EditorState::new(empty_chart(), test_metadata(), PathBuf::new());
+        for i in 0..16 {
+            state.execute(EditorAction::AddNote {
+                note: ChartNoteEntry { beat: i as f64 * 4.0, note_type: ChartNoteType::Tap },
+            });
+        }
+        let sparse = state.rating;
+
+        for i in 0..128 {
+            state.execute(EditorAction::AddNote {
+                note: ChartNoteEntry { beat: 64.0 + i as f64 * 0.25, note_type: ChartNoteType::Tap },
+            });
+        }
+        assert!(state.rating.rating > sparse.rating);
+        assert!(state.rating.peak_nps > sparse.peak_nps);
+
+        // Undo walks the rating back down
+        for _ in 0..128 {
+            state.undo();
+        }
+        assert_eq!(state.rating, sparse);
+    }
+
+    #[test]
+    fn measure_position_follows_time_signature() {
+        let tp = |beat: f64, time_signature: (u32, u32)| ChartTimingPoint {
+            beat,
+            bpm: 120.0,
+            time_signature,
+        };
+
+        let four_four = [tp(0.0, (4, 4))];
+        assert_eq!(measure_position(&four_four, 0.0), (1, 0.0));
+        assert_eq!(measure_position(&four_four, 3.5), (1, 3.5));
+        assert_eq!(measure_position(&four_four, 4.0), (2, 0.0));
+        assert_eq!(measure_position(&four_four, 17.0), (5, 1.0));
+
+        let three_four = [tp(0.0, (3, 4))];
+        assert_eq!(measure_position(&three_four, 3.0), (2, 0.0));
+        assert_eq!(measure_position(&three_four, 7.0), (3, 1.0));
+
+        // Two bars of 4/4, then 3/4 from beat 8
+        let change = [tp(0.0, (4, 4)), tp(8.0, (3, 4))];
+        assert_eq!(measure_position(&change, 7.0), (2, 3.0));
+        assert_eq!(measure_position(&change, 8.0), (3, 0.0));
+        assert_eq!(measure_position(&change, 11.0), (4, 0.0));
+
+        // No timing points: 4/4
+        assert_eq!(measure_position(&[], 9.0), (3, 1.0));
+    }
+
+    #[test]
+    fn beat_second_conversion_tracks_bpm() {
+        let mut chart = empty_chart();
+        chart.timing_points.push(ChartTimingPoint { beat: 0.0, bpm: 120.0, time_signature: (4, 4) });
+        let mut state = EditorState::new(chart, test_metadata(), PathBuf::new());
+        assert_eq!(state.beat_to_time(4.0), 2.0);
+        assert_eq!(state.time_to_beat(2.0), 4.0);
+
+        state.chart.timing_points[0].bpm = 90.0;
+        assert_eq!(state.beat_to_time(3.0), 2.0);
+        assert_eq!(state.time_to_beat(2.0), 3.0);
+        assert!((state.time_to_beat(state.beat_to_time(7.3)) - 7.3).abs() < 1e-9);
+
+        state.time_display = TimeDisplay::Seconds;
+        assert_eq!(state.format_position(3.0), "2.000s");
+        state.time_display = TimeDisplay::Beats;
+        assert_eq!(state.format_position(3.0), "3.00");
+    }
+
+    #[test]
+    fn select_all_picks_every_note() {
+        let mut state = EditorState::new(empty_chart(), test_metadata(), PathBuf::new());
+        for i in 0..40 {
+            state.execute(EditorAction::AddNote {
+                note: ChartNoteEntry { beat: i as f64 * 0.5, note_type: ChartNoteType::Tap },
+            });
+        }
+        state.selected.insert(EditorElement::PathControlPoint { segment: 0, index: 0 });
+
+        state.select_all_notes();
+        assert_eq!(state.selected.len(), state.chart.notes.len());
+        assert!(state.selected.iter().all(|e| matches!(e, EditorElement::Note { .. })));
+
+        // In-view selection: cursor at 10 with a 16-beat view covers beats 2..=18
+        state.cursor_beat = 10.0;
+        let (start, end) = state.timeline_range();
+        state.select_notes_in(start, end);
+        assert_eq!(state.selected.len(), 33);
+    }
+
+    #[test]
+    fn bookmark_navigation_is_relative_to_cursor() {
+        let mut state = EditorState::new(empty_chart(), test_metadata(), PathBuf::new());
+        state.toggle_bookmark(16.0);
+        state.toggle_bookmark(4.0);
+        state.toggle_bookmark(32.0);
+        let beats: Vec<f64> = state.bookmarks.iter().map(|(b, _)| *b).collect();
+        assert_eq!(beats, vec![4.0, 16.0, 32.0]);
+
+        assert_eq!(state.next_bookmark(0.0), Some(4.0));
+        assert_eq!(state.prev_bookmark(0.0), None);
+        // Sitting on a bookmark skips past it
+        assert_eq!(state.next_bookmark(16.0), Some(32.0));
+        assert_eq!(state.prev_bookmark(16.0), Some(4.0));
+        assert_eq!(state.next_bookmark(20.0), Some(32.0));
+        assert_eq!(state.next_bookmark(32.0), None);
+
+        // Toggling at an existing bookmark removes it
+        state.toggle_bookmark(16.0);
+        assert_eq!(state.next_bookmark(4.0), Some(32.0));
+    }
+
+    #[test]
+    fn nudge_moves_every_selected_note_by_ms() {
+        let mut chart = empty_chart();
+        chart.timing_points.push(ChartTimingPoint { beat: 0.0, bpm: 120.0, time_signature: (4, 4) });
+        let mut state = EditorState::new(chart, test_metadata(), PathBuf::new());
+        // 5 ms at 120 BPM is a hundredth of a beat
+        assert!((state.ms_to_beats(5.0) - 0.01).abs() < 1e-12);
+
+        for beat in [0.0, 1.0, 2.0] {
+            state.execute(EditorAction::AddNote {
+                note: ChartNoteEntry { beat, note_type: ChartNoteType::Tap },
+            });
+        }
+        state.select_all_notes();
+        let undo_depth = state.undo_stack.len();
+
+        state.nudge_selected(5.0);
+        let beats: Vec<f64> = state.chart.notes.iter().map(|n| n.beat).collect();
+        assert!((beats[0] - 0.01).abs() < 1e-9);
+        assert!((beats[1] - 1.01).abs() < 1e-9);
+        assert!((beats[2] - 2.01).abs() < 1e-9);
+        assert_eq!(state.undo_stack.len(), undo_depth + 1);
+
+        // Nudging earlier clamps at beat 0
+        state.nudge_selected(-50.0);
+        assert_eq!(state.chart.notes[0].beat, 0.0);
+
+        state.undo();
+        state.undo();
+        let beats: Vec<f64> = state.chart.notes.iter().map(|n| n.beat).collect();
+        assert_eq!(beats, vec![0.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn nearest_note_search_looks_both_ways() {
+        let notes: Vec<ChartNoteEntry> = [2.0, 3.0, 3.0, 8.0]
+            .into_iter()
+            .map(|beat| ChartNoteEntry { beat, note_type: ChartNoteType::Tap })
+            .collect();
+        assert_eq!(nearest_note_beat(&notes, 2.4), Some(2.0));
+        assert_eq!(nearest_note_beat(&notes, 2.6), Some(3.0));
+        assert_eq!(nearest_note_beat(&notes, 6.0), Some(8.0));
+        assert_eq!(nearest_note_beat(&notes, 5.0), Some(3.0));
+        assert_eq!(nearest_note_beat(&notes, 0.0), Some(2.0));
+        assert_eq!(nearest_note_beat(&notes, 20.0), Some(8.0));
+        assert_eq!(nearest_note_beat(&[], 1.0), None);
+    }
+
+    #[test]
+    fn duplicate_copies_note_type_exactly() {
+        let mut state = EditorState::new(empty_chart(), test_metadata(), PathBuf::new());
+        let slide_hold = ChartNoteType::SlideHold { direction: SlideDirection::SW, duration_beats: 1.5 };
+        state.execute(EditorAction::AddNote {
+            note: ChartNoteEntry { beat: 4.0, note_type: slide_hold.clone() },
+        });
+
+        // Nothing at the cursor: no-op
+        state.cursor_beat = 2.0;
+        state.duplicate_note_at_cursor();
+        assert_eq!(state.chart.notes.len(), 1);
+
+        state.grid_snap = GridSnap::Half;
+        state.cursor_beat = 4.1; // snaps to 4.0
+        state.duplicate_note_at_cursor();
+        assert_eq!(state.chart.notes.len(), 2);
+        assert_eq!(state.chart.notes[1].beat, 4.5);
+        assert_eq!(state.chart.notes[1].note_type, slide_hold);
+        assert_eq!(state.cursor_beat, 4.5);
+
+        state.undo();
+        assert_eq!(state.chart.notes.len(), 1);
+    }
+
+    #[test]
+    fn path_preview_dot_follows_cursor_progress() {
+        let points = vec![(-400.0, 0.0), (-200.0, 150.0), (0.0, -100.0), (200.0, 150.0), (400.0, 0.0)];
+        let mut chart = empty_chart();
+        chart.path_segments = vec![PathSegment::CatmullRom {
+            points: points.clone(),
+            start_beat: 0.0,
+            end_beat: 32.0,
+        }];
+        let mut state = EditorState::new(chart, test_metadata(), PathBuf::new());
+        assert_eq!(state.path_preview_points().len(), PATH_PREVIEW_SAMPLES + 1);
+
+        state.cursor_beat = 12.0;
+        let spline = SplinePath::from_catmull_rom_points(points.into_iter().map(Vec2::from).collect());
+        let progress = (12.0 / state.total_beats) as f32;
+        assert_eq!(state.path_preview_cursor(), Some(spline.position_at_progress(progress)));
+
+        // No path, no mini-map
+        let state = EditorState::new(empty_chart(), test_metadata(), PathBuf::new());
+        assert!(state.path_preview_cursor().is_none());
+        assert!(state.path_preview_points().is_empty());
+    }
+
+    #[test]
+    fn swing_moves_snapped_off_beats() {
+        let swing = 0.66;
+        // 1/8 pairs span a quarter beat; the off-beat sits 66% through the pair
+        let snap = GridSnap::Eighth;
+        assert!((snap.snap_beat(0.16, swing) - 0.165).abs() < 1e-9);
+        assert!((snap.snap_beat(0.13, swing) - 0.165).abs() < 1e-9);
+        // On-beats stay put
+        assert_eq!(snap.snap_beat(0.02, swing), 0.0);
+        assert_eq!(snap.snap_beat(0.24, swing), 0.25);
+        assert!((snap.snap_beat(1.16, swing) - 1.165).abs() < 1e-9);
+
+        // Timeline lines move the same way
+        assert!((snap.swung(0.125, swing) - 0.165).abs() < 1e-9);
+        assert_eq!(snap.swung(0.25, swing), 0.25);
+
+        // Straight swing is the plain grid, and coarse snaps never swing
+        assert_eq!(snap.snap_beat(0.13, STRAIGHT_SWING), 0.125);
+        assert_eq!(GridSnap::Half.snap_beat(0.6, swing), 0.5);
+        assert_eq!(GridSnap::Half.swung(0.5, swing), 0.5);
+    }
+
+    #[test]
+    fn density_buckets_count_notes_per_beat() {
+        let note = |beat: f64, note_type: ChartNoteType| ChartNoteEntry { beat, note_type };
+        // One note a beat for beats 0-7, four a beat for beats 16-19, plus a rest
+        let mut notes: Vec<ChartNoteEntry> = (0..8).map(|b| note(b as f64, ChartNoteType::Tap)).collect();
+        notes.extend((0..16).map(|i| note(16.0 + i as f64 * 0.25, ChartNoteType::Tap)));
+        notes.push(note(12.0, ChartNoteType::Rest));
+
+        let density = note_density(&notes, 24.0, 1.0);
+        assert_eq!(density.len(), 24);
+        assert_eq!(density[3], 1.0);
+        assert_eq!(density[12], 0.0);
+        assert_eq!(density[17], 4.0);
+
+        // A wider window smooths the edge of the dense run
+        let smoothed = note_density(&notes, 24.0, 4.0);
+        assert_eq!(smoothed[5], 1.0);
+        assert_eq!(smoothed[15], 1.5);
+    }
+}
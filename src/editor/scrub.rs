@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+use kira::sound::static_sound::{StaticSoundData, StaticSoundHandle};
+
+use super::{EditorState, PlaybackState};
+use crate::audio::KiraContext;
+use crate::config::GameSettings;
+
+/// Length of each scrub grain.
+const GRAIN_MS: u64 = 80;
+
+/// Song audio decoded once for scrubbing, plus the grain currently playing.
+#[derive(Resource, Default)]
+pub struct ScrubAudio {
+    /// `None` when the song's audio file is missing or unreadable.
+    pub data: Option<StaticSoundData>,
+    pub grain: Option<StaticSoundHandle>,
+    pub throttle: ScrubThrottle,
+}
+
+/// Decides when the stopped cursor has moved far enough, and long enough
+/// after the last grain, to play another one. Grains never overlap.
+#[derive(Debug, Clone, Default)]
+pub struct ScrubThrottle {
+    last_beat: Option<f64>,
+    next_allowed: f64,
+}
+
+impl ScrubThrottle {
+    /// Beat to play a grain at, if any. A move made while throttled is
+    /// remembered and plays once the previous grain has finished.
+    pub fn poll(&mut self, cursor_beat: f64, now: f64) -> Option<f64> {
+        if self.last_beat.is_some_and(|last| (last - cursor_beat).abs() < 1e-6) {
+            return None;
+        }
+        if now < self.next_allowed {
+            return None;
+        }
+        self.last_beat = Some(cursor_beat);
+        self.next_allowed = now + GRAIN_MS as f64 / 1000.0;
+        Some(cursor_beat)
+    }
+
+    /// Follow the cursor without playing (e.g. during playback).
+    pub fn sync(&mut self, cursor_beat: f64) {
+        self.last_beat = Some(cursor_beat);
+    }
+}
+
+/// Decode the song once when the editor opens.
+pub fn load_scrub_audio(state: &EditorState) -> ScrubAudio {
+    let path = state.song_dir.join(&state.metadata.audio_file);
+    let data = match StaticSoundData::from_file(&path) {
+        Ok(data) => Some(data),
+        Err(e) => {
+            warn!("Scrub audio unavailable for {:?}: {}", path, e);
+            None
+        }
+    };
+    let mut throttle = ScrubThrottle::default();
+    throttle.sync(state.cursor_beat);
+    ScrubAudio { data, grain: None, throttle }
+}
+
+/// Play a short grain at the cursor whenever it moves while stopped.
+pub fn scrub_audio_system(
+    state: Res<EditorState>,
+    scrub: Option<ResMut<ScrubAudio>>,
+    settings: Res<GameSettings>,
+    time: Res<Time>,
+    mut ctx: NonSendMut<KiraContext>,
+) {
+    let Some(mut scrub) = scrub else {
+        return;
+    };
+    if state.playback == PlaybackState::Playing {
+        scrub.throttle.sync(state.cursor_beat);
+        return;
+    }
+    let Some(beat) = scrub.throttle.poll(state.cursor_beat, time.elapsed_secs_f64()) else {
+        return;
+    };
+    let Some(data) = scrub.data.clone() else {
+        return;
+    };
+
+    if let Some(ref mut grain) = scrub.grain {
+        let _ = grain.stop(Default::default());
+    }
+    scrub.grain = crate::audio::play_grain(
+        &mut ctx,
+        data,
+        state.beat_to_time(beat),
+        GRAIN_MS,
+        settings.preview_amplitude(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rapid_scrubbing_is_throttled() {
+        let mut throttle = ScrubThrottle::default();
+        throttle.sync(0.0);
+
+        // Standing still plays nothing
+        assert_eq!(throttle.poll(0.0, 0.0), None);
+
+        // First move plays at once, the next moves wait out the grain
+        assert_eq!(throttle.poll(1.0, 1.0), Some(1.0));
+        assert_eq!(throttle.poll(1.5, 1.03), None);
+        assert_eq!(throttle.poll(2.0, 1.06), None);
+
+        // Once the grain is over, the latest position plays
+        assert_eq!(throttle.poll(2.0, 1.081), Some(2.0));
+        assert_eq!(throttle.poll(2.0, 2.0), None);
+    }
+
+    #[test]
+    fn playback_movement_is_not_scrubbed() {
+        let mut throttle = ScrubThrottle::default();
+        throttle.sync(0.0);
+        for frame in 1..60 {
+            throttle.sync(frame as f64 * 0.05);
+        }
+        assert_eq!(throttle.poll(59.0 * 0.05, 10.0), None);
+    }
+}
@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use image::{Rgba, RgbaImage};
+
+use super::measure_position;
+use super::ui::{note_duration, note_visual_info};
+use crate::beatmap::ChartFile;
+
+/// Horizontal scale of the snapshot.
+const PX_PER_BEAT: u32 = 32;
+/// Height of each note lane (TAP, HOLD, SLIDE, CRIT, REST).
+const LANE_HEIGHT: u32 = 20;
+const LANES: u32 = 5;
+/// Strip above the lanes where measure lines are marked.
+const RULER_HEIGHT: u32 = 12;
+const NOTE_WIDTH: u32 = 4;
+
+const BACKGROUND: Rgba<u8> = Rgba([5, 3, 15, 255]);
+const BEAT_LINE: Rgba<u8> = Rgba([40, 30, 60, 255]);
+const MEASURE_LINE: Rgba<u8> = Rgba([150, 110, 220, 255]);
+
+/// Pixel size of a snapshot covering `total_beats`.
+pub fn snapshot_size(total_beats: f64) -> (u32, u32) {
+    let beats = total_beats.ceil().max(1.0) as u32;
+    (beats * PX_PER_BEAT, RULER_HEIGHT + LANES * LANE_HEIGHT)
+}
+
+/// Rasterize the whole chart in the timeline's lane layout.
+pub fn render_timeline(chart: &ChartFile, total_beats: f64) -> RgbaImage {
+    let (width, height) = snapshot_size(total_beats);
+    let mut img = RgbaImage::from_pixel(width, height, BACKGROUND);
+    let beat_x = |beat: f64| (beat * PX_PER_BEAT as f64).round().max(0.0) as u32;
+
+    // Beat and measure lines
+    for beat in 0..width / PX_PER_BEAT {
+        let (_, in_measure) = measure_position(&chart.timing_points, beat as f64);
+        if in_measure < 0.01 {
+            fill_rect(&mut img, beat_x(beat as f64), 0, 2, height, MEASURE_LINE);
+        } else {
+            fill_rect(&mut img, beat_x(beat as f64), RULER_HEIGHT, 1, height, BEAT_LINE);
+        }
+    }
+
+    for note in &chart.notes {
+        let (color, lane) = note_visual_info(&note.note_type);
+        let [r, g, b, a] = color.to_array();
+        let color = Rgba([r, g, b, a]);
+        let x = beat_x(note.beat);
+        let y = RULER_HEIGHT + lane as u32 * LANE_HEIGHT;
+
+        if let Some(duration) = note_duration(&note.note_type) {
+            let bar = Rgba([r / 2, g / 2, b / 2, a]);
+            let end = beat_x(note.beat + duration);
+            fill_rect(&mut img, x, y + LANE_HEIGHT / 3, end.saturating_sub(x), LANE_HEIGHT / 3, bar);
+        }
+        fill_rect(
+            &mut img,
+            x.saturating_sub(NOTE_WIDTH / 2),
+            y + LANE_HEIGHT / 6,
+            NOTE_WIDTH,
+            LANE_HEIGHT * 2 / 3,
+            color,
+        );
+    }
+
+    img
+}
+
+/// Render the chart and write it as a PNG.
+pub fn export_timeline_png(chart: &ChartFile, total_beats: f64, path: &Path) -> Result<(), String> {
+    render_timeline(chart, total_beats)
+        .save(path)
+        .map_err(|e| format!("PNG write error: {e}"))
+}
+
+/// Fill a rectangle, clipped to the image.
+fn fill_rect(img: &mut RgbaImage, x: u32, y: u32, w: u32, h: u32, color: Rgba<u8>) {
+    let x_end = (x + w).min(img.width());
+    let y_end = (y + h).min(img.height());
+    for py in y..y_end {
+        for px in x..x_end {
+            img.put_pixel(px, py, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beatmap::{ChartNoteEntry, ChartNoteType, Difficulty};
+
+    #[test]
+    fn snapshot_width_scales_with_beats() {
+        let chart = ChartFile {
+            difficulty: Difficulty::Easy,
+            difficulty_rating: 1,
+            timing_points: Vec::new(),
+            path_segments: Vec::new(),
+            notes: vec![
+                ChartNoteEntry { beat: 0.0, note_type: ChartNoteType::Tap },
+                ChartNoteEntry { beat: 60.0, note_type: ChartNoteType::Hold { duration_beats: 8.0 } },
+            ],
+            events: Vec::new(),
+            travel_beats: 3.0,
+            look_ahead_beats: 3.0,
+        };
+
+        let img = render_timeline(&chart, 64.0);
+        assert_eq!(img.dimensions(), (64 * PX_PER_BEAT, RULER_HEIGHT + LANES * LANE_HEIGHT));
+        assert_eq!(snapshot_size(64.5).0, 65 * PX_PER_BEAT);
+
+        // The tap sits in the first lane at beat 0
+        let tap = img.get_pixel(1, RULER_HEIGHT + LANE_HEIGHT / 2);
+        assert_ne!(*tap, BACKGROUND);
+    }
+}
@@ -1,10 +1,18 @@
 use bevy::prelude::*;
 use bevy_egui::{EguiContexts, egui};
 
-use crate::beatmap::ChartNoteType;
+use crate::beatmap::{ChartNoteEntry, ChartNoteType, SlideDirection};
 
-use super::io::{export_chart_json, save_chart_ron};
-use super::{EditorElement, EditorMode, EditorState, GridSnap, NoteBrush, PlaybackState};
+use super::convert::{SegmentKind, convert_segment};
+use super::io::{export_chart_json, import_chart_json, unique_export_path};
+use super::snapshot::export_timeline_png;
+use super::EditorAction;
+use super::rating::MAX_RATING;
+use super::{
+    EditorElement, EditorMode, EditorState, GridSnap, MAX_TIMELINE_VIEW_BEATS,
+    MIN_TIMELINE_VIEW_BEATS, NoteBrush, PlaybackState, STRAIGHT_SWING, TimeDisplay,
+    measure_position,
+};
 
 // ─── Y2K Color Palette ──────────────────────────────────────────────
 const NEON_PURPLE: egui::Color32 = egui::Color32::from_rgb(153, 51, 255);
@@ -16,19 +24,29 @@ const PANEL_BG: egui::Color32 = egui::Color32::from_rgba_premultiplied(15, 8, 30
 const DEEP_BG: egui::Color32 = egui::Color32::from_rgba_premultiplied(5, 3, 15, 220);
 const GRID_MAJOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(80, 60, 120, 100);
 const GRID_MINOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(40, 30, 60, 60);
+const GRID_MEASURE: egui::Color32 = egui::Color32::from_rgba_premultiplied(150, 110, 220, 180);
+const BOOKMARK: egui::Color32 = egui::Color32::from_rgb(255, 200, 40);
+const GHOST_ONSET: egui::Color32 = egui::Color32::from_rgb(255, 140, 60);
+const WARNING: egui::Color32 = egui::Color32::from_rgb(255, 60, 90);
+const SUGGESTION: egui::Color32 = egui::Color32::from_rgb(255, 170, 60);
+const PREVIEW_REGION: egui::Color32 = egui::Color32::from_rgba_premultiplied(30, 70, 40, 50);
 
 /// Main egui rendering system for the editor.
-pub fn editor_ui_system(mut contexts: EguiContexts, mut state: ResMut<EditorState>) {
+pub fn editor_ui_system(
+    mut contexts: EguiContexts,
+    mut state: ResMut<EditorState>,
+    time: Res<Time>,
+) {
     let Ok(ctx) = contexts.ctx_mut() else {
         return;
     };
     apply_y2k_theme(ctx);
 
-    menu_bar(ctx, &mut state);
+    menu_bar(ctx, &mut state, time.elapsed_secs_f64());
 
     match state.mode {
         EditorMode::Chart => chart_mode_ui(ctx, &mut state),
-        EditorMode::Path => path_mode_ui(ctx, &mut state),
+        EditorMode::Path => path_mode_ui(ctx, &mut state, time.elapsed_secs_f64()),
     }
 
     toast_overlay(ctx, &state);
@@ -65,24 +83,51 @@ fn apply_y2k_theme(ctx: &egui::Context) {
 
 // ─── Menu Bar ───────────────────────────────────────────────────────
 
-fn menu_bar(ctx: &egui::Context, state: &mut EditorState) {
+fn menu_bar(ctx: &egui::Context, state: &mut EditorState, now: f64) {
     egui::TopBottomPanel::top("editor_menu_bar").show(ctx, |ui| {
         egui::MenuBar::new().ui(ui, |ui| {
             ui.menu_button("File", |ui| {
                 if ui.button("Save (Ctrl+S)").clicked() {
-                    let path = state.song_dir.join(state.chart.difficulty.filename());
-                    match save_chart_ron(&state.chart, &path) {
-                        Ok(()) => state.unsaved_changes = false,
-                        Err(e) => error!("Save failed: {e}"),
+                    if let Err(e) = state.save() {
+                        error!("Save failed: {e}");
                     }
                     ui.close();
                 }
                 if ui.button("Export JSON...").clicked() {
+                    let target = state
+                        .song_dir
+                        .join(format!("{}.json", state.chart.difficulty.filename()));
+                    let path = unique_export_path(&target);
+                    match export_chart_json(&state.chart, &path) {
+                        Ok(()) if path != target => state.show_toast(
+                            format!("{} exists, wrote {}", target.display(), path.display()),
+                            now,
+                        ),
+                        Ok(()) => state.show_toast(format!("Wrote {}", path.display()), now),
+                        Err(e) => state.show_toast(format!("Export failed: {e}"), now),
+                    }
+                    ui.close();
+                }
+                if ui.button("Export PNG snapshot").clicked() {
+                    let filename = std::path::Path::new(state.chart.difficulty.filename())
+                        .with_extension("png");
+                    let path = unique_export_path(&state.song_dir.join(filename));
+                    match export_timeline_png(&state.chart, state.total_beats, &path) {
+                        Ok(()) => state.show_toast(format!("Wrote {}", path.display()), now),
+                        Err(e) => state.show_toast(format!("Snapshot failed: {e}"), now),
+                    }
+                    ui.close();
+                }
+                if ui.button("Import JSON...").clicked() {
                     let path = state
                         .song_dir
                         .join(format!("{}.json", state.chart.difficulty.filename()));
-                    if let Err(e) = export_chart_json(&state.chart, &path) {
-                        error!("Export failed: {e}");
+                    match import_chart_json(&path) {
+                        Ok(chart) => {
+                            state.replace_chart(chart);
+                            state.show_toast("Imported JSON", now);
+                        }
+                        Err(e) => state.show_toast(format!("Import failed: {e}"), now),
                     }
                     ui.close();
                 }
@@ -161,6 +206,8 @@ fn chart_mode_ui(ctx: &egui::Context, state: &mut EditorState) {
             metadata_panel(ui, state);
             ui.add_space(12.0);
             selection_panel(ui, state);
+            validation_panel(ui, state);
+            lint_panel(ui, state);
         });
 
     // Bottom: status bar
@@ -174,7 +221,7 @@ fn chart_mode_ui(ctx: &egui::Context, state: &mut EditorState) {
 
 // ─── Path Mode: viewport-dominant ───────────────────────────────────
 
-fn path_mode_ui(ctx: &egui::Context, state: &mut EditorState) {
+fn path_mode_ui(ctx: &egui::Context, state: &mut EditorState, now: f64) {
     // Thin bottom timeline bar
     egui::TopBottomPanel::bottom("path_timeline_bar")
         .exact_height(50.0)
@@ -203,6 +250,11 @@ fn path_mode_ui(ctx: &egui::Context, state: &mut EditorState) {
                     .color(DIM_TEXT)
                     .size(11.0),
             );
+            ui.label(
+                egui::RichText::new("Only Catmull-Rom points can be edited")
+                    .color(DIM_TEXT)
+                    .size(11.0),
+            );
 
             let n_points: usize = state
                 .chart
@@ -227,10 +279,13 @@ fn path_mode_ui(ctx: &egui::Context, state: &mut EditorState) {
                 );
             }
 
+            ui.add_space(12.0);
+            segment_type_panel(ui, state, now);
             ui.add_space(12.0);
             selection_panel(ui, state);
             ui.add_space(12.0);
             chart_settings_panel(ui, state);
+            validation_panel(ui, state);
         });
 
     // The central panel is the game viewport (rendered by lyon, not egui)
@@ -299,6 +354,16 @@ fn brush_panel(ui: &mut egui::Ui, state: &mut EditorState) {
             .color(DIM_TEXT)
             .size(10.0),
     );
+
+    ui.add_enabled_ui(state.grid_snap.swings(), |ui| {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("Swing").size(11.0));
+            ui.add(
+                egui::Slider::new(&mut state.swing_ratio, STRAIGHT_SWING..=0.75)
+                    .custom_formatter(|ratio, _| format!("{:.0}%", ratio * 100.0)),
+            );
+        });
+    });
 }
 
 fn metadata_panel(ui: &mut egui::Ui, state: &mut EditorState) {
@@ -325,15 +390,128 @@ fn metadata_panel(ui: &mut egui::Ui, state: &mut EditorState) {
                 egui::RichText::new(format!("{}", state.chart.notes.len())).color(ELECTRIC_CYAN),
             );
             ui.end_row();
+            ui.label("Rating:");
+            ui.label(
+                egui::RichText::new(format!("{} / {}", state.rating.rating, MAX_RATING)).color(NEON_PURPLE),
+            );
+            ui.end_row();
+            ui.label("NPS:");
+            ui.label(
+                egui::RichText::new(format!(
+                    "{:.2} avg  {:.2} peak",
+                    state.rating.average_nps, state.rating.peak_nps
+                ))
+                .color(DIM_TEXT),
+            );
+            ui.end_row();
         });
 }
 
+/// Reasons the chart can't be played yet. Hidden while there are none.
+fn validation_panel(ui: &mut egui::Ui, state: &EditorState) {
+    if state.validation.is_empty() {
+        return;
+    }
+    ui.add_space(12.0);
+    section_heading(ui, "WARNINGS");
+    for error in &state.validation {
+        ui.label(egui::RichText::new(error.to_string()).color(WARNING).size(11.0));
+    }
+}
+
+/// Lints shown before the list is cut short.
+const LINT_LIST_LIMIT: usize = 8;
+
+/// Spots that will play awkwardly, each with a button that moves the cursor
+/// there. Hidden while there are none.
+fn lint_panel(ui: &mut egui::Ui, state: &mut EditorState) {
+    if state.lints.is_empty() {
+        return;
+    }
+    ui.add_space(12.0);
+    section_heading(ui, "SUGGESTIONS");
+    let mut jump = None;
+    for lint in state.lints.iter().take(LINT_LIST_LIMIT) {
+        ui.horizontal(|ui| {
+            if ui.small_button(format!("{:.2}", lint.beat)).clicked() {
+                jump = Some(lint.beat);
+            }
+            ui.label(egui::RichText::new(lint.to_string()).color(SUGGESTION).size(11.0));
+        });
+    }
+    let hidden = state.lints.len().saturating_sub(LINT_LIST_LIMIT);
+    if hidden > 0 {
+        ui.label(egui::RichText::new(format!("+{hidden} more")).color(DIM_TEXT).size(11.0));
+    }
+    if let Some(beat) = jump {
+        state.cursor_beat = beat;
+    }
+}
+
+/// Shape selector for the active segment (the selected point's, else the first).
+fn segment_type_panel(ui: &mut egui::Ui, state: &mut EditorState, now: f64) {
+    section_heading(ui, "SEGMENT TYPE");
+    let index = state
+        .selected
+        .iter()
+        .find_map(|e| match e {
+            EditorElement::PathControlPoint { segment, .. } => Some(*segment),
+            _ => None,
+        })
+        .unwrap_or(0);
+    let Some(segment) = state.chart.path_segments.get(index).cloned() else {
+        ui.label(egui::RichText::new("No segments").color(DIM_TEXT).italics());
+        return;
+    };
+    let current = SegmentKind::of(&segment);
+
+    ui.label(
+        egui::RichText::new(format!("Segment {index}"))
+            .color(DIM_TEXT)
+            .size(11.0),
+    );
+    ui.horizontal_wrapped(|ui| {
+        for kind in SegmentKind::ALL {
+            if ui.selectable_label(current == kind, kind.label()).clicked() && kind != current {
+                match convert_segment(&segment, kind) {
+                    Ok(new) => {
+                        // Point indices no longer refer to the same points
+                        state.selected.clear();
+                        state.execute(EditorAction::ReplaceSegment {
+                            index,
+                            old: segment.clone(),
+                            new,
+                        });
+                    }
+                    Err(e) => state.show_toast(e, now),
+                }
+            }
+        }
+    });
+}
+
+/// Past this many elements the selection panel shows a count instead of a list.
+const SELECTION_LIST_LIMIT: usize = 8;
+
 fn selection_panel(ui: &mut egui::Ui, state: &EditorState) {
     section_heading(ui, "SELECTION");
     if state.selected.is_empty() {
         ui.label(egui::RichText::new("Nothing selected").color(DIM_TEXT).italics());
         return;
     }
+    if state.selected.len() > SELECTION_LIST_LIMIT {
+        let notes = state
+            .selected
+            .iter()
+            .filter(|e| matches!(e, EditorElement::Note { .. }))
+            .count();
+        ui.label(
+            egui::RichText::new(format!("{} selected ({notes} notes)", state.selected.len()))
+                .color(BRIGHT_TEXT)
+                .size(11.0),
+        );
+        return;
+    }
     for element in &state.selected {
         match element {
             EditorElement::Note { index } => {
@@ -442,11 +620,23 @@ fn status_bar(ctx: &egui::Context, state: &EditorState) {
                         .monospace()
                         .size(11.0),
                 );
+                let position_label = match state.time_display {
+                    TimeDisplay::Beats => "Beat",
+                    TimeDisplay::Seconds => "Time",
+                };
                 ui.label(
-                    egui::RichText::new(format!("Beat {:.2}", state.cursor_beat))
-                        .color(ELECTRIC_CYAN)
-                        .monospace()
-                        .size(11.0),
+                    egui::RichText::new(format!(
+                        "{position_label} {}",
+                        state.format_position(state.cursor_beat)
+                    ))
+                    .color(ELECTRIC_CYAN)
+                    .monospace()
+                    .size(11.0),
+                );
+                ui.label(
+                    egui::RichText::new(format!("[U] {}", state.time_display.label()))
+                        .color(DIM_TEXT)
+                        .size(10.0),
                 );
                 ui.separator();
                 ui.label(
@@ -466,6 +656,14 @@ fn status_bar(ctx: &egui::Context, state: &EditorState) {
                     EditorMode::Path => "PATH MODE",
                 };
                 ui.label(egui::RichText::new(mode_label).color(BRIGHT_TEXT).size(11.0));
+                if state.recovery.is_some() {
+                    ui.separator();
+                    ui.label(
+                        egui::RichText::new("Autosave available (Ctrl+R)")
+                            .color(NEON_GREEN)
+                            .size(11.0),
+                    );
+                }
 
                 // Enter hint in Chart mode
                 if state.mode == EditorMode::Chart {
@@ -473,7 +671,7 @@ fn status_bar(ctx: &egui::Context, state: &EditorState) {
                         egui::Layout::right_to_left(egui::Align::Center),
                         |ui| {
                             ui.label(
-                                egui::RichText::new("Enter = place note | Space = play/pause")
+                                egui::RichText::new("Enter = place note | Shift+click = place in lane | T = tap/hold | M = mirror | Space = play/pause")
                                     .color(DIM_TEXT)
                                     .size(10.0),
                             );
@@ -497,36 +695,100 @@ fn timeline_view(ui: &mut egui::Ui, state: &mut EditorState) {
 
     // Compute visible beat range
     let view_beats = state.timeline_view_beats;
-    let center_beat = state.cursor_beat;
-    let start_beat = (center_beat - view_beats / 2.0).max(0.0);
-    let end_beat = start_beat + view_beats;
+    let (start_beat, end_beat) = state.timeline_range();
 
     let beat_to_x = |beat: f64| -> f32 {
         rect.left() + ((beat - start_beat) / view_beats * rect.width() as f64) as f32
     };
 
-    // Grid lines
+    // Song-select preview window, shaded behind everything else
+    let (preview_start, preview_end) = state.preview_beats();
+    if preview_end > start_beat && preview_start < end_beat {
+        let region = egui::Rect::from_min_max(
+            egui::Pos2::new(beat_to_x(preview_start).max(rect.left()), rect.top()),
+            egui::Pos2::new(beat_to_x(preview_end).min(rect.right()), rect.bottom()),
+        );
+        painter.rect_filled(region, 0.0, PREVIEW_REGION);
+    }
+
+    // Grid lines: measure boundaries heaviest, then whole beats, then subdivisions
     let grid_div = state.grid_snap.divisor().max(1.0);
     let first_grid = (start_beat * grid_div).ceil() / grid_div;
-    let mut grid_beat = first_grid;
-    while grid_beat <= end_beat {
+    let mut straight_beat = first_grid;
+    while straight_beat <= end_beat {
+        let grid_beat = state.grid_snap.swung(straight_beat, state.swing_ratio);
         let x = beat_to_x(grid_beat);
         let is_whole = (grid_beat - grid_beat.round()).abs() < 0.01;
-        let color = if is_whole { GRID_MAJOR } else { GRID_MINOR };
+        let (measure, in_measure) = measure_position(&state.chart.timing_points, grid_beat);
+        let is_downbeat = in_measure < 0.01;
+        let (width, color) = if is_downbeat {
+            (2.5, GRID_MEASURE)
+        } else if is_whole {
+            (1.5, GRID_MAJOR)
+        } else {
+            (0.5, GRID_MINOR)
+        };
         painter.line_segment(
             [egui::Pos2::new(x, rect.top()), egui::Pos2::new(x, rect.bottom())],
-            egui::Stroke::new(if is_whole { 1.5 } else { 0.5 }, color),
+            egui::Stroke::new(width, color),
         );
-        if is_whole {
+        // In seconds mode the seconds ruler below labels the top edge instead
+        let beat_labels = state.time_display == TimeDisplay::Beats;
+        if beat_labels && is_downbeat {
             painter.text(
                 egui::Pos2::new(x + 3.0, rect.top() + 4.0),
                 egui::Align2::LEFT_TOP,
-                format!("{}", grid_beat as i32),
+                format!("M{measure}"),
                 egui::FontId::monospace(11.0),
+                GRID_MEASURE,
+            );
+        } else if beat_labels && is_whole {
+            // Beat within the measure, counted from 1
+            painter.text(
+                egui::Pos2::new(x + 3.0, rect.top() + 4.0),
+                egui::Align2::LEFT_TOP,
+                format!("{}", in_measure.round() as u32 + 1),
+                egui::FontId::monospace(10.0),
                 GRID_MAJOR,
             );
         }
-        grid_beat += 1.0 / grid_div;
+        straight_beat += 1.0 / grid_div;
+    }
+
+    // Seconds ruler: a tick and label on every whole second
+    if state.time_display == TimeDisplay::Seconds {
+        let first_second = state.beat_to_time(start_beat).ceil() as u32;
+        let last_second = state.beat_to_time(end_beat).floor() as u32;
+        for second in first_second..=last_second {
+            let x = beat_to_x(state.time_to_beat(second as f64));
+            painter.line_segment(
+                [egui::Pos2::new(x, rect.top()), egui::Pos2::new(x, rect.top() + 18.0)],
+                egui::Stroke::new(1.5, ELECTRIC_CYAN.gamma_multiply(0.6)),
+            );
+            painter.text(
+                egui::Pos2::new(x + 3.0, rect.top() + 4.0),
+                egui::Align2::LEFT_TOP,
+                format!("{second}s"),
+                egui::FontId::monospace(11.0),
+                ELECTRIC_CYAN.gamma_multiply(0.8),
+            );
+        }
+    }
+
+    // Density heat strip along the bottom edge, one cell per beat
+    let first_bucket = start_beat.floor().max(0.0) as usize;
+    for (i, &density) in state.density.iter().enumerate().skip(first_bucket) {
+        if i as f64 > end_beat {
+            break;
+        }
+        if density <= 0.0 {
+            continue;
+        }
+        let cell = egui::Rect::from_min_max(
+            egui::Pos2::new(beat_to_x(i as f64).max(rect.left()), rect.bottom() - DENSITY_STRIP_PX),
+            egui::Pos2::new(beat_to_x(i as f64 + 1.0).min(rect.right()), rect.bottom()),
+        );
+        painter.rect_filled(cell, 0.0, density_color(density));
     }
 
     // Note lanes — notes are drawn as colored rectangles on horizontal "rows"
@@ -534,6 +796,19 @@ fn timeline_view(ui: &mut egui::Ui, state: &mut EditorState) {
     let lane_height = (rect.height() / 7.0).min(40.0).max(16.0);
     let lane_start_y = rect.top() + 24.0; // Below beat numbers
 
+    // Detected onsets: faint ticks behind the notes, stronger onsets brighter
+    if state.show_onsets {
+        let first = state.onsets.partition_point(|o| o.beat < start_beat);
+        for onset in state.onsets[first..].iter().take_while(|o| o.beat <= end_beat) {
+            let x = beat_to_x(onset.beat);
+            let alpha = 0.15 + 0.35 * onset.strength.clamp(0.0, 1.0);
+            painter.line_segment(
+                [egui::Pos2::new(x, lane_start_y), egui::Pos2::new(x, rect.bottom())],
+                egui::Stroke::new(1.0, GHOST_ONSET.gamma_multiply(alpha)),
+            );
+        }
+    }
+
     for (i, note) in state.chart.notes.iter().enumerate() {
         if note.beat < start_beat - 1.0 || note.beat > end_beat + 1.0 {
             continue;
@@ -552,8 +827,9 @@ fn timeline_view(ui: &mut egui::Ui, state: &mut EditorState) {
         );
         painter.rect_filled(note_rect, 2.0, color);
 
-        // Hold/SlideHold duration bar
-        if let Some(dur) = note_duration(&note.note_type) {
+        // Hold/SlideHold duration bar (live length while its end is being dragged)
+        let dragged = state.dragging_hold.filter(|(index, _)| *index == i).map(|(_, d)| d);
+        if let Some(dur) = dragged.or(note_duration(&note.note_type)) {
             let end_x = beat_to_x(note.beat + dur);
             let bar_rect = egui::Rect::from_min_max(
                 egui::Pos2::new(x, y + lane_height * 0.35),
@@ -564,6 +840,21 @@ fn timeline_view(ui: &mut egui::Ui, state: &mut EditorState) {
                 1.0,
                 color.gamma_multiply(0.4),
             );
+            // End handle
+            painter.rect_filled(
+                egui::Rect::from_center_size(bar_rect.right_center(), egui::Vec2::new(3.0, note_height)),
+                1.0,
+                color.gamma_multiply(0.8),
+            );
+            if dragged.is_some() {
+                painter.text(
+                    egui::Pos2::new(end_x + 4.0, y + lane_height * 0.5),
+                    egui::Align2::LEFT_CENTER,
+                    format!("{dur:.2}"),
+                    egui::FontId::monospace(10.0),
+                    BRIGHT_TEXT,
+                );
+            }
         }
 
         if is_selected {
@@ -573,7 +864,7 @@ fn timeline_view(ui: &mut egui::Ui, state: &mut EditorState) {
     }
 
     // Lane labels on the left edge
-    let lane_labels = [
+    let lane_labels: [&str; TIMELINE_LANES] = [
         "TAP", "HOLD", "SLIDE", "CRIT", "REST",
     ];
     for (lane, label) in lane_labels.iter().enumerate() {
@@ -587,6 +878,25 @@ fn timeline_view(ui: &mut egui::Ui, state: &mut EditorState) {
         );
     }
 
+    // Bookmarks: a flag on the ruler with its label
+    for (beat, label) in &state.bookmarks {
+        if *beat < start_beat || *beat > end_beat {
+            continue;
+        }
+        let x = beat_to_x(*beat);
+        painter.line_segment(
+            [egui::Pos2::new(x, rect.top()), egui::Pos2::new(x, rect.bottom())],
+            egui::Stroke::new(1.0, BOOKMARK.gamma_multiply(0.35)),
+        );
+        painter.text(
+            egui::Pos2::new(x - 3.0, rect.top() + 16.0),
+            egui::Align2::RIGHT_TOP,
+            label,
+            egui::FontId::monospace(9.0),
+            BOOKMARK,
+        );
+    }
+
     // Cursor line (playhead)
     let cursor_x = beat_to_x(state.cursor_beat);
     painter.line_segment(
@@ -600,50 +910,145 @@ fn timeline_view(ui: &mut egui::Ui, state: &mut EditorState) {
     painter.text(
         egui::Pos2::new(cursor_x + 4.0, rect.bottom() - 14.0),
         egui::Align2::LEFT_BOTTOM,
-        format!("{:.2}", state.cursor_beat),
+        state.format_position(state.cursor_beat),
         egui::FontId::monospace(10.0),
         ELECTRIC_CYAN,
     );
 
-    // Click to seek
-    if response.clicked() || response.dragged() {
+    path_minimap(&painter, rect, state);
+
+    let pointer_beat = |pos: egui::Pos2| {
+        start_beat + ((pos.x - rect.left()) / rect.width()).clamp(0.0, 1.0) as f64 * view_beats
+    };
+    let pointer_lane = |pos: egui::Pos2| lane_at_y(pos.y, lane_start_y, lane_height);
+    let shift = ui.input(|i| i.modifiers.shift);
+
+    // Drag a hold's end to resize it, committed as one undo step on release
+    if response.drag_started_by(egui::PointerButton::Primary) {
+        if let Some(pos) = response.interact_pointer_pos() {
+            let tolerance = HOLD_HANDLE_PX as f64 / rect.width() as f64 * view_beats;
+            state.dragging_hold =
+                hit_test_hold_end(&state.chart.notes, pointer_beat(pos), pointer_lane(pos), tolerance)
+                    .and_then(|i| Some((i, note_duration(&state.chart.notes[i].note_type)?)));
+        }
+    }
+    if let Some((index, _)) = state.dragging_hold {
+        if let (Some(pos), Some(note)) = (response.interact_pointer_pos(), state.chart.notes.get(index)) {
+            let duration =
+                dragged_hold_duration(
+                    note.beat,
+                    pointer_beat(pos),
+                    state.grid_snap,
+                    state.swing_ratio,
+                    state.grid_step(),
+                );
+            state.dragging_hold = Some((index, duration));
+        }
+        if response.drag_stopped() {
+            if let Some((index, duration)) = state.dragging_hold.take() {
+                if let Some(from) = state.chart.notes.get(index).map(|n| n.note_type.clone()) {
+                    let to = with_duration(&from, duration);
+                    if to != from {
+                        state.execute(EditorAction::ChangeNoteType { index, from, to });
+                    }
+                }
+            }
+        }
+    }
+
+    // Shift-click in a lane places that lane's note type at the snapped beat
+    if response.clicked() && shift {
+        if let Some(pos) = response.interact_pointer_pos() {
+            if let Some(note_type) = pointer_lane(pos).and_then(|lane| lane_note_type(lane, &state.note_brush)) {
+                let beat = state.snap(pointer_beat(pos));
+                state.execute(EditorAction::AddNote { note: ChartNoteEntry { beat, note_type } });
+            }
+        }
+    }
+
+    // Click to seek (primary button only; right-click opens the note menu)
+    if (response.clicked() && !shift)
+        || (response.dragged_by(egui::PointerButton::Primary) && state.dragging_hold.is_none())
+    {
         if let Some(pos) = response.interact_pointer_pos() {
             let frac = ((pos.x - rect.left()) / rect.width()).clamp(0.0, 1.0) as f64;
             let beat = start_beat + frac * view_beats;
-            state.cursor_beat = state.grid_snap.snap_beat(beat);
+            state.cursor_beat = state.snap(beat);
         }
     }
 
     // Click on a note to select it
-    if response.clicked() {
+    if response.clicked() && !shift {
         if let Some(pos) = response.interact_pointer_pos() {
-            let click_beat = start_beat
-                + ((pos.x - rect.left()) / rect.width()).clamp(0.0, 1.0) as f64 * view_beats;
-            // Find nearest note within ~0.3 beats
-            let mut best = None;
-            let mut best_dist = 0.3;
-            for (i, note) in state.chart.notes.iter().enumerate() {
-                let d = (note.beat - click_beat).abs();
-                if d < best_dist {
-                    best_dist = d;
-                    best = Some(i);
-                }
-            }
-            if let Some(idx) = best {
+            if let Some(idx) = hit_test_note(&state.chart.notes, pointer_beat(pos), None) {
                 state.selected.clear();
                 state.selected.insert(EditorElement::Note { index: idx });
             }
         }
     }
 
+    // Right-click: select the note under the pointer and open its menu
+    if response.secondary_clicked() {
+        state.context_note = response.interact_pointer_pos().and_then(|pos| {
+            hit_test_note(&state.chart.notes, pointer_beat(pos), pointer_lane(pos))
+        });
+        if let Some(index) = state.context_note {
+            state.selected.clear();
+            state.selected.insert(EditorElement::Note { index });
+        }
+    }
+    response.context_menu(|ui| note_context_menu(ui, state));
+
     // Scroll wheel to zoom timeline
     let scroll = ui.input(|i| i.raw_scroll_delta.y);
     if scroll.abs() > 0.1 {
         let zoom_factor = if scroll > 0.0 { 0.85 } else { 1.18 };
-        state.timeline_view_beats = (state.timeline_view_beats * zoom_factor).clamp(4.0, 128.0);
+        state.timeline_view_beats = (state.timeline_view_beats * zoom_factor)
+            .clamp(MIN_TIMELINE_VIEW_BEATS, MAX_TIMELINE_VIEW_BEATS);
     }
 }
 
+/// Mini-map of the note path in the timeline's top-right corner, with the
+/// cursor's position on it.
+fn path_minimap(painter: &egui::Painter, timeline: egui::Rect, state: &EditorState) {
+    let points = state.path_preview_points();
+    let Some(cursor) = state.path_preview_cursor() else {
+        return;
+    };
+    let frame = egui::Rect::from_min_size(
+        egui::Pos2::new(timeline.right() - MINIMAP_SIZE.x - 8.0, timeline.top() + 24.0),
+        MINIMAP_SIZE,
+    );
+    painter.rect_filled(frame, 3.0, PANEL_BG);
+    painter.rect_stroke(frame, 3.0, egui::Stroke::new(1.0, GRID_MAJOR), egui::StrokeKind::Inside);
+
+    // Fit the path's bounds into the frame, keeping its aspect ratio (world y is up)
+    let (min, max) = points.iter().fold((points[0], points[0]), |(lo, hi), p| (lo.min(*p), hi.max(*p)));
+    let inner = frame.shrink(8.0);
+    let span = (max - min).max(Vec2::splat(1.0));
+    let scale = (inner.width() / span.x).min(inner.height() / span.y);
+    let center = (min + max) / 2.0;
+    let to_screen = |p: Vec2| inner.center() + egui::Vec2::new(p.x - center.x, center.y - p.y) * scale;
+
+    let line: Vec<egui::Pos2> = points.iter().map(|p| to_screen(*p)).collect();
+    painter.add(egui::Shape::line(line, egui::Stroke::new(1.5, ELECTRIC_CYAN.gamma_multiply(0.7))));
+    painter.circle_filled(to_screen(cursor), 3.5, BRIGHT_TEXT);
+}
+
+/// Height of the density strip under the timeline lanes.
+const DENSITY_STRIP_PX: f32 = 5.0;
+/// Notes per beat that reads as fully red on the density strip.
+const DENSITY_HOT: f32 = 4.0;
+
+/// Green for sparse stretches through to red at `DENSITY_HOT` notes per beat.
+fn density_color(density: f32) -> egui::Color32 {
+    let t = (density / DENSITY_HOT).clamp(0.0, 1.0);
+    egui::Color32::from_rgb((255.0 * t) as u8, (220.0 * (1.0 - t)) as u8 + 35, 60)
+}
+
+/// Size of the Chart-mode path mini-map.
+const MINIMAP_SIZE: egui::Vec2 = egui::Vec2::new(160.0, 100.0);
+
 /// Compact horizontal timeline for Path mode — just shows cursor position.
 fn compact_timeline(ui: &mut egui::Ui, state: &mut EditorState) {
     ui.horizontal(|ui| {
@@ -679,6 +1084,85 @@ fn compact_timeline(ui: &mut egui::Ui, state: &mut EditorState) {
     });
 }
 
+/// Delete / duplicate / retype / edit the note chosen by a right-click.
+fn note_context_menu(ui: &mut egui::Ui, state: &mut EditorState) {
+    let Some(index) = state.context_note.filter(|&i| i < state.chart.notes.len()) else {
+        ui.label(egui::RichText::new("No note here").color(DIM_TEXT).italics());
+        return;
+    };
+    let note = state.chart.notes[index].clone();
+    ui.label(
+        egui::RichText::new(format!("Note #{index} — beat {:.3}", note.beat))
+            .color(BRIGHT_TEXT)
+            .size(11.0),
+    );
+    ui.separator();
+
+    if ui.button("Delete").clicked() {
+        state.execute(EditorAction::RemoveNote { index, note });
+        state.selected.clear();
+        state.context_note = None;
+        ui.close();
+        return;
+    }
+    if ui.button("Duplicate").clicked() {
+        state.duplicate_note(index);
+        ui.close();
+    }
+
+    ui.menu_button("Change Type", |ui| {
+        let duration = note_duration(&note.note_type).unwrap_or(1.0);
+        let direction = slide_direction(&note.note_type).unwrap_or(SlideDirection::E);
+        let options = [
+            ("Tap", ChartNoteType::Tap),
+            ("Hold", ChartNoteType::Hold { duration_beats: duration }),
+            ("Slide", ChartNoteType::Slide { direction }),
+            ("Slide Hold", ChartNoteType::SlideHold { direction, duration_beats: duration }),
+            ("Critical", ChartNoteType::Critical),
+            ("Critical Hold", ChartNoteType::CriticalHold { duration_beats: duration }),
+            ("Ad-Lib", ChartNoteType::AdLib),
+            ("Rest", ChartNoteType::Rest),
+        ];
+        for (label, to) in options {
+            let current = std::mem::discriminant(&to) == std::mem::discriminant(&note.note_type);
+            if ui.add_enabled(!current, egui::Button::new(label)).clicked() {
+                let from = note.note_type.clone();
+                state.execute(EditorAction::ChangeNoteType { index, from, to });
+                ui.close();
+            }
+        }
+    });
+
+    // Properties: hold length in grid steps, slide direction
+    if let Some(duration) = note_duration(&note.note_type) {
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(format!("Length {duration:.2}"));
+            let step = state.grid_step();
+            for (label, delta) in [("-", -step), ("+", step)] {
+                if ui.small_button(label).clicked() {
+                    let to = with_duration(&note.note_type, (duration + delta).max(step));
+                    let from = note.note_type.clone();
+                    state.execute(EditorAction::ChangeNoteType { index, from, to });
+                }
+            }
+        });
+    }
+    if let Some(current) = slide_direction(&note.note_type) {
+        ui.separator();
+        ui.horizontal_wrapped(|ui| {
+            for direction in SLIDE_DIRECTIONS {
+                let label = format!("{direction:?}");
+                if ui.selectable_label(direction == current, label).clicked() && direction != current {
+                    let to = with_direction(&note.note_type, direction);
+                    let from = note.note_type.clone();
+                    state.execute(EditorAction::ChangeNoteType { index, from, to });
+                }
+            }
+        });
+    }
+}
+
 // ─── Toast Overlay ──────────────────────────────────────────────────
 
 fn toast_overlay(ctx: &egui::Context, state: &EditorState) {
@@ -713,7 +1197,7 @@ fn section_heading(ui: &mut egui::Ui, label: &str) {
 }
 
 /// Returns (color, lane_index) for a note type.
-fn note_visual_info(note_type: &ChartNoteType) -> (egui::Color32, usize) {
+pub(super) fn note_visual_info(note_type: &ChartNoteType) -> (egui::Color32, usize) {
     match note_type {
         ChartNoteType::Tap => (egui::Color32::from_rgb(255, 102, 178), 0),
         ChartNoteType::Hold { .. } => (egui::Color32::from_rgb(255, 217, 38), 1),
@@ -723,6 +1207,10 @@ fn note_visual_info(note_type: &ChartNoteType) -> (egui::Color32, usize) {
         ChartNoteType::Critical | ChartNoteType::CriticalHold { .. } => {
             (egui::Color32::from_rgb(255, 242, 204), 3)
         }
+        // Hidden in play, so drawn as a faded Tap
+        ChartNoteType::AdLib => {
+            (egui::Color32::from_rgba_premultiplied(128, 51, 89, 128), 0)
+        }
         ChartNoteType::Rest => {
             (egui::Color32::from_rgba_premultiplied(230, 230, 255, 120), 4)
         }
@@ -733,8 +1221,122 @@ fn note_visual_info(note_type: &ChartNoteType) -> (egui::Color32, usize) {
     }
 }
 
+/// Timeline lanes: TAP, HOLD, SLIDE, CRIT, REST.
+const TIMELINE_LANES: usize = 5;
+
+/// Lane under a pointer at `y`, if it's inside the lane rows.
+fn lane_at_y(y: f32, lane_start_y: f32, lane_height: f32) -> Option<usize> {
+    let lane = ((y - lane_start_y) / lane_height).floor();
+    (0.0..TIMELINE_LANES as f32).contains(&lane).then_some(lane as usize)
+}
+
+/// Note type a Shift-click in `lane` places. Keeps the brush's length or
+/// direction when the brush belongs to that lane.
+fn lane_note_type(lane: usize, brush: &NoteBrush) -> Option<ChartNoteType> {
+    let brush_type = brush.to_chart_note_type();
+    if note_visual_info(&brush_type).1 == lane {
+        return Some(brush_type);
+    }
+    match lane {
+        0 => Some(ChartNoteType::Tap),
+        1 => Some(ChartNoteType::Hold { duration_beats: super::DEFAULT_HOLD_BEATS }),
+        2 => Some(ChartNoteType::Slide { direction: SlideDirection::E }),
+        3 => Some(ChartNoteType::Critical),
+        4 => Some(ChartNoteType::Rest),
+        _ => None,
+    }
+}
+
+/// How far (in beats) a click may land from a note and still hit it.
+const NOTE_HIT_BEATS: f64 = 0.3;
+
+const SLIDE_DIRECTIONS: [SlideDirection; 8] = [
+    SlideDirection::N,
+    SlideDirection::NE,
+    SlideDirection::E,
+    SlideDirection::SE,
+    SlideDirection::S,
+    SlideDirection::SW,
+    SlideDirection::W,
+    SlideDirection::NW,
+];
+
+/// Grab distance (px) around the end of a hold bar.
+const HOLD_HANDLE_PX: f32 = 6.0;
+
+/// Hold whose end lies within `tolerance` beats of `beat`, optionally only in `lane`.
+fn hit_test_hold_end(
+    notes: &[ChartNoteEntry],
+    beat: f64,
+    lane: Option<usize>,
+    tolerance: f64,
+) -> Option<usize> {
+    notes
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| lane.is_none_or(|l| note_visual_info(&n.note_type).1 == l))
+        .filter_map(|(i, n)| Some((i, (n.beat + note_duration(&n.note_type)? - beat).abs())))
+        .filter(|&(_, d)| d <= tolerance)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+/// Duration for a hold starting at `note_beat` whose end is dragged to
+/// `pointer_beat`: the end snaps to the grid and the length stays at least `min`.
+fn dragged_hold_duration(
+    note_beat: f64,
+    pointer_beat: f64,
+    snap: GridSnap,
+    swing_ratio: f64,
+    min: f64,
+) -> f64 {
+    (snap.snap_beat(pointer_beat, swing_ratio) - note_beat).max(min)
+}
+
+/// Nearest note to `beat` within `NOTE_HIT_BEATS`, optionally only in `lane`.
+fn hit_test_note(notes: &[ChartNoteEntry], beat: f64, lane: Option<usize>) -> Option<usize> {
+    notes
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| lane.is_none_or(|l| note_visual_info(&n.note_type).1 == l))
+        .map(|(i, n)| (i, (n.beat - beat).abs()))
+        .filter(|&(_, d)| d < NOTE_HIT_BEATS)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+fn slide_direction(note_type: &ChartNoteType) -> Option<SlideDirection> {
+    match note_type {
+        ChartNoteType::Slide { direction } | ChartNoteType::SlideHold { direction, .. } => {
+            Some(*direction)
+        }
+        _ => None,
+    }
+}
+
+fn with_duration(note_type: &ChartNoteType, duration_beats: f64) -> ChartNoteType {
+    match note_type {
+        ChartNoteType::Hold { .. } => ChartNoteType::Hold { duration_beats },
+        ChartNoteType::SlideHold { direction, .. } => {
+            ChartNoteType::SlideHold { direction: *direction, duration_beats }
+        }
+        ChartNoteType::CriticalHold { .. } => ChartNoteType::CriticalHold { duration_beats },
+        other => other.clone(),
+    }
+}
+
+fn with_direction(note_type: &ChartNoteType, direction: SlideDirection) -> ChartNoteType {
+    match note_type {
+        ChartNoteType::Slide { .. } => ChartNoteType::Slide { direction },
+        ChartNoteType::SlideHold { duration_beats, .. } => {
+            ChartNoteType::SlideHold { direction, duration_beats: *duration_beats }
+        }
+        other => other.clone(),
+    }
+}
+
 /// Returns duration in beats for note types that have one.
-fn note_duration(note_type: &ChartNoteType) -> Option<f64> {
+pub(super) fn note_duration(note_type: &ChartNoteType) -> Option<f64> {
     match note_type {
         ChartNoteType::Hold { duration_beats } => Some(*duration_beats),
         ChartNoteType::SlideHold { duration_beats, .. } => Some(*duration_beats),
@@ -742,3 +1344,77 @@ fn note_duration(note_type: &ChartNoteType) -> Option<f64> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn right_click_hits_note_in_its_lane() {
+        let notes = vec![
+            ChartNoteEntry { beat: 4.0, note_type: ChartNoteType::Tap },
+            ChartNoteEntry { beat: 4.1, note_type: ChartNoteType::Hold { duration_beats: 1.0 } },
+            ChartNoteEntry { beat: 6.0, note_type: ChartNoteType::Slide { direction: SlideDirection::N } },
+        ];
+
+        // Lane picks between notes at nearly the same beat
+        assert_eq!(hit_test_note(&notes, 4.1, Some(0)), Some(0));
+        assert_eq!(hit_test_note(&notes, 4.0, Some(1)), Some(1));
+        // Without a lane the closest note wins
+        assert_eq!(hit_test_note(&notes, 4.08, None), Some(1));
+        // Too far away, or an empty lane, hits nothing
+        assert_eq!(hit_test_note(&notes, 5.0, None), None);
+        assert_eq!(hit_test_note(&notes, 6.0, Some(3)), None);
+        assert_eq!(hit_test_note(&notes, 6.2, Some(2)), Some(2));
+    }
+
+    #[test]
+    fn shift_click_places_the_lane_type() {
+        // Lanes start 24 px down and are 20 px tall
+        let lane = |y| lane_at_y(y, 24.0, 20.0);
+        assert_eq!(lane(10.0), None);
+        assert_eq!(lane(30.0), Some(0));
+        assert_eq!(lane(50.0), Some(1));
+        assert_eq!(lane(79.9), Some(2));
+        assert_eq!(lane(90.0), Some(3));
+        assert_eq!(lane(110.0), Some(4));
+        assert_eq!(lane(130.0), None);
+
+        let brush = NoteBrush::Tap;
+        assert_eq!(lane_note_type(0, &brush), Some(ChartNoteType::Tap));
+        assert_eq!(
+            lane_note_type(1, &brush),
+            Some(ChartNoteType::Hold { duration_beats: 1.0 })
+        );
+        assert_eq!(
+            lane_note_type(2, &brush),
+            Some(ChartNoteType::Slide { direction: SlideDirection::E })
+        );
+        assert_eq!(lane_note_type(3, &brush), Some(ChartNoteType::Critical));
+
+        // A brush in the clicked lane keeps its settings
+        let brush = NoteBrush::Slide { direction: SlideDirection::SW };
+        assert_eq!(
+            lane_note_type(2, &brush),
+            Some(ChartNoteType::Slide { direction: SlideDirection::SW })
+        );
+    }
+
+    #[test]
+    fn dragging_hold_end_snaps_duration() {
+        let notes = vec![
+            ChartNoteEntry { beat: 2.0, note_type: ChartNoteType::Tap },
+            ChartNoteEntry { beat: 4.0, note_type: ChartNoteType::Hold { duration_beats: 2.0 } },
+        ];
+        // The end of the hold is at beat 6
+        assert_eq!(hit_test_hold_end(&notes, 6.05, Some(1), 0.1), Some(1));
+        assert_eq!(hit_test_hold_end(&notes, 6.05, None, 0.1), Some(1));
+        assert_eq!(hit_test_hold_end(&notes, 5.0, Some(1), 0.1), None);
+        assert_eq!(hit_test_hold_end(&notes, 6.0, Some(0), 0.1), None);
+
+        assert_eq!(dragged_hold_duration(4.0, 7.6, GridSnap::Half, STRAIGHT_SWING, 0.5), 3.5);
+        assert_eq!(dragged_hold_duration(4.0, 7.6, GridSnap::Whole, STRAIGHT_SWING, 1.0), 4.0);
+        // Dragging past the head clamps to the minimum
+        assert_eq!(dragged_hold_duration(4.0, 2.0, GridSnap::Quarter, STRAIGHT_SWING, 0.25), 0.25);
+    }
+}
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy_prototype_lyon::prelude::*;
 
-use crate::beatmap::PathSegment;
+use crate::beatmap::{PathSegment, spline_points_for};
 use crate::path::SplinePath;
 
 use super::{EditorElement, EditorEntity, EditorState};
@@ -50,15 +50,8 @@ pub fn render_viewport(
         commands.entity(e).despawn();
     }
 
-    // Collect all CatmullRom points
-    let mut all_points: Vec<Vec2> = Vec::new();
-    for seg in &state.chart.path_segments {
-        if let PathSegment::CatmullRom { points, .. } = seg {
-            for &(x, y) in points {
-                all_points.push(Vec2::new(x, y));
-            }
-        }
-    }
+    // Collect spline points from every segment, same as gameplay
+    let all_points = spline_points_for(&state.chart.path_segments);
 
     // Draw the spline curve if we have enough points
     if all_points.len() >= 4 {
@@ -0,0 +1,353 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::GameSet;
+use crate::audio::{KiraContext, fade_music_to};
+use crate::beatmap::SelectedSong;
+use crate::judgment::{Judgment, JudgmentResult};
+use crate::state::{GameScreen, InGame};
+
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameplayMods>()
+            .add_systems(OnEnter(InGame), init_health)
+            .add_systems(
+                Update,
+                (update_health, check_failure).chain().in_set(GameSet::UpdateScore),
+            );
+    }
+}
+
+// --- Health constants ---
+
+/// Health lost per Miss, out of 1.0, at the lowest and highest ratings.
+const MISS_DRAIN_RANGE: (f32, f32) = (0.05, 0.14);
+/// Health regained per non-Miss judgment, at the lowest and highest ratings.
+const HIT_RESTORE_RANGE: (f32, f32) = (0.015, 0.006);
+/// Health needed at the end of the song to clear, at the lowest and highest ratings.
+const CLEAR_THRESHOLD_RANGE: (f32, f32) = (0.0, 0.25);
+/// Highest `difficulty_rating` chart_gen assigns; harder charts scale like it.
+const MAX_DIFFICULTY_RATING: u32 = 20;
+/// No-Fail keeps health just above empty so the bar never reads as failed.
+const NO_FAIL_FLOOR: f32 = 0.01;
+/// Music fade-out when a run fails.
+const FAIL_FADE: Duration = Duration::from_millis(400);
+
+// --- Mods ---
+
+/// Mods that change how a run can fail. Only one can be active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailMod {
+    /// Health never empties; the song always plays to the end.
+    NoFail,
+    /// The first Miss ends the run.
+    SuddenDeath,
+}
+
+impl FailMod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FailMod::NoFail => "NO FAIL",
+            FailMod::SuddenDeath => "SUDDEN DEATH",
+        }
+    }
+
+    /// Short tag used in high-score keys and replays.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            FailMod::NoFail => "NF",
+            FailMod::SuddenDeath => "SD",
+        }
+    }
+}
+
+/// Gameplay modifiers picked on song select, kept across runs.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameplayMods {
+    pub fail: Option<FailMod>,
+}
+
+impl GameplayMods {
+    /// Step through off → No-Fail → Sudden-Death → off.
+    pub fn cycle_fail(&mut self) {
+        self.fail = match self.fail {
+            None => Some(FailMod::NoFail),
+            Some(FailMod::NoFail) => Some(FailMod::SuddenDeath),
+            Some(FailMod::SuddenDeath) => None,
+        };
+    }
+
+    /// Tags of the active mods, e.g. `["SD"]`. Empty for a vanilla run.
+    pub fn tags(&self) -> Vec<String> {
+        self.fail.iter().map(|m| m.tag().to_string()).collect()
+    }
+
+    /// Mods from their tags, as stored in replays. Unknown tags are ignored.
+    pub fn from_tags(tags: &[String]) -> Self {
+        let fail = [FailMod::NoFail, FailMod::SuddenDeath]
+            .into_iter()
+            .find(|m| tags.iter().any(|t| t == m.tag()));
+        Self { fail }
+    }
+
+    /// Display name of the active mods, if any.
+    pub fn label(&self) -> Option<&'static str> {
+        self.fail.map(|m| m.label())
+    }
+}
+
+// --- Difficulty scaling ---
+
+/// How hard the health bar is on one chart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HealthTuning {
+    pub miss_drain: f32,
+    pub hit_restore: f32,
+    /// Health the song must end with to count as cleared.
+    pub clear_threshold: f32,
+}
+
+impl HealthTuning {
+    /// Scale drain, restore and clear threshold from Easy-forgiving at rating 1
+    /// to Expert-punishing at the top rating.
+    pub fn for_rating(rating: u32) -> Self {
+        let top = (MAX_DIFFICULTY_RATING - 1) as f32;
+        let t = (rating.clamp(1, MAX_DIFFICULTY_RATING) - 1) as f32 / top;
+        let lerp = |(low, high): (f32, f32)| low + (high - low) * t;
+        Self {
+            miss_drain: lerp(MISS_DRAIN_RANGE),
+            hit_restore: lerp(HIT_RESTORE_RANGE),
+            clear_threshold: lerp(CLEAR_THRESHOLD_RANGE),
+        }
+    }
+}
+
+impl Default for HealthTuning {
+    /// Flat values for runs without a chart rating.
+    fn default() -> Self {
+        Self {
+            miss_drain: 0.08,
+            hit_restore: 0.01,
+            clear_threshold: 0.0,
+        }
+    }
+}
+
+// --- Resource ---
+
+#[derive(Resource, Debug, Clone)]
+pub struct Health {
+    /// 0.0 (empty) to 1.0 (full).
+    pub value: f32,
+    /// Set once the run has failed; results treat the run as not cleared.
+    pub failed: bool,
+    pub fail_mod: Option<FailMod>,
+    pub tuning: HealthTuning,
+}
+
+impl Health {
+    pub fn new(mods: &GameplayMods, tuning: HealthTuning) -> Self {
+        Self {
+            value: 1.0,
+            failed: false,
+            fail_mod: mods.fail,
+            tuning,
+        }
+    }
+
+    /// Whether the run counts as cleared: never failed, and ended with enough
+    /// health for the chart. No-Fail only asks that the song was played out.
+    pub fn cleared(&self) -> bool {
+        if self.failed {
+            return false;
+        }
+        self.fail_mod == Some(FailMod::NoFail) || self.value >= self.tuning.clear_threshold
+    }
+
+    /// Apply one judgment. Returns true if this judgment failed the run.
+    pub fn apply(&mut self, judgment: Judgment) -> bool {
+        if self.failed {
+            return false;
+        }
+        if judgment == Judgment::Miss {
+            if self.fail_mod == Some(FailMod::SuddenDeath) {
+                self.value = 0.0;
+            } else {
+                self.value -= self.tuning.miss_drain;
+            }
+        } else {
+            self.value += self.tuning.hit_restore;
+        }
+
+        let floor = if self.fail_mod == Some(FailMod::NoFail) {
+            NO_FAIL_FLOOR
+        } else {
+            0.0
+        };
+        self.value = self.value.clamp(floor, 1.0);
+        self.failed = self.value <= 0.0;
+        self.failed
+    }
+}
+
+// --- Systems ---
+
+/// Runs on entering `InGame` rather than `Playing`, so resuming from pause
+/// doesn't refill the bar.
+pub(crate) fn init_health(mut commands: Commands, mods: Res<GameplayMods>, selected: Option<Res<SelectedSong>>) {
+    let tuning = match selected {
+        Some(selected) => {
+            let rating = selected.chart.difficulty_rating;
+            let tuning = HealthTuning::for_rating(rating);
+            info!(
+                "Health for rating {rating}: -{:.3} per miss, +{:.3} per hit, clear at {:.2}",
+                tuning.miss_drain, tuning.hit_restore, tuning.clear_threshold
+            );
+            tuning
+        }
+        None => HealthTuning::default(),
+    };
+    commands.insert_resource(Health::new(&mods, tuning));
+}
+
+fn update_health(health: Option<ResMut<Health>>, mut results: MessageReader<JudgmentResult>) {
+    let Some(mut health) = health else {
+        results.clear();
+        return;
+    };
+    for result in results.read() {
+        if health.apply(result.judgment) {
+            info!("Run failed");
+            break;
+        }
+    }
+}
+
+/// Cut the run short as soon as health empties.
+fn check_failure(
+    health: Option<Res<Health>>,
+    ctx: Option<NonSendMut<KiraContext>>,
+    current_state: Res<State<GameScreen>>,
+    mut next_state: ResMut<NextState<GameScreen>>,
+) {
+    let Some(health) = health else { return };
+    if !health.failed || *current_state.get() != GameScreen::Playing {
+        return;
+    }
+    if let Some(mut ctx) = ctx {
+        fade_music_to(&mut ctx, 0.0, FAIL_FADE);
+    }
+    next_state.set(GameScreen::Results);
+}
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::state::app::StatesPlugin;
+
+    use crate::state::GameStatePlugin;
+
+    fn health_app(mods: GameplayMods) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, GameStatePlugin, HealthPlugin))
+            .add_message::<JudgmentResult>()
+            .insert_resource(mods);
+        app.world_mut()
+            .resource_mut::<NextState<GameScreen>>()
+            .set(GameScreen::Playing);
+        app.update();
+        app
+    }
+
+    fn judge(app: &mut App, judgment: Judgment) {
+        app.world_mut().write_message(JudgmentResult {
+            judgment,
+            position: Vec2::ZERO,
+            weight: 1.0,
+        });
+        app.update();
+        app.update();
+    }
+
+    fn screen(app: &App) -> GameScreen {
+        app.world().resource::<State<GameScreen>>().get().clone()
+    }
+
+    #[test]
+    fn sudden_death_fails_on_first_miss() {
+        let mut app = health_app(GameplayMods {
+            fail: Some(FailMod::SuddenDeath),
+        });
+        judge(&mut app, Judgment::Great);
+        assert_eq!(screen(&app), GameScreen::Playing);
+
+        judge(&mut app, Judgment::Miss);
+        assert!(app.world().resource::<Health>().failed);
+        assert_eq!(screen(&app), GameScreen::Results);
+    }
+
+    #[test]
+    fn no_fail_never_fails() {
+        let mut app = health_app(GameplayMods {
+            fail: Some(FailMod::NoFail),
+        });
+        for _ in 0..50 {
+            judge(&mut app, Judgment::Miss);
+        }
+        let health = app.world().resource::<Health>();
+        assert!(!health.failed);
+        assert!(health.value > 0.0);
+        assert_eq!(screen(&app), GameScreen::Playing);
+    }
+
+    #[test]
+    fn harder_charts_drain_more_per_miss() {
+        let easy = HealthTuning::for_rating(1);
+        let normal = HealthTuning::for_rating(6);
+        let expert = HealthTuning::for_rating(MAX_DIFFICULTY_RATING);
+        assert!(normal.miss_drain > easy.miss_drain);
+        assert!(expert.miss_drain > normal.miss_drain);
+        assert!(expert.hit_restore < easy.hit_restore);
+        assert!(expert.clear_threshold > easy.clear_threshold);
+        assert_eq!(easy.clear_threshold, 0.0);
+        // Ratings outside the scale behave like its ends
+        assert_eq!(HealthTuning::for_rating(0), easy);
+        assert_eq!(HealthTuning::for_rating(99), expert);
+
+        let mut health = Health::new(&GameplayMods::default(), expert);
+        health.apply(Judgment::Miss);
+        assert!((health.value - (1.0 - expert.miss_drain)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn ending_below_the_clear_threshold_is_not_a_clear() {
+        let tuning = HealthTuning::for_rating(MAX_DIFFICULTY_RATING);
+        let mut health = Health::new(&GameplayMods::default(), tuning);
+        assert!(health.cleared());
+        health.value = tuning.clear_threshold - 0.01;
+        assert!(!health.failed);
+        assert!(!health.cleared());
+
+        health.fail_mod = Some(FailMod::NoFail);
+        assert!(health.cleared());
+    }
+
+    #[test]
+    fn fail_mods_are_mutually_exclusive() {
+        let mut mods = GameplayMods::default();
+        mods.cycle_fail();
+        assert_eq!(mods.fail, Some(FailMod::NoFail));
+        mods.cycle_fail();
+        assert_eq!(mods.fail, Some(FailMod::SuddenDeath));
+        assert_eq!(mods.tags(), vec!["SD".to_string()]);
+        assert_eq!(GameplayMods::from_tags(&mods.tags()), mods);
+        mods.cycle_fail();
+        assert_eq!(mods, GameplayMods::default());
+        assert_eq!(GameplayMods::from_tags(&["XX".to_string()]), mods);
+    }
+}
@@ -1,15 +1,16 @@
 use bevy::prelude::*;
 
 use crate::GameSet;
+use crate::health::{GameplayMods, Health};
 use crate::scoring::{ChainTier, ScoreState};
-use crate::state::GameScreen;
+use crate::state::InGame;
 
 pub struct HudPlugin;
 
 impl Plugin for HudPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameScreen::Playing), setup_hud)
-            .add_systems(Update, update_hud.in_set(GameSet::Render));
+        app.add_systems(OnEnter(InGame), setup_hud)
+            .add_systems(Update, (update_hud, update_health_bar).in_set(GameSet::Render));
     }
 }
 
@@ -21,6 +22,8 @@ const HUD_BG: Color = Color::srgba(0.04, 0.02, 0.08, 0.85);
 const HUD_PANEL: Color = Color::srgba(0.08, 0.04, 0.14, 0.6);
 /// Score digits — hot white with slight cyan cast
 const SCORE_COLOR: Color = Color::srgb(0.92, 0.96, 1.0);
+/// Combo digits — bright white, tier-independent
+const COMBO_COLOR: Color = Color::srgb(1.0, 1.0, 1.0);
 /// Label text — muted lavender
 const LABEL_COLOR: Color = Color::srgb(0.55, 0.45, 0.65);
 /// Chain: Normal tier — ghostly white
@@ -39,10 +42,17 @@ const GOOD_HUD: Color = Color::srgb(1.0, 0.85, 0.0);
 const MISS_HUD: Color = Color::srgb(1.0, 0.15, 0.3);
 /// Accent border — neon purple edge glow
 const ACCENT_BORDER: Color = Color::srgb(0.6, 0.2, 1.0);
+/// Health bar fill — neon green, magenta-red when low
+const HEALTH_FILL: Color = Color::srgb(0.0, 1.0, 0.4);
+const HEALTH_LOW: Color = Color::srgb(1.0, 0.15, 0.3);
+const HEALTH_TRACK: Color = Color::srgba(0.6, 0.2, 1.0, 0.2);
+/// Below this fraction the bar turns red.
+const HEALTH_LOW_THRESHOLD: f32 = 0.25;
 
 // --- Font sizes ---
 
 const SCORE_FONT: f32 = 42.0;
+const COMBO_FONT: f32 = 36.0;
 const CHAIN_FONT: f32 = 28.0;
 const CHAIN_LABEL_FONT: f32 = 13.0;
 const GRADE_COUNT_FONT: f32 = 18.0;
@@ -53,6 +63,9 @@ const GRADE_LABEL_FONT: f32 = 11.0;
 #[derive(Component)]
 struct HudScoreText;
 
+#[derive(Component)]
+struct HudComboText;
+
 #[derive(Component)]
 struct HudChainText;
 
@@ -71,13 +84,16 @@ struct HudGoodCount;
 #[derive(Component)]
 struct HudMissCount;
 
+#[derive(Component)]
+struct HudHealthFill;
+
 // --- Systems ---
 
-fn setup_hud(mut commands: Commands) {
+pub(crate) fn setup_hud(mut commands: Commands, mods: Res<GameplayMods>) {
     // Root container — top-right corner, absolute positioned
     commands
         .spawn((
-            DespawnOnExit(GameScreen::Playing),
+            DespawnOnExit(InGame),
             Node {
                 position_type: PositionType::Absolute,
                 top: Val::Px(16.0),
@@ -109,6 +125,72 @@ fn setup_hud(mut commands: Commands) {
                 HudScoreText,
             ));
 
+            // --- Health bar ---
+            root.spawn((
+                Node {
+                    width: Val::Px(160.0),
+                    height: Val::Px(6.0),
+                    border_radius: BorderRadius::all(Val::Px(3.0)),
+                    ..default()
+                },
+                BackgroundColor(HEALTH_TRACK),
+            ))
+            .with_children(|track: &mut ChildSpawnerCommands| {
+                track.spawn((
+                    Node {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        border_radius: BorderRadius::all(Val::Px(3.0)),
+                        ..default()
+                    },
+                    BackgroundColor(HEALTH_FILL),
+                    HudHealthFill,
+                ));
+            });
+
+            // --- Active mods ---
+            if let Some(label) = mods.label() {
+                root.spawn((
+                    Text::new(label),
+                    TextFont {
+                        font_size: CHAIN_LABEL_FONT,
+                        ..default()
+                    },
+                    TextColor(LABEL_COLOR),
+                ));
+            }
+
+            // --- Combo section ---
+            root.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::FlexEnd,
+                    row_gap: Val::Px(2.0),
+                    margin: UiRect::top(Val::Px(4.0)),
+                    ..default()
+                },
+            ))
+            .with_children(|combo_section: &mut ChildSpawnerCommands| {
+                combo_section.spawn((
+                    Text::new("COMBO"),
+                    TextFont {
+                        font_size: CHAIN_LABEL_FONT,
+                        ..default()
+                    },
+                    TextColor(LABEL_COLOR),
+                ));
+
+                combo_section.spawn((
+                    Text::new("0"),
+                    TextFont {
+                        font_size: COMBO_FONT,
+                        ..default()
+                    },
+                    TextColor(COMBO_COLOR),
+                    HudComboText,
+                ));
+            });
+
             // --- Chain section ---
             root.spawn((
                 Node {
@@ -217,6 +299,7 @@ fn spawn_grade_row(
 fn update_hud(
     state: Option<Res<ScoreState>>,
     mut score_q: Query<&mut Text, (With<HudScoreText>, Without<HudChainText>)>,
+    mut combo_q: Query<&mut Text, (With<HudComboText>, Without<HudScoreText>, Without<HudChainText>)>,
     mut chain_q: Query<
         (&mut Text, &mut TextColor),
         (With<HudChainText>, Without<HudScoreText>, Without<HudChainLabel>),
@@ -225,10 +308,10 @@ fn update_hud(
         &mut TextColor,
         (With<HudChainLabel>, Without<HudChainText>, Without<HudScoreText>),
     >,
-    mut great_q: Query<&mut Text, (With<HudGreatCount>, Without<HudScoreText>, Without<HudChainText>)>,
-    mut cool_q: Query<&mut Text, (With<HudCoolCount>, Without<HudScoreText>, Without<HudChainText>, Without<HudGreatCount>)>,
-    mut good_q: Query<&mut Text, (With<HudGoodCount>, Without<HudScoreText>, Without<HudChainText>, Without<HudGreatCount>, Without<HudCoolCount>)>,
-    mut miss_q: Query<&mut Text, (With<HudMissCount>, Without<HudScoreText>, Without<HudChainText>, Without<HudGreatCount>, Without<HudCoolCount>, Without<HudGoodCount>)>,
+    mut great_q: Query<&mut Text, (With<HudGreatCount>, Without<HudScoreText>, Without<HudChainText>, Without<HudComboText>)>,
+    mut cool_q: Query<&mut Text, (With<HudCoolCount>, Without<HudScoreText>, Without<HudChainText>, Without<HudComboText>, Without<HudGreatCount>)>,
+    mut good_q: Query<&mut Text, (With<HudGoodCount>, Without<HudScoreText>, Without<HudChainText>, Without<HudComboText>, Without<HudGreatCount>, Without<HudCoolCount>)>,
+    mut miss_q: Query<&mut Text, (With<HudMissCount>, Without<HudScoreText>, Without<HudChainText>, Without<HudComboText>, Without<HudGreatCount>, Without<HudCoolCount>, Without<HudGoodCount>)>,
 ) {
     let Some(state) = state else { return };
 
@@ -237,9 +320,14 @@ fn update_hud(
         **text = format!("{}", state.score);
     }
 
+    // Combo
+    if let Ok(mut text) = combo_q.single_mut() {
+        **text = format!("{}", state.combo);
+    }
+
     // Chain + tier color
     if let Ok((mut text, mut color)) = chain_q.single_mut() {
-        **text = format!("{}", state.chain);
+        **text = format!("{}", state.chain_steps());
         let tier_color = match state.chain_tier() {
             ChainTier::Normal => CHAIN_NORMAL,
             ChainTier::Fever => CHAIN_FEVER,
@@ -272,3 +360,21 @@ fn update_hud(
         **text = format!("{}", state.miss_count);
     }
 }
+
+fn update_health_bar(
+    health: Option<Res<Health>>,
+    mut fill_q: Query<(&mut Node, &mut BackgroundColor), With<HudHealthFill>>,
+) {
+    let Some(health) = health else { return };
+    if !health.is_changed() {
+        return;
+    }
+    for (mut node, mut color) in &mut fill_q {
+        node.width = Val::Percent(health.value * 100.0);
+        *color = BackgroundColor(if health.value < HEALTH_LOW_THRESHOLD {
+            HEALTH_LOW
+        } else {
+            HEALTH_FILL
+        });
+    }
+}
@@ -1,11 +1,13 @@
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
 
 use crate::GameSet;
-use crate::action::GameAction;
+use crate::action::{GameAction, InputBindings};
 use crate::beatmap::SlideDirection;
 use crate::conductor::SongConductor;
 use crate::judgment::beats_to_ms;
+use crate::replay::ReplayPlayback;
 
 pub struct InputPlugin;
 
@@ -14,11 +16,27 @@ impl Plugin for InputPlugin {
         app.add_message::<TapInput>();
         app.add_message::<SlideInput>();
         app.add_message::<CriticalInput>();
+        app.add_message::<ScratchInput>();
+        app.add_message::<DualSlideInput>();
         app.init_resource::<CriticalDetector>();
+        app.init_resource::<StickFlicks>();
+        app.init_resource::<ScratchDetector>();
+        app.init_resource::<DualSlideDetector>();
         app.add_systems(
             Update,
-            (read_tap_input, read_slide_input, detect_critical_input)
-                .in_set(GameSet::ReadInput),
+            (
+                read_tap_input,
+                read_slide_input,
+                read_stick_slides,
+                detect_critical_input,
+                detect_scratch_input,
+                detect_dual_slide
+                    .after(read_slide_input)
+                    .after(read_stick_slides),
+            )
+                .in_set(GameSet::ReadInput)
+                // A replay supplies the inputs instead
+                .run_if(not(resource_exists::<ReplayPlayback>)),
         );
     }
 }
@@ -39,6 +57,21 @@ pub struct CriticalInput {
     pub beat: f64,
 }
 
+/// A completed back-and-forth scratch gesture, judged against Scratch notes.
+#[derive(Message, Debug, Clone)]
+pub struct ScratchInput {
+    pub beat: f64,
+}
+
+/// Two distinct slide directions registered within `DUAL_SLIDE_WINDOW_MS`,
+/// judged against DualSlide notes. Away from those, the individual
+/// `SlideInput`s still hit regular Slide notes.
+#[derive(Message, Debug, Clone)]
+pub struct DualSlideInput {
+    pub beat: f64,
+    pub directions: (SlideDirection, SlideDirection),
+}
+
 #[derive(Resource, Default)]
 struct CriticalDetector {
     last_tap_beat: Option<f64>,
@@ -46,6 +79,53 @@ struct CriticalDetector {
     last_emitted_beat: Option<f64>,
 }
 
+// --- Analog stick flicks ---
+
+/// Stick magnitude a flick must reach to register as a slide.
+const STICK_FLICK_THRESHOLD: f32 = 0.6;
+/// Stick must fall back below this magnitude before it can flick again.
+const STICK_RELEASE_THRESHOLD: f32 = 0.3;
+
+/// Per-stick re-arm state so one held deflection produces one slide.
+#[derive(Resource)]
+struct StickFlicks {
+    left_armed: bool,
+    right_armed: bool,
+}
+
+impl Default for StickFlicks {
+    fn default() -> Self {
+        Self {
+            left_armed: true,
+            right_armed: true,
+        }
+    }
+}
+
+/// Map a raw stick vector to an 8-way slide direction, ignoring anything inside
+/// the flick dead zone.
+pub fn stick_direction(v: Vec2) -> Option<SlideDirection> {
+    if v.length() < STICK_FLICK_THRESHOLD {
+        return None;
+    }
+    SlideDirection::from_vec2(v)
+}
+
+/// Advance a stick's flick state. Returns a direction on the frame the stick
+/// crosses the flick threshold from rest.
+fn update_flick(armed: &mut bool, v: Vec2) -> Option<SlideDirection> {
+    if v.length() < STICK_RELEASE_THRESHOLD {
+        *armed = true;
+        return None;
+    }
+    if !*armed {
+        return None;
+    }
+    let dir = stick_direction(v)?;
+    *armed = false;
+    Some(dir)
+}
+
 fn read_tap_input(
     action: Res<ActionState<GameAction>>,
     conductor: Option<Res<SongConductor>>,
@@ -59,35 +139,61 @@ fn read_tap_input(
     }
 }
 
+// --- Digital directions ---
+
+const DIRECTIONS: [GameAction; 4] =
+    [GameAction::Up, GameAction::Down, GameAction::Left, GameAction::Right];
+
+/// Up/Down/Left/Right from their bound keys and gamepad buttons only. The left
+/// stick also drives those actions for menus, but in gameplay it slides through
+/// `read_stick_slides`, so reading the actions would count a flick twice.
+#[derive(SystemParam)]
+struct DigitalDirections<'w, 's> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+    bindings: Res<'w, InputBindings>,
+}
+
+impl DigitalDirections<'_, '_> {
+    fn pressed(&self, action: GameAction) -> bool {
+        let keys = self.bindings.keys.get(&action).into_iter().flatten().copied();
+        let buttons = self.bindings.buttons.get(&action).into_iter().flatten().copied();
+        self.keys.any_pressed(keys)
+            || self.gamepads.iter().any(|g| g.any_pressed(buttons.clone()))
+    }
+
+    fn just_pressed(&self, action: GameAction) -> bool {
+        let keys = self.bindings.keys.get(&action).into_iter().flatten().copied();
+        let buttons = self.bindings.buttons.get(&action).into_iter().flatten().copied();
+        self.keys.any_just_pressed(keys)
+            || self.gamepads.iter().any(|g| g.any_just_pressed(buttons.clone()))
+    }
+}
+
 fn read_slide_input(
-    action: Res<ActionState<GameAction>>,
+    directions: DigitalDirections,
     conductor: Option<Res<SongConductor>>,
     mut slide_writer: MessageWriter<SlideInput>,
 ) {
     let Some(conductor) = conductor else { return };
 
-    // Emit on any directional just_pressed (keyboard arrows, d-pad, or left stick)
-    let any_dir_just_pressed = action.just_pressed(&GameAction::Up)
-        || action.just_pressed(&GameAction::Down)
-        || action.just_pressed(&GameAction::Left)
-        || action.just_pressed(&GameAction::Right);
-
-    if !any_dir_just_pressed {
+    // Emit on any directional just_pressed (keyboard arrows or d-pad)
+    if !DIRECTIONS.iter().any(|a| directions.just_pressed(*a)) {
         return;
     }
 
     // Read all currently pressed directions to compose a vector (enables diagonals)
     let mut dir = Vec2::ZERO;
-    if action.pressed(&GameAction::Up) {
+    if directions.pressed(GameAction::Up) {
         dir.y += 1.0;
     }
-    if action.pressed(&GameAction::Down) {
+    if directions.pressed(GameAction::Down) {
         dir.y -= 1.0;
     }
-    if action.pressed(&GameAction::Right) {
+    if directions.pressed(GameAction::Right) {
         dir.x += 1.0;
     }
-    if action.pressed(&GameAction::Left) {
+    if directions.pressed(GameAction::Left) {
         dir.x -= 1.0;
     }
 
@@ -99,6 +205,163 @@ fn read_slide_input(
     }
 }
 
+/// Emit slides from analog stick flicks. Each stick fires independently, so two
+/// simultaneous flicks produce two `SlideInput`s on the same beat.
+fn read_stick_slides(
+    action: Res<ActionState<GameAction>>,
+    conductor: Option<Res<SongConductor>>,
+    mut flicks: ResMut<StickFlicks>,
+    mut slide_writer: MessageWriter<SlideInput>,
+) {
+    let Some(conductor) = conductor else { return };
+
+    let left = action.axis_pair(&GameAction::LeftStick);
+    let right = action.axis_pair(&GameAction::RightStick);
+
+    let flicks = &mut *flicks;
+    for direction in [
+        update_flick(&mut flicks.left_armed, left),
+        update_flick(&mut flicks.right_armed, right),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        slide_writer.write(SlideInput {
+            beat: conductor.current_beat,
+            direction,
+        });
+    }
+}
+
+// --- Scratch gesture ---
+
+/// Horizontal stick deflection needed to count as one side of a scratch.
+const SCRATCH_DEAD_ZONE: f32 = 0.5;
+/// Max time between the two opposing strokes of a scratch.
+pub(crate) const SCRATCH_WINDOW_MS: f64 = 150.0;
+/// After a scratch fires, further strokes are ignored for this long.
+const SCRATCH_COOLDOWN_MS: f64 = 120.0;
+
+/// Detects a scratch: a horizontal stroke followed by a stroke the opposite way
+/// within `SCRATCH_WINDOW_MS`. Strokes come from stick reversals or alternating
+/// Left/Right presses.
+#[derive(Resource, Default)]
+struct ScratchDetector {
+    /// Side (-1 left, +1 right) and time of the last stroke.
+    last_stroke: Option<(i8, f64)>,
+    /// Stick side on the previous frame, so a held deflection is one stroke.
+    stick_side: i8,
+    cooldown_until_ms: f64,
+}
+
+impl ScratchDetector {
+    /// Feed one stroke. Returns true when it completes a scratch gesture.
+    fn stroke(&mut self, side: i8, now_ms: f64) -> bool {
+        if now_ms < self.cooldown_until_ms {
+            self.last_stroke = None;
+            return false;
+        }
+        if let Some((prev_side, prev_ms)) = self.last_stroke {
+            if prev_side == -side && now_ms - prev_ms <= SCRATCH_WINDOW_MS {
+                self.last_stroke = None;
+                self.cooldown_until_ms = now_ms + SCRATCH_COOLDOWN_MS;
+                return true;
+            }
+        }
+        self.last_stroke = Some((side, now_ms));
+        false
+    }
+
+    /// Turn the stick's horizontal axis into strokes on each side change.
+    fn stick(&mut self, x: f32, now_ms: f64) -> bool {
+        let side = if x >= SCRATCH_DEAD_ZONE {
+            1
+        } else if x <= -SCRATCH_DEAD_ZONE {
+            -1
+        } else {
+            0
+        };
+        let entered = side != 0 && side != self.stick_side;
+        self.stick_side = side;
+        entered && self.stroke(side, now_ms)
+    }
+}
+
+fn detect_scratch_input(
+    action: Res<ActionState<GameAction>>,
+    directions: DigitalDirections,
+    conductor: Option<Res<SongConductor>>,
+    time: Res<Time>,
+    mut detector: ResMut<ScratchDetector>,
+    mut scratch_writer: MessageWriter<ScratchInput>,
+) {
+    let Some(conductor) = conductor else { return };
+    let now_ms = time.elapsed_secs_f64() * 1000.0;
+
+    let stick = action.axis_pair(&GameAction::LeftStick);
+    let mut fired = detector.stick(stick.x, now_ms);
+
+    if directions.just_pressed(GameAction::Left) {
+        fired |= detector.stroke(-1, now_ms);
+    }
+    if directions.just_pressed(GameAction::Right) {
+        fired |= detector.stroke(1, now_ms);
+    }
+
+    if fired {
+        scratch_writer.write(ScratchInput {
+            beat: conductor.current_beat,
+        });
+    }
+}
+
+// --- Dual slide ---
+
+/// Max gap between the two slides of a dual slide.
+pub(crate) const DUAL_SLIDE_WINDOW_MS: f64 = 60.0;
+
+/// Pairs slides into dual slides. Each slide is used in at most one pair.
+#[derive(Resource, Default)]
+struct DualSlideDetector {
+    pending: Option<(SlideDirection, f64)>,
+}
+
+impl DualSlideDetector {
+    /// Feed one slide at `ms`. Returns both directions when it pairs with a
+    /// different direction inside the window.
+    fn push(&mut self, direction: SlideDirection, ms: f64) -> Option<(SlideDirection, SlideDirection)> {
+        if let Some((first, first_ms)) = self.pending {
+            if first != direction && (ms - first_ms).abs() <= DUAL_SLIDE_WINDOW_MS {
+                self.pending = None;
+                return Some((first, direction));
+            }
+        }
+        self.pending = Some((direction, ms));
+        None
+    }
+}
+
+fn detect_dual_slide(
+    conductor: Option<Res<SongConductor>>,
+    mut slides: MessageReader<SlideInput>,
+    mut detector: ResMut<DualSlideDetector>,
+    mut dual_writer: MessageWriter<DualSlideInput>,
+) {
+    let Some(conductor) = conductor else {
+        slides.clear();
+        return;
+    };
+    for slide in slides.read() {
+        let ms = beats_to_ms(slide.beat, conductor.bpm);
+        if let Some(directions) = detector.push(slide.direction, ms) {
+            dual_writer.write(DualSlideInput {
+                beat: slide.beat,
+                directions,
+            });
+        }
+    }
+}
+
 const CRITICAL_WINDOW_MS: f64 = 30.0;
 
 fn detect_critical_input(
@@ -149,3 +412,176 @@ fn detect_critical_input(
         }
     }
 }
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at_degrees(deg: f32) -> Vec2 {
+        let r = deg.to_radians();
+        Vec2::new(r.cos(), r.sin())
+    }
+
+    #[test]
+    fn stick_inside_dead_zone_is_ignored() {
+        assert_eq!(stick_direction(Vec2::ZERO), None);
+        assert_eq!(stick_direction(Vec2::new(0.4, 0.4)), None);
+        assert_eq!(stick_direction(at_degrees(45.0) * 0.59), None);
+        assert_eq!(stick_direction(at_degrees(45.0) * 0.61), Some(SlideDirection::NE));
+    }
+
+    #[test]
+    fn stick_sector_boundaries() {
+        // Boundaries sit at 22.5° + k·45°; probe just either side of each.
+        let expected = [
+            SlideDirection::E,
+            SlideDirection::NE,
+            SlideDirection::N,
+            SlideDirection::NW,
+            SlideDirection::W,
+            SlideDirection::SW,
+            SlideDirection::S,
+            SlideDirection::SE,
+        ];
+        for (k, dir) in expected.iter().enumerate() {
+            let center = k as f32 * 45.0;
+            assert_eq!(stick_direction(at_degrees(center)), Some(*dir), "center {center}");
+            assert_eq!(stick_direction(at_degrees(center - 22.0)), Some(*dir), "lower {center}");
+            assert_eq!(stick_direction(at_degrees(center + 22.0)), Some(*dir), "upper {center}");
+        }
+        assert_eq!(stick_direction(at_degrees(23.0)), Some(SlideDirection::NE));
+        assert_eq!(stick_direction(at_degrees(-23.0)), Some(SlideDirection::SE));
+        assert_eq!(stick_direction(at_degrees(179.0)), Some(SlideDirection::W));
+        assert_eq!(stick_direction(at_degrees(-179.0)), Some(SlideDirection::W));
+    }
+
+    #[test]
+    fn flick_fires_once_until_released() {
+        let mut armed = true;
+        let ne = at_degrees(45.0);
+        assert_eq!(update_flick(&mut armed, ne * 0.4), None);
+        assert_eq!(update_flick(&mut armed, ne), Some(SlideDirection::NE));
+        // Held deflection does not repeat
+        assert_eq!(update_flick(&mut armed, ne), None);
+        assert_eq!(update_flick(&mut armed, at_degrees(90.0)), None);
+        // Return to rest re-arms
+        assert_eq!(update_flick(&mut armed, Vec2::ZERO), None);
+        assert_eq!(update_flick(&mut armed, at_degrees(90.0)), Some(SlideDirection::N));
+    }
+
+    #[test]
+    fn two_sticks_flick_independently() {
+        let mut flicks = StickFlicks::default();
+        let left = update_flick(&mut flicks.left_armed, at_degrees(180.0));
+        let right = update_flick(&mut flicks.right_armed, at_degrees(0.0));
+        assert_eq!(left, Some(SlideDirection::W));
+        assert_eq!(right, Some(SlideDirection::E));
+    }
+
+    #[derive(Resource, Default)]
+    struct Slides(Vec<SlideDirection>);
+
+    fn collect_slides(mut reader: MessageReader<SlideInput>, mut slides: ResMut<Slides>) {
+        slides.0.extend(reader.read().map(|s| s.direction));
+    }
+
+    fn slide_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_message::<SlideInput>()
+            .init_resource::<ActionState<GameAction>>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<InputBindings>()
+            .init_resource::<StickFlicks>()
+            .init_resource::<Slides>()
+            .insert_resource(SongConductor::new(120.0))
+            .add_systems(Update, (read_slide_input, read_stick_slides, collect_slides).chain());
+        app
+    }
+
+    #[test]
+    fn stick_flick_ramping_up_is_one_slide() {
+        let mut app = slide_app();
+        for magnitude in [0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7] {
+            let mut action = app.world_mut().resource_mut::<ActionState<GameAction>>();
+            action.set_axis_pair(&GameAction::LeftStick, Vec2::new(0.0, magnitude));
+            // Out of the deadzone the stick also presses Up, as it does for menus
+            if magnitude > 0.15 {
+                action.press(&GameAction::Up);
+            }
+            app.update();
+        }
+        assert_eq!(app.world().resource::<Slides>().0, [SlideDirection::N]);
+    }
+
+    #[test]
+    fn dpad_slides_while_the_stick_rests_off_centre() {
+        let mut app = slide_app();
+        let mut action = app.world_mut().resource_mut::<ActionState<GameAction>>();
+        action.set_axis_pair(&GameAction::LeftStick, Vec2::new(0.0, 0.4));
+        action.press(&GameAction::Up);
+        app.update();
+        app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::ArrowRight);
+        app.update();
+        assert_eq!(app.world().resource::<Slides>().0, [SlideDirection::E]);
+    }
+
+    #[test]
+    fn alternating_keys_within_window_scratch() {
+        let mut d = ScratchDetector::default();
+        assert!(!d.stroke(-1, 0.0));
+        assert!(d.stroke(1, 100.0));
+    }
+
+    #[test]
+    fn slow_or_same_side_strokes_do_not_scratch() {
+        let mut d = ScratchDetector::default();
+        assert!(!d.stroke(-1, 0.0));
+        assert!(!d.stroke(1, 200.0));
+        // Same side twice is a repeat, not a reversal
+        assert!(!d.stroke(1, 250.0));
+    }
+
+    #[test]
+    fn stick_reversal_scratches_once_per_cooldown() {
+        let mut d = ScratchDetector::default();
+        // Small wobble inside the dead zone is ignored
+        assert!(!d.stick(0.3, 0.0));
+        assert!(!d.stick(-0.3, 16.0));
+        // Right, held, then left: one gesture
+        assert!(!d.stick(0.9, 32.0));
+        assert!(!d.stick(0.9, 48.0));
+        assert!(d.stick(-0.9, 64.0));
+        // Immediate swing back lands in the cooldown
+        assert!(!d.stick(0.9, 80.0));
+        assert!(!d.stick(-0.9, 96.0));
+        // After the cooldown a fresh back-and-forth fires again
+        assert!(!d.stick(0.9, 300.0));
+        assert!(d.stick(-0.9, 350.0));
+    }
+
+    #[test]
+    fn two_flicks_within_window_make_one_dual_slide() {
+        let mut d = DualSlideDetector::default();
+        assert_eq!(d.push(SlideDirection::W, 1000.0), None);
+        assert_eq!(
+            d.push(SlideDirection::E, 1040.0),
+            Some((SlideDirection::W, SlideDirection::E))
+        );
+        // The pair is consumed; a third slide starts over
+        assert_eq!(d.push(SlideDirection::N, 1050.0), None);
+    }
+
+    #[test]
+    fn lone_or_late_slides_are_not_dual() {
+        let mut d = DualSlideDetector::default();
+        assert_eq!(d.push(SlideDirection::W, 0.0), None);
+        assert_eq!(d.push(SlideDirection::E, 100.0), None);
+        // Same direction twice is not a dual
+        let mut d = DualSlideDetector::default();
+        assert_eq!(d.push(SlideDirection::N, 0.0), None);
+        assert_eq!(d.push(SlideDirection::N, 10.0), None);
+    }
+}
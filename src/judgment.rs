@@ -4,18 +4,28 @@ use leafwing_input_manager::prelude::*;
 
 use crate::GameSet;
 use crate::action::GameAction;
+use crate::beatmap::SlideDirection;
 use crate::conductor::SongConductor;
-use crate::input::{CriticalInput, SlideInput, TapInput};
-use crate::notes::{RestMarker, HoldEndBeat, HoldState, NoteAlive, NoteDirection, NoteKind, NoteTiming, NoteType, Playhead};
+use crate::config::GameplayTuning;
+use crate::input::{
+    CriticalInput, DUAL_SLIDE_WINDOW_MS, DualSlideInput, SCRATCH_WINDOW_MS, ScratchInput, SlideInput,
+    TapInput,
+};
+use crate::notes::{HoldEndBeat, HoldReleaseBeat, HoldState, HoldTicks, NoteAlive, NoteDirection, NoteKind, NoteTiming, NoteType, Playhead};
 use crate::path::SplinePath;
-use crate::state::GameScreen;
+use crate::replay::ReplayPlayback;
+use crate::scoring::{ScoreState, hold_tick_points, note_points, note_weight};
+use crate::state::InGame;
 use crate::visuals::spawn_feedback_visual;
 
 pub struct JudgmentPlugin;
 
 impl Plugin for JudgmentPlugin {
     fn build(&self, app: &mut App) {
-        app.add_message::<JudgmentResult>();
+        app.add_message::<JudgmentResult>()
+            .add_message::<HoldTick>()
+            .add_message::<AdLibHit>()
+            .init_resource::<GameplayTuning>();
         app.add_systems(
             Update,
             (check_hits, check_holds, despawn_missed)
@@ -24,23 +34,30 @@ impl Plugin for JudgmentPlugin {
         )
         .add_systems(
             Update,
-            spawn_feedback.in_set(GameSet::UpdateScore),
+            (spawn_feedback, spawn_score_popups, spawn_hold_tick_popups)
+                .in_set(GameSet::UpdateScore),
         )
         .add_systems(
             Update,
-            cleanup_feedback.in_set(GameSet::Render),
+            (cleanup_feedback, update_score_popups).in_set(GameSet::Render),
         );
     }
 }
 
-// --- Timing windows (milliseconds) ---
-
-const GREAT_WINDOW_MS: f64 = 20.0;
-const COOL_WINDOW_MS: f64 = 50.0;
-const GOOD_WINDOW_MS: f64 = 100.0;
-const MISS_WINDOW_MS: f64 = 100.0;
+// Timing windows live in `GameplayTuning`.
 
 const FEEDBACK_LIFETIME: f32 = 0.6;
+const POPUP_LIFETIME: f32 = 0.5;
+/// How far a score popup rises over its lifetime (world units).
+const POPUP_RISE: f32 = 48.0;
+const POPUP_FONT: f32 = 18.0;
+const TICK_POPUP_FONT: f32 = 12.0;
+
+/// After releasing a hold early, re-pressing within this window resumes it.
+const HOLD_GRACE_MS: f64 = 150.0;
+
+/// Beats between sustain ticks while a hold is held.
+pub const HOLD_TICK_INTERVAL_BEATS: f64 = 1.0;
 
 // --- Y2K Future Punk palette (Jet Set Radio vibes) ---
 
@@ -88,6 +105,20 @@ impl Judgment {
 pub struct JudgmentResult {
     pub judgment: Judgment,
     pub position: Vec2,
+    /// The judged note's share of the play pool, from `note_weight`.
+    pub weight: f64,
+}
+
+/// Message emitted by check_hits when a tap lands on a hidden Ad-Lib note.
+#[derive(Message)]
+pub struct AdLibHit {
+    pub position: Vec2,
+}
+
+/// Message emitted by check_holds for each sustain tick on a held hold note.
+#[derive(Message)]
+pub struct HoldTick {
+    pub position: Vec2,
 }
 
 #[derive(Component)]
@@ -98,22 +129,101 @@ pub struct JudgmentFeedback {
     pub max_time: f32,
 }
 
+/// Floating point-value text that rises and fades above a judged note.
+#[derive(Component)]
+pub struct ScorePopup {
+    pub origin: Vec2,
+    pub timer: f32,
+    pub max_time: f32,
+}
+
 // --- Helpers ---
 
 pub(crate) fn beats_to_ms(beat_diff: f64, bpm: f64) -> f64 {
     beat_diff * 60_000.0 / bpm
 }
 
+/// Sustain ticks a hold from `start_beat` to `end_beat` awards. Ticks land on
+/// each interval after the head and stop short of the tail, which is judged
+/// separately.
+pub fn hold_tick_count(start_beat: f64, end_beat: f64) -> u32 {
+    let span = end_beat - start_beat;
+    if span <= 0.0 {
+        return 0;
+    }
+    ((span / HOLD_TICK_INTERVAL_BEATS).ceil() as u32).saturating_sub(1)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum GraceOutcome {
+    /// Re-pressed in time — back to `HoldState::Held`.
+    Regrab,
+    /// Still released, grace not yet spent.
+    Waiting,
+    /// Stayed released past the grace window — the tail is a Miss.
+    Drop,
+}
+
+/// Resolve a dropped hold `ms_since_release` after the player let go.
+fn grace_outcome(tap_held: bool, ms_since_release: f64) -> GraceOutcome {
+    if ms_since_release > HOLD_GRACE_MS {
+        GraceOutcome::Drop
+    } else if tap_held {
+        GraceOutcome::Regrab
+    } else {
+        GraceOutcome::Waiting
+    }
+}
+
+/// Ticks that should have been awarded by `current_beat`.
+fn hold_ticks_due(start_beat: f64, end_beat: f64, current_beat: f64) -> u32 {
+    if current_beat <= start_beat {
+        return 0;
+    }
+    let elapsed = ((current_beat - start_beat) / HOLD_TICK_INTERVAL_BEATS).floor() as u32;
+    elapsed.min(hold_tick_count(start_beat, end_beat))
+}
+
 pub(crate) fn ms_to_beats(ms: f64, bpm: f64) -> f64 {
     ms * bpm / 60_000.0
 }
 
-fn grade_timing(abs_diff_ms: f64) -> Option<Judgment> {
-    if abs_diff_ms <= GREAT_WINDOW_MS {
+/// Whether a slide `diff_ms` from a `kind` note is part of that note's
+/// gesture rather than a hit of its own. Scratch strokes arrive as horizontal
+/// slides and dual slides as their two halves; the windows reach back far
+/// enough to cover the gesture's first input.
+fn gesture_claims_slide(
+    kind: NoteKind,
+    direction: SlideDirection,
+    diff_ms: f64,
+    tuning: &GameplayTuning,
+) -> bool {
+    match kind {
+        NoteKind::Scratch => {
+            direction.to_vec2().x.abs() > 0.5 && diff_ms <= tuning.good_window_ms + SCRATCH_WINDOW_MS
+        }
+        NoteKind::DualSlide { left, right } => {
+            (direction == left || direction == right)
+                && diff_ms <= tuning.good_window_ms + DUAL_SLIDE_WINDOW_MS
+        }
+        _ => false,
+    }
+}
+
+/// Whether an input pair matches a note's pair; which stick came first doesn't matter.
+fn dual_matches(
+    input: (SlideDirection, SlideDirection),
+    note: (SlideDirection, SlideDirection),
+) -> bool {
+    input == note || (input.1, input.0) == note
+}
+
+fn grade_timing(abs_diff_ms: f64, tuning: &GameplayTuning) -> Option<Judgment> {
+    if abs_diff_ms <= tuning.great_window_ms {
         Some(Judgment::Great)
-    } else if abs_diff_ms <= COOL_WINDOW_MS {
+    } else if abs_diff_ms <= tuning.cool_window_ms {
         Some(Judgment::Cool)
-    } else if abs_diff_ms <= GOOD_WINDOW_MS {
+    } else if abs_diff_ms <= tuning.good_window_ms {
         Some(Judgment::Good)
     } else {
         None
@@ -122,21 +232,26 @@ fn grade_timing(abs_diff_ms: f64) -> Option<Judgment> {
 
 // --- Systems ---
 
+#[allow(clippy::too_many_arguments)]
 fn check_hits(
     mut commands: Commands,
     mut tap_reader: MessageReader<TapInput>,
     mut slide_reader: MessageReader<SlideInput>,
     mut critical_reader: MessageReader<CriticalInput>,
+    mut scratch_reader: MessageReader<ScratchInput>,
+    mut dual_reader: MessageReader<DualSlideInput>,
     notes: Query<(Entity, &NoteTiming, &NoteType, Option<&NoteDirection>, Option<&HoldState>), With<NoteAlive>>,
     conductor: Option<Res<SongConductor>>,
     spline: Option<Res<SplinePath>>,
     playhead: Option<Res<Playhead>>,
     mut results: MessageWriter<JudgmentResult>,
+    mut adlibs: MessageWriter<AdLibHit>,
+    tuning: Res<GameplayTuning>,
 ) {
     let Some(conductor) = conductor else { return };
     let Some(spline) = spline else { return };
     let Some(playhead) = playhead else { return };
-    let pos = spline.position_at_progress(playhead.progress(conductor.current_beat));
+    let pos = spline.position_at_progress(playhead.progress_at_beat(conductor.current_beat));
     let mut consumed: Vec<Entity> = Vec::new();
 
     // --- Critical inputs (process first — most specific, consumes before Tap/Slide) ---
@@ -150,7 +265,7 @@ fn check_hits(
             let diff_beats = (critical.beat - timing.target_beat).abs();
             let diff_ms = beats_to_ms(diff_beats, conductor.bpm);
 
-            if diff_ms <= GOOD_WINDOW_MS {
+            if diff_ms <= tuning.good_window_ms {
                 if best.is_none() || diff_ms < best.unwrap().1 {
                     best = Some((entity, diff_ms));
                 }
@@ -159,24 +274,90 @@ fn check_hits(
 
         if let Some((entity, diff_ms)) = best {
             consumed.push(entity);
-            let grade = grade_timing(diff_ms).unwrap();
+            let grade = grade_timing(diff_ms, &tuning).unwrap();
             info!("{} (Critical) — {:.1}ms", grade.label(), diff_ms);
             commands.entity(entity).despawn();
-            results.write(JudgmentResult { judgment: grade, position: pos });
+            results.write(JudgmentResult {
+                judgment: grade,
+                position: pos,
+                weight: note_weight(NoteKind::Critical),
+            });
         }
     }
 
-    // --- Tap inputs hit Tap, Rest, and pending Hold heads ---
+    // --- Scratch gestures hit only Scratch notes ---
+    for scratch in scratch_reader.read() {
+        let mut best: Option<(Entity, f64)> = None;
+
+        for (entity, timing, note_type, _, _) in &notes {
+            if !matches!(note_type.0, NoteKind::Scratch) { continue; }
+            if consumed.contains(&entity) { continue; }
+
+            let diff_beats = (scratch.beat - timing.target_beat).abs();
+            let diff_ms = beats_to_ms(diff_beats, conductor.bpm);
+
+            if diff_ms <= tuning.good_window_ms {
+                if best.is_none() || diff_ms < best.unwrap().1 {
+                    best = Some((entity, diff_ms));
+                }
+            }
+        }
+
+        if let Some((entity, diff_ms)) = best {
+            consumed.push(entity);
+            let grade = grade_timing(diff_ms, &tuning).unwrap();
+            info!("{} (Scratch) — {:.1}ms", grade.label(), diff_ms);
+            commands.entity(entity).despawn();
+            results.write(JudgmentResult {
+                judgment: grade,
+                position: pos,
+                weight: note_weight(NoteKind::Scratch),
+            });
+        }
+    }
+
+    // --- Dual slides hit DualSlide notes with the same pair, in either order ---
+    for dual in dual_reader.read() {
+        let mut best: Option<(Entity, f64, NoteKind)> = None;
+
+        for (entity, timing, note_type, _, _) in &notes {
+            let NoteKind::DualSlide { left, right } = note_type.0 else { continue };
+            if !dual_matches(dual.directions, (left, right)) { continue; }
+            if consumed.contains(&entity) { continue; }
+
+            let diff_beats = (dual.beat - timing.target_beat).abs();
+            let diff_ms = beats_to_ms(diff_beats, conductor.bpm);
+
+            if diff_ms <= tuning.good_window_ms {
+                if best.is_none() || diff_ms < best.unwrap().1 {
+                    best = Some((entity, diff_ms, note_type.0));
+                }
+            }
+        }
+
+        if let Some((entity, diff_ms, kind)) = best {
+            consumed.push(entity);
+            let grade = grade_timing(diff_ms, &tuning).unwrap();
+            info!("{} (Dual Slide {:?}) — {:.1}ms", grade.label(), dual.directions, diff_ms);
+            commands.entity(entity).despawn();
+            results.write(JudgmentResult {
+                judgment: grade,
+                position: pos,
+                weight: note_weight(kind),
+            });
+        }
+    }
+
+    // --- Tap inputs hit Tap and pending Hold heads, then hidden Ad-Libs ---
     for tap in tap_reader.read() {
-        let mut best: Option<(Entity, f64, bool, bool)> = None; // (entity, diff_ms, is_hold, is_rest)
+        let mut best: Option<(Entity, f64, NoteKind)> = None;
 
         for (entity, timing, note_type, _, hold_state) in &notes {
             let is_tap = matches!(note_type.0, NoteKind::Tap);
-            let is_rest = matches!(note_type.0, NoteKind::Rest);
             let is_pending_hold = matches!(note_type.0, NoteKind::Hold { .. })
                 && hold_state.map_or(false, |s| *s == HoldState::Pending);
 
-            if !is_tap && !is_pending_hold && !is_rest {
+            if !is_tap && !is_pending_hold {
                 continue;
             }
             if consumed.contains(&entity) { continue; }
@@ -184,37 +365,58 @@ fn check_hits(
             let diff_beats = (tap.beat - timing.target_beat).abs();
             let diff_ms = beats_to_ms(diff_beats, conductor.bpm);
 
-            if diff_ms <= GOOD_WINDOW_MS {
+            if diff_ms <= tuning.good_window_ms {
                 if best.is_none() || diff_ms < best.unwrap().1 {
-                    best = Some((entity, diff_ms, is_pending_hold, is_rest));
+                    best = Some((entity, diff_ms, note_type.0));
                 }
             }
         }
 
-        if let Some((entity, diff_ms, is_hold, is_rest)) = best {
+        // Only a tap no visible note wanted can find an Ad-Lib
+        if best.is_none() {
+            let adlib = notes
+                .iter()
+                .filter(|(entity, _, note_type, _, _)| {
+                    matches!(note_type.0, NoteKind::AdLib) && !consumed.contains(entity)
+                })
+                .map(|(entity, timing, ..)| {
+                    (entity, beats_to_ms((tap.beat - timing.target_beat).abs(), conductor.bpm))
+                })
+                .filter(|(_, diff_ms)| *diff_ms <= tuning.good_window_ms)
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((entity, diff_ms)) = adlib {
+                consumed.push(entity);
+                info!("AD-LIB — {:.1}ms", diff_ms);
+                commands.entity(entity).despawn();
+                adlibs.write(AdLibHit { position: pos });
+            }
+        }
+
+        if let Some((entity, diff_ms, kind)) = best {
             consumed.push(entity);
+            let weight = note_weight(kind);
 
-            if is_rest {
-                // Tapping during a rest = MISS + chain break
-                info!("MISS (Rest) — tapped during rest at {:.1}ms", diff_ms);
-                commands.entity(entity).despawn();
-                results.write(JudgmentResult { judgment: Judgment::Miss, position: pos });
-            } else if is_hold {
-                let grade = grade_timing(diff_ms).unwrap();
+            if matches!(kind, NoteKind::Hold { .. }) {
+                let grade = grade_timing(diff_ms, &tuning).unwrap();
                 info!("{} (Hold head) — {:.1}ms", grade.label(), diff_ms);
                 commands.entity(entity).insert(HoldState::Held);
-                results.write(JudgmentResult { judgment: grade, position: pos });
+                results.write(JudgmentResult { judgment: grade, position: pos, weight });
             } else {
-                let grade = grade_timing(diff_ms).unwrap();
+                let grade = grade_timing(diff_ms, &tuning).unwrap();
                 info!("{} — {:.1}ms", grade.label(), diff_ms);
                 commands.entity(entity).despawn();
-                results.write(JudgmentResult { judgment: grade, position: pos });
+                results.write(JudgmentResult { judgment: grade, position: pos, weight });
             }
         }
     }
 
     // --- Slide inputs hit only matching-direction Slide notes ---
     for slide in slide_reader.read() {
+        let claimed = notes.iter().any(|(_, timing, note_type, _, _)| {
+            let diff_ms = beats_to_ms((slide.beat - timing.target_beat).abs(), conductor.bpm);
+            gesture_claims_slide(note_type.0, slide.direction, diff_ms, &tuning)
+        });
+        if claimed { continue; }
         let mut best: Option<(Entity, f64)> = None;
 
         for (entity, timing, note_type, note_dir, _) in &notes {
@@ -227,7 +429,7 @@ fn check_hits(
             let diff_beats = (slide.beat - timing.target_beat).abs();
             let diff_ms = beats_to_ms(diff_beats, conductor.bpm);
 
-            if diff_ms <= GOOD_WINDOW_MS {
+            if diff_ms <= tuning.good_window_ms {
                 if best.is_none() || diff_ms < best.unwrap().1 {
                     best = Some((entity, diff_ms));
                 }
@@ -236,34 +438,90 @@ fn check_hits(
 
         if let Some((entity, diff_ms)) = best {
             consumed.push(entity);
-            let grade = grade_timing(diff_ms).unwrap();
+            let grade = grade_timing(diff_ms, &tuning).unwrap();
             info!("{} (Slide {:?}) — {:.1}ms", grade.label(), slide.direction, diff_ms);
             commands.entity(entity).despawn();
-            results.write(JudgmentResult { judgment: grade, position: pos });
+            results.write(JudgmentResult {
+                judgment: grade,
+                position: pos,
+                weight: note_weight(NoteKind::Slide(slide.direction)),
+            });
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn check_holds(
     mut commands: Commands,
     action: Res<ActionState<GameAction>>,
-    holds: Query<(Entity, &HoldEndBeat, &HoldState), With<NoteAlive>>,
+    mut holds: Query<
+        (
+            Entity,
+            &NoteTiming,
+            &HoldEndBeat,
+            &HoldState,
+            &mut HoldTicks,
+            Option<&HoldReleaseBeat>,
+        ),
+        With<NoteAlive>,
+    >,
     conductor: Option<Res<SongConductor>>,
     spline: Option<Res<SplinePath>>,
     playhead: Option<Res<Playhead>>,
+    replay: Option<Res<ReplayPlayback>>,
     mut results: MessageWriter<JudgmentResult>,
+    mut ticks: MessageWriter<HoldTick>,
+    tuning: Res<GameplayTuning>,
 ) {
     let Some(conductor) = conductor else { return };
     let Some(spline) = spline else { return };
     let Some(playhead) = playhead else { return };
-    let pos = spline.position_at_progress(playhead.progress(conductor.current_beat));
-    let tap_held = action.pressed(&GameAction::Tap);
-
-    for (entity, hold_end, hold_state) in &holds {
+    let pos = spline.position_at_progress(playhead.progress_at_beat(conductor.current_beat));
+    let tap_held = match replay {
+        Some(replay) => replay.tap_held,
+        None => action.pressed(&GameAction::Tap),
+    };
+
+    for (entity, timing, hold_end, hold_state, mut awarded, released) in &mut holds {
+        let weight = note_weight(NoteKind::Hold { end_beat: hold_end.0 });
+        if *hold_state == HoldState::Dropped {
+            let Some(released) = released else { continue };
+            let since_ms = beats_to_ms(conductor.current_beat - released.0, conductor.bpm);
+            match grace_outcome(tap_held, since_ms) {
+                GraceOutcome::Regrab => {
+                    // Ticks that fell inside the gap are forfeited
+                    awarded.0 = hold_ticks_due(timing.target_beat, hold_end.0, conductor.current_beat);
+                    commands
+                        .entity(entity)
+                        .insert(HoldState::Held)
+                        .remove::<HoldReleaseBeat>();
+                }
+                GraceOutcome::Waiting => {}
+                GraceOutcome::Drop => {
+                    info!("MISS (Hold tail) — released early");
+                    commands.entity(entity).despawn();
+                    results.write(JudgmentResult {
+                        judgment: Judgment::Miss,
+                        position: pos,
+                        weight,
+                    });
+                }
+            }
+            continue;
+        }
         if *hold_state != HoldState::Held {
             continue;
         }
 
+        // Sustain ticks while still held; releasing stops them
+        if tap_held {
+            let due = hold_ticks_due(timing.target_beat, hold_end.0, conductor.current_beat);
+            while awarded.0 < due {
+                awarded.0 += 1;
+                ticks.write(HoldTick { position: pos });
+            }
+        }
+
         let end_beat = hold_end.0;
         let diff_beats = (conductor.current_beat - end_beat).abs();
         let diff_ms = beats_to_ms(diff_beats, conductor.bpm);
@@ -271,8 +529,8 @@ fn check_holds(
 
         if !tap_held {
             // Player released — check if within tail window
-            if diff_ms <= GOOD_WINDOW_MS {
-                let grade = grade_timing(diff_ms).unwrap();
+            if diff_ms <= tuning.good_window_ms {
+                let grade = grade_timing(diff_ms, &tuning).unwrap();
                 info!(
                     "{} (Hold tail) — {:.1}ms",
                     grade.label(),
@@ -282,24 +540,22 @@ fn check_holds(
                 results.write(JudgmentResult {
                     judgment: grade,
                     position: pos,
+                    weight,
                 });
             } else {
-                // Released too early — MISS the tail
-                info!("MISS (Hold tail) — released early");
-                commands.entity(entity).insert(HoldState::Dropped);
-                commands.entity(entity).despawn();
-                results.write(JudgmentResult {
-                    judgment: Judgment::Miss,
-                    position: pos,
-                });
+                // Released too early — start the grace window before missing
+                commands
+                    .entity(entity)
+                    .insert((HoldState::Dropped, HoldReleaseBeat(conductor.current_beat)));
             }
-        } else if past_end && diff_ms > GOOD_WINDOW_MS {
+        } else if past_end && diff_ms > tuning.good_window_ms {
             // Held past the tail + miss window — auto-GREAT
             info!("GREAT (Hold tail) — held through");
             commands.entity(entity).despawn();
             results.write(JudgmentResult {
                 judgment: Judgment::Great,
                 position: pos,
+                weight,
             });
         }
     }
@@ -307,38 +563,35 @@ fn check_holds(
 
 fn despawn_missed(
     mut commands: Commands,
-    notes: Query<(Entity, &NoteTiming, &NoteType, Option<&HoldState>, Option<&RestMarker>), With<NoteAlive>>,
+    notes: Query<(Entity, &NoteTiming, &NoteType, Option<&HoldState>), With<NoteAlive>>,
     conductor: Option<Res<SongConductor>>,
     spline: Option<Res<SplinePath>>,
     playhead: Option<Res<Playhead>>,
     mut results: MessageWriter<JudgmentResult>,
+    tuning: Res<GameplayTuning>,
 ) {
     let Some(conductor) = conductor else { return };
     let Some(spline) = spline else { return };
     let Some(playhead) = playhead else { return };
-    let miss_beats = ms_to_beats(MISS_WINDOW_MS, conductor.bpm);
+    let miss_beats = ms_to_beats(tuning.miss_window_ms, conductor.bpm);
 
-    for (entity, timing, note_type, hold_state, rest) in &notes {
+    for (entity, timing, note_type, hold_state) in &notes {
         if conductor.current_beat > timing.target_beat + miss_beats {
-            // Skip notes that are currently being held (check_holds handles those)
-            if hold_state.map_or(false, |s| *s == HoldState::Held) {
+            // Skip holds in progress or in their re-grab grace (check_holds handles those)
+            if hold_state.is_some_and(|s| matches!(s, HoldState::Held | HoldState::Dropped)) {
                 continue;
             }
 
-            let pos = spline.position_at_progress(playhead.progress(conductor.current_beat));
-
-            // Rest notes: correctly passing = GREAT (player didn't tap)
-            if rest.is_some() {
-                info!("GREAT (Rest) — correctly passed rest at beat {:.1}", timing.target_beat);
+            // Missed Ad-Libs just go away: no judgment, no chain break
+            if matches!(note_type.0, NoteKind::AdLib) {
                 commands.entity(entity).despawn();
-                results.write(JudgmentResult {
-                    judgment: Judgment::Great,
-                    position: pos,
-                });
                 continue;
             }
 
+            let pos = spline.position_at_progress(playhead.progress_at_beat(conductor.current_beat));
+
             let is_hold = matches!(note_type.0, NoteKind::Hold { .. });
+            let weight = note_weight(note_type.0);
 
             if is_hold {
                 // Pending hold that was never pressed: 2 MISSes (head + tail)
@@ -347,10 +600,12 @@ fn despawn_missed(
                 results.write(JudgmentResult {
                     judgment: Judgment::Miss,
                     position: pos,
+                    weight,
                 });
                 results.write(JudgmentResult {
                     judgment: Judgment::Miss,
                     position: pos,
+                    weight,
                 });
             } else {
                 info!("MISS — note at beat {:.1} auto-missed", timing.target_beat);
@@ -358,6 +613,7 @@ fn despawn_missed(
                 results.write(JudgmentResult {
                     judgment: Judgment::Miss,
                     position: pos,
+                    weight,
                 });
             }
         }
@@ -371,7 +627,7 @@ fn spawn_feedback(
 ) {
     for result in results.read() {
         let entity = commands.spawn((
-            DespawnOnExit(GameScreen::Playing),
+            DespawnOnExit(InGame),
             Transform::from_translation(result.position.extend(2.0)),
             Visibility::default(),
             JudgmentFeedback {
@@ -397,3 +653,239 @@ fn cleanup_feedback(
         }
     }
 }
+
+fn spawn_score_popups(
+    mut commands: Commands,
+    mut results: MessageReader<JudgmentResult>,
+    state: Option<Res<ScoreState>>,
+) {
+    let Some(state) = state else {
+        results.clear();
+        return;
+    };
+    for result in results.read() {
+        let points = note_points(state.base_value * result.weight, result.judgment);
+        if points == 0 {
+            continue;
+        }
+        commands.spawn((
+            DespawnOnExit(InGame),
+            Text2d::new(format!("+{points}")),
+            TextFont {
+                font_size: POPUP_FONT,
+                ..default()
+            },
+            TextColor(result.judgment.color()),
+            Transform::from_translation(result.position.extend(3.0)),
+            ScorePopup {
+                origin: result.position,
+                timer: POPUP_LIFETIME,
+                max_time: POPUP_LIFETIME,
+            },
+        ));
+    }
+}
+
+fn spawn_hold_tick_popups(
+    mut commands: Commands,
+    mut ticks: MessageReader<HoldTick>,
+    state: Option<Res<ScoreState>>,
+) {
+    let Some(state) = state else {
+        ticks.clear();
+        return;
+    };
+    let points = hold_tick_points(state.base_value);
+    for tick in ticks.read() {
+        commands.spawn((
+            DespawnOnExit(InGame),
+            Text2d::new(format!("+{points}")),
+            TextFont {
+                font_size: TICK_POPUP_FONT,
+                ..default()
+            },
+            TextColor(GREAT_COLOR.with_alpha(0.8)),
+            Transform::from_translation(tick.position.extend(3.0)),
+            ScorePopup {
+                origin: tick.position,
+                timer: POPUP_LIFETIME,
+                max_time: POPUP_LIFETIME,
+            },
+        ));
+    }
+}
+
+fn update_score_popups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut ScorePopup, &mut Transform, &mut TextColor)>,
+) {
+    for (entity, mut popup, mut transform, mut color) in &mut query {
+        popup.timer -= time.delta_secs();
+        if popup.timer <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let t = 1.0 - popup.timer / popup.max_time;
+        let rise = POPUP_RISE * (1.0 - (1.0 - t) * (1.0 - t));
+        transform.translation.x = popup.origin.x;
+        transform.translation.y = popup.origin.y + rise;
+        color.0.set_alpha(1.0 - t);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Labels and pool weights of every judgment, in order.
+    #[derive(Resource, Default)]
+    struct Judged(Vec<(&'static str, f64)>);
+
+    impl Judged {
+        fn labels(&self) -> Vec<&'static str> {
+            self.0.iter().map(|(label, _)| *label).collect()
+        }
+    }
+
+    fn collect(mut results: MessageReader<JudgmentResult>, mut judged: ResMut<Judged>) {
+        judged.0.extend(results.read().map(|r| (r.judgment.label(), r.weight)));
+    }
+
+    /// Judging-only app at 120 BPM with `notes` already on screen.
+    fn judge_app(notes: &[(f64, NoteKind)]) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, JudgmentPlugin))
+            .add_message::<TapInput>()
+            .add_message::<SlideInput>()
+            .add_message::<CriticalInput>()
+            .add_message::<ScratchInput>()
+            .add_message::<DualSlideInput>()
+            .init_resource::<ActionState<GameAction>>()
+            .init_resource::<Judged>()
+            .insert_resource(SongConductor::new(120.0))
+            .insert_resource(SplinePath::from_catmull_rom_points(vec![
+                Vec2::ZERO,
+                Vec2::X,
+                Vec2::new(2.0, 0.0),
+                Vec2::new(3.0, 0.0),
+            ]))
+            .insert_resource(Playhead { song_start_beat: 0.0, song_end_beat: 8.0 })
+            // Feedback visuals are out of scope here
+            .configure_sets(Update, (GameSet::UpdateScore, GameSet::Render).run_if(|| false))
+            .add_systems(Update, collect.after(GameSet::CheckHits));
+        for &(target_beat, kind) in notes {
+            let mut note = app.world_mut().spawn((NoteAlive, NoteTiming { target_beat }, NoteType(kind)));
+            if let NoteKind::Slide(direction) = kind {
+                note.insert(NoteDirection(direction));
+            }
+        }
+        app
+    }
+
+    /// Advance to `beat` and deliver `inputs` on that frame.
+    fn step(app: &mut App, beat: f64, inputs: impl FnOnce(&mut World)) {
+        app.world_mut().resource_mut::<SongConductor>().current_beat = beat;
+        inputs(app.world_mut());
+        app.update();
+    }
+
+    #[test]
+    fn scratch_gesture_hits_a_scratch_note_without_its_strokes_hitting_slides() {
+        let mut app = judge_app(&[(2.0, NoteKind::Scratch), (2.1, NoteKind::Slide(SlideDirection::W))]);
+        // Left then right: the W stroke is in reach of the W slide but belongs to the scratch
+        step(&mut app, 1.9, |world| {
+            world.write_message(SlideInput { beat: 1.9, direction: SlideDirection::W });
+        });
+        step(&mut app, 2.0, |world| {
+            world.write_message(SlideInput { beat: 2.0, direction: SlideDirection::E });
+            world.write_message(ScratchInput { beat: 2.0 });
+        });
+        assert_eq!(app.world().resource::<Judged>().labels(), ["GREAT"]);
+
+        // With the scratch done, the slide is still there for its own input
+        step(&mut app, 2.1, |world| {
+            world.write_message(SlideInput { beat: 2.1, direction: SlideDirection::W });
+        });
+        assert_eq!(app.world().resource::<Judged>().labels(), ["GREAT", "GREAT"]);
+    }
+
+    #[test]
+    fn a_single_press_does_not_hit_a_scratch() {
+        let mut app = judge_app(&[(1.0, NoteKind::Scratch)]);
+        step(&mut app, 1.0, |world| {
+            world.write_message(TapInput { beat: 1.0 });
+            world.write_message(SlideInput { beat: 1.0, direction: SlideDirection::E });
+        });
+        assert!(app.world().resource::<Judged>().0.is_empty());
+        // Left unplayed, it misses like any other note
+        step(&mut app, 2.0, |_| {});
+        assert_eq!(app.world().resource::<Judged>().labels(), ["MISS"]);
+    }
+
+    #[test]
+    fn dual_slide_needs_both_directions_and_outweighs_a_slide() {
+        let mut app = judge_app(&[
+            (2.0, NoteKind::DualSlide { left: SlideDirection::NW, right: SlideDirection::SE }),
+            (2.1, NoteKind::Slide(SlideDirection::SE)),
+        ]);
+        // One stick alone does nothing, not even to the nearby SE slide
+        step(&mut app, 1.95, |world| {
+            world.write_message(SlideInput { beat: 1.95, direction: SlideDirection::SE });
+        });
+        assert!(app.world().resource::<Judged>().0.is_empty());
+
+        // The other stick completes the pair; order within the pair doesn't matter
+        step(&mut app, 2.0, |world| {
+            world.write_message(SlideInput { beat: 2.0, direction: SlideDirection::NW });
+            world.write_message(DualSlideInput {
+                beat: 2.0,
+                directions: (SlideDirection::SE, SlideDirection::NW),
+            });
+        });
+        let judged = &app.world().resource::<Judged>().0;
+        assert_eq!(judged.len(), 1);
+        assert_eq!(judged[0].0, "GREAT");
+        assert!(judged[0].1 > note_weight(NoteKind::Slide(SlideDirection::SE)));
+
+        // The plain slide is still waiting for its own input
+        step(&mut app, 2.1, |world| {
+            world.write_message(SlideInput { beat: 2.1, direction: SlideDirection::SE });
+        });
+        assert_eq!(app.world().resource::<Judged>().labels(), ["GREAT", "GREAT"]);
+    }
+
+    #[test]
+    fn wrong_pair_does_not_hit_a_dual_slide() {
+        let note = (SlideDirection::W, SlideDirection::E);
+        assert!(dual_matches((SlideDirection::E, SlideDirection::W), note));
+        assert!(!dual_matches((SlideDirection::W, SlideDirection::N), note));
+    }
+
+    #[test]
+    fn four_beat_hold_ticks() {
+        assert_eq!(hold_tick_count(8.0, 12.0), 3);
+        assert_eq!(hold_ticks_due(8.0, 12.0, 8.5), 0);
+        assert_eq!(hold_ticks_due(8.0, 12.0, 9.0), 1);
+        assert_eq!(hold_ticks_due(8.0, 12.0, 11.2), 3);
+        // Never more than the hold's tick count, even held past the tail
+        assert_eq!(hold_ticks_due(8.0, 12.0, 13.0), 3);
+    }
+
+    #[test]
+    fn released_hold_can_be_regrabbed_within_grace() {
+        assert_eq!(grace_outcome(false, 0.0), GraceOutcome::Waiting);
+        assert_eq!(grace_outcome(false, HOLD_GRACE_MS), GraceOutcome::Waiting);
+        assert_eq!(grace_outcome(true, 80.0), GraceOutcome::Regrab);
+        assert_eq!(grace_outcome(false, HOLD_GRACE_MS + 1.0), GraceOutcome::Drop);
+        // Too late to re-grab even if pressed again
+        assert_eq!(grace_outcome(true, HOLD_GRACE_MS + 1.0), GraceOutcome::Drop);
+    }
+
+    #[test]
+    fn short_holds_have_no_ticks() {
+        assert_eq!(hold_tick_count(0.0, 1.0), 0);
+        assert_eq!(hold_tick_count(0.0, 0.5), 0);
+        assert_eq!(hold_tick_count(0.0, 1.5), 1);
+    }
+}
@@ -0,0 +1,307 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on, futures_lite::future};
+use kira::sound::static_sound::StaticSoundData;
+
+use crate::audio::load_song_audio;
+use crate::beatmap::{
+    ChartFile, Difficulty, SelectedSong, SongMetadata, load_playable_chart, song_audio_path,
+};
+use crate::state::GameScreen;
+
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameScreen::Loading), (start_song_load, setup_loading_ui))
+            .add_systems(
+                Update,
+                (poll_song_load, spin_loading_indicator).run_if(in_state(GameScreen::Loading)),
+            );
+    }
+}
+
+// --- Palette (matches song select) ---
+
+const BG_DARK: Color = Color::srgba(0.02, 0.01, 0.06, 0.95);
+const ACCENT_CYAN: Color = Color::srgb(0.0, 0.9, 1.0);
+const TITLE_COLOR: Color = Color::srgb(0.92, 0.96, 1.0);
+const HINT_COLOR: Color = Color::srgb(0.4, 0.35, 0.5);
+
+const SPINNER_FRAMES: [&str; 4] = ["|", "/", "-", "\\"];
+const SPINNER_FPS: f32 = 10.0;
+
+// --- Resources ---
+
+/// The song picked on song select, waiting to be loaded.
+#[derive(Resource, Clone)]
+pub struct PendingLoad {
+    pub song_dir: PathBuf,
+    pub difficulty: Difficulty,
+    pub metadata: SongMetadata,
+}
+
+/// Why the last load failed, for song select to show when it comes back.
+#[derive(Resource)]
+pub struct LoadError(pub String);
+
+/// Decoded audio for the song being played. Kept across retries so
+/// restarting doesn't decode again.
+#[derive(Resource)]
+pub struct PreloadedSong {
+    pub path: PathBuf,
+    pub data: StaticSoundData,
+}
+
+struct LoadedSong {
+    chart: ChartFile,
+    audio_path: PathBuf,
+    audio: StaticSoundData,
+}
+
+#[derive(Resource)]
+struct SongLoadTask(Task<Result<LoadedSong, String>>);
+
+#[derive(Component)]
+struct LoadingSpinner;
+
+// --- Systems ---
+
+fn start_song_load(
+    mut commands: Commands,
+    pending: Option<Res<PendingLoad>>,
+    mut next_state: ResMut<NextState<GameScreen>>,
+) {
+    let Some(pending) = pending else {
+        warn!("Entered loading with no song picked");
+        next_state.set(GameScreen::SongSelect);
+        return;
+    };
+
+    let pending = pending.clone();
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let chart = load_playable_chart(&pending.song_dir, pending.difficulty)?;
+        let audio_path = song_audio_path(&pending.song_dir, &pending.metadata)?;
+        let audio = load_song_audio(&audio_path)?;
+        Ok(LoadedSong {
+            chart,
+            audio_path,
+            audio,
+        })
+    });
+    commands.insert_resource(SongLoadTask(task));
+}
+
+fn poll_song_load(
+    mut commands: Commands,
+    task: Option<ResMut<SongLoadTask>>,
+    pending: Option<Res<PendingLoad>>,
+    mut next_state: ResMut<NextState<GameScreen>>,
+) {
+    let Some(mut task) = task else { return };
+    let Some(result) = block_on(future::poll_once(&mut task.0)) else {
+        return;
+    };
+    commands.remove_resource::<SongLoadTask>();
+    commands.remove_resource::<PendingLoad>();
+    let Some(pending) = pending else { return };
+
+    match result {
+        Ok(loaded) => {
+            info!(
+                "Loaded: {} [{}]",
+                pending.metadata.title,
+                pending.difficulty.label()
+            );
+            commands.insert_resource(PreloadedSong {
+                path: loaded.audio_path,
+                data: loaded.audio,
+            });
+            commands.insert_resource(SelectedSong {
+                song_dir: pending.song_dir.clone(),
+                difficulty: pending.difficulty,
+                metadata: pending.metadata.clone(),
+                chart: loaded.chart,
+            });
+            next_state.set(GameScreen::Playing);
+        }
+        Err(err) => {
+            error!("Failed to load chart: {}", err);
+            commands.insert_resource(LoadError(format!("Can't play this chart: {err}")));
+            next_state.set(GameScreen::SongSelect);
+        }
+    }
+}
+
+fn setup_loading_ui(mut commands: Commands, pending: Option<Res<PendingLoad>>) {
+    let title = pending.map_or_else(String::new, |p| {
+        format!("{} [{}]", p.metadata.title, p.difficulty.label())
+    });
+
+    commands
+        .spawn((
+            DespawnOnExit(GameScreen::Loading),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            BackgroundColor(BG_DARK),
+        ))
+        .with_children(|root: &mut ChildSpawnerCommands| {
+            root.spawn((
+                LoadingSpinner,
+                Text::new(SPINNER_FRAMES[0]),
+                TextFont {
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(ACCENT_CYAN),
+            ));
+            root.spawn((
+                Text::new(title),
+                TextFont {
+                    font_size: 22.0,
+                    ..default()
+                },
+                TextColor(TITLE_COLOR),
+            ));
+            root.spawn((
+                Text::new("LOADING"),
+                TextFont {
+                    font_size: 12.0,
+                    ..default()
+                },
+                TextColor(HINT_COLOR),
+            ));
+        });
+}
+
+fn spin_loading_indicator(time: Res<Time>, mut spinners: Query<&mut Text, With<LoadingSpinner>>) {
+    let frame = (time.elapsed_secs() * SPINNER_FPS) as usize % SPINNER_FRAMES.len();
+    for mut text in &mut spinners {
+        **text = SPINNER_FRAMES[frame].to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::state::app::StatesPlugin;
+
+    use crate::state::GameStatePlugin;
+
+    const CHART: &str = r#"(
+        difficulty: Normal,
+        difficulty_rating: 3,
+        timing_points: [(beat: 0.0, bpm: 120.0, time_signature: (4, 4))],
+        path_segments: [CatmullRom(
+            points: [(0.0, 0.0), (100.0, 50.0), (200.0, 0.0), (300.0, 50.0)],
+            start_beat: 0.0,
+            end_beat: 16.0,
+        )],
+        notes: [(beat: 4.0, note_type: Tap)],
+    )"#;
+
+    /// Tiny 16-bit mono PCM WAV of silence.
+    fn silent_wav() -> Vec<u8> {
+        let samples = 800u32;
+        let data_len = samples * 2;
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+        wav.extend_from_slice(&8000u32.to_le_bytes());
+        wav.extend_from_slice(&16000u32.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        wav.resize(wav.len() + data_len as usize, 0);
+        wav
+    }
+
+    fn metadata() -> SongMetadata {
+        SongMetadata {
+            title: "Test".into(),
+            artist: String::new(),
+            charter: String::new(),
+            audio_file: "song.wav".into(),
+            preview_start_ms: 0,
+            preview_duration_ms: 0,
+            source: String::new(),
+            difficulties: vec![Difficulty::Normal],
+            offset_ms: 0,
+        }
+    }
+
+    fn loading_app(song_dir: PathBuf) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, GameStatePlugin))
+            .add_systems(OnEnter(GameScreen::Loading), start_song_load)
+            .add_systems(Update, poll_song_load.run_if(in_state(GameScreen::Loading)))
+            .insert_resource(PendingLoad {
+                song_dir,
+                difficulty: Difficulty::Normal,
+                metadata: metadata(),
+            });
+        app.world_mut()
+            .resource_mut::<NextState<GameScreen>>()
+            .set(GameScreen::Loading);
+        app
+    }
+
+    fn run_until_settled(app: &mut App) -> GameScreen {
+        for _ in 0..500 {
+            app.update();
+            if *app.world().resource::<State<GameScreen>>().get() != GameScreen::Loading {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        app.world().resource::<State<GameScreen>>().get().clone()
+    }
+
+    #[test]
+    fn load_task_goes_pending_then_ready_then_playing() {
+        let dir = std::env::temp_dir().join(format!("funktrack-{}-load-ok", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(Difficulty::Normal.filename()), CHART).unwrap();
+        std::fs::write(dir.join("song.wav"), silent_wav()).unwrap();
+
+        let mut app = loading_app(dir.clone());
+        app.update();
+        // Pending: the task is running and nothing is selected yet
+        assert_eq!(*app.world().resource::<State<GameScreen>>().get(), GameScreen::Loading);
+        assert!(app.world().contains_resource::<SongLoadTask>());
+        assert!(!app.world().contains_resource::<SelectedSong>());
+
+        // Ready → Playing, with the chart and decoded audio handed over
+        assert_eq!(run_until_settled(&mut app), GameScreen::Playing);
+        std::fs::remove_dir_all(&dir).ok();
+        let world = app.world();
+        assert_eq!(world.resource::<SelectedSong>().chart.notes.len(), 1);
+        assert_eq!(world.resource::<PreloadedSong>().path, dir.join("song.wav"));
+        assert!(!world.contains_resource::<SongLoadTask>());
+        assert!(!world.contains_resource::<PendingLoad>());
+    }
+
+    #[test]
+    fn failed_load_returns_to_song_select_with_message() {
+        let dir = std::env::temp_dir().join(format!("funktrack-{}-load-missing", std::process::id()));
+
+        let mut app = loading_app(dir);
+        assert_eq!(run_until_settled(&mut app), GameScreen::SongSelect);
+        let err = &app.world().resource::<LoadError>().0;
+        assert!(err.contains("read error"), "{err}");
+        assert!(!app.world().contains_resource::<SelectedSong>());
+    }
+}
@@ -3,16 +3,21 @@ mod audio;
 mod beatmap;
 mod calibration;
 mod camera;
+mod chart_watch;
 mod conductor;
 mod config;
 mod editor;
+mod health;
 mod hud;
 mod input;
 mod judgment;
+mod loading;
+mod main_menu;
 mod notes;
 mod particles;
 mod pause;
 mod path;
+mod replay;
 mod results;
 mod scoring;
 mod settings;
@@ -21,23 +26,27 @@ mod state;
 mod visuals;
 
 use bevy::prelude::*;
-use bevy::window::PresentMode;
 
 use action::ActionPlugin;
 use audio::KiraPlugin;
 use beatmap::BeatMapPlugin;
 use calibration::CalibrationPlugin;
 use camera::CameraPlugin;
+use chart_watch::ChartWatchPlugin;
 use conductor::ConductorPlugin;
 use config::ConfigPlugin;
 use editor::EditorPluginBundle;
+use health::HealthPlugin;
 use hud::HudPlugin;
 use input::InputPlugin;
 use judgment::JudgmentPlugin;
+use loading::LoadingPlugin;
+use main_menu::MainMenuPlugin;
 use notes::NotesPlugin;
 use particles::ParticlePlugin;
 use pause::PausePlugin;
 use path::PathPlugin;
+use replay::ReplayPlugin;
 use results::ResultsPlugin;
 use scoring::ScoringPlugin;
 use settings::SettingsPlugin;
@@ -61,7 +70,6 @@ fn main() {
             primary_window: Some(Window {
                 title: "Rhythm Rail".into(),
                 resolution: (1280, 720).into(),
-                present_mode: PresentMode::AutoNoVsync,
                 ..default()
             }),
             ..default()
@@ -103,8 +111,12 @@ fn main() {
             CameraPlugin,
             ConfigPlugin,
             CalibrationPlugin,
+            ReplayPlugin,
+            LoadingPlugin,
+            MainMenuPlugin,
+            HealthPlugin,
         ))
-        .add_plugins(EditorPluginBundle)
+        .add_plugins((EditorPluginBundle, ChartWatchPlugin))
         .run();
 }
 
@@ -0,0 +1,234 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::action::{GameAction, MenuNav};
+use crate::settings::SettingsReturn;
+use crate::song_select::SongPickPurpose;
+use crate::state::GameScreen;
+
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameScreen::MainMenu), setup_main_menu)
+            .add_systems(
+                Update,
+                (navigate_main_menu, update_main_menu_ui)
+                    .chain()
+                    .run_if(in_state(GameScreen::MainMenu)),
+            );
+    }
+}
+
+// --- Y2K Future Punk palette ---
+
+const BG_DARK: Color = Color::srgba(0.02, 0.01, 0.06, 0.95);
+const PANEL_BG: Color = Color::srgba(0.06, 0.03, 0.12, 0.92);
+const PANEL_BORDER: Color = Color::srgb(0.6, 0.2, 1.0);
+const PANEL_BORDER_DIM: Color = Color::srgba(0.6, 0.2, 1.0, 0.3);
+const SELECTED_BG: Color = Color::srgba(0.12, 0.04, 0.22, 0.95);
+const SELECTED_BORDER: Color = Color::srgb(0.0, 0.9, 1.0);
+const TITLE_COLOR: Color = Color::srgb(0.92, 0.96, 1.0);
+const HINT_COLOR: Color = Color::srgb(0.4, 0.35, 0.5);
+const HEADER_COLOR: Color = Color::srgb(0.6, 0.2, 1.0);
+const ACCENT_CYAN: Color = Color::srgb(0.0, 0.9, 1.0);
+
+const HEADER_FONT: f32 = 48.0;
+const OPTION_FONT: f32 = 24.0;
+const HINT_FONT: f32 = 12.0;
+
+// --- Menu options ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MenuOption {
+    Play,
+    Settings,
+    Editor,
+    Quit,
+}
+
+impl MenuOption {
+    const ALL: &[MenuOption] = &[
+        MenuOption::Play,
+        MenuOption::Settings,
+        MenuOption::Editor,
+        MenuOption::Quit,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            MenuOption::Play => "PLAY",
+            MenuOption::Settings => "SETTINGS",
+            MenuOption::Editor => "EDITOR",
+            MenuOption::Quit => "QUIT",
+        }
+    }
+}
+
+// --- Resources ---
+
+#[derive(Resource)]
+struct MainMenuState {
+    selected: usize,
+}
+
+// --- Markers ---
+
+#[derive(Component)]
+struct MenuItem(usize);
+
+#[derive(Component)]
+struct MenuItemText(usize);
+
+// --- Systems ---
+
+fn setup_main_menu(mut commands: Commands) {
+    commands.insert_resource(MainMenuState { selected: 0 });
+    commands.insert_resource(MenuNav::default());
+
+    commands
+        .spawn((
+            DespawnOnExit(GameScreen::MainMenu),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(24.0),
+                padding: UiRect::all(Val::Px(32.0)),
+                ..default()
+            },
+            BackgroundColor(BG_DARK),
+        ))
+        .with_children(|root: &mut ChildSpawnerCommands| {
+            root.spawn((
+                Text::new("FUNKTRACK"),
+                TextFont {
+                    font_size: HEADER_FONT,
+                    ..default()
+                },
+                TextColor(HEADER_COLOR),
+                Node {
+                    margin: UiRect::bottom(Val::Px(8.0)),
+                    ..default()
+                },
+            ));
+
+            root.spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    padding: UiRect::all(Val::Px(16.0)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    border_radius: BorderRadius::all(Val::Px(6.0)),
+                    min_width: Val::Px(320.0),
+                    ..default()
+                },
+                BackgroundColor(PANEL_BG),
+                BorderColor::all(PANEL_BORDER),
+            ))
+            .with_children(|list: &mut ChildSpawnerCommands| {
+                for (i, option) in MenuOption::ALL.iter().enumerate() {
+                    let is_selected = i == 0;
+                    list.spawn((
+                        MenuItem(i),
+                        Node {
+                            justify_content: JustifyContent::Center,
+                            padding: UiRect::axes(Val::Px(16.0), Val::Px(10.0)),
+                            border: UiRect::left(Val::Px(3.0)),
+                            border_radius: BorderRadius::all(Val::Px(3.0)),
+                            ..default()
+                        },
+                        BackgroundColor(if is_selected { SELECTED_BG } else { Color::NONE }),
+                        BorderColor::all(if is_selected { SELECTED_BORDER } else { PANEL_BORDER_DIM }),
+                    ))
+                    .with_children(|item: &mut ChildSpawnerCommands| {
+                        item.spawn((
+                            MenuItemText(i),
+                            Text::new(option.label()),
+                            TextFont {
+                                font_size: OPTION_FONT,
+                                ..default()
+                            },
+                            TextColor(if is_selected { ACCENT_CYAN } else { TITLE_COLOR }),
+                        ));
+                    });
+                }
+            });
+
+            root.spawn((
+                Text::new("[UP/DOWN] select    [A/SPACE] confirm    [ESC] quit"),
+                TextFont {
+                    font_size: HINT_FONT,
+                    ..default()
+                },
+                TextColor(HINT_COLOR),
+            ));
+        });
+}
+
+fn navigate_main_menu(
+    mut commands: Commands,
+    action: Res<ActionState<GameAction>>,
+    time: Res<Time>,
+    mut nav: ResMut<MenuNav>,
+    mut state: ResMut<MainMenuState>,
+    mut next_state: ResMut<NextState<GameScreen>>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    nav.tick(&action, time.delta_secs());
+    let count = MenuOption::ALL.len();
+    if nav.fired(GameAction::Up) {
+        state.selected = (state.selected + count - 1) % count;
+    }
+    if nav.fired(GameAction::Down) {
+        state.selected = (state.selected + 1) % count;
+    }
+
+    if action.just_pressed(&GameAction::Back) {
+        exit.write(AppExit::Success);
+        return;
+    }
+    if !action.just_pressed(&GameAction::Confirm) {
+        return;
+    }
+
+    match MenuOption::ALL[state.selected] {
+        MenuOption::Play => {
+            commands.insert_resource(SongPickPurpose::Play);
+            next_state.set(GameScreen::SongSelect);
+        }
+        MenuOption::Settings => {
+            commands.insert_resource(SettingsReturn(GameScreen::MainMenu));
+            next_state.set(GameScreen::Settings);
+        }
+        // The editor works on a chart, so pick one first
+        MenuOption::Editor => {
+            commands.insert_resource(SongPickPurpose::Edit);
+            next_state.set(GameScreen::SongSelect);
+        }
+        MenuOption::Quit => {
+            exit.write(AppExit::Success);
+        }
+    }
+}
+
+fn update_main_menu_ui(
+    state: Res<MainMenuState>,
+    mut items: Query<(&MenuItem, &mut BackgroundColor, &mut BorderColor)>,
+    mut texts: Query<(&MenuItemText, &mut TextColor)>,
+) {
+    if !state.is_changed() {
+        return;
+    }
+    for (item, mut bg, mut border) in &mut items {
+        let is_selected = item.0 == state.selected;
+        *bg = BackgroundColor(if is_selected { SELECTED_BG } else { Color::NONE });
+        *border = BorderColor::all(if is_selected { SELECTED_BORDER } else { PANEL_BORDER_DIM });
+    }
+    for (text, mut color) in &mut texts {
+        let is_selected = text.0 == state.selected;
+        *color = TextColor(if is_selected { ACCENT_CYAN } else { TITLE_COLOR });
+    }
+}
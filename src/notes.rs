@@ -15,18 +15,46 @@ impl Plugin for NotesPlugin {
 
 // --- Components ---
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum NoteKind {
     Tap,
     Slide(crate::beatmap::SlideDirection),
     Hold { end_beat: f64 },
-    Rest,
     Critical,
+    /// Invisible bonus note; scores outside the play pool and is never missed.
+    AdLib,
+    /// Hit with a back-and-forth scratch gesture rather than a single press.
+    Scratch,
+    /// Two slides at once, one per stick, in either order.
+    DualSlide {
+        left: crate::beatmap::SlideDirection,
+        right: crate::beatmap::SlideDirection,
+    },
+}
+
+impl NoteKind {
+    /// Tiebreak for notes on the same beat, so their order never depends on
+    /// how the chart file happened to list them.
+    pub fn sort_rank(&self) -> u8 {
+        match self {
+            NoteKind::Tap => 0,
+            NoteKind::Slide(direction) => 1 + *direction as u8,
+            NoteKind::Hold { .. } => 9,
+            NoteKind::Critical => 10,
+            NoteKind::AdLib => 11,
+            NoteKind::Scratch => 12,
+            NoteKind::DualSlide { left, right } => 13 + 8 * *left as u8 + *right as u8,
+        }
+    }
 }
 
 #[derive(Component)]
 pub struct HoldEndBeat(pub f64);
 
+/// Beat at which a held hold was released; present while in `HoldState::Dropped`.
+#[derive(Component)]
+pub struct HoldReleaseBeat(pub f64);
+
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HoldState {
     Pending,
@@ -35,6 +63,10 @@ pub enum HoldState {
     Dropped,
 }
 
+/// Sustain ticks already awarded for a held hold note.
+#[derive(Component, Default)]
+pub struct HoldTicks(pub u32);
+
 #[derive(Component)]
 pub struct NoteType(pub NoteKind);
 
@@ -53,10 +85,6 @@ pub struct SplineProgress(pub f32);
 #[derive(Component)]
 pub struct NoteAlive;
 
-/// Marker for Rest notes: rewarded on pass, penalized on tap.
-#[derive(Component)]
-pub struct RestMarker;
-
 // --- Resources ---
 
 pub struct ChartNote {
@@ -68,6 +96,51 @@ pub struct ChartNote {
 pub struct NoteQueue {
     pub notes: Vec<ChartNote>,
     pub next_index: usize,
+    /// Beats a note takes to approach, after note-speed scaling.
+    pub travel_beats: f64,
+    /// Beats ahead of the playhead that notes spawn, after note-speed scaling.
+    pub look_ahead_beats: f64,
+}
+
+impl NoteQueue {
+    /// Queue for `notes` with approach values already scaled by note speed.
+    /// Look-ahead is raised to at least `travel_beats` so every note spawns
+    /// before its approach starts instead of popping in part way through.
+    pub fn new(notes: Vec<ChartNote>, travel_beats: f64, look_ahead_beats: f64) -> Self {
+        if look_ahead_beats < travel_beats {
+            warn!(
+                "Look-ahead of {look_ahead_beats:.2} beats is shorter than travel of \
+                 {travel_beats:.2}; using {travel_beats:.2}"
+            );
+        }
+        Self {
+            notes,
+            next_index: 0,
+            travel_beats,
+            look_ahead_beats: look_ahead_beats.max(travel_beats),
+        }
+    }
+
+    /// How far along its approach a note targeting `target_beat` is at `beat`:
+    /// 0 when it starts travelling, 1 on the judgment line.
+    pub fn approach_at(&self, target_beat: f64, beat: f64) -> f64 {
+        1.0 - (target_beat - beat) / self.travel_beats.max(f64::EPSILON)
+    }
+
+    /// Beat at which `note` enters the look-ahead window and spawns.
+    pub fn spawn_beat(&self, note: &ChartNote) -> f64 {
+        note.target_beat - self.look_ahead_beats
+    }
+}
+
+/// Shortest approach allowed, so high note speeds don't spawn notes on top of
+/// the judgment point.
+const MIN_APPROACH_BEATS: f64 = 0.5;
+
+/// Scale a chart's approach beats by the player's note speed (1.0x = chart value).
+pub fn scaled_approach_beats(chart_beats: f64, note_speed: f32) -> f64 {
+    let speed = (note_speed as f64).max(f64::EPSILON);
+    (chart_beats / speed).max(MIN_APPROACH_BEATS)
 }
 
 /// Maps song beats to spline progress (0.0→1.0).
@@ -80,21 +153,24 @@ pub struct Playhead {
 
 impl Playhead {
     /// Convert a beat to normalized spline progress, clamped 0.0→1.0.
-    pub fn progress(&self, beat: f64) -> f32 {
+    /// Everything placed on the track (notes, hold tails, camera, judgment
+    /// line) goes through this so they all agree on where a beat sits.
+    pub fn progress_at_beat(&self, beat: f64) -> f32 {
         let range = self.song_end_beat - self.song_start_beat;
         if range <= 0.0 {
             return 0.0;
         }
         ((beat - self.song_start_beat) / range).clamp(0.0, 1.0) as f32
     }
+
+    /// Head and tail progress of a hold spanning `head_beat..end_beat`.
+    pub fn hold_progress(&self, head_beat: f64, end_beat: f64) -> (f32, f32) {
+        (self.progress_at_beat(head_beat), self.progress_at_beat(end_beat))
+    }
 }
 
 // --- Systems ---
 
-/// Spawn window: notes become visible when the playhead is within this
-/// fraction of the spline from their position.
-const SPAWN_VISIBILITY_RANGE: f32 = 0.25;
-
 fn spawn_notes(
     mut commands: Commands,
     conductor: Option<Res<SongConductor>>,
@@ -111,16 +187,14 @@ fn spawn_notes(
         return;
     }
 
-    let current_progress = playhead.progress(conductor.current_beat);
-
     while queue.next_index < queue.notes.len() {
         let note = &queue.notes[queue.next_index];
-        let note_progress = playhead.progress(note.target_beat);
 
-        // Spawn notes that are within the visibility range ahead of the playhead
-        if note_progress > current_progress + SPAWN_VISIBILITY_RANGE {
+        // Spawn notes within the look-ahead window of the playhead
+        if queue.spawn_beat(note) > conductor.current_beat {
             break;
         }
+        let note_progress = playhead.progress_at_beat(note.target_beat);
 
         let kind = note.kind;
         let entity = commands.spawn((
@@ -138,10 +212,11 @@ fn spawn_notes(
                 commands.entity(entity).insert(NoteDirection(dir));
             }
             NoteKind::Hold { end_beat } => {
-                commands.entity(entity).insert((HoldEndBeat(end_beat), HoldState::Pending));
-            }
-            NoteKind::Rest => {
-                commands.entity(entity).insert(RestMarker);
+                commands.entity(entity).insert((
+                    HoldEndBeat(end_beat),
+                    HoldState::Pending,
+                    HoldTicks::default(),
+                ));
             }
             _ => {}
         }
@@ -149,3 +224,45 @@ fn spawn_notes(
         queue.next_index += 1;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn note_speed_scales_approach() {
+        assert!((scaled_approach_beats(3.0, 1.0) - 3.0).abs() < 1e-9);
+        assert!((scaled_approach_beats(3.0, 2.0) - 1.5).abs() < 1e-9);
+        assert!((scaled_approach_beats(3.0, 0.5) - 6.0).abs() < 1e-9);
+        // Faster than the minimum clamps
+        assert!((scaled_approach_beats(3.0, 10.0) - MIN_APPROACH_BEATS).abs() < 1e-9);
+        assert!((scaled_approach_beats(1.0, 3.0) - MIN_APPROACH_BEATS).abs() < 1e-9);
+    }
+
+    #[test]
+    fn short_look_ahead_is_raised_to_travel() {
+        let notes = vec![ChartNote { target_beat: 8.0, kind: NoteKind::Tap }];
+        let queue = NoteQueue::new(notes, 3.0, 1.0);
+        assert_eq!(queue.look_ahead_beats, 3.0);
+        // The note spawns right as its approach begins
+        let spawn = queue.spawn_beat(&queue.notes[0]);
+        assert_eq!(queue.approach_at(8.0, spawn), 0.0);
+
+        // A longer look-ahead is kept as authored
+        let queue = NoteQueue::new(Vec::new(), 2.0, 4.0);
+        assert_eq!(queue.look_ahead_beats, 4.0);
+    }
+
+    #[test]
+    fn hold_progress_matches_progress_at_beat() {
+        let playhead = Playhead { song_start_beat: 4.0, song_end_beat: 36.0 };
+        let (head, tail) = playhead.hold_progress(8.0, 10.5);
+        assert_eq!(head, playhead.progress_at_beat(8.0));
+        assert_eq!(tail, playhead.progress_at_beat(10.5));
+        assert!((head - 0.125).abs() < 1e-6);
+        assert!(tail > head);
+        // A tail past the end of the path clamps like any other beat
+        let (_, tail) = playhead.hold_progress(34.0, 40.0);
+        assert_eq!(tail, 1.0);
+    }
+}
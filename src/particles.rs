@@ -5,25 +5,49 @@ use bevy_prototype_lyon::prelude::*;
 
 use crate::GameSet;
 use crate::judgment::{Judgment, JudgmentResult};
-use crate::state::GameScreen;
+use crate::scoring::{ChainTier, ScoreState};
+use crate::state::InGame;
 
 pub struct ParticlePlugin;
 
 impl Plugin for ParticlePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (spawn_particles, update_particles)
-                .chain()
-                .in_set(GameSet::Render),
-        );
+        app.add_systems(OnEnter(InGame), reset_ambient_field)
+            .add_systems(
+                Update,
+                (
+                    spawn_particles,
+                    update_particles,
+                    spawn_ambient_particles,
+                    update_ambient_particles,
+                )
+                    .chain()
+                    .in_set(GameSet::Render),
+            );
     }
 }
 
 // --- Config ---
 
 const DRAG: f32 = 0.97;
+const GRAVITY: f32 = 60.0;
 const PARTICLE_SIZE: f32 = 2.5;
+/// Upper bound on live burst particles so dense charts can't pile up entities.
+const MAX_LIVE_PARTICLES: usize = 512;
+
+// --- Ambient field config ---
+
+/// Ambient spawns per second at full Fever intensity (sparse motes).
+const FEVER_SPAWN_RATE: f32 = 12.0;
+/// Ambient spawns per second at full Trance intensity (dense streaks).
+const TRANCE_SPAWN_RATE: f32 = 60.0;
+/// Intensity units per second the field ramps toward the current tier.
+const AMBIENT_RAMP_RATE: f32 = 1.5;
+/// Half-extent of the spawn area around the camera, before zoom.
+const AMBIENT_HALF_EXTENT: Vec2 = Vec2::new(700.0, 420.0);
+const AMBIENT_Z: f32 = 0.2;
+const MOTE_COLOR: Color = Color::srgb(1.0, 0.85, 0.15);
+const STREAK_COLOR: Color = Color::srgb(1.0, 1.0, 1.0);
 
 // --- Components ---
 
@@ -35,19 +59,53 @@ struct Particle {
     base_color: Color,
 }
 
+#[derive(Component)]
+struct AmbientParticle {
+    velocity: Vec2,
+    lifetime: f32,
+    max_lifetime: f32,
+    base_color: Color,
+}
+
+/// Smoothed chain-tier intensity: 0 = Normal, 1 = Fever, 2 = Trance.
+#[derive(Resource, Default)]
+struct AmbientField {
+    level: f32,
+    accumulator: f32,
+    spawned: u32,
+}
+
 // --- Systems ---
 
+/// Number of particles in a hit burst — better judgments get bigger bursts.
+fn burst_count(judgment: Judgment) -> u32 {
+    match judgment {
+        Judgment::Great => 24,
+        Judgment::Cool => 16,
+        Judgment::Good => 10,
+        Judgment::Miss => 8,
+    }
+}
+
 fn spawn_particles(
     mut commands: Commands,
     mut results: MessageReader<JudgmentResult>,
+    live: Query<(), With<Particle>>,
 ) {
+    let mut budget = MAX_LIVE_PARTICLES.saturating_sub(live.iter().count());
+
     for result in results.read() {
-        let (count, base_color, speed_range, spread) = match result.judgment {
-            Judgment::Great => (24, Color::srgb(0.0, 1.0, 0.4), (80.0, 200.0), TAU),
-            Judgment::Cool => (16, Color::srgb(0.0, 0.7, 1.0), (60.0, 160.0), TAU),
-            Judgment::Good => (10, Color::srgb(1.0, 0.85, 0.0), (40.0, 120.0), TAU),
-            Judgment::Miss => (8, Color::srgb(1.0, 0.15, 0.3), (30.0, 80.0), TAU * 0.5),
+        let (base_color, speed_range, spread) = match result.judgment {
+            Judgment::Great => (Color::srgb(0.0, 1.0, 0.4), (80.0, 200.0), TAU),
+            Judgment::Cool => (Color::srgb(0.0, 0.7, 1.0), (60.0, 160.0), TAU),
+            Judgment::Good => (Color::srgb(1.0, 0.85, 0.0), (40.0, 120.0), TAU),
+            Judgment::Miss => (Color::srgb(1.0, 0.15, 0.3), (30.0, 80.0), TAU * 0.5),
         };
+        let count = burst_count(result.judgment).min(budget as u32);
+        if count == 0 {
+            continue;
+        }
+        budget -= count as usize;
 
         let pos = result.position;
         let lifetime = match result.judgment {
@@ -79,7 +137,7 @@ fn spawn_particles(
             };
 
             commands.spawn((
-                DespawnOnExit(GameScreen::Playing),
+                DespawnOnExit(InGame),
                 Particle {
                     velocity: dir * speed,
                     lifetime: particle_lifetime,
@@ -117,7 +175,7 @@ fn update_particles(
         p.velocity *= DRAG;
 
         // Gravity (slight downward drift)
-        p.velocity.y -= 60.0 * dt;
+        p.velocity.y -= GRAVITY * dt;
 
         // Fade alpha
         let t = p.lifetime / p.max_lifetime;
@@ -132,6 +190,138 @@ fn update_particles(
     }
 }
 
+fn tier_level(tier: ChainTier) -> f32 {
+    match tier {
+        ChainTier::Normal => 0.0,
+        ChainTier::Fever => 1.0,
+        ChainTier::Trance => 2.0,
+    }
+}
+
+/// Ambient spawns per second for a smoothed tier level. Zero in Normal,
+/// interpolating through Fever up to Trance so tier changes ramp rather than pop.
+fn ambient_spawn_rate(level: f32) -> f32 {
+    let level = level.clamp(0.0, 2.0);
+    if level <= 1.0 {
+        FEVER_SPAWN_RATE * level
+    } else {
+        FEVER_SPAWN_RATE + (TRANCE_SPAWN_RATE - FEVER_SPAWN_RATE) * (level - 1.0)
+    }
+}
+
+fn reset_ambient_field(mut commands: Commands) {
+    commands.insert_resource(AmbientField::default());
+}
+
+fn spawn_ambient_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    state: Option<Res<ScoreState>>,
+    field: Option<ResMut<AmbientField>>,
+    camera_q: Query<(&Transform, &Projection), With<Camera2d>>,
+) {
+    let (Some(state), Some(mut field)) = (state, field) else {
+        return;
+    };
+    let dt = time.delta_secs();
+
+    let target = tier_level(state.chain_tier());
+    let step = AMBIENT_RAMP_RATE * dt;
+    field.level += (target - field.level).clamp(-step, step);
+
+    let rate = ambient_spawn_rate(field.level);
+    if rate <= 0.0 {
+        field.accumulator = 0.0;
+        return;
+    }
+
+    let Ok((cam_transform, projection)) = camera_q.single() else {
+        return;
+    };
+    let zoom = match projection {
+        Projection::Orthographic(ortho) => ortho.scale,
+        _ => 1.0,
+    };
+    let center = cam_transform.translation.truncate();
+    let extent = AMBIENT_HALF_EXTENT * zoom;
+
+    // Past halfway to Trance the field switches from gold motes to white streaks.
+    let streaks = field.level > 1.5;
+    // Individual particles fade with the ramp so the field eases in and out.
+    let intensity = field.level.min(1.0);
+
+    field.accumulator += rate * dt;
+    while field.accumulator >= 1.0 {
+        field.accumulator -= 1.0;
+        let i = field.spawned;
+        field.spawned = field.spawned.wrapping_add(1);
+
+        let offset = Vec2::new(
+            (pseudo_random(i, 10) * 2.0 - 1.0) * extent.x,
+            (pseudo_random(i, 11) * 2.0 - 1.0) * extent.y,
+        );
+
+        let (velocity, lifetime, base_color, size) = if streaks {
+            let speed = 400.0 + 300.0 * pseudo_random(i, 12);
+            (Vec2::new(-0.35, -1.0).normalize() * speed, 0.6, STREAK_COLOR, Vec2::new(1.5, 18.0))
+        } else {
+            let drift = Vec2::new(pseudo_random(i, 12) - 0.5, 1.0) * 30.0;
+            (drift, 1.6 + pseudo_random(i, 13), MOTE_COLOR, Vec2::splat(PARTICLE_SIZE * 1.2))
+        };
+        let base_color = base_color.with_alpha(0.6 * intensity);
+
+        let rect = shapes::Rectangle {
+            extents: size,
+            ..default()
+        };
+        let rotation = if streaks {
+            Quat::from_rotation_z(velocity.to_angle() - TAU * 0.25)
+        } else {
+            Quat::IDENTITY
+        };
+
+        commands.spawn((
+            DespawnOnExit(InGame),
+            AmbientParticle {
+                velocity,
+                lifetime,
+                max_lifetime: lifetime,
+                base_color,
+            },
+            ShapeBuilder::with(&rect).fill(base_color).build(),
+            Transform::from_translation((center + offset).extend(AMBIENT_Z))
+                .with_rotation(rotation),
+        ));
+    }
+}
+
+fn update_ambient_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particles: Query<(Entity, &mut AmbientParticle, &mut Transform, &mut Shape)>,
+) {
+    let dt = time.delta_secs();
+
+    for (entity, mut p, mut transform, mut shape) in &mut particles {
+        p.lifetime -= dt;
+        if p.lifetime <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation.x += p.velocity.x * dt;
+        transform.translation.y += p.velocity.y * dt;
+
+        // Fade in over the first fifth of life, fade out over the rest
+        let age = 1.0 - p.lifetime / p.max_lifetime;
+        let alpha = if age < 0.2 { age / 0.2 } else { (1.0 - age) / 0.8 };
+        if let Some(ref mut fill) = shape.fill {
+            let base_alpha = p.base_color.alpha();
+            fill.color = p.base_color.with_alpha(base_alpha * alpha);
+        }
+    }
+}
+
 /// Deterministic pseudo-random 0..1 based on particle index and seed.
 fn pseudo_random(index: u32, seed: u32) -> f32 {
     let n = index.wrapping_mul(1103515245).wrapping_add(seed.wrapping_mul(12345));
@@ -139,3 +329,36 @@ fn pseudo_random(index: u32, seed: u32) -> f32 {
     let n = n.wrapping_mul(2654435761);
     (n & 0xFFFF) as f32 / 65535.0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn burst_count_scales_with_judgment() {
+        assert_eq!(burst_count(Judgment::Great), 24);
+        assert_eq!(burst_count(Judgment::Cool), 16);
+        assert_eq!(burst_count(Judgment::Good), 10);
+        assert_eq!(burst_count(Judgment::Miss), 8);
+        assert!(burst_count(Judgment::Great) > burst_count(Judgment::Cool));
+        assert!(burst_count(Judgment::Cool) > burst_count(Judgment::Good));
+        assert!(burst_count(Judgment::Good) > burst_count(Judgment::Miss));
+    }
+
+    #[test]
+    fn ambient_rate_follows_tier() {
+        assert_eq!(ambient_spawn_rate(tier_level(ChainTier::Normal)), 0.0);
+        assert_eq!(ambient_spawn_rate(tier_level(ChainTier::Fever)), FEVER_SPAWN_RATE);
+        assert_eq!(ambient_spawn_rate(tier_level(ChainTier::Trance)), TRANCE_SPAWN_RATE);
+        // Ramping levels interpolate between tiers
+        let half_fever = ambient_spawn_rate(0.5);
+        assert!(half_fever > 0.0 && half_fever < FEVER_SPAWN_RATE);
+        let mid = ambient_spawn_rate(1.5);
+        assert!(mid > FEVER_SPAWN_RATE && mid < TRANCE_SPAWN_RATE);
+    }
+
+    #[test]
+    fn single_burst_fits_under_cap() {
+        assert!((burst_count(Judgment::Great) as usize) < MAX_LIVE_PARTICLES);
+    }
+}
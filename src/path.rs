@@ -75,11 +75,21 @@ impl ArcLengthLut {
     }
 }
 
+/// Samples per curve segment in the precomputed [`SplinePath`] table.
+///
+/// Queries lerp between neighbouring samples, so this bounds how far a
+/// table lookup can stray from the true curve.
+pub const PATH_SAMPLES_PER_SEGMENT: usize = 256;
+
 /// The spline path that notes travel along.
+///
+/// Positions and tangents are sampled once at evenly spaced arc-length
+/// progress, so per-frame queries are a table lerp instead of a LUT search
+/// plus a cubic evaluation.
 #[derive(Resource)]
 pub struct SplinePath {
-    curve: CubicCurve<Vec2>,
-    lut: ArcLengthLut,
+    /// (position, tangent) at progress `i / (len - 1)`.
+    samples: Vec<(Vec2, Vec2)>,
 }
 
 impl SplinePath {
@@ -88,21 +98,72 @@ impl SplinePath {
         let spline = CubicCardinalSpline::new_catmull_rom(points);
         let curve = spline.to_curve().expect("need at least 4 control points");
         let lut = ArcLengthLut::build(&curve, 1000);
-        Self { curve, lut }
+
+        let count = curve.segments().len() * PATH_SAMPLES_PER_SEGMENT;
+        let samples = (0..=count)
+            .map(|i| {
+                let distance = i as f32 / count as f32 * lut.total_length();
+                let t = lut.distance_to_parameter(distance);
+                (curve.position(t), curve.velocity(t))
+            })
+            .collect();
+        Self { samples }
+    }
+
+    /// Neighbouring table samples around `progress` and the blend between them.
+    fn sample_pair(&self, progress: f32) -> (&(Vec2, Vec2), &(Vec2, Vec2), f32) {
+        let last = self.samples.len() - 1;
+        let scaled = progress.clamp(0.0, 1.0) * last as f32;
+        let i = (scaled.floor() as usize).min(last - 1);
+        (&self.samples[i], &self.samples[i + 1], scaled - i as f32)
     }
 
     /// Sample position at normalized progress (0.0 = start, 1.0 = end).
     pub fn position_at_progress(&self, progress: f32) -> Vec2 {
-        let distance = progress.clamp(0.0, 1.0) * self.lut.total_length();
-        let t = self.lut.distance_to_parameter(distance);
-        self.curve.position(t)
+        let ((a, _), (b, _), frac) = self.sample_pair(progress);
+        a.lerp(*b, frac)
     }
 
     /// Sample tangent (velocity direction) at normalized progress.
     pub fn tangent_at_progress(&self, progress: f32) -> Vec2 {
-        let distance = progress.clamp(0.0, 1.0) * self.lut.total_length();
-        let t = self.lut.distance_to_parameter(distance);
-        self.curve.velocity(t)
+        let ((_, a), (_, b), frac) = self.sample_pair(progress);
+        a.lerp(*b, frac)
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_queries_match_direct_evaluation() {
+        let points = vec![
+            Vec2::new(-400.0, 0.0),
+            Vec2::new(-200.0, 150.0),
+            Vec2::new(0.0, -150.0),
+            Vec2::new(200.0, 200.0),
+            Vec2::new(400.0, 0.0),
+            Vec2::new(500.0, -100.0),
+        ];
+        let path = SplinePath::from_catmull_rom_points(points.clone());
+        let curve = CubicCardinalSpline::new_catmull_rom(points)
+            .to_curve()
+            .unwrap();
+        let lut = ArcLengthLut::build(&curve, 1000);
+
+        for i in 0..=997 {
+            let progress = i as f32 / 997.0;
+            let t = lut.distance_to_parameter(progress * lut.total_length());
+
+            let pos_err = path.position_at_progress(progress).distance(curve.position(t));
+            assert!(pos_err < 0.5, "position off by {pos_err} at {progress}");
+
+            let direct = curve.velocity(t).normalize();
+            let tangent = path.tangent_at_progress(progress).normalize();
+            assert!(
+                tangent.dot(direct) > 0.999,
+                "tangent off at {progress}: {tangent} vs {direct}"
+            );
+        }
+    }
+}
@@ -1,8 +1,15 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
+use kira::Tween;
 use leafwing_input_manager::prelude::*;
 
-use crate::action::GameAction;
-use crate::audio::KiraContext;
+use crate::action::{GameAction, MenuNav};
+use crate::audio::{KiraContext, fade_music_to};
+use crate::beatmap::SelectedSong;
+use crate::conductor::SongConductor;
+use crate::config::GameSettings;
+use crate::editor::save_metadata_ron;
 use crate::state::GameScreen;
 
 pub struct PausePlugin;
@@ -16,9 +23,11 @@ impl Plugin for PausePlugin {
         .add_systems(OnEnter(GameScreen::Paused), (pause_audio, spawn_pause_ui))
         .add_systems(
             Update,
-            handle_pause_input.run_if(in_state(GameScreen::Paused)),
+            (handle_pause_input, update_pause_buttons, update_offset_text)
+                .chain()
+                .run_if(in_state(GameScreen::Paused)),
         )
-        .add_systems(OnExit(GameScreen::Paused), resume_audio);
+        .add_systems(OnExit(GameScreen::Paused), (save_offsets, resume_audio));
     }
 }
 
@@ -32,14 +41,89 @@ const TEXT_MUTED: Color = Color::srgb(0.4, 0.35, 0.5);
 const BUTTON_BG: Color = Color::srgba(0.12, 0.06, 0.2, 0.8);
 const BUTTON_HOVER: Color = Color::srgba(0.2, 0.1, 0.35, 0.9);
 const BUTTON_BORDER: Color = Color::srgb(0.5, 0.2, 0.8);
+const BUTTON_SELECTED_BORDER: Color = Color::srgb(0.0, 0.9, 1.0);
+
+/// How long the music takes to fade out on pause and back in on resume.
+const PAUSE_FADE: Duration = Duration::from_millis(150);
+
+/// Offset change per left/right press, in ms.
+const OFFSET_STEP_MS: i32 = 5;
+/// Offset bound, matching the audio offset range in Settings.
+const OFFSET_LIMIT_MS: i32 = 200;
+
+// --- Menu options ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PauseOption {
+    Resume,
+    Restart,
+    QuitToSelect,
+}
+
+impl PauseOption {
+    const ALL: &[PauseOption] = &[
+        PauseOption::Resume,
+        PauseOption::Restart,
+        PauseOption::QuitToSelect,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            PauseOption::Resume => "RESUME",
+            PauseOption::Restart => "RESTART",
+            PauseOption::QuitToSelect => "QUIT TO SELECT",
+        }
+    }
+
+    /// Screen to transition to when this option is chosen.
+    fn target(self) -> GameScreen {
+        match self {
+            PauseOption::Resume => GameScreen::Playing,
+            PauseOption::Restart => GameScreen::Restarting,
+            PauseOption::QuitToSelect => GameScreen::SongSelect,
+        }
+    }
+}
+
+// --- Resources ---
+
+#[derive(Resource, Default)]
+struct PauseMenu {
+    selected: usize,
+    /// The song's offset was changed and metadata.ron needs rewriting.
+    song_offset_dirty: bool,
+    /// The global audio offset was changed and settings need saving.
+    settings_dirty: bool,
+    /// Picked when the menu opens, so nudging an override to 0 keeps it.
+    offset_target: OffsetTarget,
+}
+
+/// Which offset left/right adjusts while paused.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum OffsetTarget {
+    /// The song has its own override, so that's what's off.
+    Song,
+    #[default]
+    Global,
+}
+
+impl OffsetTarget {
+    fn for_song(song_offset_ms: i32) -> Self {
+        if song_offset_ms != 0 {
+            OffsetTarget::Song
+        } else {
+            OffsetTarget::Global
+        }
+    }
+}
 
 // --- Marker components ---
 
 #[derive(Component)]
-struct PauseResumeButton;
+struct PauseButton(PauseOption);
 
 #[derive(Component)]
-struct PauseQuitButton;
+struct OffsetText;
 
 // --- Systems ---
 
@@ -53,17 +137,27 @@ fn detect_pause(
     }
 }
 
-fn pause_audio(mut ctx: NonSendMut<KiraContext>) {
+fn pause_audio(mut ctx: NonSendMut<KiraContext>, conductor: Option<ResMut<SongConductor>>) {
+    if let Some(mut conductor) = conductor {
+        conductor.pause();
+    }
     if let Some(ref mut clock) = ctx.clock {
         clock.pause();
     }
+    fade_music_to(&mut ctx, 0.0, PAUSE_FADE);
+    // The sound keeps playing through the fade; resume_audio rewinds that tail
     if let Some(ref mut sound) = ctx.sound {
-        let _ = sound.pause(Default::default());
+        let _ = sound.pause(Tween {
+            duration: PAUSE_FADE,
+            ..default()
+        });
     }
 }
 
 fn resume_audio(
     mut ctx: NonSendMut<KiraContext>,
+    conductor: Option<ResMut<SongConductor>>,
+    settings: Res<GameSettings>,
     state: Res<State<GameScreen>>,
     next_state: Res<NextState<GameScreen>>,
 ) {
@@ -78,15 +172,30 @@ fn resume_audio(
         return;
     }
 
+    if let Some(mut conductor) = conductor {
+        conductor.resume();
+    }
     if let Some(ref mut clock) = ctx.clock {
         clock.start();
     }
     if let Some(ref mut sound) = ctx.sound {
+        sound.seek_by(-PAUSE_FADE.as_secs_f64());
         let _ = sound.resume(Default::default());
     }
+    fade_music_to(&mut ctx, settings.master_amplitude(), PAUSE_FADE);
 }
 
-fn spawn_pause_ui(mut commands: Commands) {
+fn spawn_pause_ui(
+    mut commands: Commands,
+    selected: Option<Res<SelectedSong>>,
+    settings: Res<GameSettings>,
+) {
+    let song_offset_ms = selected.map_or(0, |s| s.metadata.offset_ms);
+    let offset_target = OffsetTarget::for_song(song_offset_ms);
+
+    commands.insert_resource(PauseMenu { offset_target, ..default() });
+    commands.insert_resource(MenuNav::default());
+
     commands
         .spawn((
             DespawnOnExit(GameScreen::Paused),
@@ -143,21 +252,30 @@ fn spawn_pause_ui(mut commands: Commands) {
                             ..default()
                         },))
                         .with_children(|buttons: &mut ChildSpawnerCommands| {
-                            spawn_pause_button(
-                                buttons,
-                                "RESUME",
-                                PauseResumeButton,
-                            );
-                            spawn_pause_button(
-                                buttons,
-                                "QUIT TO MENU",
-                                PauseQuitButton,
-                            );
+                            for &option in PauseOption::ALL {
+                                spawn_pause_button(buttons, option);
+                            }
                         });
 
+                    panel.spawn((
+                        OffsetText,
+                        Text::new(format_offset(
+                            offset_target,
+                            settings.audio_offset_ms,
+                            song_offset_ms,
+                        )),
+                        TextFont {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                        TextColor(TEXT_PRIMARY),
+                    ));
+
                     // Hint
                     panel.spawn((
-                        Text::new("[ESC] Resume  /  [A / SPACE] Select"),
+                        Text::new(
+                            "[ESC] Resume  /  [UP/DOWN] Choose  /  [A / SPACE] Select  /  [LEFT/RIGHT] Offset",
+                        ),
                         TextFont {
                             font_size: 11.0,
                             ..default()
@@ -168,14 +286,10 @@ fn spawn_pause_ui(mut commands: Commands) {
         });
 }
 
-fn spawn_pause_button(
-    parent: &mut ChildSpawnerCommands,
-    label: &str,
-    marker: impl Component,
-) {
+fn spawn_pause_button(parent: &mut ChildSpawnerCommands, option: PauseOption) {
     parent
         .spawn((
-            marker,
+            PauseButton(option),
             Button,
             Node {
                 padding: UiRect {
@@ -195,7 +309,7 @@ fn spawn_pause_button(
         ))
         .with_children(|btn: &mut ChildSpawnerCommands| {
             btn.spawn((
-                Text::new(label.to_string()),
+                Text::new(option.label()),
                 TextFont {
                     font_size: 18.0,
                     ..default()
@@ -205,19 +319,35 @@ fn spawn_pause_button(
         });
 }
 
+/// The offset left/right adjusts, with the global one alongside a song override.
+fn format_offset(target: OffsetTarget, global_offset_ms: i32, song_offset_ms: i32) -> String {
+    match target {
+        OffsetTarget::Song => {
+            format!("SONG OFFSET  {song_offset_ms:+} ms  (GLOBAL {global_offset_ms:+} ms)")
+        }
+        OffsetTarget::Global => format!("AUDIO OFFSET  {global_offset_ms:+} ms"),
+    }
+}
+
+/// Step an offset, staying within the Settings offset range.
+fn nudge_offset(offset_ms: i32, steps: i32) -> i32 {
+    (offset_ms + steps * OFFSET_STEP_MS).clamp(-OFFSET_LIMIT_MS, OFFSET_LIMIT_MS)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn handle_pause_input(
     action: Res<ActionState<GameAction>>,
+    time: Res<Time>,
+    mut nav: ResMut<MenuNav>,
+    mut menu: ResMut<PauseMenu>,
     mut next_state: ResMut<NextState<GameScreen>>,
-    resume_q: Query<&Interaction, (Changed<Interaction>, With<PauseResumeButton>)>,
-    quit_q: Query<&Interaction, (Changed<Interaction>, With<PauseQuitButton>)>,
-    mut button_bgs: Query<
-        (&Interaction, &mut BackgroundColor),
-        (
-            Or<(With<PauseResumeButton>, With<PauseQuitButton>)>,
-            Changed<Interaction>,
-        ),
-    >,
+    buttons: Query<(&Interaction, &PauseButton), Changed<Interaction>>,
+    selected: Option<ResMut<SelectedSong>>,
+    conductor: Option<ResMut<SongConductor>>,
+    mut settings: ResMut<GameSettings>,
 ) {
+    nav.tick(&action, time.delta_secs());
+
     // Keyboard/gamepad: Back (Escape/East) resumes
     if action.just_pressed(&GameAction::Back) {
         info!("Resuming game");
@@ -225,29 +355,164 @@ fn handle_pause_input(
         return;
     }
 
-    // Mouse hover effects
-    for (interaction, mut bg) in &mut button_bgs {
-        match interaction {
-            Interaction::Hovered => *bg = BackgroundColor(BUTTON_HOVER),
-            _ => *bg = BackgroundColor(BUTTON_BG),
+    let count = PauseOption::ALL.len();
+    if nav.fired(GameAction::Up) {
+        menu.selected = (menu.selected + count - 1) % count;
+    }
+    if nav.fired(GameAction::Down) {
+        menu.selected = (menu.selected + 1) % count;
+    }
+
+    // Left/right adjust the song's override if it had one on pause, else the
+    // global offset. The conductor re-seeds its beat estimate on resume.
+    let steps = nav.fired(GameAction::Right) as i32 - nav.fired(GameAction::Left) as i32;
+    if steps != 0 {
+        match (menu.offset_target, selected) {
+            (OffsetTarget::Song, Some(mut selected)) => {
+                let offset_ms = nudge_offset(selected.metadata.offset_ms, steps);
+                selected.metadata.offset_ms = offset_ms;
+                if let Some(mut conductor) = conductor {
+                    conductor.song_offset_ms = offset_ms;
+                }
+                menu.song_offset_dirty = true;
+            }
+            _ => {
+                settings.audio_offset_ms = nudge_offset(settings.audio_offset_ms, steps);
+                menu.settings_dirty = true;
+            }
         }
     }
 
-    // Resume button click
-    for interaction in &resume_q {
-        if *interaction == Interaction::Pressed {
-            info!("Resuming game");
-            next_state.set(GameScreen::Playing);
-            return;
+    let mut chosen = action
+        .just_pressed(&GameAction::Confirm)
+        .then(|| PauseOption::ALL[menu.selected]);
+
+    // Mouse: hover moves the selection, click chooses
+    for (interaction, button) in &buttons {
+        let index = PauseOption::ALL.iter().position(|&o| o == button.0).unwrap_or(0);
+        match interaction {
+            Interaction::Hovered => menu.selected = index,
+            Interaction::Pressed => chosen = Some(button.0),
+            Interaction::None => {}
         }
     }
 
-    // Quit button click
-    for interaction in &quit_q {
-        if *interaction == Interaction::Pressed {
-            info!("Quitting to song select");
-            next_state.set(GameScreen::Results);
-            return;
+    if let Some(option) = chosen {
+        info!("Pause menu: {}", option.label());
+        next_state.set(option.target());
+    }
+}
+
+fn update_offset_text(
+    menu: Res<PauseMenu>,
+    selected: Option<Res<SelectedSong>>,
+    settings: Res<GameSettings>,
+    mut texts: Query<&mut Text, With<OffsetText>>,
+) {
+    let song_changed = selected.as_ref().is_some_and(|s| s.is_changed());
+    if !song_changed && !settings.is_changed() {
+        return;
+    }
+    let song_offset_ms = selected.map_or(0, |s| s.metadata.offset_ms);
+    for mut text in &mut texts {
+        **text = format_offset(menu.offset_target, settings.audio_offset_ms, song_offset_ms);
+    }
+}
+
+/// Persist offsets changed while paused: the global one to settings, a song
+/// override to the song's metadata.ron.
+fn save_offsets(menu: Res<PauseMenu>, settings: Res<GameSettings>, selected: Option<Res<SelectedSong>>) {
+    if menu.settings_dirty {
+        settings.save();
+        info!("Saved audio offset {} ms", settings.audio_offset_ms);
+    }
+    if !menu.song_offset_dirty {
+        return;
+    }
+    let Some(selected) = selected else { return };
+    let path = selected.song_dir.join("metadata.ron");
+    match save_metadata_ron(&selected.metadata, &path) {
+        Ok(()) => info!("Saved song offset {} ms to {}", selected.metadata.offset_ms, path.display()),
+        Err(e) => error!("Failed to save song offset: {e}"),
+    }
+}
+
+fn update_pause_buttons(
+    menu: Res<PauseMenu>,
+    mut buttons: Query<(&PauseButton, &mut BackgroundColor, &mut BorderColor)>,
+) {
+    if !menu.is_changed() {
+        return;
+    }
+    for (button, mut bg, mut border) in &mut buttons {
+        let is_selected = PauseOption::ALL[menu.selected] == button.0;
+        *bg = BackgroundColor(if is_selected { BUTTON_HOVER } else { BUTTON_BG });
+        *border = BorderColor::all(if is_selected { BUTTON_SELECTED_BORDER } else { BUTTON_BORDER });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nudges_stay_inside_the_settings_offset_range() {
+        assert_eq!(nudge_offset(0, 1), 5);
+        assert_eq!(nudge_offset(-10, -2), -20);
+        assert_eq!(nudge_offset(195, 3), OFFSET_LIMIT_MS);
+        assert_eq!(nudge_offset(OFFSET_LIMIT_MS, 1), OFFSET_LIMIT_MS);
+        assert_eq!(nudge_offset(-OFFSET_LIMIT_MS, -1), -OFFSET_LIMIT_MS);
+    }
+
+    #[test]
+    fn a_song_override_takes_the_nudge() {
+        assert_eq!(OffsetTarget::for_song(0), OffsetTarget::Global);
+        assert_eq!(OffsetTarget::for_song(-15), OffsetTarget::Song);
+        assert_eq!(format_offset(OffsetTarget::Global, 10, 0), "AUDIO OFFSET  +10 ms");
+        assert_eq!(
+            format_offset(OffsetTarget::Song, 10, -15),
+            "SONG OFFSET  -15 ms  (GLOBAL +10 ms)"
+        );
+    }
+
+    #[test]
+    fn nudging_a_song_override_through_zero_stays_on_the_song() {
+        use bevy::ecs::system::RunSystemOnce;
+        use bevy::state::app::StatesPlugin;
+        use bevy::time::TimeUpdateStrategy;
+
+        use crate::state::GameStatePlugin;
+
+        let mut metadata: crate::beatmap::SongMetadata =
+            ron::from_str(r#"(title: "", artist: "", charter: "", audio_file: "")"#).unwrap();
+        metadata.offset_ms = -5;
+        let chart =
+            ron::from_str("(difficulty: Normal, timing_points: [], path_segments: [], notes: [])")
+                .unwrap();
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, GameStatePlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(100)))
+            .init_resource::<ActionState<GameAction>>()
+            .init_resource::<GameSettings>()
+            .insert_resource(SelectedSong {
+                song_dir: "assets/songs/neon_drive".into(),
+                difficulty: crate::beatmap::Difficulty::Normal,
+                metadata,
+                chart,
+            })
+            .add_systems(Update, handle_pause_input);
+        app.world_mut().run_system_once(spawn_pause_ui).unwrap();
+        let global_ms = app.world().resource::<GameSettings>().audio_offset_ms;
+
+        // Right twice: -5 to 0, then on to +5 rather than over to the global offset
+        app.update();
+        for _ in 0..2 {
+            app.world_mut().resource_mut::<ActionState<GameAction>>().press(&GameAction::Right);
+            app.update();
+            app.world_mut().resource_mut::<ActionState<GameAction>>().release(&GameAction::Right);
+            app.update();
         }
+        assert_eq!(app.world().resource::<SelectedSong>().metadata.offset_ms, 5);
+        assert_eq!(app.world().resource::<GameSettings>().audio_offset_ms, global_ms);
     }
 }
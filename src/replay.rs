@@ -0,0 +1,563 @@
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::GameSet;
+use crate::action::GameAction;
+use crate::beatmap::{Difficulty, SelectedSong, SlideDirection};
+use crate::conductor::SongConductor;
+use crate::config::config_file;
+use crate::health::{GameplayMods, init_health};
+use crate::hud::setup_hud;
+use crate::input::{CriticalInput, DualSlideInput, ScratchInput, SlideInput, TapInput};
+use crate::state::{GameScreen, InGame};
+
+/// Records every gameplay input while a song is played and saves the run as a
+/// RON replay once the results screen opens. Run with `--replay <file>` to
+/// play a saved replay back instead of reading live input.
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(path) = replay_arg() {
+            match load_replay(&path) {
+                Ok(replay) => {
+                    info!("Replay mode: {:?}", path);
+                    app.insert_resource(PendingReplay(replay));
+                }
+                Err(e) => error!("{}", e),
+            }
+        }
+
+        // Playback runs second so a replay run drops the recorder it just got,
+        // and before health and the HUD read the mods it swaps in
+        app.add_systems(
+            OnEnter(InGame),
+            (start_recording, start_playback).chain().before(init_health).before(setup_hud),
+        )
+            .add_systems(OnExit(InGame), stop_recording)
+            .add_systems(
+                Update,
+                play_replay
+                    .in_set(GameSet::ReadInput)
+                    .run_if(resource_exists::<ReplayPlayback>),
+            )
+            .add_systems(
+                Update,
+                record_inputs
+                    .in_set(GameSet::CheckHits)
+                    .run_if(resource_exists::<ReplayRecorder>),
+            )
+            .add_systems(OnEnter(GameScreen::Results), save_replay);
+    }
+}
+
+// --- Data ---
+
+/// One recorded input. Beats are conductor beats, audio offset included.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEvent {
+    pub beat: f64,
+    pub input: ReplayInput,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ReplayInput {
+    Tap,
+    Slide(SlideDirection),
+    Critical,
+    Scratch,
+    DualSlide(SlideDirection, SlideDirection),
+    /// Tap released, which ends held notes.
+    Release,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    /// Song folder name, e.g. `"neon_drive"`.
+    pub song: String,
+    pub difficulty: Difficulty,
+    /// Gameplay modifiers active for the run.
+    #[serde(default)]
+    pub mods: Vec<String>,
+    pub events: Vec<ReplayEvent>,
+}
+
+impl Replay {
+    pub fn new(song: String, difficulty: Difficulty) -> Self {
+        Self {
+            song,
+            difficulty,
+            mods: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Add an event, keeping the stream in beat order. Inputs read in the same
+    /// frame arrive grouped by type, so they are slotted in by beat; ties keep
+    /// arrival order.
+    pub fn record(&mut self, beat: f64, input: ReplayInput) {
+        let at = self.events.partition_point(|e| e.beat <= beat);
+        self.events.insert(at, ReplayEvent { beat, input });
+    }
+
+    /// Whether this replay was recorded on the given song and difficulty.
+    pub fn matches(&self, song_dir: &Path, difficulty: Difficulty) -> bool {
+        self.difficulty == difficulty && song_dir.file_name().is_some_and(|name| *name == *self.song)
+    }
+}
+
+pub fn load_replay(path: &Path) -> Result<Replay, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read replay {:?}: {}", path, e))?;
+    ron::from_str(&contents).map_err(|e| format!("Failed to parse replay {:?}: {}", path, e))
+}
+
+fn replay_arg() -> Option<PathBuf> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--replay");
+    args.next()?;
+    args.next().map(PathBuf::from)
+}
+
+#[derive(Resource)]
+struct ReplayRecorder(Replay);
+
+/// Replay loaded from `--replay`, waiting for its song to be picked.
+#[derive(Resource)]
+struct PendingReplay(Replay);
+
+/// A replay being fed into gameplay in place of live input.
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    replay: Replay,
+    next: usize,
+    /// Stands in for the live Tap button, for held notes.
+    pub tap_held: bool,
+    /// The player's own mods, put back when the replay ends.
+    live_mods: GameplayMods,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay, live_mods: GameplayMods) -> Self {
+        Self {
+            replay,
+            next: 0,
+            tap_held: false,
+            live_mods,
+        }
+    }
+
+    /// Events due by `current_beat` that have not been played yet.
+    fn due(&mut self, current_beat: f64) -> &[ReplayEvent] {
+        let start = self.next;
+        let remaining = &self.replay.events[start..];
+        self.next += remaining.partition_point(|e| e.beat <= current_beat);
+        &self.replay.events[start..self.next]
+    }
+}
+
+// --- Systems ---
+
+fn start_recording(
+    mut commands: Commands,
+    selected: Option<Res<SelectedSong>>,
+    mods: Option<Res<GameplayMods>>,
+) {
+    let Some(selected) = selected else { return };
+    let song = selected
+        .song_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut replay = Replay::new(song, selected.difficulty);
+    replay.mods = mods.map(|m| m.tags()).unwrap_or_default();
+    commands.insert_resource(ReplayRecorder(replay));
+}
+
+/// Swap live input for the pending replay, but only on the song it was
+/// recorded on. The replay plays once; later runs read live input again.
+/// The run uses the replay's mods so it fails and scores as recorded.
+fn start_playback(
+    mut commands: Commands,
+    pending: Option<Res<PendingReplay>>,
+    selected: Option<Res<SelectedSong>>,
+    mods: Option<ResMut<GameplayMods>>,
+) {
+    let (Some(pending), Some(selected)) = (pending, selected) else {
+        return;
+    };
+    let replay = &pending.0;
+    if !replay.matches(&selected.song_dir, selected.difficulty) {
+        warn!(
+            "Replay is for {} [{}], not {:?} [{}]; playing normally",
+            replay.song,
+            replay.difficulty.label(),
+            selected.song_dir,
+            selected.difficulty.label()
+        );
+        return;
+    }
+    let replay_mods = GameplayMods::from_tags(&replay.mods);
+    let live_mods = match mods {
+        Some(mut mods) => std::mem::replace(&mut *mods, replay_mods),
+        None => {
+            commands.insert_resource(replay_mods);
+            GameplayMods::default()
+        }
+    };
+    commands.remove_resource::<ReplayRecorder>();
+    commands.insert_resource(ReplayPlayback::new(replay.clone(), live_mods));
+    commands.remove_resource::<PendingReplay>();
+}
+
+fn stop_recording(mut commands: Commands, playback: Option<Res<ReplayPlayback>>) {
+    commands.remove_resource::<ReplayRecorder>();
+    commands.remove_resource::<ReplayPlayback>();
+    if let Some(playback) = playback {
+        commands.insert_resource(playback.live_mods.clone());
+    }
+}
+
+/// Emit the recorded inputs through the same messages live input produces.
+fn play_replay(
+    mut playback: ResMut<ReplayPlayback>,
+    conductor: Option<Res<SongConductor>>,
+    mut taps: MessageWriter<TapInput>,
+    mut slides: MessageWriter<SlideInput>,
+    mut criticals: MessageWriter<CriticalInput>,
+    mut scratches: MessageWriter<ScratchInput>,
+    mut duals: MessageWriter<DualSlideInput>,
+) {
+    let Some(conductor) = conductor else { return };
+    let mut held = playback.tap_held;
+    for event in playback.due(conductor.current_beat) {
+        let beat = event.beat;
+        match event.input {
+            ReplayInput::Tap => {
+                held = true;
+                taps.write(TapInput { beat });
+            }
+            ReplayInput::Slide(direction) => {
+                slides.write(SlideInput { beat, direction });
+            }
+            ReplayInput::Critical => {
+                criticals.write(CriticalInput { beat });
+            }
+            ReplayInput::Scratch => {
+                scratches.write(ScratchInput { beat });
+            }
+            ReplayInput::DualSlide(first, second) => {
+                duals.write(DualSlideInput { beat, directions: (first, second) });
+            }
+            ReplayInput::Release => held = false,
+        }
+    }
+    playback.tap_held = held;
+}
+
+fn record_inputs(
+    mut recorder: ResMut<ReplayRecorder>,
+    action: Res<ActionState<GameAction>>,
+    conductor: Option<Res<SongConductor>>,
+    mut taps: MessageReader<TapInput>,
+    mut slides: MessageReader<SlideInput>,
+    mut criticals: MessageReader<CriticalInput>,
+    mut scratches: MessageReader<ScratchInput>,
+    mut duals: MessageReader<DualSlideInput>,
+) {
+    let replay = &mut recorder.0;
+    for tap in taps.read() {
+        replay.record(tap.beat, ReplayInput::Tap);
+    }
+    for slide in slides.read() {
+        replay.record(slide.beat, ReplayInput::Slide(slide.direction));
+    }
+    for critical in criticals.read() {
+        replay.record(critical.beat, ReplayInput::Critical);
+    }
+    for scratch in scratches.read() {
+        replay.record(scratch.beat, ReplayInput::Scratch);
+    }
+    for dual in duals.read() {
+        let (first, second) = dual.directions;
+        replay.record(dual.beat, ReplayInput::DualSlide(first, second));
+    }
+    if action.just_released(&GameAction::Tap) {
+        if let Some(conductor) = conductor {
+            replay.record(conductor.current_beat, ReplayInput::Release);
+        }
+    }
+}
+
+fn replay_path(replay: &Replay) -> Option<PathBuf> {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let name = format!(
+        "{}_{}_{stamp}.ron",
+        replay.song,
+        replay.difficulty.label().to_lowercase()
+    );
+    config_file("replays").map(|dir| dir.join(name))
+}
+
+fn save_replay(recorder: Option<Res<ReplayRecorder>>) {
+    let Some(recorder) = recorder else { return };
+    let Some(path) = replay_path(&recorder.0) else {
+        warn!("No config directory available, cannot save replay");
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            warn!("Failed to create replay directory {:?}: {}", parent, e);
+            return;
+        }
+    }
+    let ron = match ron::ser::to_string_pretty(&recorder.0, ron::ser::PrettyConfig::default()) {
+        Ok(ron) => ron,
+        Err(e) => {
+            warn!("Failed to serialize replay: {}", e);
+            return;
+        }
+    };
+    match std::fs::write(&path, ron) {
+        Ok(()) => info!("Saved replay to {:?}", path),
+        Err(e) => warn!("Failed to write replay to {:?}: {}", path, e),
+    }
+}
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn recording_keeps_inputs_in_beat_order() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_message::<TapInput>()
+            .add_message::<SlideInput>()
+            .add_message::<CriticalInput>()
+            .add_message::<ScratchInput>()
+            .add_message::<DualSlideInput>()
+            .init_resource::<ActionState<GameAction>>()
+            .insert_resource(ReplayRecorder(Replay::new("song".into(), Difficulty::Normal)))
+            .add_systems(Update, record_inputs);
+
+        // One frame: a slide read after a tap that came later
+        app.world_mut().write_message(TapInput { beat: 1.0 });
+        app.world_mut().write_message(SlideInput { beat: 0.5, direction: SlideDirection::E });
+        app.update();
+        // Next frame
+        app.world_mut().write_message(CriticalInput { beat: 2.0 });
+        app.world_mut().write_message(TapInput { beat: 2.0 });
+        app.update();
+
+        let events: Vec<(f64, ReplayInput)> = app
+            .world()
+            .resource::<ReplayRecorder>()
+            .0
+            .events
+            .iter()
+            .map(|e| (e.beat, e.input))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                (0.5, ReplayInput::Slide(SlideDirection::E)),
+                (1.0, ReplayInput::Tap),
+                (2.0, ReplayInput::Tap),
+                (2.0, ReplayInput::Critical),
+            ]
+        );
+    }
+
+    #[test]
+    fn replay_roundtrips_through_ron() {
+        let mut replay = Replay::new("song".into(), Difficulty::Hard);
+        replay.record(0.25, ReplayInput::Tap);
+        replay.record(0.75, ReplayInput::Release);
+        let text = ron::ser::to_string_pretty(&replay, ron::ser::PrettyConfig::default()).unwrap();
+        let back: Replay = ron::from_str(&text).unwrap();
+        assert_eq!(back, replay);
+    }
+
+    /// Step through the chart frame by frame, either feeding `script` in as
+    /// live input (recording it) or playing `replay` back.
+    fn run_chart(script: &[(f64, ReplayInput)], replay: Option<Replay>) -> (BTreeMap<&'static str, u32>, Replay) {
+        use crate::judgment::{JudgmentPlugin, JudgmentResult};
+        use crate::notes::{NoteAlive, NoteDirection, NoteKind, NoteTiming, NoteType, Playhead};
+        use crate::path::SplinePath;
+
+        #[derive(Resource, Default)]
+        struct Counts(BTreeMap<&'static str, u32>);
+
+        fn count(mut results: MessageReader<JudgmentResult>, mut counts: ResMut<Counts>) {
+            for result in results.read() {
+                *counts.0.entry(result.judgment.label()).or_default() += 1;
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, JudgmentPlugin, ReplayPlugin))
+            .add_message::<TapInput>()
+            .add_message::<SlideInput>()
+            .add_message::<CriticalInput>()
+            .add_message::<ScratchInput>()
+            .add_message::<DualSlideInput>()
+            .init_resource::<ActionState<GameAction>>()
+            .init_resource::<Counts>()
+            .insert_resource(SongConductor::new(120.0))
+            .insert_resource(SplinePath::from_catmull_rom_points(vec![
+                Vec2::ZERO,
+                Vec2::X,
+                Vec2::new(2.0, 0.0),
+                Vec2::new(3.0, 0.0),
+            ]))
+            .insert_resource(Playhead { song_start_beat: 0.0, song_end_beat: 8.0 })
+            // Judging only; feedback visuals are out of scope here
+            .configure_sets(Update, (GameSet::ReadInput, GameSet::CheckHits).chain())
+            .configure_sets(Update, (GameSet::UpdateScore, GameSet::Render).run_if(|| false))
+            .add_systems(Update, count.after(GameSet::CheckHits));
+        match replay {
+            Some(replay) => app.insert_resource(ReplayPlayback::new(replay, GameplayMods::default())),
+            None => app.insert_resource(ReplayRecorder(Replay::new("song".into(), Difficulty::Normal))),
+        };
+
+        let notes = [
+            (1.0, NoteKind::Tap),
+            (2.0, NoteKind::Slide(SlideDirection::E)),
+            (3.0, NoteKind::Critical),
+            (4.0, NoteKind::Tap),
+            (5.0, NoteKind::Tap),
+        ];
+        for (target_beat, kind) in notes {
+            let mut note = app.world_mut().spawn((NoteAlive, NoteTiming { target_beat }, NoteType(kind)));
+            if let NoteKind::Slide(direction) = kind {
+                note.insert(NoteDirection(direction));
+            }
+        }
+
+        let mut pending = script.iter().peekable();
+        for frame in 0..140 {
+            let beat = frame as f64 * 0.05;
+            app.world_mut().resource_mut::<SongConductor>().current_beat = beat;
+            while let Some(&&(at, input)) = pending.peek() {
+                if at > beat {
+                    break;
+                }
+                pending.next();
+                let world = app.world_mut();
+                match input {
+                    ReplayInput::Tap => {
+                        world.write_message(TapInput { beat });
+                    }
+                    ReplayInput::Slide(direction) => {
+                        world.write_message(SlideInput { beat, direction });
+                    }
+                    ReplayInput::Critical => {
+                        world.write_message(CriticalInput { beat });
+                    }
+                    ReplayInput::Scratch => {
+                        world.write_message(ScratchInput { beat });
+                    }
+                    ReplayInput::DualSlide(first, second) => {
+                        world.write_message(DualSlideInput { beat, directions: (first, second) });
+                    }
+                    ReplayInput::Release => {}
+                }
+            }
+            app.update();
+        }
+
+        let counts = app.world_mut().remove_resource::<Counts>().unwrap().0;
+        let recorded = app
+            .world_mut()
+            .remove_resource::<ReplayRecorder>()
+            .map_or_else(|| Replay::new("song".into(), Difficulty::Normal), |r| r.0);
+        (counts, recorded)
+    }
+
+    #[test]
+    fn replay_reproduces_the_recorded_judgments() {
+        // A Great, a Cool slide, a critical, a tap too late to count, and a skipped note
+        let script = [
+            (1.0, ReplayInput::Tap),
+            (2.1, ReplayInput::Slide(SlideDirection::E)),
+            (3.0, ReplayInput::Critical),
+            (4.5, ReplayInput::Tap),
+        ];
+        let (live, recorded) = run_chart(&script, None);
+        assert_eq!(recorded.events.len(), script.len());
+        assert_eq!(live.values().sum::<u32>(), 5);
+        assert_eq!(live.get("MISS"), Some(&2));
+
+        let (replayed, _) = run_chart(&[], Some(recorded));
+        assert_eq!(replayed, live);
+    }
+
+    #[test]
+    fn pending_replay_plays_once_without_recording() {
+        use bevy::state::app::StatesPlugin;
+
+        use crate::beatmap::{ChartFile, SongMetadata};
+        use crate::state::GameStatePlugin;
+
+        let metadata: SongMetadata =
+            ron::from_str(r#"(title: "", artist: "", charter: "", audio_file: "")"#).unwrap();
+        let chart: ChartFile =
+            ron::from_str("(difficulty: Normal, timing_points: [], path_segments: [], notes: [])")
+                .unwrap();
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, GameStatePlugin, ReplayPlugin))
+            .add_message::<TapInput>()
+            .add_message::<SlideInput>()
+            .add_message::<CriticalInput>()
+            .add_message::<ScratchInput>()
+            .add_message::<DualSlideInput>()
+            .init_resource::<ActionState<GameAction>>()
+            .insert_resource(SelectedSong {
+                song_dir: PathBuf::from("assets/songs/neon_drive"),
+                difficulty: Difficulty::Normal,
+                metadata,
+                chart,
+            })
+            .init_resource::<GameplayMods>();
+        let mut replay = Replay::new("neon_drive".into(), Difficulty::Normal);
+        replay.mods = vec!["SD".to_string()];
+        app.insert_resource(PendingReplay(replay));
+
+        let set_screen = |app: &mut App, screen: GameScreen| {
+            app.world_mut().resource_mut::<NextState<GameScreen>>().set(screen);
+            app.update();
+        };
+
+        // The replay run plays back under its own mods and records nothing
+        set_screen(&mut app, GameScreen::Playing);
+        assert!(app.world().contains_resource::<ReplayPlayback>());
+        assert!(!app.world().contains_resource::<ReplayRecorder>());
+        assert!(!app.world().contains_resource::<PendingReplay>());
+        assert_eq!(app.world().resource::<GameplayMods>().tags(), ["SD"]);
+
+        // The next run of the same song is live again, with the player's mods
+        set_screen(&mut app, GameScreen::SongSelect);
+        assert_eq!(*app.world().resource::<GameplayMods>(), GameplayMods::default());
+        set_screen(&mut app, GameScreen::Playing);
+        assert!(!app.world().contains_resource::<ReplayPlayback>());
+        assert!(app.world().contains_resource::<ReplayRecorder>());
+    }
+
+    #[test]
+    fn replay_only_matches_its_own_chart() {
+        let replay = Replay::new("neon_drive".into(), Difficulty::Hard);
+        assert!(replay.matches(Path::new("assets/songs/neon_drive"), Difficulty::Hard));
+        assert!(!replay.matches(Path::new("assets/songs/neon_drive"), Difficulty::Easy));
+        assert!(!replay.matches(Path::new("assets/songs/other"), Difficulty::Hard));
+    }
+}
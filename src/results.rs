@@ -1,16 +1,22 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
 
 use crate::GameSet;
 use crate::action::GameAction;
-use crate::audio::{KiraContext, stop_song};
+use crate::audio::{KiraContext, fade_music_to, stop_song};
+use crate::beatmap::{SelectedSong, SongPlaylist};
 use crate::conductor::SongConductor;
-use crate::beatmap::SelectedSong;
+use crate::config::HighScores;
+use crate::health::{GameplayMods, Health};
 use crate::judgment::JudgmentFeedback;
+use crate::loading::PendingLoad;
 use crate::notes::{NoteAlive, NoteQueue, Playhead};
+use crate::replay::ReplayPlayback;
 use crate::path::SplinePath;
-use crate::scoring::{GradeRank, ScoreState};
-use crate::state::GameScreen;
+use crate::scoring::{ClearBadge, GradeRank, ScoreState};
+use crate::state::{GameScreen, InGame};
 
 pub struct ResultsPlugin;
 
@@ -25,7 +31,7 @@ impl Plugin for ResultsPlugin {
             Update,
             dismiss_results.run_if(in_state(GameScreen::Results)),
         )
-        .add_systems(OnExit(GameScreen::Results), cleanup_gameplay);
+        .add_systems(OnExit(InGame), cleanup_gameplay);
     }
 }
 
@@ -62,6 +68,13 @@ const COOL_CLR: Color = Color::srgb(0.0, 0.7, 1.0);
 const GOOD_CLR: Color = Color::srgb(1.0, 0.85, 0.0);
 const MISS_CLR: Color = Color::srgb(1.0, 0.15, 0.3);
 
+// Personal best comparison
+const DELTA_UP: Color = Color::srgb(0.0, 1.0, 0.4);
+const DELTA_DOWN: Color = Color::srgb(1.0, 0.15, 0.3);
+const RECORD_CLR: Color = Color::srgb(1.0, 0.85, 0.15);
+const FULL_COMBO_CLR: Color = Color::srgb(0.0, 0.9, 1.0);
+const ALL_GREAT_CLR: Color = Color::srgb(0.0, 1.0, 0.4);
+
 // Font sizes
 const RANK_FONT: f32 = 72.0;
 const TOTAL_FONT: f32 = 36.0;
@@ -70,6 +83,11 @@ const BREAKDOWN_LABEL: f32 = 14.0;
 const GRADE_FONT: f32 = 18.0;
 const GRADE_LABEL: f32 = 12.0;
 const DISMISS_FONT: f32 = 13.0;
+const DELTA_FONT: f32 = 20.0;
+const BANNER_FONT: f32 = 16.0;
+
+/// Music fade-out between the last judgment and the results overlay.
+const SONG_END_FADE: Duration = Duration::from_millis(600);
 
 // --- Resource ---
 
@@ -82,6 +100,7 @@ fn check_song_end(
     state: Option<Res<ScoreState>>,
     complete: Option<ResMut<SongComplete>>,
     feedback_q: Query<&JudgmentFeedback>,
+    mut ctx: NonSendMut<KiraContext>,
     mut next_state: ResMut<NextState<GameScreen>>,
     current_state: Res<State<GameScreen>>,
 ) {
@@ -109,6 +128,14 @@ fn check_song_end(
         return;
     }
 
+    // Fade the music out before showing results
+    if ctx.music_amplitude > 0.0 {
+        if ctx.music_fade.is_none() {
+            fade_music_to(&mut ctx, 0.0, SONG_END_FADE);
+        }
+        return;
+    }
+
     complete.0 = true;
     info!(
         "Song complete! Score: {} | Rank: {}",
@@ -120,9 +147,16 @@ fn check_song_end(
 
 fn spawn_results_overlay(
     mut commands: Commands,
-    state: Option<Res<ScoreState>>,
+    state: Option<ResMut<ScoreState>>,
+    selected: Option<Res<SelectedSong>>,
+    mut scores: ResMut<HighScores>,
+    health: Option<Res<Health>>,
+    mods: Res<GameplayMods>,
+    playback: Option<Res<ReplayPlayback>>,
 ) {
-    let Some(state) = state else { return };
+    let Some(mut state) = state else { return };
+    let failed = health.is_some_and(|h| !h.cleared());
+    state.failed = failed;
 
     let rank = state.grade_rank();
     let rank_color = grade_rank_color(rank);
@@ -132,6 +166,28 @@ fn spawn_results_overlay(
     let clear = state.clear_bonus();
     let total = state.total_score();
 
+    // Compare against the stored best, then store this run if it beat it.
+    // Failed runs and replays are shown but never stored.
+    let store = !failed && playback.is_none();
+    let badge = state.clear_badge().filter(|_| !failed);
+    let previous_best = selected.as_ref().filter(|_| store).and_then(|selected| {
+        let previous = scores.record(&selected.song_dir, selected.difficulty, &mods, total);
+        let new_badge = badge.is_some_and(|badge| {
+            scores.record_badge(&selected.song_dir, selected.difficulty, &mods, badge)
+        });
+        if new_badge || previous.is_none_or(|best| total > best) {
+            scores.save();
+        }
+        previous
+    });
+    let banner = match previous_best {
+        _ if failed => Some("FAILED"),
+        _ if !store => None,
+        None if selected.is_some() => Some("FIRST CLEAR"),
+        Some(best) if total > best => Some("NEW RECORD"),
+        _ => None,
+    };
+
     commands
         // Full-screen backdrop
         .spawn((
@@ -181,15 +237,82 @@ fn spawn_results_overlay(
                         TextColor(rank_color),
                     ));
 
-                    // --- Total score ---
-                    panel.spawn((
-                        Text::new(format!("{total}")),
-                        TextFont {
-                            font_size: TOTAL_FONT,
+                    // --- Total score, with the change from the previous best ---
+                    panel
+                        .spawn((Node {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Baseline,
+                            column_gap: Val::Px(12.0),
                             ..default()
-                        },
-                        TextColor(TEXT_PRIMARY),
-                    ));
+                        },))
+                        .with_children(|total_row: &mut ChildSpawnerCommands| {
+                            total_row.spawn((
+                                Text::new(format!("{total}")),
+                                TextFont {
+                                    font_size: TOTAL_FONT,
+                                    ..default()
+                                },
+                                TextColor(TEXT_PRIMARY),
+                            ));
+                            if let Some(best) = previous_best {
+                                let delta = total as i64 - best as i64;
+                                total_row.spawn((
+                                    Text::new(format_score_delta(delta)),
+                                    TextFont {
+                                        font_size: DELTA_FONT,
+                                        ..default()
+                                    },
+                                    TextColor(if delta >= 0 { DELTA_UP } else { DELTA_DOWN }),
+                                ));
+                            }
+                        });
+
+                    if let Some(banner) = banner {
+                        panel.spawn((
+                            Text::new(banner),
+                            TextFont {
+                                font_size: BANNER_FONT,
+                                ..default()
+                            },
+                            TextColor(if failed { MISS_CLR } else { RECORD_CLR }),
+                        ));
+                    }
+
+                    // --- Active mods ---
+                    if let Some(label) = mods.label() {
+                        panel.spawn((
+                            Text::new(label),
+                            TextFont {
+                                font_size: GRADE_LABEL,
+                                ..default()
+                            },
+                            TextColor(TEXT_LABEL),
+                        ));
+                    }
+
+                    // --- Clean-run badge ---
+                    if let Some(badge) = badge {
+                        panel
+                            .spawn((
+                                Node {
+                                    padding: UiRect::axes(Val::Px(12.0), Val::Px(4.0)),
+                                    border: UiRect::all(Val::Px(1.0)),
+                                    border_radius: BorderRadius::all(Val::Px(4.0)),
+                                    ..default()
+                                },
+                                BorderColor::all(badge_color(badge)),
+                            ))
+                            .with_children(|pill: &mut ChildSpawnerCommands| {
+                                pill.spawn((
+                                    Text::new(badge.label()),
+                                    TextFont {
+                                        font_size: BANNER_FONT,
+                                        ..default()
+                                    },
+                                    TextColor(badge_color(badge)),
+                                ));
+                            });
+                    }
 
                     // --- Divider ---
                     panel.spawn((
@@ -225,6 +348,14 @@ fn spawn_results_overlay(
                                 &format!("{clear}"),
                                 TEXT_PRIMARY,
                             );
+                            if state.adlib_bonus > 0 {
+                                spawn_breakdown_row(
+                                    breakdown,
+                                    "AD-LIB",
+                                    &format!("+{}", state.adlib_bonus),
+                                    RECORD_CLR,
+                                );
+                            }
                         });
 
                     // --- Divider ---
@@ -254,6 +385,33 @@ fn spawn_results_overlay(
                             spawn_grade_column(grades, "MISS", state.miss_count, MISS_CLR);
                         });
 
+                    // --- Max combo ---
+                    panel
+                        .spawn((Node {
+                            flex_direction: FlexDirection::Row,
+                            column_gap: Val::Px(8.0),
+                            margin: UiRect::top(Val::Px(4.0)),
+                            ..default()
+                        },))
+                        .with_children(|combo_row: &mut ChildSpawnerCommands| {
+                            combo_row.spawn((
+                                Text::new("MAX COMBO"),
+                                TextFont {
+                                    font_size: GRADE_LABEL,
+                                    ..default()
+                                },
+                                TextColor(TEXT_LABEL),
+                            ));
+                            combo_row.spawn((
+                                Text::new(format!("{}", state.max_combo)),
+                                TextFont {
+                                    font_size: GRADE_FONT,
+                                    ..default()
+                                },
+                                TextColor(TEXT_PRIMARY),
+                            ));
+                        });
+
                     // --- Max chain ---
                     panel
                         .spawn((Node {
@@ -274,7 +432,8 @@ fn spawn_results_overlay(
                             chain_row.spawn((
                                 Text::new(format!(
                                     "{} / {}",
-                                    state.max_chain, state.total_notes
+                                    state.max_chain_steps(),
+                                    state.total_notes
                                 )),
                                 TextFont {
                                     font_size: GRADE_FONT,
@@ -286,7 +445,7 @@ fn spawn_results_overlay(
 
                     // --- Dismiss hint ---
                     panel.spawn((
-                        Text::new("[A / SPACE]"),
+                        Text::new("[A / SPACE] BACK    [X / R] RETRY    [Y / N] NEXT"),
                         TextFont {
                             font_size: DISMISS_FONT,
                             ..default()
@@ -302,12 +461,40 @@ fn spawn_results_overlay(
 }
 
 fn dismiss_results(
+    mut commands: Commands,
     action: Res<ActionState<GameAction>>,
+    selected: Option<Res<SelectedSong>>,
+    playlist: Option<Res<SongPlaylist>>,
     mut next_state: ResMut<NextState<GameScreen>>,
 ) {
     if action.just_pressed(&GameAction::Confirm) {
         info!("Results dismissed → Song Select");
         next_state.set(GameScreen::SongSelect);
+        return;
+    }
+
+    // Restarting tears the run down and rebuilds it from SelectedSong
+    if action.just_pressed(&GameAction::Retry) {
+        info!("Results → Retry");
+        next_state.set(GameScreen::Restarting);
+        return;
+    }
+
+    if action.just_pressed(&GameAction::Next) {
+        let (Some(selected), Some(playlist)) = (selected, playlist) else {
+            return;
+        };
+        let Some((song, difficulty)) = playlist.next_after(&selected.song_dir, selected.difficulty) else {
+            return;
+        };
+        // Chart parsing and audio decode happen on the loading screen, as from song select
+        info!("Results → Next: {} [{}]", song.metadata.title, difficulty.label());
+        commands.insert_resource(PendingLoad {
+            song_dir: song.dir.clone(),
+            difficulty,
+            metadata: song.metadata.clone(),
+        });
+        next_state.set(GameScreen::Loading);
     }
 }
 
@@ -325,7 +512,8 @@ fn cleanup_gameplay(
     commands.remove_resource::<SongConductor>();
     commands.remove_resource::<ScoreState>();
     commands.remove_resource::<SongComplete>();
-    commands.remove_resource::<SelectedSong>();
+    commands.remove_resource::<Health>();
+    // SelectedSong is kept so a restart can rebuild the same chart
 
     for entity in &note_entities {
         commands.entity(entity).despawn();
@@ -412,3 +600,38 @@ fn grade_rank_color(rank: GradeRank) -> Color {
         GradeRank::D => RANK_D,
     }
 }
+
+fn badge_color(badge: ClearBadge) -> Color {
+    match badge {
+        ClearBadge::FullCombo => FULL_COMBO_CLR,
+        ClearBadge::AllGreat => ALL_GREAT_CLR,
+    }
+}
+
+/// Signed score change with thousands separators, e.g. `+1,234` or `-567`.
+fn format_score_delta(delta: i64) -> String {
+    let digits = delta.unsigned_abs().to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    let sign = if delta < 0 { '-' } else { '+' };
+    format!("{sign}{grouped}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_delta_is_signed_and_grouped() {
+        assert_eq!(format_score_delta(1234), "+1,234");
+        assert_eq!(format_score_delta(-567), "-567");
+        assert_eq!(format_score_delta(0), "+0");
+        assert_eq!(format_score_delta(1_000_000), "+1,000,000");
+        assert_eq!(format_score_delta(-100_000), "-100,000");
+    }
+}
@@ -1,29 +1,36 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::GameSet;
-use crate::judgment::{Judgment, JudgmentResult};
-use crate::notes::{NoteKind, NoteQueue};
-use crate::state::GameScreen;
+use crate::config::GameplayTuning;
+use crate::judgment::{AdLibHit, HoldTick, Judgment, JudgmentResult, hold_tick_count};
+use crate::notes::{ChartNote, NoteKind, NoteQueue};
+use crate::state::InGame;
 
 pub struct ScoringPlugin;
 
 impl Plugin for ScoringPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameScreen::Playing), init_score_state)
+        app.init_resource::<GameplayTuning>()
+            .add_systems(OnEnter(InGame), init_score_state)
             .add_systems(Update, update_score.in_set(GameSet::UpdateScore));
     }
 }
 
 // --- Score constants ---
-
-const PLAY_SCORE_POOL: f64 = 850_000.0;
-const MAX_CHAIN_BONUS: u64 = 100_000;
-const CLEAR_BONUS: u64 = 50_000;
-
-// --- Chain tier thresholds ---
-
-const FEVER_THRESHOLD: u32 = 10;
-const TRANCE_THRESHOLD: u32 = 100;
+// The pool, chain/clear bonuses and tier thresholds live in `GameplayTuning`.
+
+/// A hold sustain tick is worth this fraction of a GREAT note.
+const HOLD_TICK_WEIGHT: f64 = 0.1;
+/// Criticals take a dedicated input, so they're worth more of the pool.
+const CRITICAL_WEIGHT: f64 = 1.5;
+/// Dual slides need both sticks at once, so they weigh the same as a Critical.
+const DUAL_SLIDE_WEIGHT: f64 = 1.5;
+/// Chain is counted in quarter steps so Cool and Good can earn a fraction of
+/// a Great. One Great at the Normal tier is one whole step.
+pub const CHAIN_UNIT: u32 = 4;
+/// Extra points per Ad-Lib hit, on top of the 1,000,000 a clean run can reach.
+const ADLIB_BONUS: u64 = 2_000;
 
 // --- Types ---
 
@@ -59,19 +66,51 @@ impl GradeRank {
     }
 }
 
+/// Clean-run recognition, best last so badges compare with `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ClearBadge {
+    FullCombo,
+    AllGreat,
+}
+
+impl ClearBadge {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ClearBadge::FullCombo => "FULL COMBO",
+            ClearBadge::AllGreat => "ALL GREAT",
+        }
+    }
+}
+
 // --- Resource ---
 
-#[derive(Resource)]
+#[derive(Resource, Clone)]
 pub struct ScoreState {
     pub score: u64,
+    /// Chain in `CHAIN_UNIT`s; see `chain_steps` for the shown value.
     pub chain: u32,
     pub max_chain: u32,
+    /// Plain note streak: +1 per non-miss, reset on miss. Independent of chain tiers.
+    pub combo: u32,
+    pub max_combo: u32,
     pub great_count: u32,
     pub cool_count: u32,
     pub good_count: u32,
     pub miss_count: u32,
+    pub hold_ticks: u32,
     pub total_notes: u32,
     pub base_value: f64,
+    /// Pool share earned so far: each judgment adds its note weight × grade multiplier.
+    pub earned_weight: f64,
+    /// Pool share played so far, earned or not: note weights plus tick weights.
+    pub judged_weight: f64,
+    /// Points from hidden Ad-Lib hits, outside the play pool.
+    pub adlib_bonus: u64,
+    /// Balance values this run was started with.
+    pub tuning: GameplayTuning,
+    /// Set on the results screen when the run didn't clear; a failed run
+    /// earns no clear bonus.
+    pub failed: bool,
 }
 
 impl ScoreState {
@@ -79,10 +118,21 @@ impl ScoreState {
         self.great_count + self.cool_count + self.good_count + self.miss_count
     }
 
+    /// Chain in whole steps, as the HUD shows it.
+    pub fn chain_steps(&self) -> u32 {
+        self.chain / CHAIN_UNIT
+    }
+
+    /// Best chain of the run in whole steps.
+    pub fn max_chain_steps(&self) -> u32 {
+        self.max_chain / CHAIN_UNIT
+    }
+
     pub fn chain_tier(&self) -> ChainTier {
-        if self.chain >= TRANCE_THRESHOLD {
+        let steps = self.chain_steps();
+        if steps >= self.tuning.trance_threshold {
             ChainTier::Trance
-        } else if self.chain >= FEVER_THRESHOLD {
+        } else if steps >= self.tuning.fever_threshold {
             ChainTier::Fever
         } else {
             ChainTier::Normal
@@ -90,33 +140,118 @@ impl ScoreState {
     }
 
     pub fn play_score(&self) -> u64 {
-        // Recompute from counts to avoid drift from rounding
-        let great_pts = (self.great_count as f64 * self.base_value * grade_multiplier(Judgment::Great)).round() as u64;
-        let cool_pts = (self.cool_count as f64 * self.base_value * grade_multiplier(Judgment::Cool)).round() as u64;
-        let good_pts = (self.good_count as f64 * self.base_value * grade_multiplier(Judgment::Good)).round() as u64;
-        great_pts + cool_pts + good_pts
+        // Recompute from the earned weight to avoid drift from rounding
+        let note_pts = (self.earned_weight * self.base_value).round() as u64;
+        let tick_pts = self.hold_ticks as u64 * hold_tick_points(self.base_value);
+        note_pts + tick_pts
     }
 
     pub fn chain_bonus(&self) -> u64 {
         if self.total_notes == 0 {
             return 0;
         }
-        let raw = MAX_CHAIN_BONUS as f64 * self.max_chain as f64 / self.total_notes as f64;
-        (raw.round() as u64).min(MAX_CHAIN_BONUS)
+        let max_bonus = self.tuning.max_chain_bonus;
+        let max_steps = self.max_chain as f64 / CHAIN_UNIT as f64;
+        let raw = max_bonus as f64 * max_steps / self.total_notes as f64;
+        (raw.round() as u64).min(max_bonus)
     }
 
     pub fn clear_bonus(&self) -> u64 {
-        CLEAR_BONUS
+        if self.failed {
+            return 0;
+        }
+        self.tuning.clear_bonus
     }
 
     pub fn total_score(&self) -> u64 {
-        self.play_score() + self.chain_bonus() + self.clear_bonus()
+        self.play_score() + self.chain_bonus() + self.clear_bonus() + self.adlib_bonus
+    }
+
+    /// Every note judged and none missed.
+    pub fn is_full_combo(&self) -> bool {
+        self.notes_judged() > 0 && self.miss_count == 0
+    }
+
+    /// Every judgment a GREAT.
+    pub fn is_all_great(&self) -> bool {
+        self.is_full_combo() && self.cool_count == 0 && self.good_count == 0
+    }
+
+    /// The best badge this run earned, if any.
+    pub fn clear_badge(&self) -> Option<ClearBadge> {
+        if self.is_all_great() {
+            Some(ClearBadge::AllGreat)
+        } else if self.is_full_combo() {
+            Some(ClearBadge::FullCombo)
+        } else {
+            None
+        }
     }
 
     pub fn grade_rank(&self) -> GradeRank {
         let total = self.total_score();
         grade_rank_from_score(total)
     }
+
+    /// Apply a single judgment to counts, chain, combo and running score.
+    /// `weight` is the judged note's [`note_weight`].
+    pub fn record_judgment(&mut self, judgment: Judgment, weight: f64) {
+        // Update grade counts
+        match judgment {
+            Judgment::Great => self.great_count += 1,
+            Judgment::Cool => self.cool_count += 1,
+            Judgment::Good => self.good_count += 1,
+            Judgment::Miss => self.miss_count += 1,
+        }
+
+        // Update chain and combo
+        if judgment == Judgment::Miss {
+            self.chain = 0;
+            self.combo = 0;
+        } else {
+            let tier = self.chain_tier();
+            self.chain += chain_increment(tier, judgment);
+            self.max_chain = self.max_chain.max(self.chain);
+            self.combo += 1;
+            self.max_combo = self.max_combo.max(self.combo);
+        }
+
+        // Update running score (play score portion only — bonuses computed at end)
+        self.judged_weight += weight;
+        self.earned_weight += weight * grade_multiplier(judgment);
+        self.score += note_points(self.base_value * weight, judgment);
+    }
+
+    /// Re-split the play pool after the chart changes mid-song (hot reload).
+    /// What has been played keeps its share; `outstanding_judgments` and
+    /// `outstanding_weight` cover everything still to come.
+    pub fn rebase(&mut self, outstanding_judgments: u32, outstanding_weight: f64) {
+        self.total_notes = self.notes_judged() + outstanding_judgments;
+        let weight = self.judged_weight + outstanding_weight;
+        self.base_value = if weight > 0.0 {
+            self.tuning.play_score_pool / weight
+        } else {
+            0.0
+        };
+        self.score = self.play_score() + self.adlib_bonus;
+    }
+
+    /// Apply an Ad-Lib hit: bonus points only, no judgment, chain or combo.
+    pub fn record_adlib(&mut self) {
+        self.adlib_bonus += ADLIB_BONUS;
+        self.score += ADLIB_BONUS;
+    }
+
+    /// Apply a hold sustain tick: a mini-hit that feeds score and chain.
+    pub fn record_hold_tick(&mut self) {
+        self.hold_ticks += 1;
+        self.judged_weight += HOLD_TICK_WEIGHT;
+        // Sustaining a hold counts as clean play
+        let tier = self.chain_tier();
+        self.chain += chain_increment(tier, Judgment::Great);
+        self.max_chain = self.max_chain.max(self.chain);
+        self.score += hold_tick_points(self.base_value);
+    }
 }
 
 // --- Pure functions ---
@@ -130,12 +265,32 @@ pub fn grade_multiplier(judgment: Judgment) -> f64 {
     }
 }
 
-pub fn chain_increment(tier: ChainTier) -> u32 {
-    match tier {
+/// Points awarded for a single judged note.
+pub fn note_points(base_value: f64, judgment: Judgment) -> u64 {
+    (base_value * grade_multiplier(judgment)).round() as u64
+}
+
+/// Points awarded for a single hold sustain tick.
+pub fn hold_tick_points(base_value: f64) -> u64 {
+    (base_value * HOLD_TICK_WEIGHT).round() as u64
+}
+
+/// Chain growth in `CHAIN_UNIT`s for a hit at `tier`. A Great earns the
+/// tier's full step, a Cool three quarters and a Good half, so precision
+/// builds chain faster. A Miss resets the chain elsewhere and earns nothing here.
+pub fn chain_increment(tier: ChainTier, judgment: Judgment) -> u32 {
+    let steps = match tier {
         ChainTier::Normal => 1,
         ChainTier::Fever => 2,
         ChainTier::Trance => 4,
-    }
+    };
+    let units = match judgment {
+        Judgment::Great => CHAIN_UNIT,
+        Judgment::Cool => CHAIN_UNIT * 3 / 4,
+        Judgment::Good => CHAIN_UNIT / 2,
+        Judgment::Miss => 0,
+    };
+    steps * units
 }
 
 pub fn grade_rank_from_score(score: u64) -> GradeRank {
@@ -156,16 +311,68 @@ pub fn grade_rank_from_score(score: u64) -> GradeRank {
     }
 }
 
-// --- Systems ---
+/// Share of the play pool one judgment of `kind` is worth, relative to a Tap.
+pub fn note_weight(kind: NoteKind) -> f64 {
+    match kind {
+        NoteKind::Tap | NoteKind::Slide(_) | NoteKind::Hold { .. } | NoteKind::Scratch => 1.0,
+        NoteKind::Critical => CRITICAL_WEIGHT,
+        NoteKind::DualSlide { .. } => DUAL_SLIDE_WEIGHT,
+        // Bonus only; never part of the pool
+        NoteKind::AdLib => 0.0,
+    }
+}
 
-fn init_score_state(mut commands: Commands, queue: Option<Res<NoteQueue>>) {
-    let Some(queue) = queue else { return };
-    let total: u32 = queue.notes.iter().map(|n| match n.kind {
+/// Judgments a note list produces: one per note, two per hold (head + tail),
+/// none for Ad-Libs.
+pub fn total_judgments(notes: &[ChartNote]) -> u32 {
+    notes.iter().map(|n| match n.kind {
         NoteKind::Hold { .. } => 2u32,
+        NoteKind::AdLib => 0u32,
         _ => 1u32,
-    }).sum();
-    let base_value = if total > 0 {
-        PLAY_SCORE_POOL / total as f64
+    }).sum()
+}
+
+/// Total weight a perfect run earns, so `base_value` can split the pool by it.
+/// Ticks share the pool too, so a perfect run still lands on the same max score.
+pub fn pool_weight(notes: &[ChartNote]) -> f64 {
+    notes
+        .iter()
+        .map(|n| outstanding_pool(n.kind, n.target_beat, false, 0).1)
+        .sum()
+}
+
+/// Judgments and pool weight a note still owes. A hold whose head has been
+/// judged owes only its tail and the ticks not yet awarded.
+pub fn outstanding_pool(
+    kind: NoteKind,
+    target_beat: f64,
+    head_judged: bool,
+    ticks_awarded: u32,
+) -> (u32, f64) {
+    match kind {
+        NoteKind::Hold { end_beat } => {
+            let judgments = if head_judged { 1 } else { 2 };
+            let ticks = hold_tick_count(target_beat, end_beat).saturating_sub(ticks_awarded);
+            let weight = judgments as f64 * note_weight(kind) + ticks as f64 * HOLD_TICK_WEIGHT;
+            (judgments, weight)
+        }
+        NoteKind::AdLib => (0, 0.0),
+        kind => (1, note_weight(kind)),
+    }
+}
+
+// --- Systems ---
+
+fn init_score_state(
+    mut commands: Commands,
+    queue: Option<Res<NoteQueue>>,
+    tuning: Res<GameplayTuning>,
+) {
+    let Some(queue) = queue else { return };
+    let total = total_judgments(&queue.notes);
+    let weight = pool_weight(&queue.notes);
+    let base_value = if weight > 0.0 {
+        tuning.play_score_pool / weight
     } else {
         0.0
     };
@@ -174,41 +381,38 @@ fn init_score_state(mut commands: Commands, queue: Option<Res<NoteQueue>>) {
         score: 0,
         chain: 0,
         max_chain: 0,
+        combo: 0,
+        max_combo: 0,
         great_count: 0,
         cool_count: 0,
         good_count: 0,
         miss_count: 0,
+        hold_ticks: 0,
         total_notes: total,
         base_value,
+        earned_weight: 0.0,
+        judged_weight: 0.0,
+        adlib_bonus: 0,
+        tuning: tuning.clone(),
+        failed: false,
     });
 }
 
 fn update_score(
     state: Option<ResMut<ScoreState>>,
     mut results: MessageReader<JudgmentResult>,
+    mut ticks: MessageReader<HoldTick>,
+    mut adlibs: MessageReader<AdLibHit>,
 ) {
     let Some(mut state) = state else { return };
     for result in results.read() {
-        // Update grade counts
-        match result.judgment {
-            Judgment::Great => state.great_count += 1,
-            Judgment::Cool => state.cool_count += 1,
-            Judgment::Good => state.good_count += 1,
-            Judgment::Miss => state.miss_count += 1,
-        }
-
-        // Update chain
-        if result.judgment == Judgment::Miss {
-            state.chain = 0;
-        } else {
-            let tier = state.chain_tier();
-            state.chain += chain_increment(tier);
-            state.max_chain = state.max_chain.max(state.chain);
-        }
-
-        // Update running score (play score portion only — bonuses computed at end)
-        let note_score = (state.base_value * grade_multiplier(result.judgment)).round() as u64;
-        state.score += note_score;
+        state.record_judgment(result.judgment, result.weight);
+    }
+    for _ in ticks.read() {
+        state.record_hold_tick();
+    }
+    for _ in adlibs.read() {
+        state.record_adlib();
     }
 }
 
@@ -218,6 +422,10 @@ fn update_score(
 mod tests {
     use super::*;
 
+    const PLAY_SCORE_POOL: f64 = 850_000.0;
+    const MAX_CHAIN_BONUS: u64 = 100_000;
+    const CLEAR_BONUS: u64 = 50_000;
+
     #[test]
     fn grade_multipliers() {
         assert!((grade_multiplier(Judgment::Great) - 1.0).abs() < f64::EPSILON);
@@ -228,36 +436,58 @@ mod tests {
 
     #[test]
     fn chain_increments() {
-        assert_eq!(chain_increment(ChainTier::Normal), 1);
-        assert_eq!(chain_increment(ChainTier::Fever), 2);
-        assert_eq!(chain_increment(ChainTier::Trance), 4);
+        assert_eq!(chain_increment(ChainTier::Normal, Judgment::Great), CHAIN_UNIT);
+        assert_eq!(chain_increment(ChainTier::Fever, Judgment::Great), 2 * CHAIN_UNIT);
+        assert_eq!(chain_increment(ChainTier::Trance, Judgment::Great), 4 * CHAIN_UNIT);
+    }
+
+    #[test]
+    fn great_grows_chain_more_than_good_at_every_tier() {
+        for tier in [ChainTier::Normal, ChainTier::Fever, ChainTier::Trance] {
+            let great = chain_increment(tier, Judgment::Great);
+            let cool = chain_increment(tier, Judgment::Cool);
+            let good = chain_increment(tier, Judgment::Good);
+            assert!(great > cool && cool > good && good > 0, "{tier:?}");
+            assert_eq!(chain_increment(tier, Judgment::Miss), 0);
+        }
+        // Tier multipliers still apply on top of the judgment weight
+        assert!(
+            chain_increment(ChainTier::Trance, Judgment::Good)
+                > chain_increment(ChainTier::Normal, Judgment::Good)
+        );
     }
 
     #[test]
     fn chain_tier_thresholds() {
         let mut state = ScoreState {
-            score: 0, chain: 0, max_chain: 0,
-            great_count: 0, cool_count: 0, good_count: 0, miss_count: 0,
+            score: 0, chain: 0, max_chain: 0, combo: 0, max_combo: 0,
+            great_count: 0, cool_count: 0, good_count: 0, miss_count: 0, hold_ticks: 0,
             total_notes: 100, base_value: 8500.0,
+            earned_weight: 0.0, judged_weight: 0.0, adlib_bonus: 0,
+            tuning: default(),
+            failed: false,
         };
 
         assert_eq!(state.chain_tier(), ChainTier::Normal);
-        state.chain = 9;
+        state.chain = 9 * CHAIN_UNIT;
         assert_eq!(state.chain_tier(), ChainTier::Normal);
-        state.chain = 10;
+        state.chain = 10 * CHAIN_UNIT;
         assert_eq!(state.chain_tier(), ChainTier::Fever);
-        state.chain = 99;
+        state.chain = 99 * CHAIN_UNIT;
         assert_eq!(state.chain_tier(), ChainTier::Fever);
-        state.chain = 100;
+        state.chain = 100 * CHAIN_UNIT;
         assert_eq!(state.chain_tier(), ChainTier::Trance);
     }
 
     #[test]
     fn chain_bonus_capped() {
         let state = ScoreState {
-            score: 0, chain: 0, max_chain: 200,
-            great_count: 0, cool_count: 0, good_count: 0, miss_count: 0,
+            score: 0, chain: 0, max_chain: 200 * CHAIN_UNIT, combo: 0, max_combo: 0,
+            great_count: 0, cool_count: 0, good_count: 0, miss_count: 0, hold_ticks: 0,
             total_notes: 40, base_value: 21250.0,
+            earned_weight: 0.0, judged_weight: 0.0, adlib_bonus: 0,
+            tuning: default(),
+            failed: false,
         };
         // max_chain/total_notes = 200/40 = 5.0, raw = 500_000, capped to 100_000
         assert_eq!(state.chain_bonus(), MAX_CHAIN_BONUS);
@@ -266,9 +496,12 @@ mod tests {
     #[test]
     fn chain_bonus_partial() {
         let state = ScoreState {
-            score: 0, chain: 0, max_chain: 20,
-            great_count: 0, cool_count: 0, good_count: 0, miss_count: 0,
+            score: 0, chain: 0, max_chain: 20 * CHAIN_UNIT, combo: 0, max_combo: 0,
+            great_count: 0, cool_count: 0, good_count: 0, miss_count: 0, hold_ticks: 0,
             total_notes: 40, base_value: 21250.0,
+            earned_weight: 0.0, judged_weight: 0.0, adlib_bonus: 0,
+            tuning: default(),
+            failed: false,
         };
         // 100_000 * 20/40 = 50_000
         assert_eq!(state.chain_bonus(), 50_000);
@@ -297,9 +530,12 @@ mod tests {
         let total = 40u32;
         let base = PLAY_SCORE_POOL / total as f64;
         let state = ScoreState {
-            score: 0, chain: 0, max_chain: total,
-            great_count: total, cool_count: 0, good_count: 0, miss_count: 0,
+            score: 0, chain: 0, max_chain: total * CHAIN_UNIT, combo: 0, max_combo: 0,
+            great_count: total, cool_count: 0, good_count: 0, miss_count: 0, hold_ticks: 0,
             total_notes: total, base_value: base,
+            earned_weight: total as f64, judged_weight: total as f64, adlib_bonus: 0,
+            tuning: default(),
+            failed: false,
         };
         // play = 850_000, chain = 100_000 (40/40 = 1.0), clear = 50_000 → 1_000_000
         assert_eq!(state.total_score(), 1_000_000);
@@ -310,12 +546,380 @@ mod tests {
         let total = 40u32;
         let base = PLAY_SCORE_POOL / total as f64;
         let state = ScoreState {
-            score: 0, chain: 0, max_chain: 0,
-            great_count: 0, cool_count: 0, good_count: 0, miss_count: total,
+            score: 0, chain: 0, max_chain: 0, combo: 0, max_combo: 0,
+            great_count: 0, cool_count: 0, good_count: 0, miss_count: total, hold_ticks: 0,
             total_notes: total, base_value: base,
+            earned_weight: 0.0, judged_weight: 0.0, adlib_bonus: 0,
+            tuning: default(),
+            failed: false,
         };
         assert_eq!(state.play_score(), 0);
         assert_eq!(state.chain_bonus(), 0);
         assert_eq!(state.total_score(), CLEAR_BONUS);
+
+        // A failed run keeps what it played but not the clear bonus
+        let failed = ScoreState { failed: true, ..state };
+        assert_eq!(failed.clear_bonus(), 0);
+        assert_eq!(failed.total_score(), 0);
+    }
+
+    #[test]
+    fn hold_ticks_feed_chain_not_combo() {
+        let mut state = ScoreState {
+            score: 0, chain: 0, max_chain: 0, combo: 0, max_combo: 0,
+            great_count: 0, cool_count: 0, good_count: 0, miss_count: 0, hold_ticks: 0,
+            total_notes: 2, base_value: PLAY_SCORE_POOL / (2.0 + 3.0 * HOLD_TICK_WEIGHT),
+            earned_weight: 0.0, judged_weight: 0.0, adlib_bonus: 0,
+            tuning: default(),
+            failed: false,
+        };
+        state.record_judgment(Judgment::Great, 1.0);
+        for _ in 0..3 {
+            state.record_hold_tick();
+        }
+        state.record_judgment(Judgment::Great, 1.0);
+        assert_eq!(state.hold_ticks, 3);
+        assert_eq!(state.chain_steps(), 5);
+        assert_eq!(state.combo, 2);
+        // A perfect run with all ticks still fills the play pool (up to per-tick rounding)
+        let diff = state.play_score() as i64 - PLAY_SCORE_POOL as i64;
+        assert!(diff.abs() <= 3, "play score off by {diff}");
+    }
+
+    #[test]
+    fn rebase_keeps_a_perfect_run_at_the_full_pool() {
+        // Four taps planned, two played, then a reload leaves five to come
+        let mut state = ScoreState {
+            score: 0, chain: 0, max_chain: 0, combo: 0, max_combo: 0,
+            great_count: 0, cool_count: 0, good_count: 0, miss_count: 0, hold_ticks: 0,
+            total_notes: 4, base_value: PLAY_SCORE_POOL / 4.0,
+            earned_weight: 0.0, judged_weight: 0.0, adlib_bonus: 0,
+            tuning: default(),
+            failed: false,
+        };
+        state.record_judgment(Judgment::Great, 1.0);
+        state.record_judgment(Judgment::Great, 1.0);
+        state.rebase(5, 5.0);
+        assert_eq!(state.total_notes, 7);
+        assert_eq!(state.score, state.play_score());
+
+        for _ in 0..5 {
+            state.record_judgment(Judgment::Great, 1.0);
+        }
+        assert_eq!(state.notes_judged(), state.total_notes);
+        assert_eq!(state.play_score(), PLAY_SCORE_POOL as u64);
+    }
+
+    #[test]
+    fn held_hold_owes_its_tail_and_remaining_ticks() {
+        let hold = NoteKind::Hold { end_beat: 4.0 };
+        // Three ticks in a four-beat hold
+        let (judgments, weight) = outstanding_pool(hold, 0.0, false, 0);
+        assert_eq!(judgments, 2);
+        assert!((weight - (2.0 + 3.0 * HOLD_TICK_WEIGHT)).abs() < 1e-9);
+
+        let (judgments, weight) = outstanding_pool(hold, 0.0, true, 2);
+        assert_eq!(judgments, 1);
+        assert!((weight - (1.0 + HOLD_TICK_WEIGHT)).abs() < 1e-9);
+        assert_eq!(outstanding_pool(NoteKind::AdLib, 0.0, false, 0), (0, 0.0));
+    }
+
+    #[test]
+    fn combo_counts_streak_and_resets_on_miss() {
+        let mut state = ScoreState {
+            score: 0, chain: 0, max_chain: 0, combo: 0, max_combo: 0,
+            great_count: 0, cool_count: 0, good_count: 0, miss_count: 0, hold_ticks: 0,
+            total_notes: 9, base_value: PLAY_SCORE_POOL / 9.0,
+            earned_weight: 0.0, judged_weight: 0.0, adlib_bonus: 0,
+            tuning: default(),
+            failed: false,
+        };
+        for _ in 0..5 {
+            state.record_judgment(Judgment::Great, 1.0);
+        }
+        state.record_judgment(Judgment::Miss, 1.0);
+        for _ in 0..3 {
+            state.record_judgment(Judgment::Good, 1.0);
+        }
+        assert_eq!(state.combo, 3);
+        assert_eq!(state.max_combo, 5);
+        // Goods keep building chain, at half a Great's rate
+        assert_eq!(state.chain, 3 * CHAIN_UNIT / 2);
+        assert_eq!(state.max_chain_steps(), 5);
+    }
+
+    #[test]
+    fn note_points_match_running_score() {
+        let base = PLAY_SCORE_POOL / 7.0;
+        assert_eq!(note_points(base, Judgment::Great), 121_429);
+        assert_eq!(note_points(base, Judgment::Cool), 97_143);
+        assert_eq!(note_points(base, Judgment::Good), 60_714);
+        assert_eq!(note_points(base, Judgment::Miss), 0);
+
+        let mut state = ScoreState {
+            score: 0, chain: 0, max_chain: 0, combo: 0, max_combo: 0,
+            great_count: 0, cool_count: 0, good_count: 0, miss_count: 0, hold_ticks: 0,
+            total_notes: 7, base_value: base,
+            earned_weight: 0.0, judged_weight: 0.0, adlib_bonus: 0,
+            tuning: default(),
+            failed: false,
+        };
+        let mut expected = 0;
+        for j in [Judgment::Great, Judgment::Cool, Judgment::Good, Judgment::Miss] {
+            state.record_judgment(j, 1.0);
+            expected += note_points(base, j);
+        }
+        assert_eq!(state.score, expected);
+    }
+
+    #[test]
+    fn weighted_perfect_mixed_chart_fills_the_pool() {
+        use crate::beatmap::SlideDirection;
+
+        let note = |beat: f64, kind| ChartNote { target_beat: beat, kind };
+        let notes = vec![
+            note(0.0, NoteKind::Tap),
+            note(1.0, NoteKind::Critical),
+            note(2.0, NoteKind::Slide(SlideDirection::E)),
+            note(3.0, NoteKind::Hold { end_beat: 5.0 }),
+            note(6.0, NoteKind::Critical),
+            note(7.0, NoteKind::Tap),
+        ];
+        let weight = pool_weight(&notes);
+        let mut state = ScoreState {
+            score: 0, chain: 0, max_chain: 0, combo: 0, max_combo: 0,
+            great_count: 0, cool_count: 0, good_count: 0, miss_count: 0, hold_ticks: 0,
+            total_notes: total_judgments(&notes), base_value: PLAY_SCORE_POOL / weight,
+            earned_weight: 0.0, judged_weight: 0.0, adlib_bonus: 0,
+            tuning: default(),
+            failed: false,
+        };
+
+        for n in &notes {
+            state.record_judgment(Judgment::Great, note_weight(n.kind));
+            if let NoteKind::Hold { end_beat } = n.kind {
+                for _ in 0..hold_tick_count(n.target_beat, end_beat) {
+                    state.record_hold_tick();
+                }
+                state.record_judgment(Judgment::Great, note_weight(n.kind));
+            }
+        }
+        let diff = state.play_score() as i64 - PLAY_SCORE_POOL as i64;
+        assert!(diff.abs() <= 3, "play score off by {diff}");
+        let running = state.score as i64 - PLAY_SCORE_POOL as i64;
+        assert!(running.abs() <= 10, "running score off by {running}");
+
+        // A Critical GREAT is worth more than a Tap GREAT
+        assert!(
+            note_points(state.base_value * note_weight(NoteKind::Critical), Judgment::Great)
+                > note_points(state.base_value, Judgment::Great)
+        );
+    }
+
+    #[test]
+    fn adlib_hits_add_bonus_without_counting_as_notes() {
+        let notes = vec![
+            ChartNote { target_beat: 0.0, kind: NoteKind::Tap },
+            ChartNote { target_beat: 0.5, kind: NoteKind::AdLib },
+            ChartNote { target_beat: 1.0, kind: NoteKind::Tap },
+            ChartNote { target_beat: 1.5, kind: NoteKind::AdLib },
+        ];
+        // Ad-Libs take no share of the pool
+        assert_eq!(total_judgments(&notes), 2);
+        assert_eq!(pool_weight(&notes), 2.0);
+
+        let mut state = ScoreState {
+            score: 0, chain: 0, max_chain: 0, combo: 0, max_combo: 0,
+            great_count: 0, cool_count: 0, good_count: 0, miss_count: 0, hold_ticks: 0,
+            total_notes: 2, base_value: PLAY_SCORE_POOL / 2.0,
+            earned_weight: 0.0, judged_weight: 0.0, adlib_bonus: 0,
+            tuning: default(),
+            failed: false,
+        };
+        state.record_judgment(Judgment::Great, 1.0);
+        state.record_adlib();
+        state.record_judgment(Judgment::Great, 1.0);
+        state.record_adlib();
+
+        assert_eq!(state.notes_judged(), 2);
+        assert_eq!(state.adlib_bonus, 2 * ADLIB_BONUS);
+        assert_eq!(state.combo, 2);
+        // A clean run plus Ad-Libs goes past the normal maximum
+        assert_eq!(state.total_score(), 1_000_000 + 2 * ADLIB_BONUS);
+    }
+
+    #[test]
+    fn clean_run_badges() {
+        let mut state = ScoreState {
+            score: 0, chain: 0, max_chain: 0, combo: 0, max_combo: 0,
+            great_count: 0, cool_count: 0, good_count: 0, miss_count: 0, hold_ticks: 0,
+            total_notes: 4, base_value: PLAY_SCORE_POOL / 4.0,
+            earned_weight: 0.0, judged_weight: 0.0, adlib_bonus: 0,
+            tuning: default(),
+            failed: false,
+        };
+        // Nothing judged yet earns nothing
+        assert_eq!(state.clear_badge(), None);
+
+        for _ in 0..3 {
+            state.record_judgment(Judgment::Great, 1.0);
+        }
+        assert!(state.is_all_great());
+        assert!(state.is_full_combo());
+        assert_eq!(state.clear_badge(), Some(ClearBadge::AllGreat));
+
+        let mut cool = state.clone();
+        cool.record_judgment(Judgment::Cool, 1.0);
+        assert!(!cool.is_all_great());
+        assert!(cool.is_full_combo());
+        assert_eq!(cool.clear_badge(), Some(ClearBadge::FullCombo));
+
+        state.record_judgment(Judgment::Miss, 1.0);
+        assert!(!state.is_all_great());
+        assert!(!state.is_full_combo());
+        assert_eq!(state.clear_badge(), None);
+    }
+
+    #[test]
+    fn tap_only_charts_keep_even_split() {
+        let notes: Vec<ChartNote> = (0..40)
+            .map(|i| ChartNote { target_beat: i as f64, kind: NoteKind::Tap })
+            .collect();
+        assert_eq!(pool_weight(&notes), 40.0);
+        assert_eq!(PLAY_SCORE_POOL / pool_weight(&notes), 21250.0);
+    }
+
+    #[test]
+    fn restart_resets_judged_notes_but_resume_does_not() {
+        use bevy::state::app::StatesPlugin;
+
+        use crate::notes::ChartNote;
+        use crate::state::{GameScreen, GameStatePlugin};
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, GameStatePlugin, ScoringPlugin))
+            .add_message::<JudgmentResult>()
+            .add_message::<HoldTick>()
+            .add_message::<AdLibHit>()
+            .insert_resource(NoteQueue {
+                notes: (0..4)
+                    .map(|i| ChartNote { target_beat: i as f64, kind: NoteKind::Tap })
+                    .collect(),
+                next_index: 0,
+                travel_beats: 3.0,
+                look_ahead_beats: 3.0,
+            });
+
+        let set_screen = |app: &mut App, screen: GameScreen| {
+            app.world_mut().resource_mut::<NextState<GameScreen>>().set(screen);
+            app.update();
+        };
+        let judged = |app: &App| app.world().resource::<ScoreState>().notes_judged();
+
+        set_screen(&mut app, GameScreen::Playing);
+        for judgment in [Judgment::Great, Judgment::Miss] {
+            app.world_mut().write_message(JudgmentResult { judgment, position: Vec2::ZERO, weight: 1.0 });
+        }
+        app.update();
+        assert_eq!(judged(&app), 2);
+
+        // Pause → resume keeps the run
+        set_screen(&mut app, GameScreen::Paused);
+        set_screen(&mut app, GameScreen::Playing);
+        assert_eq!(judged(&app), 2);
+
+        // Pause → restart rebuilds it (Restarting bounces back to Playing next frame)
+        set_screen(&mut app, GameScreen::Paused);
+        set_screen(&mut app, GameScreen::Restarting);
+        app.update();
+        assert_eq!(*app.world().resource::<State<GameScreen>>().get(), GameScreen::Playing);
+        assert_eq!(judged(&app), 0);
+    }
+
+    #[test]
+    fn retry_from_results_resets_the_run() {
+        use bevy::state::app::StatesPlugin;
+
+        use crate::notes::ChartNote;
+        use crate::state::{GameScreen, GameStatePlugin};
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, GameStatePlugin, ScoringPlugin))
+            .add_message::<JudgmentResult>()
+            .add_message::<HoldTick>()
+            .add_message::<AdLibHit>()
+            .insert_resource(NoteQueue {
+                notes: (0..2)
+                    .map(|i| ChartNote { target_beat: i as f64, kind: NoteKind::Tap })
+                    .collect(),
+                next_index: 0,
+                travel_beats: 3.0,
+                look_ahead_beats: 3.0,
+            });
+
+        let set_screen = |app: &mut App, screen: GameScreen| {
+            app.world_mut().resource_mut::<NextState<GameScreen>>().set(screen);
+            app.update();
+        };
+
+        set_screen(&mut app, GameScreen::Playing);
+        for judgment in [Judgment::Great, Judgment::Great] {
+            app.world_mut().write_message(JudgmentResult { judgment, position: Vec2::ZERO, weight: 1.0 });
+        }
+        app.update();
+        set_screen(&mut app, GameScreen::Results);
+        assert_eq!(app.world().resource::<ScoreState>().notes_judged(), 2);
+
+        // Retry goes through Restarting and lands back in Playing with a fresh score
+        set_screen(&mut app, GameScreen::Restarting);
+        app.update();
+        assert_eq!(*app.world().resource::<State<GameScreen>>().get(), GameScreen::Playing);
+        let state = app.world().resource::<ScoreState>();
+        assert_eq!(state.notes_judged(), 0);
+        assert_eq!(state.score, 0);
+        assert_eq!(state.max_chain, 0);
+    }
+
+    #[test]
+    fn rests_are_not_counted_as_notes() {
+        use bevy::state::app::StatesPlugin;
+
+        use crate::beatmap::{ChartFile, ChartNoteEntry, ChartNoteType, Difficulty, chart_notes};
+        use crate::state::{GameScreen, GameStatePlugin};
+
+        let chart = ChartFile {
+            difficulty: Difficulty::Easy,
+            difficulty_rating: 1,
+            timing_points: Vec::new(),
+            path_segments: Vec::new(),
+            notes: vec![
+                ChartNoteEntry { beat: 1.0, note_type: ChartNoteType::Tap },
+                ChartNoteEntry { beat: 2.0, note_type: ChartNoteType::Rest },
+                ChartNoteEntry { beat: 3.0, note_type: ChartNoteType::Hold { duration_beats: 1.0 } },
+                ChartNoteEntry { beat: 5.0, note_type: ChartNoteType::Rest },
+            ],
+            events: Vec::new(),
+            travel_beats: 3.0,
+            look_ahead_beats: 3.0,
+        };
+        let notes = chart_notes(&chart);
+        assert_eq!(notes.iter().map(|n| n.target_beat).collect::<Vec<_>>(), vec![1.0, 3.0]);
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, GameStatePlugin, ScoringPlugin))
+            .add_message::<JudgmentResult>()
+            .add_message::<HoldTick>()
+            .add_message::<AdLibHit>()
+            .insert_resource(NoteQueue {
+                notes,
+                next_index: 0,
+                travel_beats: 3.0,
+                look_ahead_beats: 3.0,
+            });
+        app.world_mut().resource_mut::<NextState<GameScreen>>().set(GameScreen::Playing);
+        app.update();
+
+        // Tap + hold head + hold tail; the rests contribute nothing
+        assert_eq!(app.world().resource::<ScoreState>().total_notes, 3);
     }
 }
@@ -1,15 +1,20 @@
+use std::time::Instant;
+
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
 
-use crate::action::GameAction;
-use crate::config::GameSettings;
+use crate::action::{GameAction, InputBindings, MENU_ACTIONS, MenuNav};
+use crate::config::{FullscreenMode, GameSettings, RESOLUTIONS};
 use crate::state::GameScreen;
 
 pub struct SettingsPlugin;
 
 impl Plugin for SettingsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameScreen::Settings), setup_settings)
+        app.insert_resource(FrameLimiter { last_frame: Instant::now() })
+            .add_systems(Startup, apply_display_settings)
+            .add_systems(Last, limit_frame_rate)
+            .add_systems(OnEnter(GameScreen::Settings), setup_settings)
             .add_systems(
                 Update,
                 (navigate_settings, capture_remap, update_settings_ui)
@@ -80,8 +85,8 @@ impl SettingsTab {
         match self {
             SettingsTab::Input => 6,   // 5 remappable + reset
             SettingsTab::Audio => 5,   // master, sfx, preview, audio offset, calibrate
-            SettingsTab::Visual => 2,  // visual offset, note speed
-            SettingsTab::Display => 1, // fullscreen
+            SettingsTab::Visual => 5,  // visual offset, note speed, background dim, approach ring, beat pulse
+            SettingsTab::Display => 5, // fullscreen, vsync, fps cap, resolution, fullscreen mode
         }
     }
 }
@@ -95,6 +100,8 @@ enum RowKind {
     Slider { min: f32, max: f32, step: f32 },
     Offset { min: i32, max: i32, step: i32 },
     Toggle,
+    /// One of a fixed list of options, cycled with left/right.
+    Choice,
     NavAction,
 }
 
@@ -123,9 +130,16 @@ fn rows_for_tab(tab: SettingsTab) -> Vec<RowDef> {
         SettingsTab::Visual => vec![
             RowDef { label: "VISUAL OFFSET", kind: RowKind::Offset { min: -200, max: 200, step: 5 } },
             RowDef { label: "NOTE SPEED", kind: RowKind::Slider { min: 0.5, max: 3.0, step: 0.1 } },
+            RowDef { label: "BACKGROUND DIM", kind: RowKind::Slider { min: 0.0, max: 100.0, step: 5.0 } },
+            RowDef { label: "APPROACH RING", kind: RowKind::Toggle },
+            RowDef { label: "BEAT PULSE", kind: RowKind::Toggle },
         ],
         SettingsTab::Display => vec![
             RowDef { label: "FULLSCREEN", kind: RowKind::Toggle },
+            RowDef { label: "VSYNC", kind: RowKind::Toggle },
+            RowDef { label: "FPS CAP", kind: RowKind::Slider { min: 0.0, max: 240.0, step: 30.0 } },
+            RowDef { label: "WINDOW SIZE", kind: RowKind::Choice },
+            RowDef { label: "FULLSCREEN MODE", kind: RowKind::Choice },
         ],
     }
 }
@@ -137,9 +151,22 @@ struct SettingsState {
     active_tab: SettingsTab,
     selected_row: usize,
     listening: bool,
+    /// Set once the press that started listening has been released from this frame,
+    /// so the Confirm key/button itself isn't captured as the new binding.
+    capture_armed: bool,
     dirty: bool,
 }
 
+/// Screen that Back returns to; the main menu if nothing set it.
+#[derive(Resource)]
+pub struct SettingsReturn(pub GameScreen);
+
+/// When the previous frame finished, for the FPS cap.
+#[derive(Resource)]
+struct FrameLimiter {
+    last_frame: Instant,
+}
+
 // --- Markers ---
 
 #[derive(Component)]
@@ -177,10 +204,12 @@ fn setup_settings(
     input_map: Res<InputMap<GameAction>>,
     settings: Res<GameSettings>,
 ) {
+    commands.insert_resource(MenuNav::default());
     commands.insert_resource(SettingsState {
         active_tab: SettingsTab::Input,
         selected_row: 0,
         listening: false,
+        capture_armed: false,
         dirty: true,
     });
 
@@ -314,6 +343,7 @@ fn setup_settings(
                 spawn_hint(hints, "UP/DOWN", "select");
                 spawn_hint(hints, "LEFT/RIGHT", "adjust");
                 spawn_hint(hints, "CONFIRM", "rebind");
+                spawn_hint(hints, "BKSP/SELECT", "clear key/pad");
                 spawn_hint(hints, "ESC", "back");
             });
         });
@@ -442,6 +472,14 @@ fn spawn_setting_row(
                     TextColor(if on { TOGGLE_ON } else { TOGGLE_OFF }),
                 ));
             }
+            RowKind::Choice => {
+                row.spawn((
+                    RowValueText(tab, index),
+                    Text::new(format_choice(tab, index, settings)),
+                    TextFont { font_size: ROW_VALUE_FONT, ..default() },
+                    TextColor(VALUE_COLOR),
+                ));
+            }
             RowKind::ResetBindings | RowKind::NavAction => {}
         }
     });
@@ -476,6 +514,8 @@ fn get_slider_value(tab: SettingsTab, index: usize, settings: &GameSettings) ->
         (SettingsTab::Audio, 1) => settings.sfx_volume,
         (SettingsTab::Audio, 2) => settings.preview_volume,
         (SettingsTab::Visual, 1) => settings.note_speed,
+        (SettingsTab::Visual, 2) => settings.background_dim,
+        (SettingsTab::Display, 2) => settings.fps_cap as f32,
         _ => 0.0,
     }
 }
@@ -486,6 +526,8 @@ fn set_slider_value(tab: SettingsTab, index: usize, settings: &mut GameSettings,
         (SettingsTab::Audio, 1) => settings.sfx_volume = value,
         (SettingsTab::Audio, 2) => settings.preview_volume = value,
         (SettingsTab::Visual, 1) => settings.note_speed = value,
+        (SettingsTab::Visual, 2) => settings.background_dim = value,
+        (SettingsTab::Display, 2) => settings.fps_cap = value.round() as u32,
         _ => {}
     }
 }
@@ -508,21 +550,80 @@ fn set_offset_value(tab: SettingsTab, index: usize, settings: &mut GameSettings,
 
 fn get_toggle_value(tab: SettingsTab, index: usize, settings: &GameSettings) -> bool {
     match (tab, index) {
+        (SettingsTab::Visual, 3) => settings.approach_ring,
+        (SettingsTab::Visual, 4) => settings.beat_pulse,
         (SettingsTab::Display, 0) => settings.fullscreen,
+        (SettingsTab::Display, 1) => settings.vsync,
         _ => false,
     }
 }
 
 fn set_toggle_value(tab: SettingsTab, index: usize, settings: &mut GameSettings, value: bool) {
     match (tab, index) {
+        (SettingsTab::Visual, 3) => settings.approach_ring = value,
+        (SettingsTab::Visual, 4) => settings.beat_pulse = value,
         (SettingsTab::Display, 0) => settings.fullscreen = value,
+        (SettingsTab::Display, 1) => settings.vsync = value,
         _ => {}
     }
 }
 
+/// Options of a Choice row, in cycling order.
+fn choice_options(tab: SettingsTab, index: usize) -> Vec<String> {
+    match (tab, index) {
+        (SettingsTab::Display, 3) => RESOLUTIONS.iter().map(|(w, h)| format!("{w} x {h}")).collect(),
+        (SettingsTab::Display, 4) => FullscreenMode::ALL.iter().map(|m| m.label().to_string()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn get_choice_index(tab: SettingsTab, index: usize, settings: &GameSettings) -> usize {
+    match (tab, index) {
+        // A size edited into settings.json by hand shows as the first preset
+        (SettingsTab::Display, 3) => RESOLUTIONS.iter().position(|&r| r == settings.resolution).unwrap_or(0),
+        (SettingsTab::Display, 4) => FullscreenMode::ALL.iter().position(|&m| m == settings.fullscreen_mode).unwrap_or(0),
+        _ => 0,
+    }
+}
+
+fn set_choice_index(tab: SettingsTab, index: usize, settings: &mut GameSettings, choice: usize) {
+    match (tab, index) {
+        (SettingsTab::Display, 3) => settings.resolution = RESOLUTIONS[choice],
+        (SettingsTab::Display, 4) => settings.fullscreen_mode = FullscreenMode::ALL[choice],
+        _ => {}
+    }
+}
+
+/// Step a Choice row by `delta` options, wrapping at either end.
+fn cycle_choice(tab: SettingsTab, index: usize, settings: &mut GameSettings, delta: isize) {
+    let count = choice_options(tab, index).len();
+    if count == 0 {
+        return;
+    }
+    let current = get_choice_index(tab, index, settings) as isize;
+    let next = (current + delta).rem_euclid(count as isize) as usize;
+    set_choice_index(tab, index, settings, next);
+}
+
+fn format_choice(tab: SettingsTab, index: usize, settings: &GameSettings) -> String {
+    let options = choice_options(tab, index);
+    let current = get_choice_index(tab, index, settings);
+    options.get(current).map_or_else(|| "---".to_string(), |o| format!("< {o} >"))
+}
+
+/// Push the window size, fullscreen and VSync settings to the primary window.
+fn apply_window_settings(settings: &GameSettings, window: &mut Window) {
+    window.mode = settings.window_mode();
+    let (width, height) = settings.resolution;
+    window.resolution.set(width as f32, height as f32);
+    window.present_mode = settings.present_mode();
+}
+
 fn format_slider_value(tab: SettingsTab, index: usize, value: f32) -> String {
     match (tab, index) {
         (SettingsTab::Visual, 1) => format!("{value:.1}x"),
+        (SettingsTab::Display, 2) if value <= 0.0 => "OFF".to_string(),
+        (SettingsTab::Display, 2) => format!("{value:.0} FPS"),
         _ => format!("{:.0}%", value),
     }
 }
@@ -545,15 +646,23 @@ fn binding_display_name(input_map: &InputMap<GameAction>, action: &GameAction) -
 
 // --- Navigation ---
 
+#[allow(clippy::too_many_arguments)]
 fn navigate_settings(
     action: Res<ActionState<GameAction>>,
     keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut nav: ResMut<MenuNav>,
     mut state: ResMut<SettingsState>,
     mut next_state: ResMut<NextState<GameScreen>>,
     mut settings: ResMut<GameSettings>,
+    mut bindings: ResMut<InputBindings>,
     mut commands: Commands,
     mut windows: Query<&mut Window>,
+    gamepads: Query<&Gamepad>,
+    return_to: Option<Res<SettingsReturn>>,
 ) {
+    nav.tick(&action, time.delta_secs());
+
     if state.listening {
         if action.just_pressed(&GameAction::Back) {
             state.listening = false;
@@ -563,7 +672,7 @@ fn navigate_settings(
     }
 
     if action.just_pressed(&GameAction::Back) {
-        next_state.set(GameScreen::SongSelect);
+        next_state.set(return_to.map_or(GameScreen::MainMenu, |r| r.0.clone()));
         return;
     }
 
@@ -587,7 +696,7 @@ fn navigate_settings(
 
     // Row navigation
     let row_count = state.active_tab.row_count();
-    if action.just_pressed(&GameAction::Up) {
+    if nav.fired(GameAction::Up) {
         if state.selected_row > 0 {
             state.selected_row -= 1;
         } else {
@@ -595,7 +704,7 @@ fn navigate_settings(
         }
         state.dirty = true;
     }
-    if action.just_pressed(&GameAction::Down) {
+    if nav.fired(GameAction::Down) {
         state.selected_row = (state.selected_row + 1) % row_count;
         state.dirty = true;
     }
@@ -607,27 +716,46 @@ fn navigate_settings(
     let row_def = &rows[row];
 
     match row_def.kind {
-        RowKind::KeyBind(_) => {
+        RowKind::KeyBind(bound) => {
             if action.just_pressed(&GameAction::Confirm) {
                 state.listening = true;
+                state.capture_armed = false;
                 state.dirty = true;
+            } else if keys.any_just_pressed([KeyCode::Backspace, KeyCode::Delete]) {
+                if bindings.clear_keys(bound) {
+                    commands.insert_resource(bindings.to_input_map());
+                    info!("Cleared keyboard binding for {}", row_def.label);
+                    state.dirty = true;
+                } else if MENU_ACTIONS.contains(&bound) {
+                    info!("{} is needed for menus; rebind it instead of clearing", row_def.label);
+                }
+            } else if gamepads.iter().any(|g| g.just_pressed(GamepadButton::Select)) {
+                if bindings.clear_buttons(bound) {
+                    commands.insert_resource(bindings.to_input_map());
+                    info!("Cleared gamepad binding for {}", row_def.label);
+                    state.dirty = true;
+                } else if MENU_ACTIONS.contains(&bound) {
+                    info!("{} is needed for menus; rebind it instead of clearing", row_def.label);
+                }
             }
         }
         RowKind::ResetBindings => {
             if action.just_pressed(&GameAction::Confirm) {
-                commands.insert_resource(default_input_map());
+                *bindings = InputBindings::default();
+                commands.insert_resource(bindings.to_input_map());
                 info!("Input bindings reset to defaults");
                 state.dirty = true;
             }
         }
+        // Holding left/right sweeps sliders and offsets at the menu repeat rate
         RowKind::Slider { min, max, step } => {
-            if action.just_pressed(&GameAction::Left) {
+            if nav.fired(GameAction::Left) {
                 let val = get_slider_value(tab, row, &settings);
                 let new_val = (val - step).max(min);
                 set_slider_value(tab, row, &mut settings, new_val);
                 state.dirty = true;
             }
-            if action.just_pressed(&GameAction::Right) {
+            if nav.fired(GameAction::Right) {
                 let val = get_slider_value(tab, row, &settings);
                 let new_val = (val + step).min(max);
                 set_slider_value(tab, row, &mut settings, new_val);
@@ -635,13 +763,13 @@ fn navigate_settings(
             }
         }
         RowKind::Offset { min, max, step } => {
-            if action.just_pressed(&GameAction::Left) {
+            if nav.fired(GameAction::Left) {
                 let val = get_offset_value(tab, row, &settings);
                 let new_val = (val - step).max(min);
                 set_offset_value(tab, row, &mut settings, new_val);
                 state.dirty = true;
             }
-            if action.just_pressed(&GameAction::Right) {
+            if nav.fired(GameAction::Right) {
                 let val = get_offset_value(tab, row, &settings);
                 let new_val = (val + step).min(max);
                 set_offset_value(tab, row, &mut settings, new_val);
@@ -655,14 +783,28 @@ fn navigate_settings(
             {
                 let val = get_toggle_value(tab, row, &settings);
                 set_toggle_value(tab, row, &mut settings, !val);
-                // Apply fullscreen immediately
-                if tab == SettingsTab::Display && row == 0 {
+                // Apply fullscreen and vsync immediately
+                if tab == SettingsTab::Display {
+                    if let Ok(mut window) = windows.single_mut() {
+                        apply_window_settings(&settings, &mut window);
+                    }
+                }
+                state.dirty = true;
+            }
+        }
+        RowKind::Choice => {
+            let delta = if nav.fired(GameAction::Left) {
+                -1
+            } else if nav.fired(GameAction::Right) || action.just_pressed(&GameAction::Confirm) {
+                1
+            } else {
+                0
+            };
+            if delta != 0 {
+                cycle_choice(tab, row, &mut settings, delta);
+                if tab == SettingsTab::Display {
                     if let Ok(mut window) = windows.single_mut() {
-                        window.mode = if !val {
-                            bevy::window::WindowMode::BorderlessFullscreen(MonitorSelection::Current)
-                        } else {
-                            bevy::window::WindowMode::Windowed
-                        };
+                        apply_window_settings(&settings, &mut window);
                     }
                 }
                 state.dirty = true;
@@ -679,13 +821,21 @@ fn navigate_settings(
 
 fn capture_remap(
     keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
     mut state: ResMut<SettingsState>,
+    mut bindings: ResMut<InputBindings>,
     mut input_map: ResMut<InputMap<GameAction>>,
 ) {
     if !state.listening || state.active_tab != SettingsTab::Input {
         return;
     }
 
+    // Skip the frame listening started on — that press is the Confirm itself
+    if !state.capture_armed {
+        state.capture_armed = true;
+        return;
+    }
+
     let rows = rows_for_tab(SettingsTab::Input);
     let RowKind::KeyBind(action) = rows[state.selected_row].kind else {
         return;
@@ -707,13 +857,29 @@ fn capture_remap(
             continue;
         }
 
-        input_map.clear_action(&action);
-        input_map.insert(action, key);
+        bindings.bind_key(action, key);
+        *input_map = bindings.to_input_map();
         info!("Rebound {} -> {:?}", rows[state.selected_row].label, key);
         state.listening = false;
         state.dirty = true;
         return;
     }
+
+    for gamepad in &gamepads {
+        for &button in gamepad.get_just_pressed() {
+            // East is Back, which cancels listening
+            if button == GamepadButton::East {
+                continue;
+            }
+
+            bindings.bind_button(action, button);
+            *input_map = bindings.to_input_map();
+            info!("Rebound {} -> {:?}", rows[state.selected_row].label, button);
+            state.listening = false;
+            state.dirty = true;
+            return;
+        }
+    }
 }
 
 // --- UI Update ---
@@ -796,7 +962,7 @@ fn update_settings_ui(
             RowKind::KeyBind(action) => {
                 let is_listening = state.listening && tab == active_tab && idx == selected_row;
                 if is_listening {
-                    **text = "[PRESS KEY]".to_string();
+                    **text = "[PRESS KEY / BUTTON]".to_string();
                     *color = TextColor(LISTENING_COLOR);
                 } else {
                     **text = binding_display_name(&input_map, &action);
@@ -819,6 +985,10 @@ fn update_settings_ui(
                 **text = if on { "ON" } else { "OFF" }.to_string();
                 *color = TextColor(if on { TOGGLE_ON } else { TOGGLE_OFF });
             }
+            RowKind::Choice => {
+                **text = format_choice(tab, idx, &settings);
+                *color = TextColor(VALUE_COLOR);
+            }
             RowKind::ResetBindings | RowKind::NavAction => {}
         }
     }
@@ -843,35 +1013,56 @@ fn save_on_exit(settings: Res<GameSettings>) {
     settings.save();
 }
 
-/// Rebuild the default input map (mirrors `GameAction::default_input_map` in action.rs).
-fn default_input_map() -> InputMap<GameAction> {
-    use GameAction::*;
-    let mut map = InputMap::default();
-
-    map.insert(Tap, KeyCode::Space);
-    map.insert(Tap, GamepadButton::South);
-
-    map.insert(Confirm, KeyCode::Space);
-    map.insert(Confirm, KeyCode::Enter);
-    map.insert(Confirm, GamepadButton::South);
+// --- Display ---
 
-    map.insert(Back, KeyCode::Escape);
-    map.insert(Back, GamepadButton::East);
-
-    map.insert(Up, KeyCode::ArrowUp);
-    map.insert(Down, KeyCode::ArrowDown);
-    map.insert(Left, KeyCode::ArrowLeft);
-    map.insert(Right, KeyCode::ArrowRight);
-
-    map.insert(Up, GamepadButton::DPadUp);
-    map.insert(Down, GamepadButton::DPadDown);
-    map.insert(Left, GamepadButton::DPadLeft);
-    map.insert(Right, GamepadButton::DPadRight);
+fn apply_display_settings(settings: Res<GameSettings>, mut windows: Query<&mut Window>) {
+    if let Ok(mut window) = windows.single_mut() {
+        apply_window_settings(&settings, &mut window);
+    }
+}
 
-    map.insert(Up, GamepadControlDirection::LEFT_UP);
-    map.insert(Down, GamepadControlDirection::LEFT_DOWN);
-    map.insert(Left, GamepadControlDirection::LEFT_LEFT);
-    map.insert(Right, GamepadControlDirection::LEFT_RIGHT);
+/// Sleep off whatever is left of the frame budget when an FPS cap is set.
+fn limit_frame_rate(settings: Res<GameSettings>, mut limiter: ResMut<FrameLimiter>) {
+    if let Some(budget) = settings.frame_budget() {
+        let elapsed = limiter.last_frame.elapsed();
+        if elapsed < budget {
+            std::thread::sleep(budget - elapsed);
+        }
+    }
+    limiter.last_frame = Instant::now();
+}
 
-    map
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_choices_cycle_and_persist() {
+        let mut settings = GameSettings::default();
+        assert_eq!(format_choice(SettingsTab::Display, 3, &settings), "< 1280 x 720 >");
+
+        cycle_choice(SettingsTab::Display, 3, &mut settings, 1);
+        assert_eq!(settings.resolution, (1600, 900));
+        // Stepping back past the first preset wraps to the last
+        cycle_choice(SettingsTab::Display, 3, &mut settings, -2);
+        assert_eq!(settings.resolution, *RESOLUTIONS.last().unwrap());
+
+        // Borderless stays the default; exclusive only matters once fullscreen is on
+        cycle_choice(SettingsTab::Display, 4, &mut settings, 1);
+        assert_eq!(settings.fullscreen_mode, FullscreenMode::Exclusive);
+        assert_eq!(settings.window_mode(), bevy::window::WindowMode::Windowed);
+
+        let json = serde_json::to_string(&settings).unwrap();
+        let loaded: GameSettings = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.resolution, settings.resolution);
+        assert_eq!(loaded.fullscreen_mode, FullscreenMode::Exclusive);
+
+        // Older settings files have neither field
+        let old: GameSettings = serde_json::from_str(r#"{ "fullscreen": true }"#).unwrap();
+        assert_eq!(old.resolution, (1280, 720));
+        assert_eq!(
+            old.window_mode(),
+            bevy::window::WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+        );
+    }
 }
@@ -1,21 +1,43 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
 
-use crate::action::GameAction;
+use crate::action::{GameAction, MenuNav};
 use crate::audio::{KiraContext, play_preview, stop_preview};
-use crate::beatmap::{Difficulty, DiscoveredSong, SelectedSong, discover_songs, load_chart};
+use crate::beatmap::{
+    ChartFile, Difficulty, DiscoveredSong, SongDurations, SongMetadata, SongPlaylist, chart_notes,
+    discover_songs, find_uncharted_audio, load_chart,
+};
 use crate::config::GameSettings;
-use crate::editor::EditingSong;
+use crate::editor::{EditingSong, create_song_from_audio};
+use crate::health::GameplayMods;
+use crate::loading::{LoadError, PendingLoad, PreloadedSong};
+use crate::notes::NoteKind;
+use crate::settings::SettingsReturn;
 use crate::state::GameScreen;
 
 pub struct SongSelectPlugin;
 
 impl Plugin for SongSelectPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameScreen::SongSelect), setup_song_select)
+        app.insert_resource(SongRng::from_env())
+            .init_resource::<SongDurations>()
+            .add_systems(OnEnter(GameScreen::SongSelect), setup_song_select)
             .add_systems(
                 Update,
-                (navigate_songs, update_song_select_ui)
+                (
+                    navigate_songs,
+                    edit_song_filter,
+                    update_preview,
+                    cache_note_counts,
+                    update_song_select_ui,
+                )
                     .chain()
                     .run_if(in_state(GameScreen::SongSelect)),
             )
@@ -38,26 +60,281 @@ const DIFF_INACTIVE: Color = Color::srgb(0.35, 0.3, 0.45);
 const HINT_COLOR: Color = Color::srgb(0.4, 0.35, 0.5);
 const HEADER_COLOR: Color = Color::srgb(0.6, 0.2, 1.0);
 const ACCENT_CYAN: Color = Color::srgb(0.0, 0.9, 1.0);
+const ERROR_COLOR: Color = Color::srgb(1.0, 0.15, 0.3);
+
+/// Where songs are discovered, relative to the working directory.
+const SONGS_ROOT: &str = "assets/songs";
+
+/// Idle time on a highlighted song before its preview starts, so scrolling
+/// through the list doesn't stutter audio.
+const PREVIEW_DEBOUNCE_SECS: f32 = 0.35;
 
 const HEADER_FONT: f32 = 36.0;
 const SONG_TITLE_FONT: f32 = 22.0;
 const SONG_ARTIST_FONT: f32 = 14.0;
+const SONG_INFO_FONT: f32 = 12.0;
 const DIFF_FONT: f32 = 13.0;
 const HINT_FONT: f32 = 12.0;
 
+// --- Sorting ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortMode {
+    Title,
+    Artist,
+    Rating,
+}
+
+impl SortMode {
+    fn next(self) -> Self {
+        match self {
+            SortMode::Title => SortMode::Artist,
+            SortMode::Artist => SortMode::Rating,
+            SortMode::Rating => SortMode::Title,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SortMode::Title => "TITLE",
+            SortMode::Artist => "ARTIST",
+            SortMode::Rating => "RATING",
+        }
+    }
+}
+
+fn compare_title(a: &SongMetadata, b: &SongMetadata) -> Ordering {
+    a.title.to_lowercase().cmp(&b.title.to_lowercase())
+}
+
+fn compare_artist(a: &SongMetadata, b: &SongMetadata) -> Ordering {
+    a.artist
+        .to_lowercase()
+        .cmp(&b.artist.to_lowercase())
+        .then_with(|| compare_title(a, b))
+}
+
+/// Ascending by rating; songs without a chart for the difficulty sort last.
+fn compare_rating(a: Option<u32>, b: Option<u32>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// The chart's `difficulty_rating` for `difficulty`, if the song has that chart.
+fn chart_rating(song: &DiscoveredSong, difficulty: Difficulty) -> Option<u32> {
+    if !song.metadata.difficulties.contains(&difficulty) {
+        return None;
+    }
+    load_chart(&song.dir, difficulty).ok().map(|chart| chart.difficulty_rating)
+}
+
+/// Notes the player has to hit in the chart for `difficulty`, if the song has it.
+fn chart_note_count(song: &DiscoveredSong, difficulty: Difficulty) -> Option<usize> {
+    if !song.metadata.difficulties.contains(&difficulty) {
+        return None;
+    }
+    let chart = load_chart(&song.dir, difficulty).ok()?;
+    Some(count_hittable(&chart))
+}
+
+/// The notes gameplay judges, so Rests, skipped types, merged duplicates and
+/// hidden Ad-Libs don't count.
+fn count_hittable(chart: &ChartFile) -> usize {
+    chart_notes(chart)
+        .iter()
+        .filter(|n| !matches!(n.kind, NoteKind::AdLib))
+        .count()
+}
+
+/// A song length as m:ss.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs_f64().round() as u64;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// Length and note count shown under a song's artist; either may be unknown.
+fn song_info_line(duration: Option<Duration>, note_count: Option<usize>) -> String {
+    let mut parts = Vec::new();
+    if let Some(duration) = duration {
+        parts.push(format_duration(duration));
+    }
+    if let Some(count) = note_count {
+        parts.push(format!("{count} NOTES"));
+    }
+    parts.join("  ·  ")
+}
+
 // --- Resources ---
 
+/// Xorshift RNG for random song picks. Set `FUNKTRACK_SEED` to make picks
+/// reproducible when testing.
+#[derive(Resource)]
+struct SongRng(u64);
+
+impl SongRng {
+    fn from_env() -> Self {
+        let seed = std::env::var("FUNKTRACK_SEED")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_nanos() as u64)
+                    .unwrap_or(0x2545_F491_4F6C_DD1D)
+            });
+        // Xorshift state must be non-zero
+        Self(seed.max(1))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// Pick a random `(song, difficulty index)` from `candidates`, skipping songs
+/// with no difficulties. `candidates` pairs a song index with its difficulty count.
+fn random_pick(rng: &mut SongRng, candidates: &[(usize, usize)]) -> Option<(usize, usize)> {
+    let playable: Vec<(usize, usize)> = candidates
+        .iter()
+        .copied()
+        .filter(|&(_, diff_count)| diff_count > 0)
+        .collect();
+    if playable.is_empty() {
+        return None;
+    }
+    let (song, diff_count) = playable[rng.below(playable.len())];
+    Some((song, rng.below(diff_count)))
+}
+
+/// What confirming a song does: play it, or open it in the editor.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SongPickPurpose {
+    #[default]
+    Play,
+    Edit,
+}
+
+impl SongPickPurpose {
+    fn header(self) -> &'static str {
+        match self {
+            SongPickPurpose::Play => "SELECT TRACK",
+            SongPickPurpose::Edit => "SELECT TRACK TO EDIT",
+        }
+    }
+}
+
 #[derive(Resource)]
 struct SongSelectState {
     songs: Vec<DiscoveredSong>,
     selected_index: usize,
     selected_difficulty_index: usize,
     preview_playing_index: Option<usize>,
+    /// Song waiting out the debounce before its preview starts, and time left.
+    preview_pending: Option<(usize, f32)>,
+    /// Case-insensitive title/artist filter. Empty shows every song.
+    filter: String,
+    /// True while keystrokes are being typed into the filter.
+    searching: bool,
+    sort_mode: SortMode,
+    /// Why the last chart picked couldn't be played, cleared on navigation.
+    error: Option<String>,
+    purpose: SongPickPurpose,
+    /// With no songs at all: a folder holding just an audio file, offered as a new chart.
+    uncharted: Option<(PathBuf, String)>,
+    /// Note counts already read from disk, by song folder and difficulty.
+    note_counts: HashMap<(PathBuf, Difficulty), Option<usize>>,
+    /// Chart ratings already read from disk for sorting, keyed the same way.
+    ratings: HashMap<(PathBuf, Difficulty), Option<u32>>,
 }
 
 impl SongSelectState {
-    fn available_difficulties(&self) -> &[Difficulty] {
+    fn is_visible(&self, index: usize) -> bool {
+        self.songs
+            .get(index)
+            .is_some_and(|song| matches_filter(&song.metadata, &self.filter))
+    }
+
+    fn visible_indices(&self) -> Vec<usize> {
+        (0..self.songs.len()).filter(|&i| self.is_visible(i)).collect()
+    }
+
+    /// Move the selection by `delta` rows within the filtered list, wrapping.
+    fn step_selection(&mut self, delta: isize) {
+        let visible = self.visible_indices();
+        if visible.is_empty() {
+            return;
+        }
+        let pos = visible
+            .iter()
+            .position(|&i| i == self.selected_index)
+            .unwrap_or(0) as isize;
+        let next = (pos + delta).rem_euclid(visible.len() as isize) as usize;
+        self.selected_index = visible[next];
+        self.selected_difficulty_index = 0;
+    }
+
+    /// Keep the current song if it still matches the filter, otherwise jump to
+    /// the first match.
+    fn refresh_filter(&mut self) {
+        if self.is_visible(self.selected_index) {
+            return;
+        }
+        if let Some(&first) = self.visible_indices().first() {
+            self.selected_index = first;
+            self.selected_difficulty_index = 0;
+        }
+    }
+
+    /// Re-sort `songs` by `sort_mode`, keeping the highlighted song selected.
+    fn apply_sort(&mut self) {
         if self.songs.is_empty() {
+            return;
+        }
+        let selected_dir = self.songs[self.selected_index].dir.clone();
+        match self.sort_mode {
+            SortMode::Title => self.songs.sort_by(|a, b| compare_title(&a.metadata, &b.metadata)),
+            SortMode::Artist => self.songs.sort_by(|a, b| compare_artist(&a.metadata, &b.metadata)),
+            SortMode::Rating => {
+                let difficulty = self.current_difficulty().unwrap_or(Difficulty::Normal);
+                let ratings = &mut self.ratings;
+                let mut rated: Vec<(Option<u32>, DiscoveredSong)> = self
+                    .songs
+                    .drain(..)
+                    .map(|song| {
+                        let rating = *ratings
+                            .entry((song.dir.clone(), difficulty))
+                            .or_insert_with(|| chart_rating(&song, difficulty));
+                        (rating, song)
+                    })
+                    .collect();
+                rated.sort_by(|(ra, a), (rb, b)| {
+                    compare_rating(*ra, *rb).then_with(|| compare_title(&a.metadata, &b.metadata))
+                });
+                self.songs = rated.into_iter().map(|(_, song)| song).collect();
+            }
+        }
+        if let Some(pos) = self.songs.iter().position(|s| s.dir == selected_dir) {
+            self.selected_index = pos;
+        }
+        // The highlighted song is unchanged, so the running preview still matches
+        self.preview_playing_index = Some(self.selected_index);
+    }
+
+    fn available_difficulties(&self) -> &[Difficulty] {
+        if !self.is_visible(self.selected_index) {
             return &[];
         }
         &self.songs[self.selected_index].metadata.difficulties
@@ -67,6 +344,19 @@ impl SongSelectState {
         let diffs = self.available_difficulties();
         diffs.get(self.selected_difficulty_index).copied()
     }
+
+    /// Difficulty every row's note count is shown for: the highlighted one.
+    fn row_difficulty(&self) -> Difficulty {
+        self.current_difficulty().unwrap_or(Difficulty::Normal)
+    }
+
+    fn note_count(&self, index: usize) -> Option<usize> {
+        let song = self.songs.get(index)?;
+        self.note_counts
+            .get(&(song.dir.clone(), self.row_difficulty()))
+            .copied()
+            .flatten()
+    }
 }
 
 // --- Markers ---
@@ -80,22 +370,81 @@ struct SongTitleText(usize);
 #[derive(Component)]
 struct SongArtistText(usize);
 
+#[derive(Component)]
+struct SongInfoText(usize);
+
 #[derive(Component)]
 struct DifficultyIndicator(usize, Difficulty);
 
 #[derive(Component)]
 struct DifficultyDisplay;
 
+#[derive(Component)]
+struct HeaderText;
+
+#[derive(Component)]
+struct NoMatchesText;
+
+#[derive(Component)]
+struct SortModeText;
+
+#[derive(Component)]
+struct ChartErrorText;
+
+// --- Filtering ---
+
+/// Sort order, plus the active mods when there are any.
+fn sort_line(sort_mode: SortMode, mods: &GameplayMods) -> String {
+    match mods.label() {
+        Some(label) => format!("SORT: {}    MOD: {label}", sort_mode.label()),
+        None => format!("SORT: {}", sort_mode.label()),
+    }
+}
+
+/// Whether a song's title or artist contains `query`, ignoring case.
+fn matches_filter(metadata: &SongMetadata, query: &str) -> bool {
+    if query.is_empty() {
+        return true;
+    }
+    let query = query.to_lowercase();
+    metadata.title.to_lowercase().contains(&query)
+        || metadata.artist.to_lowercase().contains(&query)
+}
+
 // --- Systems ---
 
-fn setup_song_select(mut commands: Commands, mut ctx: NonSendMut<KiraContext>, settings: Res<GameSettings>) {
-    let songs = discover_songs(std::path::Path::new("assets/songs"));
+fn setup_song_select(
+    mut commands: Commands,
+    mut ctx: NonSendMut<KiraContext>,
+    settings: Res<GameSettings>,
+    load_error: Option<Res<LoadError>>,
+    purpose: Option<Res<SongPickPurpose>>,
+    mods: Res<GameplayMods>,
+    mut durations: ResMut<SongDurations>,
+) {
+    let songs_root = Path::new(SONGS_ROOT);
+    let songs = discover_songs(songs_root, &mut durations);
+    let uncharted = if songs.is_empty() { find_uncharted_audio(songs_root) } else { None };
+
+    // Back from gameplay or a failed load: drop the decoded song, surface the error
+    commands.remove_resource::<PreloadedSong>();
+    commands.remove_resource::<LoadError>();
+    commands.insert_resource(MenuNav::default());
 
     let mut state = SongSelectState {
         songs,
         selected_index: 0,
         selected_difficulty_index: 0,
         preview_playing_index: None,
+        preview_pending: None,
+        filter: String::new(),
+        searching: false,
+        sort_mode: SortMode::Title,
+        error: load_error.map(|e| e.0.clone()),
+        purpose: purpose.map(|p| *p).unwrap_or_default(),
+        uncharted,
+        note_counts: HashMap::new(),
+        ratings: HashMap::new(),
     };
 
     // Start preview for first song
@@ -123,7 +472,8 @@ fn setup_song_select(mut commands: Commands, mut ctx: NonSendMut<KiraContext>, s
         .with_children(|root: &mut ChildSpawnerCommands| {
             // Header
             root.spawn((
-                Text::new("SELECT TRACK"),
+                HeaderText,
+                Text::new(state.purpose.header()),
                 TextFont {
                     font_size: HEADER_FONT,
                     ..default()
@@ -135,6 +485,17 @@ fn setup_song_select(mut commands: Commands, mut ctx: NonSendMut<KiraContext>, s
                 },
             ));
 
+            // Sort mode
+            root.spawn((
+                SortModeText,
+                Text::new(sort_line(state.sort_mode, &mods)),
+                TextFont {
+                    font_size: HINT_FONT,
+                    ..default()
+                },
+                TextColor(ARTIST_COLOR),
+            ));
+
             // Song list panel
             root.spawn((
                 Node {
@@ -153,19 +514,25 @@ fn setup_song_select(mut commands: Commands, mut ctx: NonSendMut<KiraContext>, s
             ))
             .with_children(|list: &mut ChildSpawnerCommands| {
                 if state.songs.is_empty() {
+                    spawn_empty_list_help(list, &state);
+                } else {
+                    for (i, song) in state.songs.iter().enumerate() {
+                        let is_selected = i == state.selected_index;
+                        spawn_song_row(list, i, song, is_selected);
+                    }
                     list.spawn((
-                        Text::new("No songs found in assets/songs/"),
+                        NoMatchesText,
+                        Text::new("No matches"),
                         TextFont {
                             font_size: SONG_TITLE_FONT,
                             ..default()
                         },
                         TextColor(HINT_COLOR),
+                        Node {
+                            display: Display::None,
+                            ..default()
+                        },
                     ));
-                } else {
-                    for (i, song) in state.songs.iter().enumerate() {
-                        let is_selected = i == state.selected_index;
-                        spawn_song_row(list, i, song, is_selected);
-                    }
                 }
             });
 
@@ -218,6 +585,17 @@ fn setup_song_select(mut commands: Commands, mut ctx: NonSendMut<KiraContext>, s
                 }
             });
 
+            // Chart load/validation error for the last pick
+            root.spawn((
+                ChartErrorText,
+                Text::new(""),
+                TextFont {
+                    font_size: HINT_FONT,
+                    ..default()
+                },
+                TextColor(ERROR_COLOR),
+            ));
+
             // Controls hint
             root.spawn((
                 Node {
@@ -230,9 +608,21 @@ fn setup_song_select(mut commands: Commands, mut ctx: NonSendMut<KiraContext>, s
             .with_children(|hints: &mut ChildSpawnerCommands| {
                 spawn_hint(hints, "UP/DOWN", "select");
                 spawn_hint(hints, "LEFT/RIGHT", "difficulty");
-                spawn_hint(hints, "A/SPACE", "play");
+                spawn_hint(
+                    hints,
+                    "A/SPACE",
+                    match state.purpose {
+                        SongPickPurpose::Play => "play",
+                        SongPickPurpose::Edit => "edit",
+                    },
+                );
+                spawn_hint(hints, "/", "search");
+                spawn_hint(hints, "S", "sort");
+                spawn_hint(hints, "R", "random");
+                spawn_hint(hints, "M", "mods");
                 spawn_hint(hints, "E", "edit");
                 spawn_hint(hints, "TAB", "settings");
+                spawn_hint(hints, "ESC", "menu");
             });
         });
 
@@ -289,6 +679,16 @@ fn spawn_song_row(
                 },
                 TextColor(ARTIST_COLOR),
             ));
+            // Filled in by update_song_select_ui once the note count is cached
+            row.spawn((
+                SongInfoText(index),
+                Text::new(song_info_line(song.duration, None)),
+                TextFont {
+                    font_size: SONG_INFO_FONT,
+                    ..default()
+                },
+                TextColor(HINT_COLOR),
+            ));
         });
 }
 
@@ -319,76 +719,123 @@ fn spawn_hint(parent: &mut ChildSpawnerCommands, key: &str, action: &str) {
         });
 }
 
+/// What to show instead of the list when there are no songs: where we looked,
+/// how a song folder is laid out, and a shortcut if there's audio waiting.
+fn spawn_empty_list_help(list: &mut ChildSpawnerCommands, state: &SongSelectState) {
+    let searched = std::env::current_dir()
+        .map(|cwd| cwd.join(SONGS_ROOT))
+        .unwrap_or_else(|_| PathBuf::from(SONGS_ROOT));
+    list.spawn((
+        Text::new("No songs found"),
+        TextFont {
+            font_size: SONG_TITLE_FONT,
+            ..default()
+        },
+        TextColor(HINT_COLOR),
+    ));
+    let mut lines = vec![
+        format!("Searched {}", searched.display()),
+        "Add one folder per song holding metadata.ron, the audio file".to_string(),
+        "and easy/normal/hard/expert.ron charts (chart_gen can make them).".to_string(),
+    ];
+    if let Some((dir, audio)) = &state.uncharted {
+        let name = dir.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        lines.push(String::new());
+        lines.push(format!("{name}/ has {audio} but no metadata.ron."));
+        lines.push("[CONFIRM] Start a new chart for it in the editor".to_string());
+    }
+    for line in lines {
+        list.spawn((
+            Text::new(line),
+            TextFont {
+                font_size: SONG_ARTIST_FONT,
+                ..default()
+            },
+            TextColor(ARTIST_COLOR),
+        ));
+    }
+}
+
 fn start_preview_for_song(state: &SongSelectState, ctx: &mut KiraContext, settings: &GameSettings) {
     if state.songs.is_empty() {
         return;
     }
     let song = &state.songs[state.selected_index];
     let audio_path = song.dir.join(&song.metadata.audio_file);
-    let Some(audio_str) = audio_path.to_str() else {
+    // Songs without audio just stay silent on the select screen
+    if !audio_path.is_file() {
         return;
-    };
+    }
     play_preview(
         ctx,
-        audio_str,
+        &audio_path,
         song.metadata.preview_start_ms,
         song.metadata.preview_duration_ms,
         settings.preview_amplitude(),
     );
 }
 
+#[allow(clippy::too_many_arguments)]
 fn navigate_songs(
     action: Res<ActionState<GameAction>>,
     keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut nav: ResMut<MenuNav>,
     mut state: ResMut<SongSelectState>,
     mut commands: Commands,
     mut next_state: ResMut<NextState<GameScreen>>,
-    mut ctx: NonSendMut<KiraContext>,
-    settings: Res<GameSettings>,
+    mut rng: ResMut<SongRng>,
+    mut mods: ResMut<GameplayMods>,
 ) {
+    nav.tick(&action, time.delta_secs());
+
+    // Escape clears a filter first; with none left it goes back to the menu
+    if action.just_pressed(&GameAction::Back) && !state.searching && state.filter.is_empty() {
+        next_state.set(GameScreen::MainMenu);
+        return;
+    }
+
     if state.songs.is_empty() {
+        if action.just_pressed(&GameAction::Confirm) {
+            if let Some((dir, audio)) = state.uncharted.clone() {
+                open_new_chart(&dir, &audio, &mut commands, &mut next_state);
+            }
+        }
         return;
     }
 
-    let mut song_changed = false;
+    let moved = [GameAction::Up, GameAction::Down, GameAction::Left, GameAction::Right]
+        .into_iter()
+        .any(|a| nav.fired(a));
+    if moved && state.error.is_some() {
+        state.error = None;
+    }
 
-    if action.just_pressed(&GameAction::Up) {
-        if state.selected_index > 0 {
-            state.selected_index -= 1;
-        } else {
-            state.selected_index = state.songs.len() - 1;
-        }
-        state.selected_difficulty_index = 0;
-        song_changed = true;
+    if nav.fired(GameAction::Up) {
+        state.step_selection(-1);
     }
 
-    if action.just_pressed(&GameAction::Down) {
-        state.selected_index = (state.selected_index + 1) % state.songs.len();
-        state.selected_difficulty_index = 0;
-        song_changed = true;
+    if nav.fired(GameAction::Down) {
+        state.step_selection(1);
     }
 
-    if action.just_pressed(&GameAction::Left) {
+    if nav.fired(GameAction::Left) {
         let diffs = state.available_difficulties();
         if !diffs.is_empty() && state.selected_difficulty_index > 0 {
             state.selected_difficulty_index -= 1;
         }
     }
 
-    if action.just_pressed(&GameAction::Right) {
+    if nav.fired(GameAction::Right) {
         let diffs = state.available_difficulties();
         if !diffs.is_empty() && state.selected_difficulty_index < diffs.len() - 1 {
             state.selected_difficulty_index += 1;
         }
     }
 
-    // Switch preview when song changes
-    if song_changed {
-        let idx = state.selected_index;
-        if state.preview_playing_index != Some(idx) {
-            start_preview_for_song(&state, &mut ctx, &settings);
-            state.preview_playing_index = Some(idx);
-        }
+    // While typing a filter, Space/E/Tab are text, not commands
+    if state.searching {
+        return;
     }
 
     if action.just_pressed(&GameAction::Confirm) {
@@ -397,26 +844,31 @@ fn navigate_songs(
             return;
         };
 
-        let song = &state.songs[state.selected_index];
-        match load_chart(&song.dir, difficulty) {
-            Ok(chart) => {
-                info!(
-                    "Selected: {} [{}]",
-                    song.metadata.title,
-                    difficulty.label()
-                );
-                commands.insert_resource(SelectedSong {
-                    song_dir: song.dir.clone(),
-                    difficulty,
-                    metadata: song.metadata.clone(),
-                    chart,
-                });
-                next_state.set(GameScreen::Playing);
-            }
-            Err(err) => {
-                error!("Failed to load chart: {}", err);
-            }
+        if state.purpose == SongPickPurpose::Edit {
+            open_in_editor(&state, difficulty, &mut commands, &mut next_state);
+            return;
         }
+
+        // Chart parsing and audio decode happen on the loading screen
+        let song = &state.songs[state.selected_index];
+        info!(
+            "Selected: {} [{}]",
+            song.metadata.title,
+            difficulty.label()
+        );
+        commands.insert_resource(PendingLoad {
+            song_dir: song.dir.clone(),
+            difficulty,
+            metadata: song.metadata.clone(),
+        });
+        commands.insert_resource(SongPlaylist {
+            songs: state
+                .visible_indices()
+                .into_iter()
+                .map(|i| state.songs[i].clone())
+                .collect(),
+        });
+        next_state.set(GameScreen::Loading);
     }
 
     // E → Editor screen
@@ -425,69 +877,303 @@ fn navigate_songs(
             warn!("No difficulty selected");
             return;
         };
+        open_in_editor(&state, difficulty, &mut commands, &mut next_state);
+    }
 
-        let song = &state.songs[state.selected_index];
-        match load_chart(&song.dir, difficulty) {
-            Ok(chart) => {
-                info!(
-                    "Editing: {} [{}]",
-                    song.metadata.title,
-                    difficulty.label()
-                );
-                commands.insert_resource(EditingSong {
-                    song_dir: song.dir.clone(),
-                    difficulty,
-                    metadata: song.metadata.clone(),
-                    chart,
-                });
-                next_state.set(GameScreen::Editor);
-            }
-            Err(err) => {
-                error!("Failed to load chart for editing: {}", err);
-            }
+    // R → random song and difficulty among the visible songs
+    if keys.just_pressed(KeyCode::KeyR) {
+        let candidates: Vec<(usize, usize)> = state
+            .visible_indices()
+            .into_iter()
+            .map(|i| (i, state.songs[i].metadata.difficulties.len()))
+            .collect();
+        if let Some((song, diff)) = random_pick(&mut rng, &candidates) {
+            state.selected_index = song;
+            state.selected_difficulty_index = diff;
         }
     }
 
+    // M → cycle the fail mod (off / No-Fail / Sudden-Death)
+    if keys.just_pressed(KeyCode::KeyM) {
+        mods.cycle_fail();
+        state.set_changed();
+    }
+
+    // S → cycle sort order
+    if keys.just_pressed(KeyCode::KeyS) {
+        state.sort_mode = state.sort_mode.next();
+        state.apply_sort();
+    }
+
     // Tab → Settings screen
     if keys.just_pressed(KeyCode::Tab) {
+        commands.insert_resource(SettingsReturn(GameScreen::SongSelect));
         next_state.set(GameScreen::Settings);
     }
 }
 
+/// Load the highlighted chart into the editor.
+fn open_in_editor(
+    state: &SongSelectState,
+    difficulty: Difficulty,
+    commands: &mut Commands,
+    next_state: &mut NextState<GameScreen>,
+) {
+    let song = &state.songs[state.selected_index];
+    match load_chart(&song.dir, difficulty) {
+        Ok(chart) => {
+            info!(
+                "Editing: {} [{}]",
+                song.metadata.title,
+                difficulty.label()
+            );
+            commands.insert_resource(EditingSong {
+                song_dir: song.dir.clone(),
+                difficulty,
+                metadata: song.metadata.clone(),
+                chart,
+            });
+            next_state.set(GameScreen::Editor);
+        }
+        Err(err) => {
+            error!("Failed to load chart for editing: {}", err);
+        }
+    }
+}
+
+/// Give an audio-only folder a metadata.ron and starter chart, then edit it.
+fn open_new_chart(
+    dir: &Path,
+    audio_file: &str,
+    commands: &mut Commands,
+    next_state: &mut NextState<GameScreen>,
+) {
+    match create_song_from_audio(dir, audio_file) {
+        Ok((metadata, chart)) => {
+            info!("New chart for {} in {:?}", audio_file, dir);
+            commands.insert_resource(EditingSong {
+                song_dir: dir.to_path_buf(),
+                difficulty: chart.difficulty,
+                metadata,
+                chart,
+            });
+            next_state.set(GameScreen::Editor);
+        }
+        Err(err) => {
+            error!("Failed to start a new chart in {:?}: {}", dir, err);
+        }
+    }
+}
+
+/// Stop the old preview as soon as the highlight moves, then start the new one
+/// once the highlight has rested for `PREVIEW_DEBOUNCE_SECS`.
+fn update_preview(
+    time: Res<Time>,
+    mut state: ResMut<SongSelectState>,
+    mut ctx: NonSendMut<KiraContext>,
+    settings: Res<GameSettings>,
+) {
+    if state.songs.is_empty() {
+        return;
+    }
+
+    let idx = state.selected_index;
+    if state.preview_playing_index == Some(idx) {
+        if state.preview_pending.is_some() {
+            state.preview_pending = None;
+        }
+        return;
+    }
+
+    match state.preview_pending {
+        Some((pending, remaining)) if pending == idx => {
+            let remaining = remaining - time.delta_secs();
+            if remaining <= 0.0 {
+                start_preview_for_song(&state, &mut ctx, &settings);
+                state.preview_playing_index = Some(idx);
+                state.preview_pending = None;
+            } else {
+                state.preview_pending = Some((idx, remaining));
+            }
+        }
+        _ => {
+            stop_preview(&mut ctx);
+            state.preview_playing_index = None;
+            state.preview_pending = Some((idx, PREVIEW_DEBOUNCE_SECS));
+        }
+    }
+}
+
+/// `/` starts typing a filter; characters narrow the list, Backspace edits,
+/// Enter keeps the filter and returns to navigation, Escape clears it.
+fn edit_song_filter(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut keyboard: MessageReader<KeyboardInput>,
+    mut state: ResMut<SongSelectState>,
+) {
+    if !state.searching {
+        keyboard.clear();
+        if keys.just_pressed(KeyCode::Slash) {
+            state.searching = true;
+        } else if keys.just_pressed(KeyCode::Escape) && !state.filter.is_empty() {
+            state.filter.clear();
+            state.refresh_filter();
+        }
+        return;
+    }
+
+    let mut filter_changed = false;
+    for event in keyboard.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Escape => {
+                state.filter.clear();
+                state.searching = false;
+                filter_changed = true;
+            }
+            Key::Enter => {
+                state.searching = false;
+            }
+            Key::Backspace => {
+                filter_changed |= state.filter.pop().is_some();
+            }
+            Key::Space => {
+                state.filter.push(' ');
+                filter_changed = true;
+            }
+            Key::Character(text) => {
+                state.filter.push_str(text);
+                filter_changed = true;
+            }
+            _ => {}
+        }
+        if !state.searching {
+            break;
+        }
+    }
+
+    if filter_changed {
+        state.refresh_filter();
+    }
+}
+
+/// Read note counts for the difficulty the rows show, once per song and
+/// difficulty, so the UI never touches the disk.
+fn cache_note_counts(mut state: ResMut<SongSelectState>) {
+    if !state.is_changed() {
+        return;
+    }
+    let difficulty = state.row_difficulty();
+    let missing: Vec<(PathBuf, Option<usize>)> = state
+        .songs
+        .iter()
+        .filter(|song| !state.note_counts.contains_key(&(song.dir.clone(), difficulty)))
+        .map(|song| (song.dir.clone(), chart_note_count(song, difficulty)))
+        .collect();
+    for (dir, count) in missing {
+        state.note_counts.insert((dir, difficulty), count);
+    }
+}
+
 fn cleanup_song_select(mut ctx: NonSendMut<KiraContext>) {
     stop_preview(&mut ctx);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn update_song_select_ui(
     state: Res<SongSelectState>,
-    mut song_items: Query<(&SongListItem, &mut BackgroundColor, &mut BorderColor)>,
-    mut title_texts: Query<(&SongTitleText, &mut TextColor), Without<SongArtistText>>,
+    mods: Res<GameplayMods>,
+    mut song_items: Query<(&SongListItem, &mut Node, &mut BackgroundColor, &mut BorderColor), Without<NoMatchesText>>,
+    mut title_texts: Query<(&SongTitleText, &mut Text, &mut TextColor), Without<SongArtistText>>,
+    mut artist_texts: Query<(&SongArtistText, &mut Text), Without<SongTitleText>>,
+    mut info_texts: Query<
+        (&SongInfoText, &mut Text),
+        (
+            Without<SongTitleText>,
+            Without<SongArtistText>,
+            Without<HeaderText>,
+            Without<SortModeText>,
+            Without<ChartErrorText>,
+        ),
+    >,
     mut diff_indicators: Query<(&DifficultyIndicator, &mut TextColor), Without<SongTitleText>>,
+    mut header: Query<&mut Text, (With<HeaderText>, Without<SongTitleText>, Without<SongArtistText>, Without<SortModeText>)>,
+    mut sort_text: Query<&mut Text, (With<SortModeText>, Without<SongTitleText>, Without<SongArtistText>)>,
+    mut no_matches: Query<&mut Node, (With<NoMatchesText>, Without<SongListItem>)>,
+    mut error_text: Query<
+        &mut Text,
+        (
+            With<ChartErrorText>,
+            Without<HeaderText>,
+            Without<SortModeText>,
+            Without<SongTitleText>,
+            Without<SongArtistText>,
+        ),
+    >,
 ) {
     if !state.is_changed() {
         return;
     }
 
-    // Update song list selection visuals
-    for (item, mut bg, mut border) in &mut song_items {
+    if let Ok(mut text) = error_text.single_mut() {
+        **text = state.error.clone().unwrap_or_default();
+    }
+
+    // Header shows the active filter
+    if let Ok(mut text) = header.single_mut() {
+        let title = state.purpose.header();
+        **text = if state.searching {
+            format!("{title}  /{}_", state.filter)
+        } else if !state.filter.is_empty() {
+            format!("{title}  /{}", state.filter)
+        } else {
+            title.to_string()
+        };
+    }
+
+    // Update song list selection visuals and hide filtered-out rows
+    let mut any_visible = false;
+    for (item, mut node, mut bg, mut border) in &mut song_items {
+        let visible = state.is_visible(item.0);
+        any_visible |= visible;
+        node.display = if visible { Display::Flex } else { Display::None };
         let is_selected = item.0 == state.selected_index;
         *bg = BackgroundColor(if is_selected { SELECTED_BG } else { Color::NONE });
         *border = BorderColor::all(if is_selected { SELECTED_BORDER } else { PANEL_BORDER_DIM });
     }
+    if let Ok(mut node) = no_matches.single_mut() {
+        node.display = if any_visible { Display::None } else { Display::Flex };
+    }
 
-    for (title, mut color) in &mut title_texts {
+    if let Ok(mut text) = sort_text.single_mut() {
+        **text = sort_line(state.sort_mode, &mods);
+    }
+
+    // Row text follows `songs`, which may have been re-sorted
+    for (title, mut text, mut color) in &mut title_texts {
         let is_selected = title.0 == state.selected_index;
         *color = TextColor(if is_selected { ACCENT_CYAN } else { TITLE_COLOR });
+        if let Some(song) = state.songs.get(title.0) {
+            **text = song.metadata.title.clone();
+        }
+    }
+    for (artist, mut text) in &mut artist_texts {
+        if let Some(song) = state.songs.get(artist.0) {
+            **text = song.metadata.artist.clone();
+        }
+    }
+    for (info, mut text) in &mut info_texts {
+        if let Some(song) = state.songs.get(info.0) {
+            **text = song_info_line(song.duration, state.note_count(info.0));
+        }
     }
 
     // Update difficulty indicators
     let current_diff = state.current_difficulty();
     for (indicator, mut color) in &mut diff_indicators {
-        let is_available = !state.songs.is_empty()
-            && state.songs[state.selected_index]
-                .metadata
-                .difficulties
-                .contains(&indicator.1);
+        let is_available = state.available_difficulties().contains(&indicator.1);
         let is_selected = current_diff == Some(indicator.1);
 
         *color = TextColor(if is_selected {
@@ -499,3 +1185,161 @@ fn update_song_select_ui(
         });
     }
 }
+
+// --- Tests ---
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(title: &str, artist: &str) -> SongMetadata {
+        SongMetadata {
+            title: title.to_string(),
+            artist: artist.to_string(),
+            charter: String::new(),
+            audio_file: String::new(),
+            preview_start_ms: 0,
+            preview_duration_ms: 15000,
+            source: String::new(),
+            difficulties: vec![],
+            offset_ms: 0,
+        }
+    }
+
+    #[test]
+    fn song_info_shows_length_and_note_count() {
+        assert_eq!(format_duration(Duration::from_secs(187)), "3:07");
+        assert_eq!(format_duration(Duration::from_millis(59_600)), "1:00");
+        assert_eq!(format_duration(Duration::from_secs(3)), "0:03");
+        assert_eq!(
+            song_info_line(Some(Duration::from_secs(125)), Some(412)),
+            "2:05  ·  412 NOTES"
+        );
+        // Unknown parts are left out
+        assert_eq!(song_info_line(Some(Duration::from_secs(60)), None), "1:00");
+        assert_eq!(song_info_line(None, Some(8)), "8 NOTES");
+        assert_eq!(song_info_line(None, None), "");
+    }
+
+    #[test]
+    fn note_count_matches_what_gameplay_judges() {
+        use crate::beatmap::{ChartNoteEntry, ChartNoteType, SlideDirection};
+
+        let note = |beat: f64, note_type: ChartNoteType| ChartNoteEntry { beat, note_type };
+        let chart = ChartFile {
+            difficulty: Difficulty::Normal,
+            difficulty_rating: 1,
+            timing_points: Vec::new(),
+            path_segments: Vec::new(),
+            notes: vec![
+                note(1.0, ChartNoteType::Tap),
+                note(1.0, ChartNoteType::Tap),
+                note(2.0, ChartNoteType::Slide { direction: SlideDirection::N }),
+                note(3.0, ChartNoteType::Hold { duration_beats: 1.0 }),
+                note(5.0, ChartNoteType::Critical),
+                note(6.0, ChartNoteType::Scratch),
+                note(
+                    7.0,
+                    ChartNoteType::DualSlide { left: SlideDirection::W, right: SlideDirection::E },
+                ),
+                note(8.0, ChartNoteType::Beat),
+                note(8.0, ChartNoteType::Rest),
+                note(9.0, ChartNoteType::AdLib),
+                note(10.0, ChartNoteType::CriticalHold { duration_beats: 1.0 }),
+                note(
+                    11.0,
+                    ChartNoteType::SlideHold { direction: SlideDirection::S, duration_beats: 1.0 },
+                ),
+            ],
+            events: Vec::new(),
+            travel_beats: 3.0,
+            look_ahead_beats: 3.0,
+        };
+        // One of the doubled taps, slide, hold, critical, scratch and dual slide
+        assert_eq!(count_hittable(&chart), 6);
+    }
+
+    #[test]
+    fn filter_matches_title_or_artist_case_insensitive() {
+        let song = metadata("Click Track 120", "Test Artist");
+        assert!(matches_filter(&song, ""));
+        assert!(matches_filter(&song, "click"));
+        assert!(matches_filter(&song, "TRACK 1"));
+        assert!(matches_filter(&song, "artist"));
+        assert!(!matches_filter(&song, "trance"));
+        assert!(!matches_filter(&song, "click artist"));
+    }
+
+    #[test]
+    fn title_and_artist_comparators() {
+        let a = metadata("alpha", "Zed");
+        let b = metadata("Bravo", "amy");
+        let c = metadata("Charlie", "Amy");
+        assert_eq!(compare_title(&a, &b), Ordering::Less);
+        assert_eq!(compare_title(&c, &b), Ordering::Greater);
+        assert_eq!(compare_artist(&b, &a), Ordering::Less);
+        // Same artist (ignoring case) falls back to title
+        assert_eq!(compare_artist(&b, &c), Ordering::Less);
+    }
+
+    #[test]
+    fn rating_comparator_puts_missing_last() {
+        assert_eq!(compare_rating(Some(3), Some(7)), Ordering::Less);
+        assert_eq!(compare_rating(Some(7), Some(7)), Ordering::Equal);
+        assert_eq!(compare_rating(Some(12), None), Ordering::Less);
+        assert_eq!(compare_rating(None, Some(1)), Ordering::Greater);
+        assert_eq!(compare_rating(None, None), Ordering::Equal);
+    }
+
+    #[test]
+    fn rating_sort_reads_cached_ratings() {
+        let song = |title: &str| DiscoveredSong {
+            dir: PathBuf::from(format!("no-such-songs/{title}")),
+            metadata: SongMetadata {
+                difficulties: vec![Difficulty::Normal],
+                ..metadata(title, "")
+            },
+            duration: None,
+        };
+        let mut state = SongSelectState {
+            songs: vec![song("alpha"), song("bravo"), song("charlie")],
+            selected_index: 0,
+            selected_difficulty_index: 0,
+            preview_playing_index: None,
+            preview_pending: None,
+            filter: String::new(),
+            searching: false,
+            sort_mode: SortMode::Rating,
+            error: None,
+            purpose: SongPickPurpose::Play,
+            uncharted: None,
+            note_counts: HashMap::new(),
+            ratings: HashMap::new(),
+        };
+        // None of these charts exist, so the order can only come from the cache
+        for (title, rating) in [("alpha", 9), ("bravo", 2)] {
+            let key = (PathBuf::from(format!("no-such-songs/{title}")), Difficulty::Normal);
+            state.ratings.insert(key, Some(rating));
+        }
+        state.apply_sort();
+        let titles: Vec<&str> = state.songs.iter().map(|s| s.metadata.title.as_str()).collect();
+        assert_eq!(titles, ["bravo", "alpha", "charlie"]);
+        assert_eq!(state.selected_index, 1);
+        // Charlie's missing chart was looked up once and remembered
+        assert_eq!(state.ratings.len(), 3);
+    }
+
+    #[test]
+    fn random_pick_lands_on_available_difficulty() {
+        let mut rng = SongRng(12345);
+        let candidates = [(0, 3), (1, 0), (2, 1), (5, 4)];
+        for _ in 0..1000 {
+            let (song, diff) = random_pick(&mut rng, &candidates).unwrap();
+            let &(_, count) = candidates.iter().find(|(i, _)| *i == song).unwrap();
+            assert!(count > 0, "picked song {song} with no difficulties");
+            assert!(diff < count, "difficulty {diff} out of range for song {song}");
+        }
+        assert_eq!(random_pick(&mut rng, &[]), None);
+        assert_eq!(random_pick(&mut rng, &[(0, 0)]), None);
+    }
+}
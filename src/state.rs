@@ -3,19 +3,62 @@ use bevy::prelude::*;
 #[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum GameScreen {
     #[default]
+    MainMenu,
     SongSelect,
+    /// Parsing the picked chart and decoding its audio off the main thread.
+    Loading,
     Playing,
     Paused,
     Results,
     Settings,
     Calibration,
     Editor,
+    /// Transient state used to tear down and re-enter gameplay from scratch.
+    Restarting,
+}
+
+/// Active while a song is loaded: Playing, Paused, and the Results overlay.
+/// Gameplay setup/teardown hangs off this so pausing doesn't rebuild the song.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InGame;
+
+impl ComputedStates for InGame {
+    type SourceStates = GameScreen;
+
+    fn compute(sources: GameScreen) -> Option<Self> {
+        matches!(
+            sources,
+            GameScreen::Playing | GameScreen::Paused | GameScreen::Results
+        )
+        .then_some(InGame)
+    }
 }
 
 pub struct GameStatePlugin;
 
 impl Plugin for GameStatePlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<GameScreen>();
+        app.init_state::<GameScreen>()
+            .add_computed_state::<InGame>()
+            .add_systems(OnEnter(GameScreen::Restarting), finish_restart);
+    }
+}
+
+/// By the time this runs, leaving `InGame` has cleaned up the previous run.
+fn finish_restart(mut next_state: ResMut<NextState<GameScreen>>) {
+    next_state.set(GameScreen::Playing);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::state::app::StatesPlugin;
+
+    #[test]
+    fn game_starts_on_main_menu() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, GameStatePlugin));
+        app.update();
+        assert_eq!(*app.world().resource::<State<GameScreen>>().get(), GameScreen::MainMenu);
     }
 }
@@ -6,14 +6,15 @@ use bevy_prototype_lyon::prelude::*;
 use crate::GameSet;
 use crate::beatmap::SlideDirection;
 use crate::conductor::SongConductor;
+use crate::config::GameSettings;
 use crate::judgment::{Judgment, JudgmentFeedback};
 use crate::notes::{
-    HoldEndBeat, HoldState, NoteAlive, NoteDirection, NoteKind,
-    NoteTiming, NoteType, Playhead, SplineProgress,
+    HoldEndBeat, HoldState, NoteAlive, NoteKind,
+    NoteQueue, NoteTiming, Playhead, SplineProgress,
 };
 use crate::path::SplinePath;
 use crate::scoring::{ChainTier, ScoreState};
-use crate::state::GameScreen;
+use crate::state::{GameScreen, InGame};
 
 pub struct VisualsPlugin;
 
@@ -21,6 +22,7 @@ impl Plugin for VisualsPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(ShapePlugin)
             .init_resource::<SmoothedPlayhead>()
+            .add_systems(OnEnter(InGame), (spawn_background_dim, spawn_beat_pulse))
             .add_systems(
                 Update,
                 spawn_path_visual
@@ -32,9 +34,12 @@ impl Plugin for VisualsPlugin {
                 (
                     update_playhead_visual,
                     update_note_visuals,
-                    update_hold_visuals,
+                    update_approach_rings,
+                    // Builds ribbons for notes update_note_visuals is about to mark placed
+                    update_hold_visuals.before(update_note_visuals),
                     update_feedback_visuals,
                     update_chain_visuals,
+                    update_beat_pulse,
                 )
                     .in_set(GameSet::Render),
             );
@@ -57,6 +62,23 @@ const HOLD_HELD_COLOR: Color = Color::srgb(1.0, 0.95, 0.5);
 const HOLD_DROPPED_COLOR: Color = Color::srgb(0.5, 0.4, 0.1);
 const CRITICAL_COLOR: Color = Color::srgb(1.0, 0.95, 0.8);
 const CRITICAL_FILL: Color = Color::srgba(1.0, 0.95, 0.8, 0.2);
+const SCRATCH_COLOR: Color = Color::srgb(0.75, 0.3, 1.0);
+const SCRATCH_FILL: Color = Color::srgba(0.75, 0.3, 1.0, 0.2);
+const DUAL_SLIDE_COLOR: Color = Color::srgb(0.3, 1.0, 0.8);
+const DUAL_SLIDE_FILL: Color = Color::srgba(0.3, 1.0, 0.8, 0.1);
+
+/// Radius of tap/slide note bodies; the approach ring closes down to this.
+const NOTE_RADIUS: f32 = 14.0;
+/// Approach ring radius when a note first appears.
+const APPROACH_RING_START_RADIUS: f32 = 56.0;
+const APPROACH_RING_COLOR: Color = Color::srgb(0.9, 0.9, 1.0);
+
+/// Radius of the ring marking the hit point at the end of the path.
+const JUDGE_MARKER_RADIUS: f32 = 26.0;
+const JUDGE_MARKER_WIDTH: f32 = 1.5;
+const JUDGE_MARKER_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.6);
+/// Extra scale the marker swells to on each beat before settling back.
+const JUDGE_MARKER_PULSE: f32 = 0.15;
 
 /// Smoothing factor for the playhead visual (higher = snappier, must match camera feel).
 const PLAYHEAD_SMOOTHING: f32 = 8.0;
@@ -70,8 +92,15 @@ struct SmoothedPlayhead(Vec2);
 #[derive(Component)]
 pub struct PathVisual;
 
+/// One ring of the playhead, with its stroke width at the Normal chain tier.
 #[derive(Component)]
-struct PlayheadVisual;
+struct PlayheadVisual {
+    width: f32,
+}
+
+/// Fixed ring at the hit point, `position_at_progress(1.0)`.
+#[derive(Component)]
+struct JudgeMarker;
 
 #[derive(Component)]
 struct NoteVisual;
@@ -88,6 +117,15 @@ struct CriticalHalo;
 #[derive(Component)]
 struct HoldRibbon;
 
+/// Notes never move along the path once spawned, so their spline position,
+/// tangent and hold ribbon are sampled on the first frame they're drawn and
+/// this marker skips them afterwards.
+#[derive(Component)]
+struct NotePlaced;
+
+#[derive(Component)]
+struct ApproachRing;
+
 // Feedback markers
 #[derive(Component)]
 struct FeedbackOuterRing;
@@ -110,6 +148,7 @@ fn spawn_path_visual(
     mut commands: Commands,
     spline: Option<Res<SplinePath>>,
     existing: Query<(), With<PathVisual>>,
+    existing_playhead: Query<(), With<PlayheadVisual>>,
     mut smoothed: ResMut<SmoothedPlayhead>,
 ) {
     if !existing.is_empty() {
@@ -117,9 +156,6 @@ fn spawn_path_visual(
     }
     let Some(spline) = spline else { return };
 
-    // Reset smoothed playhead to spline start for this song
-    smoothed.0 = spline.position_at_progress(0.0);
-
     // Build path from spline samples
     let resolution = 200;
     let mut shape_path = ShapePath::new().move_to(spline.position_at_progress(0.0));
@@ -130,13 +166,21 @@ fn spawn_path_visual(
 
     commands.spawn((
         PathVisual,
-        DespawnOnExit(GameScreen::Playing),
+        DespawnOnExit(InGame),
         ShapeBuilder::with(&shape_path)
             .stroke((PATH_COLOR, PATH_WIDTH))
             .build(),
         Transform::from_translation(Vec3::Z * 0.0),
     ));
 
+    // A chart reload rebuilds only the path; the playhead carries on
+    if !existing_playhead.is_empty() {
+        return;
+    }
+
+    // Reset smoothed playhead to spline start for this song
+    smoothed.0 = spline.position_at_progress(0.0);
+
     // Playhead visual — double white circle that moves along the track
     let playhead_pos = spline.position_at_progress(0.0);
 
@@ -145,8 +189,8 @@ fn spawn_path_visual(
         center: Vec2::ZERO,
     };
     commands.spawn((
-        PlayheadVisual,
-        DespawnOnExit(GameScreen::Playing),
+        PlayheadVisual { width: 2.0 },
+        DespawnOnExit(InGame),
         ShapeBuilder::with(&circle_inner)
             .stroke((JUDGMENT_COLOR, 2.0))
             .build(),
@@ -158,32 +202,153 @@ fn spawn_path_visual(
         center: Vec2::ZERO,
     };
     commands.spawn((
-        PlayheadVisual,
-        DespawnOnExit(GameScreen::Playing),
+        PlayheadVisual { width: 1.0 },
+        DespawnOnExit(InGame),
         ShapeBuilder::with(&circle_outer)
             .stroke((JUDGMENT_COLOR, 1.0))
             .build(),
         Transform::from_translation(playhead_pos.extend(0.1)),
     ));
+
+    // Hit point marker; update_playhead_visual keeps it on the path end
+    let marker = shapes::Circle {
+        radius: JUDGE_MARKER_RADIUS,
+        center: Vec2::ZERO,
+    };
+    commands.spawn((
+        JudgeMarker,
+        DespawnOnExit(InGame),
+        ShapeBuilder::with(&marker)
+            .stroke((JUDGE_MARKER_COLOR, JUDGE_MARKER_WIDTH))
+            .build(),
+        Transform::from_translation(judge_marker_position(&spline).extend(0.08)),
+    ));
+}
+
+fn judge_marker_position(spline: &SplinePath) -> Vec2 {
+    spline.position_at_progress(1.0)
+}
+
+/// Marker scale at `beat`: full swell on the beat, easing back before the next.
+fn judge_marker_scale(beat: f64) -> f32 {
+    let since_beat = beat.rem_euclid(1.0) as f32;
+    1.0 + JUDGE_MARKER_PULSE * (1.0 - since_beat).powi(3)
+}
+
+// --- Background dim ---
+
+/// Sits just above the path (z 0.0) and below the playhead (z 0.1) and notes.
+const BACKGROUND_DIM_Z: f32 = 0.05;
+/// Large enough to cover the view at any camera pan/zoom/rotation.
+const BACKGROUND_DIM_SIZE: f32 = 100_000.0;
+
+fn spawn_background_dim(mut commands: Commands, settings: Res<GameSettings>) {
+    let alpha = (settings.background_dim / 100.0).clamp(0.0, 1.0);
+    if alpha <= 0.0 {
+        return;
+    }
+    commands.spawn((
+        DespawnOnExit(InGame),
+        Sprite::from_color(Color::BLACK.with_alpha(alpha), Vec2::splat(BACKGROUND_DIM_SIZE)),
+        Transform::from_translation(Vec3::Z * BACKGROUND_DIM_Z),
+    ));
+}
+
+// --- Beat pulse ---
+
+/// Above the background dim so a heavy dim doesn't swallow it, below the playhead.
+const BEAT_PULSE_Z: f32 = 0.06;
+const BEAT_PULSE_COLOR: Color = Color::srgb(0.6, 0.2, 1.0);
+/// Peak opacity on the beat. Kept low: this is ambience, not a flash.
+const BEAT_PULSE_PEAK_ALPHA: f32 = 0.08;
+/// Exponential fade rate after each beat, per second.
+const BEAT_PULSE_DECAY: f32 = 6.0;
+
+/// Full-screen wash that brightens on every whole beat of the song.
+#[derive(Component, Default)]
+struct BeatPulse {
+    last_beat: Option<f64>,
+    intensity: f32,
+}
+
+fn spawn_beat_pulse(mut commands: Commands, settings: Res<GameSettings>) {
+    if !settings.beat_pulse {
+        return;
+    }
+    commands.spawn((
+        DespawnOnExit(InGame),
+        BeatPulse::default(),
+        Sprite::from_color(BEAT_PULSE_COLOR.with_alpha(0.0), Vec2::splat(BACKGROUND_DIM_SIZE)),
+        Transform::from_translation(Vec3::Z * BEAT_PULSE_Z),
+    ));
+}
+
+/// Whether the beat moved past a whole-beat boundary since `prev`.
+fn crossed_whole_beat(prev: f64, current: f64) -> bool {
+    current.floor() > prev.floor()
+}
+
+fn update_beat_pulse(
+    conductor: Option<Res<SongConductor>>,
+    settings: Option<Res<GameSettings>>,
+    time: Res<Time>,
+    mut pulses: Query<(&mut BeatPulse, &mut Sprite)>,
+) {
+    let Some(conductor) = conductor else { return };
+    let beat = conductor.visual_beat(settings.map_or(0, |s| s.visual_offset_ms));
+
+    for (mut pulse, mut sprite) in &mut pulses {
+        if pulse.last_beat.is_some_and(|prev| crossed_whole_beat(prev, beat)) {
+            pulse.intensity = 1.0;
+        } else {
+            pulse.intensity *= (-BEAT_PULSE_DECAY * time.delta_secs()).exp();
+        }
+        pulse.last_beat = Some(beat);
+        sprite.color = BEAT_PULSE_COLOR.with_alpha(BEAT_PULSE_PEAK_ALPHA * pulse.intensity);
+    }
 }
 
 // --- Note visual spawning ---
 
 pub fn spawn_note_visual(commands: &mut Commands, entity: Entity, kind: &NoteKind) {
     match kind {
+        // Ad-Libs are found by ear, not by sight
+        NoteKind::AdLib => return,
         NoteKind::Tap => spawn_tap_visual(commands, entity),
         NoteKind::Slide(dir) => {
             spawn_slide_visual(commands, entity, *dir, SLIDE_COLOR, SLIDE_FILL, 14.0)
         }
         NoteKind::Hold { .. } => spawn_hold_visual(commands, entity),
-        NoteKind::Rest => spawn_rest_visual(commands, entity),
         NoteKind::Critical => spawn_critical_visual(commands, entity),
+        NoteKind::Scratch => spawn_scratch_visual(commands, entity),
+        NoteKind::DualSlide { left, right } => {
+            spawn_dual_slide_visual(commands, entity, *left, *right)
+        }
     }
+    spawn_approach_ring(commands, entity);
+}
+
+fn spawn_approach_ring(commands: &mut Commands, parent: Entity) {
+    let circle = shapes::Circle {
+        radius: NOTE_RADIUS,
+        center: Vec2::ZERO,
+    };
+    let ring = commands
+        .spawn((
+            ApproachRing,
+            ShapeBuilder::with(&circle)
+                .stroke((APPROACH_RING_COLOR.with_alpha(0.0), 1.5))
+                .build(),
+            Transform::from_translation(Vec3::Z * 1.2),
+            Visibility::Hidden,
+        ))
+        .id();
+    commands.entity(parent).add_children(&[ring]);
 }
 
 fn spawn_tap_visual(commands: &mut Commands, parent: Entity) {
     let circle = shapes::Circle {
-        radius: 14.0,
+        radius: NOTE_RADIUS,
         center: Vec2::ZERO,
     };
     let shape = commands
@@ -294,78 +459,107 @@ fn spawn_hold_visual(commands: &mut Commands, parent: Entity) {
         .add_children(&[ribbon, outer, inner]);
 }
 
-fn spawn_rest_visual(commands: &mut Commands, parent: Entity) {
-    let rest_color = Color::srgba(0.9, 0.9, 1.0, 0.3);
-
-    // Circle outline
-    let circle = shapes::Circle {
-        radius: 12.0,
-        center: Vec2::ZERO,
-    };
-    let circle_entity = commands
+fn spawn_critical_visual(commands: &mut Commands, parent: Entity) {
+    // Glow halo — slightly larger star at low alpha
+    let halo = star_polygon(20.0, 10.0, 5);
+    let halo_entity = commands
         .spawn((
-            NoteVisual,
-            ShapeBuilder::with(&circle)
-                .stroke((rest_color, 1.5))
+            CriticalHalo,
+            ShapeBuilder::with(&halo)
+                .stroke((Color::srgba(1.0, 0.95, 0.8, 0.25), 1.5))
                 .build(),
-            Transform::from_translation(Vec3::Z * 0.5),
+            Transform::from_translation(Vec3::Z * 0.9),
         ))
         .id();
 
-    // X diagonal 1: top-left to bottom-right
-    let x_size = 8.0;
-    let line1 = shapes::Line(Vec2::new(-x_size, x_size), Vec2::new(x_size, -x_size));
-    let line1_entity = commands
+    let star = star_polygon(16.0, 8.0, 5);
+    let shape = commands
         .spawn((
             NoteVisual,
-            ShapeBuilder::with(&line1)
-                .stroke((rest_color, 1.5))
+            ShapeBuilder::with(&star)
+                .fill(CRITICAL_FILL)
+                .stroke((CRITICAL_COLOR, 2.0))
                 .build(),
-            Transform::from_translation(Vec3::Z * 0.6),
+            Transform::from_translation(Vec3::Z * 1.0),
         ))
         .id();
 
-    // X diagonal 2: bottom-left to top-right
-    let line2 = shapes::Line(Vec2::new(-x_size, -x_size), Vec2::new(x_size, x_size));
-    let line2_entity = commands
+    commands.entity(parent).add_children(&[halo_entity, shape]);
+}
+
+fn spawn_scratch_visual(commands: &mut Commands, parent: Entity) {
+    let circle = shapes::Circle {
+        radius: NOTE_RADIUS,
+        center: Vec2::ZERO,
+    };
+    let shape = commands
         .spawn((
             NoteVisual,
-            ShapeBuilder::with(&line2)
-                .stroke((rest_color, 1.5))
+            ShapeBuilder::with(&circle)
+                .fill(SCRATCH_FILL)
+                .stroke((SCRATCH_COLOR, 2.0))
                 .build(),
-            Transform::from_translation(Vec3::Z * 0.6),
+            Transform::from_translation(Vec3::Z * 1.0),
         ))
         .id();
 
-    commands.entity(parent).add_children(&[circle_entity, line1_entity, line2_entity]);
-}
+    // Double-headed arrow: back and forth
+    let mut children = vec![shape];
+    for dir in [Vec2::X, Vec2::NEG_X] {
+        let arrow = arrow_path(dir, 20.0);
+        children.push(
+            commands
+                .spawn((
+                    ArrowVisual,
+                    ShapeBuilder::with(&arrow)
+                        .stroke((SCRATCH_COLOR, 2.0))
+                        .build(),
+                    Transform::from_translation(Vec3::Z * 1.1),
+                ))
+                .id(),
+        );
+    }
 
-fn spawn_critical_visual(commands: &mut Commands, parent: Entity) {
-    // Glow halo — slightly larger star at low alpha
-    let halo = star_polygon(20.0, 10.0, 5);
-    let halo_entity = commands
-        .spawn((
-            CriticalHalo,
-            ShapeBuilder::with(&halo)
-                .stroke((Color::srgba(1.0, 0.95, 0.8, 0.25), 1.5))
-                .build(),
-            Transform::from_translation(Vec3::Z * 0.9),
-        ))
-        .id();
+    commands.entity(parent).add_children(&children);
+}
 
-    let star = star_polygon(16.0, 8.0, 5);
+/// A larger diamond carrying both arrows.
+fn spawn_dual_slide_visual(
+    commands: &mut Commands,
+    parent: Entity,
+    left: SlideDirection,
+    right: SlideDirection,
+) {
+    let diamond = diamond_polygon(18.0);
     let shape = commands
         .spawn((
             NoteVisual,
-            ShapeBuilder::with(&star)
-                .fill(CRITICAL_FILL)
-                .stroke((CRITICAL_COLOR, 2.0))
+            ShapeBuilder::with(&diamond)
+                .fill(DUAL_SLIDE_FILL)
+                .stroke((DUAL_SLIDE_COLOR, 2.0))
                 .build(),
             Transform::from_translation(Vec3::Z * 1.0),
         ))
         .id();
 
-    commands.entity(parent).add_children(&[halo_entity, shape]);
+    let mut children = vec![shape];
+    for dir in [left, right] {
+        let arrow = arrow_path(dir.to_vec2(), 12.0);
+        children.push(
+            commands
+                .spawn((
+                    ArrowVisual,
+                    ShapeBuilder::with(&arrow)
+                        .stroke((DUAL_SLIDE_COLOR, 2.0))
+                        .build(),
+                    // Each arrow sits toward its own side of the diamond
+                    Transform::from_translation((dir.to_vec2() * 6.0).extend(1.1)),
+                ))
+                .id(),
+        );
+    }
+
+    commands.entity(parent).add_children(&children);
 }
 
 // --- Feedback visual spawning ---
@@ -455,19 +649,25 @@ pub fn spawn_feedback_visual(commands: &mut Commands, entity: Entity, judgment:
 
 // --- Update systems ---
 
+#[allow(clippy::too_many_arguments)]
 fn update_playhead_visual(
     conductor: Option<Res<SongConductor>>,
+    settings: Option<Res<GameSettings>>,
     playhead: Option<Res<Playhead>>,
     spline: Option<Res<SplinePath>>,
     time: Res<Time>,
     mut smoothed: ResMut<SmoothedPlayhead>,
-    mut playhead_q: Query<&mut Transform, With<PlayheadVisual>>,
+    mut playhead_q: Query<&mut Transform, (With<PlayheadVisual>, Without<JudgeMarker>)>,
+    mut marker_q: Query<&mut Transform, With<JudgeMarker>>,
 ) {
     let Some(conductor) = conductor else { return };
     let Some(playhead) = playhead else { return };
     let Some(spline) = spline else { return };
 
-    let progress = playhead.progress(conductor.current_beat);
+    // Render at the display-lag-compensated beat; judgment still uses current_beat
+    let visual_offset_ms = settings.as_ref().map_or(0, |s| s.visual_offset_ms);
+    let visual_beat = conductor.visual_beat(visual_offset_ms);
+    let progress = playhead.progress_at_beat(visual_beat);
     let target = spline.position_at_progress(progress);
 
     // On first frame (or after reset), snap directly to target
@@ -481,30 +681,33 @@ fn update_playhead_visual(
     for mut t in &mut playhead_q {
         t.translation = smoothed.0.extend(t.translation.z);
     }
+
+    // Follows a reloaded path too, so it's set every frame rather than once
+    let hit_point = judge_marker_position(&spline);
+    // The swell is part of the beat pulse, so it stays still with the pulse off
+    let pulse = settings.is_some_and(|s| s.beat_pulse);
+    let scale = if pulse { judge_marker_scale(visual_beat) } else { 1.0 };
+    for mut t in &mut marker_q {
+        t.translation = hit_point.extend(t.translation.z);
+        t.scale = Vec3::splat(scale);
+    }
 }
 
 fn update_note_visuals(
-    notes: Query<
-        (
-            Entity,
-            &SplineProgress,
-            &NoteType,
-            Option<&NoteDirection>,
-            &Children,
-        ),
-        With<NoteAlive>,
-    >,
+    mut commands: Commands,
+    unplaced: Query<(Entity, &SplineProgress, &Children), (With<NoteAlive>, Without<NotePlaced>)>,
     conductor: Option<Res<SongConductor>>,
+    settings: Option<Res<GameSettings>>,
     spline: Option<Res<SplinePath>>,
-    mut transforms: Query<&mut Transform>,
-    mut shapes: Query<&mut Shape>,
+    mut transforms: Query<&mut Transform, Without<CriticalHalo>>,
     tangent_lines: Query<&TangentLine>,
-    critical_halos: Query<&CriticalHalo>,
+    mut critical_halos: Query<&mut Transform, With<CriticalHalo>>,
 ) {
     let Some(spline) = spline else { return };
     let Some(conductor) = conductor else { return };
+    let beat = conductor.visual_beat(settings.map_or(0, |s| s.visual_offset_ms));
 
-    for (entity, progress, note_type, _note_dir, children) in &notes {
+    for (entity, progress, children) in &unplaced {
         let p = progress.0.min(1.0);
         let pos = spline.position_at_progress(p);
         let tangent = spline.tangent_at_progress(p).normalize_or_zero();
@@ -514,33 +717,59 @@ fn update_note_visuals(
             t.translation = pos.extend(t.translation.z);
         }
 
+        // Tap: rotate tangent line toward path direction
         for child in children.iter() {
-            // Tap: rotate tangent line toward path direction
             if tangent_lines.get(child).is_ok() {
                 if let Ok(mut t) = transforms.get_mut(child) {
                     let angle = tangent.y.atan2(tangent.x);
                     t.rotation = Quat::from_rotation_z(angle);
                 }
             }
-
-            // Critical: slow spin on the glow halo
-            if critical_halos.get(child).is_ok() {
-                if let Ok(mut t) = transforms.get_mut(child) {
-                    let spin = conductor.current_beat as f32 * TAU * 0.15;
-                    t.rotation = Quat::from_rotation_z(spin);
-                }
-            }
         }
+        commands.entity(entity).insert(NotePlaced);
+    }
 
-        // Rest: gentle pulse alpha (0.25–0.35)
-        if matches!(note_type.0, NoteKind::Rest) {
-            let pulse = 0.25 + 0.10 * (conductor.current_beat as f32 * TAU).sin().abs();
-            for child in children.iter() {
-                if let Ok(mut shape) = shapes.get_mut(child) {
-                    if let Some(ref mut stroke) = shape.stroke {
-                        stroke.color = Color::srgba(0.9, 0.9, 1.0, pulse);
-                    }
-                }
+    // Critical: slow spin on the glow halo (no spline sampling needed)
+    let spin = beat as f32 * TAU * 0.15;
+    for mut t in &mut critical_halos {
+        t.rotation = Quat::from_rotation_z(spin);
+    }
+}
+
+/// Approach ring radius for an approach fraction (0 = just spawned, 1 = on the
+/// judgment line). Lands exactly on the note body at 1.
+fn approach_ring_radius(approach: f32) -> f32 {
+    let t = approach.clamp(0.0, 1.0);
+    APPROACH_RING_START_RADIUS + (NOTE_RADIUS - APPROACH_RING_START_RADIUS) * t
+}
+
+fn update_approach_rings(
+    notes: Query<(&NoteTiming, &Children), With<NoteAlive>>,
+    conductor: Option<Res<SongConductor>>,
+    settings: Option<Res<GameSettings>>,
+    queue: Option<Res<NoteQueue>>,
+    mut rings: Query<(&mut Transform, &mut Visibility, &mut Shape), With<ApproachRing>>,
+) {
+    let Some(conductor) = conductor else { return };
+    let Some(queue) = queue else { return };
+    let enabled = settings.as_ref().is_some_and(|s| s.approach_ring);
+    let beat = conductor.visual_beat(settings.map_or(0, |s| s.visual_offset_ms));
+
+    for (timing, children) in &notes {
+        let approach = queue.approach_at(timing.target_beat, beat) as f32;
+
+        for child in children.iter() {
+            let Ok((mut t, mut vis, mut shape)) = rings.get_mut(child) else {
+                continue;
+            };
+            if !enabled || !(0.0..=1.0).contains(&approach) {
+                *vis = Visibility::Hidden;
+                continue;
+            }
+            *vis = Visibility::Inherited;
+            t.scale = Vec3::splat(approach_ring_radius(approach) / NOTE_RADIUS);
+            if let Some(ref mut stroke) = shape.stroke {
+                stroke.color = APPROACH_RING_COLOR.with_alpha(0.3 + 0.6 * approach);
             }
         }
     }
@@ -549,10 +778,10 @@ fn update_note_visuals(
 fn update_hold_visuals(
     holds: Query<
         (
-            &SplineProgress,
             &NoteTiming,
             Option<&HoldEndBeat>,
-            Option<&HoldState>,
+            Option<Ref<HoldState>>,
+            Has<NotePlaced>,
             &Children,
         ),
         With<NoteAlive>,
@@ -566,11 +795,16 @@ fn update_hold_visuals(
     let Some(spline) = spline else { return };
     let Some(playhead) = playhead else { return };
 
-    for (progress, _timing, hold_end, hold_state, children) in &holds {
+    for (timing, hold_end, hold_state, placed, children) in &holds {
         let Some(hold_end) = hold_end else {
             continue;
         };
-        let state = hold_state.copied().unwrap_or(HoldState::Pending);
+        // Geometry is fixed once placed; only the state color ever changes
+        let state_changed = hold_state.as_ref().is_some_and(|s| s.is_changed());
+        if placed && !state_changed {
+            continue;
+        }
+        let state = hold_state.map_or(HoldState::Pending, |s| *s);
 
         let color = match state {
             HoldState::Held => HOLD_HELD_COLOR,
@@ -578,47 +812,19 @@ fn update_hold_visuals(
             _ => HOLD_COLOR,
         };
 
-        // Head is at the note's fixed spline position
-        let head_p = progress.0;
-        // Tail is at the hold end beat's spline position
-        let tail_p = playhead.progress(hold_end.0);
-
-        // Parent entity's world position (used to convert to local coords)
-        let parent_pos = spline.position_at_progress(head_p);
-
         for child in children.iter() {
-            // Update ribbon — stretches from head to tail along spline
             if ribbons.get(child).is_ok() {
-                let p_start = head_p.min(tail_p);
-                let p_end = head_p.max(tail_p);
-                if p_end > p_start {
-                    let segments = 16;
-                    let step = (p_end - p_start) / segments as f32;
-
-                    let mut top_pts = Vec::with_capacity(segments + 1);
-                    let mut bot_pts = Vec::with_capacity(segments + 1);
-                    for i in 0..=segments {
-                        let pa = p_start + step * i as f32;
-                        let a = spline.position_at_progress(pa) - parent_pos;
-                        let tang = spline.tangent_at_progress(pa).normalize_or_zero();
-                        let perp = Vec2::new(-tang.y, tang.x) * 4.0;
-                        top_pts.push(a + perp);
-                        bot_pts.push(a - perp);
+                let Ok(mut shape) = shapes.get_mut(child) else {
+                    continue;
+                };
+                if placed {
+                    if let Some(ref mut stroke) = shape.stroke {
+                        stroke.color = color;
                     }
-
-                    let mut points = top_pts;
-                    bot_pts.reverse();
-                    points.extend(bot_pts);
-
-                    let ribbon_shape = shapes::Polygon {
-                        points,
-                        closed: true,
-                    };
-
-                    if let Ok(mut shape) = shapes.get_mut(child) {
-                        *shape = ShapeBuilder::with(&ribbon_shape)
-                            .stroke((color, 2.0))
-                            .build();
+                } else {
+                    let (head_p, tail_p) = playhead.hold_progress(timing.target_beat, hold_end.0);
+                    if let Some(ribbon) = hold_ribbon_shape(&spline, head_p, tail_p) {
+                        *shape = ShapeBuilder::with(&ribbon).stroke((color, 2.0)).build();
                     }
                 }
             }
@@ -638,6 +844,38 @@ fn update_hold_visuals(
     }
 }
 
+/// Ribbon stretching along the spline from the hold's head (`head_p`) to its
+/// tail (`tail_p`), in coordinates local to the head.
+fn hold_ribbon_shape(spline: &SplinePath, head_p: f32, tail_p: f32) -> Option<shapes::Polygon> {
+    let p_start = head_p.min(tail_p);
+    let p_end = head_p.max(tail_p);
+    if p_end <= p_start {
+        return None;
+    }
+    let parent_pos = spline.position_at_progress(head_p);
+    let segments = 16;
+    let step = (p_end - p_start) / segments as f32;
+
+    let mut top_pts = Vec::with_capacity(segments + 1);
+    let mut bot_pts = Vec::with_capacity(segments + 1);
+    for i in 0..=segments {
+        let pa = p_start + step * i as f32;
+        let a = spline.position_at_progress(pa) - parent_pos;
+        let tang = spline.tangent_at_progress(pa).normalize_or_zero();
+        let perp = Vec2::new(-tang.y, tang.x) * 4.0;
+        top_pts.push(a + perp);
+        bot_pts.push(a - perp);
+    }
+
+    let mut points = top_pts;
+    bot_pts.reverse();
+    points.extend(bot_pts);
+    Some(shapes::Polygon {
+        points,
+        closed: true,
+    })
+}
+
 fn update_feedback_visuals(
     feedbacks: Query<(&JudgmentFeedback, &Children)>,
     mut transforms: Query<&mut Transform>,
@@ -751,24 +989,44 @@ fn update_feedback_visuals(
     }
 }
 
+const FEVER_COLOR: Color = Color::srgb(1.0, 0.85, 0.15);
+const TRANCE_COLOR: Color = Color::WHITE;
+/// Stroke width relative to Normal; Trance's heavy stroke reads as a glow.
+const FEVER_WIDTH_SCALE: f32 = 5.0 / 3.0;
+const TRANCE_WIDTH_SCALE: f32 = 7.0 / 3.0;
+
+/// Stroke for a chain tier, given the element's Normal-tier stroke.
+fn chain_tier_stroke(tier: ChainTier, color: Color, width: f32) -> (Color, f32) {
+    match tier {
+        ChainTier::Normal => (color, width),
+        ChainTier::Fever => (FEVER_COLOR, width * FEVER_WIDTH_SCALE),
+        ChainTier::Trance => (TRANCE_COLOR, width * TRANCE_WIDTH_SCALE),
+    }
+}
+
 fn update_chain_visuals(
     score: Option<Res<ScoreState>>,
-    mut path_q: Query<&mut Shape, With<PathVisual>>,
+    mut path_q: Query<&mut Shape, (With<PathVisual>, Without<PlayheadVisual>)>,
+    mut playhead_q: Query<(&mut Shape, &PlayheadVisual)>,
 ) {
     let Some(score) = score else { return };
+    let tier = score.chain_tier();
 
-    let (color, width) = match score.chain_tier() {
-        ChainTier::Normal => (PATH_COLOR, PATH_WIDTH),
-        ChainTier::Fever => (Color::srgb(1.0, 0.85, 0.15), 5.0),
-        ChainTier::Trance => (Color::WHITE, 7.0),
-    };
-
+    let (color, width) = chain_tier_stroke(tier, PATH_COLOR, PATH_WIDTH);
     for mut shape in &mut path_q {
         if let Some(ref mut stroke) = shape.stroke {
             stroke.color = color;
             stroke.options.line_width = width;
         }
     }
+
+    for (mut shape, ring) in &mut playhead_q {
+        let (color, width) = chain_tier_stroke(tier, JUDGMENT_COLOR, ring.width);
+        if let Some(ref mut stroke) = shape.stroke {
+            stroke.color = color;
+            stroke.options.line_width = width;
+        }
+    }
 }
 
 // --- Shape builder helpers ---
@@ -820,3 +1078,121 @@ fn star_polygon(outer_r: f32, inner_r: f32, num_points: usize) -> shapes::Polygo
         closed: true,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approach_ring_closes_onto_note() {
+        assert_eq!(approach_ring_radius(0.0), APPROACH_RING_START_RADIUS);
+        assert_eq!(approach_ring_radius(1.0), NOTE_RADIUS);
+        let mid = approach_ring_radius(0.5);
+        assert!(mid > NOTE_RADIUS && mid < APPROACH_RING_START_RADIUS);
+        // Clamped outside the approach window
+        assert_eq!(approach_ring_radius(1.5), NOTE_RADIUS);
+    }
+
+    #[test]
+    fn chain_tier_strokes_scale_from_normal() {
+        // Normal keeps each element's own look: cyan path, white playhead
+        assert_eq!(chain_tier_stroke(ChainTier::Normal, PATH_COLOR, PATH_WIDTH), (PATH_COLOR, PATH_WIDTH));
+        assert_eq!(chain_tier_stroke(ChainTier::Normal, JUDGMENT_COLOR, 2.0), (JUDGMENT_COLOR, 2.0));
+
+        // The path keeps its old 5 px Fever and 7 px Trance strokes
+        let (color, width) = chain_tier_stroke(ChainTier::Fever, PATH_COLOR, PATH_WIDTH);
+        assert_eq!(color, FEVER_COLOR);
+        assert!((width - 5.0).abs() < 1e-5);
+        let (color, width) = chain_tier_stroke(ChainTier::Trance, PATH_COLOR, PATH_WIDTH);
+        assert_eq!(color, TRANCE_COLOR);
+        assert!((width - 7.0).abs() < 1e-5);
+
+        // The playhead takes the same colours, widened in proportion
+        let (color, width) = chain_tier_stroke(ChainTier::Fever, JUDGMENT_COLOR, 2.0);
+        assert_eq!(color, FEVER_COLOR);
+        assert!(width > 2.0);
+        let (_, trance_width) = chain_tier_stroke(ChainTier::Trance, JUDGMENT_COLOR, 2.0);
+        assert!(trance_width > width);
+    }
+
+    #[test]
+    fn beat_pulse_fires_once_per_whole_beat() {
+        // Two seconds at 60 fps and 120 BPM, starting just before the downbeat
+        let stream: Vec<f64> = (0..120).map(|i| -0.1 + i as f64 * 2.0 / 60.0).collect();
+        let crossings: Vec<f64> = stream
+            .windows(2)
+            .filter(|w| crossed_whole_beat(w[0], w[1]))
+            .map(|w| w[1].floor())
+            .collect();
+        assert_eq!(crossings, vec![0.0, 1.0, 2.0, 3.0]);
+
+        // A frozen beat (paused, or the conductor holding still) never re-fires
+        assert!(!crossed_whole_beat(2.0, 2.0));
+        assert!(!crossed_whole_beat(2.5, 2.9));
+        assert!(crossed_whole_beat(2.9, 3.0));
+    }
+
+    #[test]
+    fn judge_marker_sits_on_the_path_end() {
+        let spline = SplinePath::from_catmull_rom_points(vec![
+            Vec2::ZERO,
+            Vec2::new(100.0, 0.0),
+            Vec2::new(200.0, 100.0),
+            Vec2::new(300.0, 100.0),
+        ]);
+        let hit_point = spline.position_at_progress(1.0);
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .init_resource::<SmoothedPlayhead>()
+            .insert_resource(SongConductor::new(120.0))
+            .insert_resource(Playhead { song_start_beat: 0.0, song_end_beat: 16.0 })
+            .insert_resource(spline)
+            .add_systems(Update, update_playhead_visual);
+        let marker = app.world_mut().spawn((JudgeMarker, Transform::default())).id();
+        app.update();
+
+        let t = app.world().get::<Transform>(marker).unwrap();
+        assert_eq!(t.translation.truncate(), hit_point);
+
+        // Swells on the beat and settles back before the next one
+        assert!((judge_marker_scale(4.0) - (1.0 + JUDGE_MARKER_PULSE)).abs() < 1e-6);
+        assert!(judge_marker_scale(4.5) < judge_marker_scale(4.0));
+        assert!(judge_marker_scale(4.99) - 1.0 < 1e-3);
+    }
+
+    #[test]
+    fn stationary_notes_are_sampled_once() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(SongConductor::new(120.0))
+            .insert_resource(SplinePath::from_catmull_rom_points(vec![
+                Vec2::ZERO,
+                Vec2::new(100.0, 0.0),
+                Vec2::new(200.0, 100.0),
+                Vec2::new(300.0, 100.0),
+            ]))
+            .add_systems(Update, update_note_visuals);
+
+        for i in 0..1000 {
+            app.world_mut()
+                .spawn((NoteAlive, SplineProgress(i as f32 / 1000.0), Transform::default()))
+                .with_children(|note| {
+                    note.spawn((TangentLine, Transform::default()));
+                });
+        }
+        let mut needs_sampling = app
+            .world_mut()
+            .query_filtered::<(), (With<NoteAlive>, Without<NotePlaced>)>();
+        assert_eq!(needs_sampling.iter(app.world()).count(), 1000);
+
+        // First frame places every note; later frames sample none of them
+        app.update();
+        assert_eq!(needs_sampling.iter(app.world()).count(), 0);
+        app.update();
+        assert_eq!(needs_sampling.iter(app.world()).count(), 0);
+
+        let mut placed = app.world_mut().query_filtered::<&Transform, With<NotePlaced>>();
+        assert!(placed.iter(app.world()).any(|t| t.translation.x > 0.0));
+    }
+}
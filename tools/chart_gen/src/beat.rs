@@ -1,6 +1,9 @@
 use crate::onset::OnsetEvent;
 use crate::stft::Spectrogram;
 
+/// Beats per bar assumed for downbeat detection (charts are written in 4/4).
+pub const BEATS_PER_MEASURE: usize = 4;
+
 /// Result of beat tracking.
 #[derive(Debug, Clone)]
 pub struct BeatGrid {
@@ -8,6 +11,10 @@ pub struct BeatGrid {
     pub beats: Vec<f64>,
     /// Estimated BPM.
     pub bpm: f64,
+    /// Beat number of the first bar's beat one; bars start every
+    /// `BEATS_PER_MEASURE` beats from here. Beat numbers themselves always
+    /// count from the first detected beat.
+    pub downbeat_offset: usize,
 }
 
 impl BeatGrid {
@@ -40,6 +47,13 @@ impl BeatGrid {
         (self.beats.len() - 1) as f64 + beats_past
     }
 
+    /// Move every beat later by `seconds` (e.g. to undo a silence trim).
+    pub fn shift(&mut self, seconds: f64) {
+        for beat in &mut self.beats {
+            *beat += seconds;
+        }
+    }
+
     /// Total duration covered by the beat grid in seconds.
     pub fn duration_seconds(&self) -> f64 {
         if self.beats.is_empty() {
@@ -60,10 +74,12 @@ impl BeatGrid {
 /// Track beats from onset events and spectrogram.
 ///
 /// If `bpm_override` is Some, skip tempo detection and use the given BPM.
+/// If `downbeat` is set, `downbeat_offset` marks which beat starts each bar.
 pub fn track_beats(
     spectrogram: &Spectrogram,
     onsets: &[OnsetEvent],
     bpm_override: Option<f64>,
+    downbeat: bool,
 ) -> BeatGrid {
     let duration = spectrogram.frames.len() as f64 * spectrogram.hop_size as f64
         / spectrogram.sample_rate as f64;
@@ -80,7 +96,40 @@ pub fn track_beats(
     // Step 3: Find optimal beat positions via dynamic programming
     let beats = find_beats(&onset_envelope, bpm, spectrogram.sample_rate, spectrogram.hop_size, duration);
 
-    BeatGrid { beats, bpm }
+    // Step 4: Find which beat starts a bar, leaving the beat numbering alone
+    let downbeat_offset = if downbeat {
+        let frame_rate = spectrogram.sample_rate as f64 / spectrogram.hop_size as f64;
+        downbeat_phase(&onset_envelope, &beats, frame_rate, BEATS_PER_MEASURE)
+    } else {
+        0
+    };
+
+    BeatGrid { beats, bpm, downbeat_offset }
+}
+
+/// How far a beat may sit from an onset and still count as landing on it.
+const CONFIDENCE_WINDOW_SECONDS: f64 = 0.07;
+
+/// Fraction of grid beats within `CONFIDENCE_WINDOW_SECONDS` of an onset:
+/// near 1 when the grid locks onto the music, low when the tempo guess is off.
+///
+/// `onsets` must be on the same clock as the grid.
+pub fn bpm_confidence(beat_grid: &BeatGrid, onsets: &[OnsetEvent]) -> f64 {
+    if beat_grid.beats.is_empty() {
+        return 0.0;
+    }
+    let mut times: Vec<f64> = onsets.iter().map(|o| o.time_seconds).collect();
+    times.sort_by(f64::total_cmp);
+    let hits = beat_grid
+        .beats
+        .iter()
+        .filter(|&&beat| {
+            let i = times.partition_point(|&t| t < beat);
+            let near = |j: usize| times.get(j).is_some_and(|t| (t - beat).abs() <= CONFIDENCE_WINDOW_SECONDS);
+            near(i) || (i > 0 && near(i - 1))
+        })
+        .count();
+    hits as f64 / beat_grid.beats.len() as f64
 }
 
 /// Build an onset strength envelope: one value per spectrogram frame.
@@ -235,3 +284,92 @@ fn find_beats(
 
     beats
 }
+
+/// Estimate which beat index (mod `beats_per_measure`) is the downbeat.
+///
+/// Averages onset strength at every beat sharing a bar position; the kick on
+/// beat one makes that position the strongest over the whole song.
+fn downbeat_phase(envelope: &[f32], beats: &[f64], frame_rate: f64, beats_per_measure: usize) -> usize {
+    let mut sums = vec![0.0f64; beats_per_measure];
+    let mut counts = vec![0u32; beats_per_measure];
+
+    for (i, &time) in beats.iter().enumerate() {
+        let frame = (time * frame_rate).round();
+        if frame < 0.0 || frame as usize >= envelope.len() {
+            continue;
+        }
+        sums[i % beats_per_measure] += envelope[frame as usize] as f64;
+        counts[i % beats_per_measure] += 1;
+    }
+
+    let mut best_phase = 0;
+    let mut best_score = f64::NEG_INFINITY;
+    for phase in 0..beats_per_measure {
+        if counts[phase] == 0 {
+            continue;
+        }
+        let score = sums[phase] / counts[phase] as f64;
+        // Strictly greater so ties keep the earliest phase (no shift)
+        if score > best_score {
+            best_score = score;
+            best_phase = phase;
+        }
+    }
+    best_phase
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 44_100;
+    const HOP: usize = 512;
+
+    /// 4/4 at 120 BPM with a two-beat pickup: quiet hits every beat and a
+    /// strong kick on every bar's beat one, first kick at 1.5s.
+    fn kick_pattern() -> (Spectrogram, Vec<OnsetEvent>) {
+        let frame_rate = SAMPLE_RATE as f64 / HOP as f64;
+        let duration = 16.0;
+        let spectrogram = Spectrogram {
+            frames: vec![Vec::new(); (duration * frame_rate) as usize],
+            hop_size: HOP,
+            sample_rate: SAMPLE_RATE,
+        };
+        let onsets = (0..30)
+            .map(|k| {
+                let time_seconds = 0.5 + k as f64 * 0.5;
+                OnsetEvent {
+                    frame: (time_seconds * frame_rate).round() as usize,
+                    strength: if k % 4 == 2 { 1.0 } else { 0.3 },
+                    time_seconds,
+                    band: crate::onset::OnsetBand::Full,
+                }
+            })
+            .collect();
+        (spectrogram, onsets)
+    }
+
+    #[test]
+    fn downbeat_marks_the_kick_as_bar_one() {
+        let (spectrogram, onsets) = kick_pattern();
+
+        // The grid starts on the first (weak) onset; the kick is two beats in
+        let grid = track_beats(&spectrogram, &onsets, Some(120.0), true);
+        assert_eq!(grid.downbeat_offset, 2);
+        let raw = track_beats(&spectrogram, &onsets, Some(120.0), false);
+        assert_eq!(raw.downbeat_offset, 0);
+    }
+
+    #[test]
+    fn downbeat_leaves_onset_beats_where_they_are() {
+        let (spectrogram, onsets) = kick_pattern();
+        let grid = track_beats(&spectrogram, &onsets, Some(120.0), true);
+        let raw = track_beats(&spectrogram, &onsets, Some(120.0), false);
+        assert_eq!(grid.beats, raw.beats);
+        for onset in &onsets {
+            let beat = grid.time_to_beat(onset.time_seconds);
+            assert_eq!(beat, raw.time_to_beat(onset.time_seconds));
+        }
+        assert!((grid.time_to_beat(1.5) - 2.0).abs() < 0.1);
+    }
+}
@@ -0,0 +1,232 @@
+use crate::beat::BeatGrid;
+use crate::chart::{ChartEvent, EventType};
+use crate::decode::StereoSamples;
+use crate::stft::Spectrogram;
+
+// Mirrors the engine's clamps in the game's camera.rs
+const ZOOM_MIN: f32 = 0.5;
+const ZOOM_MAX: f32 = 2.0;
+const ROTATION_LIMIT_DEGREES: f32 = 30.0;
+
+/// A beat counts as a spike when its energy beats the preceding average by this factor.
+const SPIKE_RATIO: f64 = 1.8;
+/// Beats of history used as the spike baseline.
+const SPIKE_BASELINE_BEATS: usize = 8;
+/// Minimum beats between two zoom punches.
+const SPIKE_COOLDOWN_BEATS: usize = 8;
+
+/// Phrase length in beats (two bars of 4/4).
+const PHRASE_BEATS: usize = 8;
+/// Log-energy change between neighbouring phrases that marks a section boundary.
+const PHRASE_CHANGE: f64 = 0.3;
+/// Horizontal pan at a section boundary (world units).
+const PAN_OFFSET: f32 = 40.0;
+/// Tilt at a section boundary before scaling by the energy change.
+const ROTATE_DEGREES: f32 = 6.0;
+/// Stereo balance a phrase needs before the pan follows the mix instead of alternating.
+const STEREO_LEAN: f32 = 0.1;
+
+/// Build camera events from the energy envelope: zoom punches on energy
+/// spikes and gentle pan/tilt moves at phrase boundaries where the level changes.
+///
+/// `lead_offset_seconds` maps spectrogram time onto the beat grid's time.
+/// With `stereo` channels, pans head toward the side a louder section leans to.
+pub fn generate_camera_events(
+    spectrogram: &Spectrogram,
+    beat_grid: &BeatGrid,
+    lead_offset_seconds: f64,
+    stereo: Option<&StereoSamples>,
+) -> Vec<ChartEvent> {
+    let energy = beat_energies(spectrogram, beat_grid, lead_offset_seconds);
+    let balances = stereo.map(|st| {
+        beat_balances(st, spectrogram.sample_rate, beat_grid, lead_offset_seconds)
+    });
+    let mut events = spike_zooms(&energy);
+    events.extend(phrase_moves(&energy, balances.as_deref()));
+    events.sort_by(|a, b| a.beat.partial_cmp(&b.beat).unwrap());
+    events
+}
+
+/// Mean frame energy within each whole beat.
+fn beat_energies(spectrogram: &Spectrogram, beat_grid: &BeatGrid, lead_offset_seconds: f64) -> Vec<f64> {
+    let num_beats = beat_grid.total_beats().ceil() as usize + 1;
+    let mut sums = vec![0.0f64; num_beats];
+    let mut counts = vec![0u32; num_beats];
+
+    for frame in 0..spectrogram.frames.len() {
+        let beat = beat_grid.time_to_beat(spectrogram.frame_to_seconds(frame) + lead_offset_seconds);
+        if beat < 0.0 || beat as usize >= num_beats {
+            continue;
+        }
+        sums[beat as usize] += spectrogram.frame_energy(frame) as f64;
+        counts[beat as usize] += 1;
+    }
+
+    sums.iter()
+        .zip(&counts)
+        .map(|(&s, &c)| if c > 0 { s / c as f64 } else { 0.0 })
+        .collect()
+}
+
+/// Stereo balance within each whole beat (-1 left .. 1 right).
+fn beat_balances(
+    stereo: &StereoSamples,
+    sample_rate: u32,
+    beat_grid: &BeatGrid,
+    lead_offset_seconds: f64,
+) -> Vec<f32> {
+    let to_sample = |time: f64| ((time - lead_offset_seconds).max(0.0) * sample_rate as f64) as usize;
+    beat_grid
+        .beats
+        .windows(2)
+        .map(|w| stereo.balance(to_sample(w[0]), to_sample(w[1])))
+        .collect()
+}
+
+/// Quick zoom-in on each spike, easing back out over the next bar.
+fn spike_zooms(energy: &[f64]) -> Vec<ChartEvent> {
+    let mut events = Vec::new();
+    let mut last_spike: Option<usize> = None;
+
+    for beat in SPIKE_BASELINE_BEATS..energy.len() {
+        if last_spike.is_some_and(|last| beat - last < SPIKE_COOLDOWN_BEATS) {
+            continue;
+        }
+        let history = &energy[beat - SPIKE_BASELINE_BEATS..beat];
+        let baseline = history.iter().sum::<f64>() / history.len() as f64;
+        if baseline <= 0.0 || energy[beat] < baseline * SPIKE_RATIO {
+            continue;
+        }
+
+        let ratio = (energy[beat] / baseline) as f32;
+        let scale = (1.0 + 0.1 * ratio).clamp(1.15, 1.4).clamp(ZOOM_MIN, ZOOM_MAX);
+        events.push(ChartEvent {
+            beat: beat as f64,
+            event: EventType::CameraZoom { scale, duration_beats: 0.25 },
+        });
+        events.push(ChartEvent {
+            beat: beat as f64 + 1.0,
+            event: EventType::CameraZoom { scale: 1.0, duration_beats: 3.0 },
+        });
+        last_spike = Some(beat);
+    }
+    events
+}
+
+/// Pan and tilt into sections that get louder, settle back when they quieten.
+/// Pans alternate sides unless `balances` shows the new section leaning one way.
+fn phrase_moves(energy: &[f64], balances: Option<&[f32]>) -> Vec<ChartEvent> {
+    let mut events = Vec::new();
+    let mut side = 1.0f32;
+
+    let mut boundary = PHRASE_BEATS;
+    while boundary + PHRASE_BEATS <= energy.len() {
+        let before = mean(&energy[boundary - PHRASE_BEATS..boundary]);
+        let after = mean(&energy[boundary..boundary + PHRASE_BEATS]);
+        if before > 0.0 && after > 0.0 {
+            let change = (after / before).ln();
+            if change > PHRASE_CHANGE {
+                let lean = balances
+                    .and_then(|b| b.get(boundary..(boundary + PHRASE_BEATS).min(b.len())))
+                    .filter(|phrase| !phrase.is_empty())
+                    .map(|phrase| phrase.iter().sum::<f32>() / phrase.len() as f32);
+                if let Some(lean) = lean.filter(|l| l.abs() >= STEREO_LEAN) {
+                    side = lean.signum();
+                }
+                let angle = (ROTATE_DEGREES * change as f32 / PHRASE_CHANGE as f32)
+                    .min(ROTATE_DEGREES * 2.0)
+                    .clamp(-ROTATION_LIMIT_DEGREES, ROTATION_LIMIT_DEGREES);
+                events.push(ChartEvent {
+                    beat: boundary as f64,
+                    event: EventType::CameraPan { offset: (PAN_OFFSET * side, 0.0), duration_beats: 4.0 },
+                });
+                events.push(ChartEvent {
+                    beat: boundary as f64,
+                    event: EventType::CameraRotate { angle_degrees: angle * side, duration_beats: 4.0 },
+                });
+                side = -side;
+            } else if change < -PHRASE_CHANGE {
+                events.push(ChartEvent {
+                    beat: boundary as f64,
+                    event: EventType::CameraPan { offset: (0.0, 0.0), duration_beats: 4.0 },
+                });
+                events.push(ChartEvent {
+                    beat: boundary as f64,
+                    event: EventType::CameraRotate { angle_degrees: 0.0, duration_beats: 4.0 },
+                });
+            }
+        }
+        boundary += PHRASE_BEATS;
+    }
+    events
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 44_100;
+    const HOP: usize = 512;
+
+    #[test]
+    fn dominant_spike_gets_a_zoom() {
+        // 120 BPM, 40 beats of steady low energy with a single loud beat at 20
+        let frame_rate = SAMPLE_RATE as f64 / HOP as f64;
+        let beat_grid = BeatGrid {
+            beats: (0..40).map(|b| b as f64 * 0.5).collect(),
+            bpm: 120.0,
+            downbeat_offset: 0,
+        };
+        let frames = (0..(20.0 * frame_rate) as usize)
+            .map(|f| {
+                let time = f as f64 / frame_rate;
+                let level = if (10.0..10.5).contains(&time) { 1.0 } else { 0.1 };
+                vec![level; 64]
+            })
+            .collect();
+        let spectrogram = Spectrogram { frames, hop_size: HOP, sample_rate: SAMPLE_RATE };
+
+        let events = generate_camera_events(&spectrogram, &beat_grid, 0.0, None);
+        let zooms: Vec<(f64, f32)> = events
+            .iter()
+            .filter_map(|e| match e.event {
+                EventType::CameraZoom { scale, .. } if scale > 1.0 => Some((e.beat, scale)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(zooms.len(), 1);
+        assert!((zooms[0].0 - 20.0).abs() <= 1.0, "zoom at beat {}", zooms[0].0);
+        assert!((ZOOM_MIN..=ZOOM_MAX).contains(&zooms[0].1));
+    }
+
+    #[test]
+    fn pans_follow_the_stereo_lean() {
+        // Three phrases, each louder than the last
+        let energy: Vec<f64> = (0..PHRASE_BEATS * 3)
+            .map(|b| [1.0, 2.0, 4.0][b / PHRASE_BEATS])
+            .collect();
+        let pans = |balances: Option<&[f32]>| -> Vec<f32> {
+            phrase_moves(&energy, balances)
+                .iter()
+                .filter_map(|e| match e.event {
+                    EventType::CameraPan { offset, .. } => Some(offset.0),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        // Without stereo the sides alternate
+        assert_eq!(pans(None), vec![PAN_OFFSET, -PAN_OFFSET]);
+        // A mix leaning left keeps both pans on the left
+        let left = vec![-0.5; energy.len()];
+        assert_eq!(pans(Some(&left)), vec![-PAN_OFFSET, -PAN_OFFSET]);
+        // A centred mix falls back to alternating
+        let centre = vec![0.02; energy.len()];
+        assert_eq!(pans(Some(&centre)), vec![PAN_OFFSET, -PAN_OFFSET]);
+    }
+}
@@ -32,6 +32,14 @@ pub enum Difficulty {
 }
 
 impl Difficulty {
+    /// Easiest first.
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
     pub fn filename(&self) -> &'static str {
         match self {
             Difficulty::Easy => "easy.ron",
@@ -115,6 +123,27 @@ pub enum PathSegment {
         start_beat: f64,
         end_beat: f64,
     },
+    // The generator only writes CatmullRom; the other shapes come from the
+    // editor and are mirrored so `verify` can read any chart.
+    Bezier {
+        control_points: Vec<(f32, f32)>,
+        start_beat: f64,
+        end_beat: f64,
+    },
+    Arc {
+        center: (f32, f32),
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        start_beat: f64,
+        end_beat: f64,
+    },
+    Linear {
+        start: (f32, f32),
+        end: (f32, f32),
+        start_beat: f64,
+        end_beat: f64,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -134,6 +163,8 @@ pub enum ChartNoteType {
     Critical,
     CriticalHold { duration_beats: f64 },
     DualSlide { left: SlideDirection, right: SlideDirection },
+    /// Hidden bonus note: hit like a Tap, never drawn, never missed.
+    AdLib,
     Rest,
 }
 
@@ -179,6 +210,20 @@ pub enum EventType {
     SpeedChange { multiplier: f32, duration_beats: f64 },
 }
 
+/// Detected onsets written to `onsets.ron` for the editor's ghost overlay.
+/// Not a game file; only the editor reads it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnsetDump {
+    pub onsets: Vec<DetectedOnset>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DetectedOnset {
+    pub beat: f64,
+    /// Normalized onset strength (0-1).
+    pub strength: f32,
+}
+
 /// Serialize a ChartFile to pretty-printed RON.
 pub fn serialize_chart(chart: &ChartFile) -> Result<String, String> {
     let config = ron::ser::PrettyConfig::new()
@@ -199,6 +244,15 @@ pub fn serialize_metadata(meta: &SongMetadata) -> Result<String, String> {
         .map_err(|e| format!("RON serialization error: {e}"))
 }
 
+/// Serialize an OnsetDump to RON, one onset per line.
+pub fn serialize_onsets(dump: &OnsetDump) -> Result<String, String> {
+    let config = ron::ser::PrettyConfig::new()
+        .depth_limit(2)
+        .enumerate_arrays(false);
+    ron::ser::to_string_pretty(dump, config)
+        .map_err(|e| format!("RON serialization error: {e}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -10,12 +10,50 @@ use symphonia::core::probe::Hint;
 pub struct AudioData {
     pub samples: Vec<f32>,
     pub sample_rate: u32,
+    /// Seconds trimmed from the start; add to analysis times to get file times.
+    pub lead_offset_seconds: f64,
+    /// Separate channels, kept only when requested and the source is stereo.
+    pub stereo: Option<StereoSamples>,
+}
+
+/// Left/right channels, sample-aligned with `AudioData::samples`.
+pub struct StereoSamples {
+    pub left: Vec<f32>,
+    pub right: Vec<f32>,
+}
+
+impl StereoSamples {
+    /// Balance over `[start, end)` samples: -1 = hard left, 0 = centre, 1 = hard right.
+    pub fn balance(&self, start: usize, end: usize) -> f32 {
+        let end = end.min(self.left.len());
+        if start >= end {
+            return 0.0;
+        }
+        let rms = |ch: &[f32]| (ch.iter().map(|s| s * s).sum::<f32>() / ch.len() as f32).sqrt();
+        let left = rms(&self.left[start..end]);
+        let right = rms(&self.right[start..end]);
+        if left + right <= f32::EPSILON {
+            return 0.0;
+        }
+        (right - left) / (right + left)
+    }
 }
 
 const TARGET_SAMPLE_RATE: u32 = 44100;
 
+/// Amplitude below which samples count as silence (-60 dB).
+pub const SILENCE_THRESHOLD: f32 = 0.001;
+
+/// Level the analysis copy is scaled to (-1 dBFS).
+pub const NORMALIZE_TARGET: f32 = 0.89;
+/// Fraction of samples allowed above the target; stray clicks then clip
+/// instead of holding the rest of the track down.
+const NORMALIZE_PERCENTILE: f64 = 0.999;
+
 /// Decode an audio file to mono f32 PCM at 44100 Hz.
-pub fn decode_audio(path: &Path) -> Result<AudioData, String> {
+///
+/// With `keep_stereo`, stereo sources also keep their first two channels.
+pub fn decode_audio(path: &Path, keep_stereo: bool) -> Result<AudioData, String> {
     let file = std::fs::File::open(path)
         .map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
 
@@ -46,6 +84,10 @@ pub fn decode_audio(path: &Path) -> Result<AudioData, String> {
         .map_err(|e| format!("Failed to create decoder: {e}"))?;
 
     let mut all_samples: Vec<f32> = Vec::new();
+    let mut stereo = (keep_stereo && channels >= 2).then(|| StereoSamples {
+        left: Vec::new(),
+        right: Vec::new(),
+    });
 
     loop {
         let packet = match format.next_packet() {
@@ -68,66 +110,108 @@ pub fn decode_audio(path: &Path) -> Result<AudioData, String> {
             Err(e) => return Err(format!("Decode error: {e}")),
         };
 
-        append_mono_samples(&decoded, channels, &mut all_samples);
+        append_samples(&decoded, channels, &mut all_samples, stereo.as_mut());
     }
 
     // Resample to 44100 Hz if needed
     if source_sample_rate != TARGET_SAMPLE_RATE {
         all_samples = resample(&all_samples, source_sample_rate, TARGET_SAMPLE_RATE);
+        if let Some(ref mut st) = stereo {
+            st.left = resample(&st.left, source_sample_rate, TARGET_SAMPLE_RATE);
+            st.right = resample(&st.right, source_sample_rate, TARGET_SAMPLE_RATE);
+        }
     }
 
     Ok(AudioData {
         samples: all_samples,
         sample_rate: TARGET_SAMPLE_RATE,
+        lead_offset_seconds: 0.0,
+        stereo,
     })
 }
 
-/// Extract samples from a decoded audio buffer, mixing to mono.
-fn append_mono_samples(buf: &AudioBufferRef, channels: usize, out: &mut Vec<f32>) {
-    match buf {
-        AudioBufferRef::F32(b) => {
-            let frames = b.frames();
-            for frame in 0..frames {
-                let mut sum = 0.0f32;
-                for ch in 0..channels {
-                    sum += b.chan(ch)[frame];
-                }
-                out.push(sum / channels as f32);
-            }
+/// Scale samples so the high-percentile peak reaches `target`, clamping the
+/// few samples above it. Returns the applied gain (1.0 for silent audio).
+///
+/// Only the in-memory analysis copy changes; the song file is untouched.
+pub fn normalize(audio: &mut AudioData, target: f32) -> f32 {
+    let mut magnitudes: Vec<f32> = audio.samples.iter().map(|s| s.abs()).collect();
+    if magnitudes.is_empty() {
+        return 1.0;
+    }
+    let idx = ((magnitudes.len() - 1) as f64 * NORMALIZE_PERCENTILE) as usize;
+    let (_, peak, _) = magnitudes.select_nth_unstable_by(idx, |a, b| a.total_cmp(b));
+    let peak = *peak;
+    if peak <= f32::EPSILON {
+        return 1.0;
+    }
+
+    let gain = target / peak;
+    for s in &mut audio.samples {
+        *s = (*s * gain).clamp(-1.0, 1.0);
+    }
+    if let Some(ref mut st) = audio.stereo {
+        for s in st.left.iter_mut().chain(st.right.iter_mut()) {
+            *s = (*s * gain).clamp(-1.0, 1.0);
         }
-        AudioBufferRef::S16(b) => {
-            let frames = b.frames();
-            for frame in 0..frames {
-                let mut sum = 0.0f32;
-                for ch in 0..channels {
-                    sum += b.chan(ch)[frame] as f32 / 32768.0;
-                }
-                out.push(sum / channels as f32);
-            }
+    }
+    gain
+}
+
+/// Drop leading and trailing samples quieter than `threshold`.
+///
+/// Records the lead in `lead_offset_seconds` and returns the
+/// (leading, trailing) sample counts removed. Fully silent audio is left as is.
+pub fn trim_silence(audio: &mut AudioData, threshold: f32) -> (usize, usize) {
+    let Some(first) = audio.samples.iter().position(|s| s.abs() >= threshold) else {
+        return (0, 0);
+    };
+    let last = audio.samples.iter().rposition(|s| s.abs() >= threshold).unwrap();
+
+    let trailing = audio.samples.len() - last - 1;
+    audio.samples.truncate(last + 1);
+    audio.samples.drain(..first);
+    if let Some(ref mut st) = audio.stereo {
+        for ch in [&mut st.left, &mut st.right] {
+            ch.truncate(last + 1);
+            ch.drain(..first);
         }
+    }
+    audio.lead_offset_seconds += first as f64 / audio.sample_rate as f64;
+
+    (first, trailing)
+}
+
+/// Extract samples from a decoded audio buffer, mixing to mono and
+/// optionally keeping the first two channels.
+fn append_samples(
+    buf: &AudioBufferRef,
+    channels: usize,
+    out: &mut Vec<f32>,
+    mut stereo: Option<&mut StereoSamples>,
+) {
+    let (frames, sample): (usize, Box<dyn Fn(usize, usize) -> f32 + '_>) = match buf {
+        AudioBufferRef::F32(b) => (b.frames(), Box::new(|ch, f| b.chan(ch)[f])),
+        AudioBufferRef::S16(b) => (b.frames(), Box::new(|ch, f| b.chan(ch)[f] as f32 / 32768.0)),
         AudioBufferRef::S32(b) => {
-            let frames = b.frames();
-            for frame in 0..frames {
-                let mut sum = 0.0f32;
-                for ch in 0..channels {
-                    sum += b.chan(ch)[frame] as f32 / 2_147_483_648.0;
-                }
-                out.push(sum / channels as f32);
-            }
+            (b.frames(), Box::new(|ch, f| b.chan(ch)[f] as f32 / 2_147_483_648.0))
         }
         AudioBufferRef::U8(b) => {
-            let frames = b.frames();
-            for frame in 0..frames {
-                let mut sum = 0.0f32;
-                for ch in 0..channels {
-                    sum += (b.chan(ch)[frame] as f32 - 128.0) / 128.0;
-                }
-                out.push(sum / channels as f32);
-            }
+            (b.frames(), Box::new(|ch, f| (b.chan(ch)[f] as f32 - 128.0) / 128.0))
         }
         _ => {
             // Fallback: skip unsupported formats
             eprintln!("Warning: unsupported sample format, skipping packet");
+            return;
+        }
+    };
+
+    for frame in 0..frames {
+        let sum: f32 = (0..channels).map(|ch| sample(ch, frame)).sum();
+        out.push(sum / channels as f32);
+        if let Some(ref mut st) = stereo {
+            st.left.push(sample(0, frame));
+            st.right.push(sample(1, frame));
         }
     }
 }
@@ -159,3 +243,111 @@ fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
 
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trim_removes_padding_silence() {
+        let tone: Vec<f32> = (0..500).map(|i| 0.5 * (i as f32 * 0.1).sin() + 0.01).collect();
+        let mut samples = vec![0.0; 1000];
+        samples.extend(&tone);
+        samples.extend(vec![0.0002; 300]);
+        let mut audio = AudioData {
+            samples,
+            sample_rate: TARGET_SAMPLE_RATE,
+            lead_offset_seconds: 0.0,
+            stereo: None,
+        };
+
+        let (lead, trail) = trim_silence(&mut audio, SILENCE_THRESHOLD);
+        assert_eq!((lead, trail), (1000, 300));
+        assert_eq!(audio.samples, tone);
+        assert!((audio.lead_offset_seconds - 1000.0 / TARGET_SAMPLE_RATE as f64).abs() < 1e-12);
+    }
+
+    /// Decaying 80 Hz thumps every half second.
+    fn thumps(amplitude: f32) -> AudioData {
+        let sr = TARGET_SAMPLE_RATE as f32;
+        let samples = (0..TARGET_SAMPLE_RATE as usize * 8)
+            .map(|i| {
+                let t = (i % (TARGET_SAMPLE_RATE as usize / 2)) as f32 / sr;
+                amplitude * (-t * 20.0).exp() * (std::f32::consts::TAU * 80.0 * i as f32 / sr).sin()
+            })
+            .collect();
+        AudioData { samples, sample_rate: TARGET_SAMPLE_RATE, lead_offset_seconds: 0.0, stereo: None }
+    }
+
+    fn onset_count(audio: &AudioData) -> usize {
+        let spectrogram = crate::stft::compute_stft(&audio.samples, audio.sample_rate);
+        crate::onset::detect_onsets(&spectrogram, 1.5, 50.0, 500.0, false).len()
+    }
+
+    /// Write a 16-bit PCM WAV of `frames` interleaved across `channels`.
+    fn write_wav(path: &Path, channels: u16, frames: &[Vec<f32>]) {
+        let rate = TARGET_SAMPLE_RATE;
+        let data_len = (frames.len() * channels as usize * 2) as u32;
+        let mut bytes = Vec::new();
+        bytes.extend(b"RIFF");
+        bytes.extend((36 + data_len).to_le_bytes());
+        bytes.extend(b"WAVEfmt ");
+        bytes.extend(16u32.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(channels.to_le_bytes());
+        bytes.extend(rate.to_le_bytes());
+        bytes.extend((rate * channels as u32 * 2).to_le_bytes());
+        bytes.extend((channels * 2).to_le_bytes());
+        bytes.extend(16u16.to_le_bytes());
+        bytes.extend(b"data");
+        bytes.extend(data_len.to_le_bytes());
+        for frame in frames {
+            for s in frame {
+                bytes.extend(((s * 32767.0) as i16).to_le_bytes());
+            }
+        }
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn stereo_is_kept_only_for_stereo_sources() {
+        let dir = std::env::temp_dir().join(format!("chart_gen_stereo_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let tone = |i: usize| 0.5 * (i as f32 * 0.05).sin();
+
+        // Left carries the tone, right a quieter copy
+        let stereo_path = dir.join("stereo.wav");
+        let frames: Vec<Vec<f32>> = (0..4410).map(|i| vec![tone(i), 0.25 * tone(i)]).collect();
+        write_wav(&stereo_path, 2, &frames);
+        let audio = decode_audio(&stereo_path, true).unwrap();
+        let st = audio.stereo.as_ref().expect("stereo source keeps both channels");
+        assert_eq!(st.left.len(), audio.samples.len());
+        assert_eq!(st.right.len(), audio.samples.len());
+        assert!(st.balance(0, st.left.len()) < -0.5, "mix leans left");
+        // The mono mixdown is still there for onset/STFT analysis
+        assert!((audio.samples[100] - (st.left[100] + st.right[100]) / 2.0).abs() < 1e-4);
+        // Not asked for, not kept
+        assert!(decode_audio(&stereo_path, false).unwrap().stereo.is_none());
+
+        let mono_path = dir.join("mono.wav");
+        let frames: Vec<Vec<f32>> = (0..4410).map(|i| vec![tone(i)]).collect();
+        write_wav(&mono_path, 1, &frames);
+        let audio = decode_audio(&mono_path, true).unwrap();
+        assert!(audio.stereo.is_none());
+        assert_eq!(audio.samples.len(), 4410);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn normalized_quiet_copy_matches_original() {
+        let mut loud = thumps(0.8);
+        let mut quiet = thumps(0.8 * 1e-5);
+        // Too quiet for the onset silence gate as-is
+        assert!(onset_count(&quiet) < onset_count(&loud));
+
+        normalize(&mut loud, NORMALIZE_TARGET);
+        normalize(&mut quiet, NORMALIZE_TARGET);
+        assert_eq!(onset_count(&quiet), onset_count(&loud));
+    }
+}
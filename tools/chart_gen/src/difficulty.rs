@@ -1,5 +1,8 @@
-use crate::chart::Difficulty;
+use crate::beat::BEATS_PER_MEASURE;
+use crate::chart::{ChartNoteEntry, ChartNoteType, Difficulty};
+use crate::onset::OnsetBand;
 use crate::quantize::QuantizedNote;
+use crate::rating::{self, DifficultyRating, RatedKind, RatedNote};
 
 /// A note scored by importance for difficulty filtering.
 #[derive(Debug, Clone)]
@@ -7,64 +10,99 @@ pub struct ScoredNote {
     pub beat: f64,
     pub strength: f32,
     pub importance: f64,
+    pub band: OnsetBand,
 }
 
 /// Filter quantized notes by difficulty, keeping only the most important ones.
 ///
-/// Returns notes sorted by beat position, filtered according to:
-/// - Onset strength weighting
-/// - Beat position weighting (downbeats > beats > off-beats > subdivisions)
-/// - Percentile thresholding per difficulty
+/// Returns notes sorted by beat position. Notes are ranked once by importance
+/// (onset strength weighted by beat position: downbeats > beats > off-beats >
+/// subdivisions, with bars starting at beat `downbeat_offset`) and every
+/// difficulty takes a prefix of that ranking. The
+/// result is built up level by level from Easy, so on the same input each
+/// difficulty keeps everything the easier ones kept.
 pub fn filter_by_difficulty(
     notes: &[QuantizedNote],
     difficulty: Difficulty,
-    bpm: f64,
+    downbeat_offset: usize,
 ) -> Vec<ScoredNote> {
     if notes.is_empty() {
         return Vec::new();
     }
 
     // Score each note by importance
-    let mut scored: Vec<ScoredNote> = notes
+    let mut ranked: Vec<ScoredNote> = notes
         .iter()
         .map(|n| {
-            let beat_weight = compute_beat_weight(n.beat);
+            let beat_weight = compute_beat_weight(n.beat, downbeat_offset);
             let importance = n.strength as f64 * beat_weight;
             ScoredNote {
                 beat: n.beat,
                 strength: n.strength,
                 importance,
+                band: n.band,
             }
         })
         .collect();
 
-    // Determine percentile threshold
-    let percentile = difficulty.importance_percentile();
+    // Most important first; earlier beats win ties so the order is deterministic
+    ranked.sort_by(|a, b| {
+        b.importance
+            .total_cmp(&a.importance)
+            .then(a.beat.total_cmp(&b.beat))
+    });
 
-    if percentile > 0.0 {
-        let mut importances: Vec<f64> = scored.iter().map(|n| n.importance).collect();
-        importances.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let threshold_idx = (importances.len() as f64 * percentile) as usize;
-        let threshold = importances.get(threshold_idx).copied().unwrap_or(0.0);
+    let mut kept: Vec<ScoredNote> = Vec::new();
+    for level in Difficulty::ALL {
+        let take = ranked_count(ranked.len(), level.importance_percentile());
+        let min_interval = min_interval(level);
+        for note in &ranked[..take] {
+            if is_spaced(&kept, note.beat, min_interval) {
+                let pos = kept.partition_point(|n| n.beat < note.beat);
+                kept.insert(pos, note.clone());
+            }
+        }
+        fill_empty_measures(&mut kept);
 
-        scored.retain(|n| n.importance >= threshold);
+        if level == difficulty {
+            break;
+        }
     }
 
-    // Post-filter rules
-    apply_post_filter_rules(&mut scored, difficulty, bpm);
+    kept
+}
+
+/// How many of the top-ranked notes a difficulty draws from.
+fn ranked_count(len: usize, percentile: f64) -> usize {
+    len - ((len as f64 * percentile) as usize).min(len)
+}
+
+/// Minimum inter-note interval (in beats).
+fn min_interval(difficulty: Difficulty) -> f64 {
+    match difficulty {
+        Difficulty::Easy => 1.0,
+        Difficulty::Normal => 0.5,
+        Difficulty::Hard => 0.25,
+        Difficulty::Expert => 0.125,
+    }
+}
 
-    scored
+/// Whether `beat` is at least `min_interval` from every note in sorted `kept`.
+fn is_spaced(kept: &[ScoredNote], beat: f64, min_interval: f64) -> bool {
+    let pos = kept.partition_point(|n| n.beat < beat);
+    let clear_of = |n: &ScoredNote| (n.beat - beat).abs() >= min_interval - 0.001;
+    kept.get(pos).is_none_or(clear_of) && (pos == 0 || clear_of(&kept[pos - 1]))
 }
 
 /// Weight by beat position: downbeats are most important.
-fn compute_beat_weight(beat: f64) -> f64 {
+fn compute_beat_weight(beat: f64, downbeat_offset: usize) -> f64 {
     let frac = beat - beat.floor();
     let tolerance = 0.01;
 
     if frac < tolerance || frac > 1.0 - tolerance {
         // On a whole beat
-        let whole = beat.round() as u32;
-        if whole % 4 == 0 {
+        let whole = beat.round() as i64 - downbeat_offset as i64;
+        if whole.rem_euclid(BEATS_PER_MEASURE as i64) == 0 {
             1.0 // Downbeat (start of measure in 4/4)
         } else {
             0.8 // Other beats
@@ -76,84 +114,166 @@ fn compute_beat_weight(beat: f64) -> f64 {
     }
 }
 
-/// Apply post-filter rules to ensure chart quality.
-fn apply_post_filter_rules(notes: &mut Vec<ScoredNote>, difficulty: Difficulty, bpm: f64) {
-    if notes.is_empty() {
+/// Ensure at least one note per 4-beat measure.
+fn fill_empty_measures(notes: &mut Vec<ScoredNote>) {
+    if notes.len() < 2 {
         return;
     }
+    let first_beat = notes.first().unwrap().beat;
+    let last_beat = notes.last().unwrap().beat;
+    let total_measures = ((last_beat - first_beat) / 4.0).ceil() as usize;
 
-    // Minimum inter-note interval (in beats)
-    let min_interval = match difficulty {
-        Difficulty::Easy => 1.0,
-        Difficulty::Normal => 0.5,
-        Difficulty::Hard => 0.25,
-        Difficulty::Expert => 0.125,
-    };
+    for measure in 0..total_measures {
+        let measure_start = first_beat + measure as f64 * 4.0;
+        let measure_end = measure_start + 4.0;
 
-    // Remove notes that are too close together (keep the stronger one)
-    let mut i = 1;
-    while i < notes.len() {
-        let gap = notes[i].beat - notes[i - 1].beat;
-        if gap < min_interval - 0.001 {
-            // Remove the weaker note
-            if notes[i].importance < notes[i - 1].importance {
-                notes.remove(i);
-            } else {
-                notes.remove(i - 1);
-            }
-        } else {
-            i += 1;
+        let has_note = notes.iter().any(|n| n.beat >= measure_start && n.beat < measure_end);
+
+        if !has_note {
+            // Insert a note on the downbeat of this measure
+            let insert_beat = measure_start;
+            let pos = notes.partition_point(|n| n.beat < insert_beat);
+            notes.insert(pos, ScoredNote {
+                beat: insert_beat,
+                strength: 0.5,
+                importance: 0.5,
+                band: OnsetBand::Full,
+            });
         }
     }
+}
 
-    // Ensure at least one note per 4-beat measure
-    if notes.len() >= 2 {
-        let first_beat = notes.first().unwrap().beat;
-        let last_beat = notes.last().unwrap().beat;
-        let total_measures = ((last_beat - first_beat) / 4.0).ceil() as usize;
-
-        for measure in 0..total_measures {
-            let measure_start = first_beat + measure as f64 * 4.0;
-            let measure_end = measure_start + 4.0;
-
-            let has_note = notes.iter().any(|n| n.beat >= measure_start && n.beat < measure_end);
-
-            if !has_note {
-                // Insert a note on the downbeat of this measure
-                let insert_beat = measure_start;
-                let pos = notes.partition_point(|n| n.beat < insert_beat);
-                notes.insert(pos, ScoredNote {
-                    beat: insert_beat,
-                    strength: 0.5,
-                    importance: 0.5,
-                });
-            }
+/// Sliding window used when capping notes per second.
+const NPS_WINDOW_SECONDS: f64 = 2.0;
+
+/// Thin notes so no `NPS_WINDOW_SECONDS` window holds more than `max_nps`
+/// notes per second, keeping the strongest onsets. Notes stay sorted by beat.
+pub fn cap_density(notes: &mut Vec<ScoredNote>, max_nps: f64, bpm: f64) {
+    if notes.is_empty() || max_nps <= 0.0 {
+        return;
+    }
+    let window_beats = NPS_WINDOW_SECONDS * bpm / 60.0;
+    let allowed = ((max_nps * NPS_WINDOW_SECONDS).floor() as usize).max(1);
+
+    // Strongest first; each note is kept only if every window around it stays under the cap
+    let mut order: Vec<usize> = (0..notes.len()).collect();
+    order.sort_by(|&a, &b| notes[b].strength.total_cmp(&notes[a].strength));
+
+    let mut kept_beats: Vec<f64> = Vec::new();
+    let mut keep = vec![false; notes.len()];
+    for idx in order {
+        let beat = notes[idx].beat;
+        let lo = kept_beats.partition_point(|&b| b <= beat - window_beats);
+        let hi = kept_beats.partition_point(|&b| b < beat + window_beats);
+        let mut nearby = kept_beats[lo..hi].to_vec();
+        let pos = nearby.partition_point(|&b| b < beat);
+        nearby.insert(pos, beat);
+
+        if max_window_count(&nearby, window_beats) <= allowed {
+            kept_beats.insert(kept_beats.partition_point(|&b| b < beat), beat);
+            keep[idx] = true;
         }
     }
 
-    // Compute difficulty rating based on notes per second
-    let _ = compute_difficulty_rating(notes, bpm);
+    let mut keep = keep.into_iter();
+    notes.retain(|_| keep.next().unwrap());
 }
 
-/// Compute a 1-10 difficulty rating based on note density.
-pub fn compute_difficulty_rating(notes: &[ScoredNote], bpm: f64) -> u32 {
-    if notes.is_empty() {
-        return 1;
+/// Largest number of sorted beats inside any half-open window of `window_beats`.
+fn max_window_count(sorted_beats: &[f64], window_beats: f64) -> usize {
+    let mut best = 0;
+    let mut start = 0;
+    for end in 0..sorted_beats.len() {
+        while sorted_beats[end] - sorted_beats[start] >= window_beats {
+            start += 1;
+        }
+        best = best.max(end - start + 1);
     }
+    best
+}
 
-    let first_beat = notes.first().unwrap().beat;
-    let last_beat = notes.last().unwrap().beat;
-    let duration_beats = last_beat - first_beat;
+/// Average notes per second between the first and last note.
+pub fn average_nps(notes: &[ScoredNote], bpm: f64) -> f64 {
+    let (Some(first), Some(last)) = (notes.first(), notes.last()) else {
+        return 0.0;
+    };
+    let duration_seconds = (last.beat - first.beat) * 60.0 / bpm;
+    if duration_seconds <= 0.0 {
+        return 0.0;
+    }
+    notes.len() as f64 / duration_seconds
+}
+
+/// Rate finished chart notes with the shared model; rests don't count.
+pub fn rate_chart(notes: &[ChartNoteEntry], bpm: f64) -> DifficultyRating {
+    let rated: Vec<RatedNote> = notes
+        .iter()
+        .filter_map(|n| {
+            let kind = rating::rated_kind!(n.note_type)?;
+            Some(RatedNote { beat: n.beat, kind })
+        })
+        .collect();
+    rating::rate(&rated, bpm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if duration_beats <= 0.0 {
-        return 1;
+    #[test]
+    fn easier_difficulties_are_subsets_of_harder_ones() {
+        // Irregular strengths on a 16th grid, so every level has to choose
+        let notes: Vec<QuantizedNote> = (0..256)
+            .map(|i| QuantizedNote {
+                beat: i as f64 * 0.25,
+                strength: ((i * 37 % 101) as f32) / 100.0,
+                original_time: i as f64 * 0.125,
+                band: OnsetBand::Full,
+            })
+            .collect();
+
+        let beats = |d| -> Vec<f64> {
+            filter_by_difficulty(&notes, d, 0).iter().map(|n| n.beat).collect()
+        };
+        let easy = beats(Difficulty::Easy);
+        let normal = beats(Difficulty::Normal);
+        let hard = beats(Difficulty::Hard);
+
+        assert!(easy.len() < hard.len());
+        assert!(easy.iter().all(|b| hard.contains(b)));
+        assert!(easy.iter().all(|b| normal.contains(b)));
+        assert!(normal.iter().all(|b| hard.contains(b)));
+    }
+
+    #[test]
+    fn downbeat_offset_moves_the_bar_weight() {
+        assert_eq!(compute_beat_weight(4.0, 0), 1.0);
+        assert_eq!(compute_beat_weight(4.0, 2), 0.8);
+        assert_eq!(compute_beat_weight(2.0, 2), 1.0);
+        assert_eq!(compute_beat_weight(6.0, 2), 1.0);
+        assert_eq!(compute_beat_weight(2.5, 2), 0.5);
     }
 
-    let duration_seconds = duration_beats * 60.0 / bpm;
-    let nps = notes.len() as f64 / duration_seconds; // notes per second
+    #[test]
+    fn dense_series_is_thinned_to_cap() {
+        // 16th notes at 120 BPM = 8 NPS, alternating strong and weak
+        let mut notes: Vec<ScoredNote> = (0..128)
+            .map(|i| ScoredNote {
+                beat: i as f64 * 0.25,
+                strength: if i % 2 == 0 { 0.9 } else { 0.2 },
+                importance: 0.5,
+                band: OnsetBand::Full,
+            })
+            .collect();
+
+        cap_density(&mut notes, 3.0, 120.0);
 
-    // Map NPS to 1-10 rating
-    // ~0.5 NPS = 1, ~1 NPS = 2, ~2 NPS = 4, ~4 NPS = 6, ~8 NPS = 8, ~12+ NPS = 10
-    let rating = (nps * 1.5).log2().max(0.0) * 2.5 + 1.0;
-    (rating.round() as u32).clamp(1, 10)
+        let beats: Vec<f64> = notes.iter().map(|n| n.beat).collect();
+        let window_beats = NPS_WINDOW_SECONDS * 120.0 / 60.0;
+        assert!(max_window_count(&beats, window_beats) <= 6);
+        assert!(beats.windows(2).all(|w| w[0] < w[1]));
+        // Strong onsets fill the budget before any weak one
+        assert!(notes.iter().all(|n| n.strength > 0.5));
+        assert!(average_nps(&notes, 120.0) > 2.5);
+    }
 }
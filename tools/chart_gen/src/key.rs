@@ -0,0 +1,174 @@
+use crate::beat::BeatGrid;
+use crate::chart::{ChartEvent, EventType};
+use crate::stft::Spectrogram;
+
+/// Krumhansl-Kessler key profiles, indexed by semitones above the tonic.
+const MAJOR_PROFILE: [f64; 12] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+const MINOR_PROFILE: [f64; 12] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+
+/// Frequency range folded into the chroma profile.
+const CHROMA_RANGE_HZ: (f64, f64) = (55.0, 5000.0);
+/// Beats per key estimate (eight bars of 4/4).
+const KEY_WINDOW_BEATS: usize = 32;
+/// Beats each ColorShift takes to blend in.
+const COLOR_SHIFT_BEATS: f64 = 4.0;
+
+/// A musical key: tonic pitch class (C = 0) and mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    pub tonic: usize,
+    pub minor: bool,
+}
+
+impl Key {
+    /// Hue in degrees: keys walk the circle of fifths in 30° steps, so related
+    /// keys get neighbouring colours. Minor keys sit 15° past their relative major.
+    pub fn hue(self) -> f32 {
+        let major_tonic = if self.minor { (self.tonic + 3) % 12 } else { self.tonic };
+        let fifths = (major_tonic * 7) % 12;
+        fifths as f32 * 30.0 + if self.minor { 15.0 } else { 0.0 }
+    }
+}
+
+/// Emit a ColorShift at the start and wherever the estimated key changes.
+///
+/// `lead_offset_seconds` maps spectrogram time onto the beat grid's time.
+pub fn generate_key_events(
+    spectrogram: &Spectrogram,
+    beat_grid: &BeatGrid,
+    lead_offset_seconds: f64,
+) -> Vec<ChartEvent> {
+    let num_windows = (beat_grid.total_beats() / KEY_WINDOW_BEATS as f64).ceil() as usize + 1;
+    let mut windows = vec![[0.0f64; 12]; num_windows];
+
+    for frame in 0..spectrogram.frames.len() {
+        let beat = beat_grid.time_to_beat(spectrogram.frame_to_seconds(frame) + lead_offset_seconds);
+        if beat < 0.0 {
+            continue;
+        }
+        let window = beat as usize / KEY_WINDOW_BEATS;
+        if window < num_windows {
+            add_chroma(spectrogram, frame, &mut windows[window]);
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<Key> = None;
+    for (i, chroma) in windows.iter().enumerate() {
+        let Some(key) = estimate_key(chroma) else { continue };
+        if current != Some(key) {
+            events.push(ChartEvent {
+                beat: (i * KEY_WINDOW_BEATS) as f64,
+                event: EventType::ColorShift { hue: key.hue(), duration_beats: COLOR_SHIFT_BEATS },
+            });
+            current = Some(key);
+        }
+    }
+    events
+}
+
+/// Fold a frame's spectral energy into 12 pitch classes (C = 0).
+fn add_chroma(spectrogram: &Spectrogram, frame: usize, chroma: &mut [f64; 12]) {
+    let (lo, hi) = CHROMA_RANGE_HZ;
+    let lo_bin = spectrogram.hz_to_bin(lo).max(1);
+    let hi_bin = spectrogram.hz_to_bin(hi).min(spectrogram.num_bins() - 1);
+
+    for bin in lo_bin..=hi_bin {
+        let magnitude = spectrogram.frames[frame][bin] as f64;
+        if magnitude <= 0.0 {
+            continue;
+        }
+        // MIDI note number, then pitch class (MIDI 60 = C4)
+        let midi = 69.0 + 12.0 * (spectrogram.bin_to_hz(bin) / 440.0).log2();
+        let pitch_class = (midi.round() as i64).rem_euclid(12) as usize;
+        chroma[pitch_class] += magnitude * magnitude;
+    }
+}
+
+/// Best-correlating major or minor key for a chroma vector (None if silent).
+pub fn estimate_key(chroma: &[f64; 12]) -> Option<Key> {
+    if chroma.iter().all(|&c| c <= 0.0) {
+        return None;
+    }
+
+    let mut best: Option<(Key, f64)> = None;
+    for tonic in 0..12 {
+        for (minor, profile) in [(false, &MAJOR_PROFILE), (true, &MINOR_PROFILE)] {
+            let rotated: Vec<f64> = (0..12).map(|pc| profile[(pc + 12 - tonic) % 12]).collect();
+            let score = correlation(chroma, &rotated);
+            if best.is_none_or(|(_, s)| score > s) {
+                best = Some((Key { tonic, minor }, score));
+            }
+        }
+    }
+    best.map(|(key, _)| key)
+}
+
+/// Pearson correlation of two equal-length series.
+fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let n = a.len() as f64;
+    let mean_a = a.iter().sum::<f64>() / n;
+    let mean_b = b.iter().sum::<f64>() / n;
+    let mut cov = 0.0;
+    let mut var_a = 0.0;
+    let mut var_b = 0.0;
+    for (x, y) in a.iter().zip(b) {
+        cov += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a).powi(2);
+        var_b += (y - mean_b).powi(2);
+    }
+    if var_a <= 0.0 || var_b <= 0.0 {
+        return 0.0;
+    }
+    cov / (var_a * var_b).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stft::WINDOW_SIZE;
+
+    const SAMPLE_RATE: u32 = 44_100;
+    const HOP: usize = 512;
+
+    /// Frames holding a single partial at `hz`.
+    fn tone(hz: f64, seconds: f64) -> Spectrogram {
+        let mut spectrogram = Spectrogram { frames: Vec::new(), hop_size: HOP, sample_rate: SAMPLE_RATE };
+        let bin = (hz * WINDOW_SIZE as f64 / SAMPLE_RATE as f64).round() as usize;
+        let num_frames = (seconds * SAMPLE_RATE as f64 / HOP as f64) as usize;
+        spectrogram.frames = (0..num_frames)
+            .map(|_| {
+                let mut frame = vec![0.0f32; WINDOW_SIZE / 2 + 1];
+                frame[bin] = 1.0;
+                frame
+            })
+            .collect();
+        spectrogram
+    }
+
+    #[test]
+    fn a440_maps_to_a_fixed_hue() {
+        let spectrogram = tone(440.0, 20.0);
+        let beat_grid = BeatGrid { beats: (0..40).map(|b| b as f64 * 0.5).collect(), bpm: 120.0, downbeat_offset: 0 };
+
+        let events = generate_key_events(&spectrogram, &beat_grid, 0.0);
+        // A steady tone never changes key: one shift at the start
+        assert_eq!(events.len(), 1);
+        let EventType::ColorShift { hue, .. } = events[0].event else {
+            panic!("expected a ColorShift");
+        };
+        // A major: three fifths above C
+        assert_eq!(hue, 90.0);
+        assert_eq!(events[0].beat, 0.0);
+    }
+
+    #[test]
+    fn relative_keys_sit_side_by_side() {
+        let c_major = Key { tonic: 0, minor: false };
+        let a_minor = Key { tonic: 9, minor: true };
+        let g_major = Key { tonic: 7, minor: false };
+        assert_eq!(c_major.hue(), 0.0);
+        assert_eq!(a_minor.hue(), 15.0);
+        assert_eq!(g_major.hue(), 30.0);
+    }
+}
@@ -1,26 +1,47 @@
 mod beat;
+mod camera;
 mod chart;
 mod decode;
 mod difficulty;
+mod key;
 mod note_types;
 mod onset;
+mod osu;
 mod path;
 mod quantize;
+mod rating;
+mod stats;
 mod stft;
+mod verify;
 
 use std::path::{Path, PathBuf};
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 use chart::{
-    ChartFile, ChartTimingPoint, Difficulty, SongMetadata, serialize_chart, serialize_metadata,
+    ChartFile, ChartTimingPoint, DetectedOnset, Difficulty, OnsetDump, SongMetadata,
+    serialize_chart, serialize_metadata, serialize_onsets,
 };
 
 #[derive(Parser)]
-#[command(name = "chart_gen", about = "Auto-generate FunkTrack charts from audio files")]
+#[command(
+    name = "chart_gen",
+    about = "Auto-generate FunkTrack charts from audio files",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Path to the audio file (MP3, OGG, FLAC, WAV)
-    audio_file: PathBuf,
+    #[arg(required_unless_present = "export_osu")]
+    audio_file: Option<PathBuf>,
+
+    /// Convert an existing chart to an osu! mania `.osu` file instead of generating
+    /// (written next to the chart, or to --output)
+    #[arg(long, value_name = "CHART")]
+    export_osu: Option<PathBuf>,
 
     /// Generate a single difficulty
     #[arg(short, long)]
@@ -42,18 +63,80 @@ struct Cli {
     #[arg(long)]
     bpm: Option<f64>,
 
-    /// Onset detection sensitivity (default: 1.5, higher = fewer notes)
+    /// Detect which beat starts each bar (pass `--downbeat false` to disable)
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    downbeat: bool,
+
+    /// Onset threshold margin in local standard deviations (default: 1.5, higher = fewer notes)
     #[arg(long, default_value = "1.5")]
     sensitivity: f64,
 
+    /// Window for the moving onset threshold in ms (default: 500)
+    #[arg(long, default_value = "500")]
+    onset_window: f64,
+
     /// Minimum inter-onset interval in ms (default: 50)
     #[arg(long, default_value = "50")]
     min_interval: f64,
 
+    /// How far notes move from their detected timing onto the grid
+    /// (0 = as played, keeping swing; 1 = fully snapped)
+    #[arg(long, default_value = "1.0", value_parser = parse_unit_interval)]
+    quantize_strength: f64,
+
+    /// Minimum gap between slides in ms (default depends on difficulty)
+    #[arg(long)]
+    slide_interval: Option<f64>,
+
+    /// Minimum gap from one hold's end to the next hold in ms (default depends on difficulty)
+    #[arg(long)]
+    hold_interval: Option<f64>,
+
+    /// Detect kicks and snares/hats separately (kicks become taps, snares slides)
+    #[arg(long)]
+    band_split: bool,
+
+    /// Normalize the analysis copy of the audio (pass `--normalize false` to disable)
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    normalize: bool,
+
+    /// Override travel beats; one value for all difficulties or a comma list
+    /// matching the generated difficulties in order (easy,normal,hard,expert)
+    #[arg(long, value_delimiter = ',')]
+    travel_beats: Vec<f64>,
+
+    /// Override look-ahead beats (defaults to the travel beats); same forms as --travel-beats
+    #[arg(long, value_delimiter = ',')]
+    look_ahead_beats: Vec<f64>,
+
+    /// Thin each chart so local density never exceeds this many notes per second
+    #[arg(long)]
+    max_nps: Option<f64>,
+
+    /// Add camera zooms on energy spikes and pans/tilts at phrase boundaries
+    #[arg(long)]
+    camera_events: bool,
+
+    /// Swing the path with stereo balance and lift it with spectral brightness
+    #[arg(long)]
+    expressive_path: bool,
+
+    /// Add ColorShift events whose hue follows the estimated musical key
+    #[arg(long)]
+    color_from_key: bool,
+
+    /// Keep leading/trailing silence instead of trimming it before analysis
+    #[arg(long)]
+    no_trim: bool,
+
     /// Generate metadata.ron alongside charts
     #[arg(long)]
     metadata: bool,
 
+    /// Write detected onsets (beat + strength) to onsets.ron for the editor overlay
+    #[arg(long)]
+    dump_onsets: bool,
+
     /// Song title (for metadata generation)
     #[arg(long)]
     title: Option<String>,
@@ -62,14 +145,51 @@ struct Cli {
     #[arg(long)]
     artist: Option<String>,
 
+    /// Write a JSON summary (onsets, BPM, note counts, NPS, rating per difficulty) to this path
+    #[arg(long, value_name = "PATH")]
+    stats_json: Option<PathBuf>,
+
     /// Show detailed analysis output
     #[arg(short, long)]
     verbose: bool,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// Check a song folder, or a folder of songs, the way the game loads it.
+    /// Prints one `<file>: <problem>` line per issue and exits non-zero if any.
+    Verify {
+        song_dir: PathBuf,
+    },
+}
+
 fn main() {
     let cli = Cli::parse();
 
+    if let Some(Command::Verify { ref song_dir }) = cli.command {
+        let issues = verify::verify(song_dir);
+        for issue in &issues {
+            println!("{issue}");
+        }
+        if !issues.is_empty() {
+            eprintln!("{} issue(s) found", issues.len());
+            std::process::exit(1);
+        }
+        eprintln!("All charts OK");
+        return;
+    }
+
+    if let Some(ref chart_path) = cli.export_osu {
+        if let Err(e) = export_osu(chart_path, cli.output.as_deref()) {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+    let Some(audio_file) = cli.audio_file.clone() else {
+        unreachable!("clap requires the audio file unless exporting");
+    };
+
     // Determine which difficulties to generate
     let difficulties = if cli.all_difficulties {
         vec![
@@ -84,12 +204,43 @@ fn main() {
         vec![Difficulty::Normal]
     };
 
+    for (flag, values) in [
+        ("--travel-beats", &cli.travel_beats),
+        ("--look-ahead-beats", &cli.look_ahead_beats),
+    ] {
+        if values.len() > 1 && values.len() != difficulties.len() {
+            eprintln!(
+                "Error: {flag} takes one value or one per difficulty ({} given, {} difficulties)",
+                values.len(),
+                difficulties.len()
+            );
+            std::process::exit(1);
+        }
+    }
+
     // Step 1: Decode audio
-    eprintln!("Decoding {}...", cli.audio_file.display());
-    let audio = decode::decode_audio(&cli.audio_file).unwrap_or_else(|e| {
+    eprintln!("Decoding {}...", audio_file.display());
+    let keep_stereo = cli.expressive_path || cli.camera_events;
+    let mut audio = decode::decode_audio(&audio_file, keep_stereo).unwrap_or_else(|e| {
         eprintln!("Error: {e}");
         std::process::exit(1);
     });
+    if cli.normalize {
+        let gain = decode::normalize(&mut audio, decode::NORMALIZE_TARGET);
+        if cli.verbose {
+            eprintln!("  Normalized: {:+.1} dB", 20.0 * gain.log10());
+        }
+    }
+    if !cli.no_trim {
+        let (lead, trail) = decode::trim_silence(&mut audio, decode::SILENCE_THRESHOLD);
+        if lead + trail > 0 {
+            eprintln!(
+                "  Trimmed silence: {:.2}s lead, {:.2}s tail",
+                lead as f64 / audio.sample_rate as f64,
+                trail as f64 / audio.sample_rate as f64
+            );
+        }
+    }
     let duration_seconds = audio.samples.len() as f64 / audio.sample_rate as f64;
     eprintln!(
         "  {} samples, {} Hz, {:.1}s",
@@ -105,8 +256,18 @@ fn main() {
 
     // Step 3: Onset detection
     eprintln!("Detecting onsets (sensitivity={})...", cli.sensitivity);
-    let onsets = onset::detect_onsets(&spectrogram, cli.sensitivity, cli.min_interval);
+    let mut onsets = onset::detect_onsets(
+        &spectrogram,
+        cli.sensitivity,
+        cli.min_interval,
+        cli.onset_window,
+        cli.band_split,
+    );
     eprintln!("  {} onsets detected", onsets.len());
+    if cli.band_split {
+        let low = onsets.iter().filter(|o| o.band == onset::OnsetBand::Low).count();
+        eprintln!("  {} low band, {} high band", low, onsets.len() - low);
+    }
 
     if cli.verbose && !onsets.is_empty() {
         let strengths: Vec<f32> = onsets.iter().map(|o| o.strength).collect();
@@ -117,45 +278,116 @@ fn main() {
 
     // Step 4: Beat tracking
     eprintln!("Tracking beats...");
-    let beat_grid = beat::track_beats(&spectrogram, &onsets, cli.bpm);
+    let mut beat_grid = beat::track_beats(&spectrogram, &onsets, cli.bpm, cli.downbeat);
     eprintln!("  BPM: {:.1}, {} beats", beat_grid.bpm, beat_grid.beats.len());
 
+    // Analysis ran on trimmed audio; move times back onto the original file
+    if audio.lead_offset_seconds > 0.0 {
+        for onset in &mut onsets {
+            onset.time_seconds += audio.lead_offset_seconds;
+        }
+        beat_grid.shift(audio.lead_offset_seconds);
+    }
+
+    if cli.dump_onsets {
+        let dump = OnsetDump {
+            onsets: onsets
+                .iter()
+                .map(|o| DetectedOnset {
+                    beat: beat_grid.time_to_beat(o.time_seconds),
+                    strength: o.strength,
+                })
+                .filter(|o| o.beat >= 0.0)
+                .collect(),
+        };
+        let dump_ron = serialize_onsets(&dump).unwrap_or_else(|e| {
+            eprintln!("Error serializing onsets: {e}");
+            std::process::exit(1);
+        });
+        let dump_path = sidecar_path(&cli, "onsets.ron");
+        if let Some(parent) = dump_path.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+        std::fs::write(&dump_path, &dump_ron).unwrap_or_else(|e| {
+            eprintln!("Error writing onsets: {e}");
+            std::process::exit(1);
+        });
+        eprintln!("  Wrote {}", dump_path.display());
+    }
+
+    let mut stats = stats::GenerationStats {
+        onset_count: onsets.len(),
+        bpm: beat_grid.bpm,
+        bpm_confidence: beat::bpm_confidence(&beat_grid, &onsets),
+        difficulties: Vec::new(),
+    };
+    if cli.verbose {
+        eprintln!("  BPM confidence: {:.0}%", stats.bpm_confidence * 100.0);
+    }
+
+    let mut events = Vec::new();
+    if cli.camera_events {
+        let camera_events = camera::generate_camera_events(
+            &spectrogram,
+            &beat_grid,
+            audio.lead_offset_seconds,
+            audio.stereo.as_ref(),
+        );
+        eprintln!("  {} camera events", camera_events.len());
+        events.extend(camera_events);
+    }
+    if cli.color_from_key {
+        let key_events = key::generate_key_events(&spectrogram, &beat_grid, audio.lead_offset_seconds);
+        eprintln!("  {} key color shifts", key_events.len());
+        events.extend(key_events);
+    }
+    events.sort_by(|a, b| a.beat.partial_cmp(&b.beat).unwrap());
+
     // Step 5: Generate charts for each difficulty
-    for diff in &difficulties {
+    for (diff_idx, diff) in difficulties.iter().enumerate() {
         eprintln!("\nGenerating {:?} chart...", diff);
 
         // Quantize
         let grid_res = diff.grid_resolution();
-        let quantized = quantize::quantize_onsets(&onsets, &beat_grid, grid_res);
+        let quantized = quantize::quantize_onsets(&onsets, &beat_grid, grid_res, cli.quantize_strength);
         eprintln!("  {} quantized notes (grid: 1/{})", quantized.len(), grid_res);
 
         // Difficulty filter
-        let filtered = difficulty::filter_by_difficulty(&quantized, *diff, beat_grid.bpm);
-        let rating = difficulty::compute_difficulty_rating(&filtered, beat_grid.bpm);
-        eprintln!("  {} notes after filtering (rating: {})", filtered.len(), rating);
+        let mut filtered =
+            difficulty::filter_by_difficulty(&quantized, *diff, beat_grid.downbeat_offset);
+        if let Some(max_nps) = cli.max_nps {
+            difficulty::cap_density(&mut filtered, max_nps, beat_grid.bpm);
+        }
+        if cli.verbose {
+            eprintln!("  Average NPS: {:.2}", difficulty::average_nps(&filtered, beat_grid.bpm));
+        }
+        eprintln!("  {} notes after filtering", filtered.len());
 
         // Assign note types
-        let notes = note_types::assign_note_types(&filtered, *diff, beat_grid.bpm);
-
+        let mut notes = note_types::assign_note_types(&filtered, *diff, beat_grid.bpm);
+        let mut intervals = note_types::TypeIntervals::for_difficulty(*diff);
+        if let Some(ms) = cli.slide_interval {
+            intervals.slide_ms = ms;
+        }
+        if let Some(ms) = cli.hold_interval {
+            intervals.hold_ms = ms;
+        }
+        note_types::enforce_type_intervals(&mut notes, intervals, beat_grid.bpm);
+        let rating = difficulty::rate_chart(&notes, beat_grid.bpm);
+        eprintln!(
+            "  Rating: {} (avg {:.2} NPS, peak {:.2} NPS)",
+            rating.rating, rating.average_nps, rating.peak_nps
+        );
+
+        let diff_stats = stats::DifficultyStats::new(*diff, &notes, &rating);
         if cli.verbose {
-            let mut type_counts = std::collections::HashMap::new();
-            for n in &notes {
-                let name = match &n.note_type {
-                    chart::ChartNoteType::Tap => "Tap",
-                    chart::ChartNoteType::Hold { .. } => "Hold",
-                    chart::ChartNoteType::Slide { .. } => "Slide",
-                    chart::ChartNoteType::Critical => "Critical",
-                    chart::ChartNoteType::Rest => "Rest",
-                    _ => "Other",
-                };
-                *type_counts.entry(name).or_insert(0u32) += 1;
-            }
-            let mut counts: Vec<_> = type_counts.into_iter().collect();
-            counts.sort_by_key(|(_, c)| std::cmp::Reverse(*c));
+            let mut counts: Vec<_> = diff_stats.note_counts.iter().collect();
+            counts.sort_by_key(|(_, c)| std::cmp::Reverse(**c));
             for (name, count) in &counts {
                 eprintln!("    {name}: {count}");
             }
         }
+        stats.difficulties.push(diff_stats);
 
         // Generate path
         let total_beats = if beat_grid.beats.is_empty() {
@@ -163,12 +395,18 @@ fn main() {
         } else {
             beat_grid.total_beats() + 8.0 // Add 8 beats of buffer
         };
-        let path_segment = path::generate_path(&spectrogram, total_beats, beat_grid.bpm);
+        let path_segment = path::generate_path(
+            &spectrogram,
+            total_beats,
+            beat_grid.bpm,
+            cli.expressive_path.then_some(&audio),
+        );
 
         // Build chart file
+        let (travel_beats, look_ahead_beats) = approach_beats(&cli, diff, diff_idx);
         let chart_file = ChartFile {
             difficulty: *diff,
-            difficulty_rating: rating,
+            difficulty_rating: rating.rating,
             timing_points: vec![ChartTimingPoint {
                 beat: 0.0,
                 bpm: beat_grid.bpm,
@@ -176,9 +414,9 @@ fn main() {
             }],
             path_segments: vec![path_segment],
             notes,
-            events: Vec::new(),
-            travel_beats: diff.travel_beats(),
-            look_ahead_beats: diff.travel_beats(),
+            events: events.clone(),
+            travel_beats,
+            look_ahead_beats,
         };
 
         // Serialize and write
@@ -201,8 +439,7 @@ fn main() {
 
     // Optional: generate metadata.ron
     if cli.metadata {
-        let audio_filename = cli
-            .audio_file
+        let audio_filename = audio_file
             .file_name()
             .unwrap_or_default()
             .to_string_lossy()
@@ -210,7 +447,7 @@ fn main() {
         let title = cli
             .title
             .clone()
-            .unwrap_or_else(|| stem_name(&cli.audio_file));
+            .unwrap_or_else(|| stem_name(&audio_file));
         let artist = cli.artist.clone().unwrap_or_else(|| "Unknown".to_string());
 
         let metadata = SongMetadata {
@@ -218,7 +455,7 @@ fn main() {
             artist,
             charter: "chart_gen".to_string(),
             audio_file: audio_filename,
-            preview_start_ms: 0,
+            preview_start_ms: (audio.lead_offset_seconds * 1000.0) as u64,
             preview_duration_ms: 15000,
             source: String::new(),
             difficulties: difficulties.clone(),
@@ -229,13 +466,7 @@ fn main() {
             std::process::exit(1);
         });
 
-        let meta_path = if let Some(ref dir) = cli.output_dir {
-            dir.join("metadata.ron")
-        } else if let Some(ref out) = cli.output {
-            out.parent().unwrap_or(Path::new(".")).join("metadata.ron")
-        } else {
-            PathBuf::from("metadata.ron")
-        };
+        let meta_path = sidecar_path(&cli, "metadata.ron");
 
         std::fs::write(&meta_path, &meta_ron).unwrap_or_else(|e| {
             eprintln!("Error writing metadata: {e}");
@@ -244,9 +475,68 @@ fn main() {
         eprintln!("\nWrote {}", meta_path.display());
     }
 
+    if let Some(ref stats_path) = cli.stats_json {
+        if let Err(e) = stats::write_stats_json(&stats, stats_path) {
+            eprintln!("Error writing stats: {e}");
+            std::process::exit(1);
+        }
+        eprintln!("Wrote {}", stats_path.display());
+    }
+
     eprintln!("\nDone!");
 }
 
+/// Write `chart_path` as a `.osu` file, picking up `metadata.ron` from the
+/// chart's folder when there is one.
+fn export_osu(chart_path: &Path, output: Option<&Path>) -> Result<(), String> {
+    let contents = std::fs::read_to_string(chart_path)
+        .map_err(|e| format!("failed to read {}: {e}", chart_path.display()))?;
+    let chart: ChartFile = ron::from_str(&contents)
+        .map_err(|e| format!("failed to parse {}: {e}", chart_path.display()))?;
+
+    let meta_path = chart_path.parent().unwrap_or(Path::new(".")).join("metadata.ron");
+    let metadata: Option<SongMetadata> = std::fs::read_to_string(&meta_path)
+        .ok()
+        .and_then(|s| ron::from_str(&s).ok());
+    if metadata.is_none() {
+        eprintln!("  No metadata.ron next to the chart; song fields left blank");
+    }
+
+    let osu = osu::export_osu(&chart, metadata.as_ref());
+    let out_path = output.map_or_else(|| chart_path.with_extension("osu"), Path::to_path_buf);
+    std::fs::write(&out_path, osu)
+        .map_err(|e| format!("failed to write {}: {e}", out_path.display()))?;
+    eprintln!("Wrote {}", out_path.display());
+    Ok(())
+}
+
+/// CLI override for the difficulty at `idx`: a single value applies to every difficulty.
+fn pick_override(values: &[f64], idx: usize) -> Option<f64> {
+    match values {
+        [] => None,
+        [only] => Some(*only),
+        _ => values.get(idx).copied(),
+    }
+}
+
+/// Travel and look-ahead beats for the difficulty at `idx`: the CLI overrides
+/// if given, else the difficulty's own travel, with look-ahead following travel.
+fn approach_beats(cli: &Cli, difficulty: &Difficulty, idx: usize) -> (f64, f64) {
+    let travel_beats = pick_override(&cli.travel_beats, idx).unwrap_or(difficulty.travel_beats());
+    let look_ahead_beats = pick_override(&cli.look_ahead_beats, idx).unwrap_or(travel_beats);
+    (travel_beats, look_ahead_beats)
+}
+
+/// clap value parser for a number in `0..=1`.
+fn parse_unit_interval(s: &str) -> Result<f64, String> {
+    let value: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    if (0.0..=1.0).contains(&value) {
+        Ok(value)
+    } else {
+        Err(format!("{value} is not between 0 and 1"))
+    }
+}
+
 fn parse_difficulty(s: &str) -> Difficulty {
     match s.to_lowercase().as_str() {
         "easy" => Difficulty::Easy,
@@ -276,9 +566,63 @@ fn determine_output_path(cli: &Cli, difficulty: &Difficulty) -> PathBuf {
     }
 }
 
+/// Path for a file that sits next to the generated charts.
+fn sidecar_path(cli: &Cli, name: &str) -> PathBuf {
+    if let Some(ref dir) = cli.output_dir {
+        dir.join(name)
+    } else if let Some(ref out) = cli.output {
+        out.parent().unwrap_or(Path::new(".")).join(name)
+    } else {
+        PathBuf::from(name)
+    }
+}
+
 fn stem_name(path: &Path) -> String {
     path.file_stem()
         .unwrap_or_default()
         .to_string_lossy()
         .to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approach_overrides_reach_chart_file() {
+        let cli = Cli::parse_from([
+            "chart_gen",
+            "song.ogg",
+            "--all-difficulties",
+            "--travel-beats",
+            "5,4,3.5,2.5",
+            "--look-ahead-beats",
+            "6",
+        ]);
+        assert_eq!(approach_beats(&cli, &Difficulty::Expert, 3), (2.5, 6.0));
+        assert_eq!(approach_beats(&cli, &Difficulty::Normal, 1), (4.0, 6.0));
+
+        // Without overrides each difficulty keeps its own travel, and look-ahead follows it
+        let defaults = Cli::parse_from(["chart_gen", "song.ogg", "--travel-beats", "3"]);
+        assert_eq!(approach_beats(&defaults, &Difficulty::Hard, 2), (3.0, 3.0));
+        let plain = Cli::parse_from(["chart_gen", "song.ogg"]);
+        let hard = Difficulty::Hard.travel_beats();
+        assert_eq!(approach_beats(&plain, &Difficulty::Hard, 2), (hard, hard));
+
+        let (travel_beats, look_ahead_beats) = approach_beats(&cli, &Difficulty::Easy, 0);
+        let chart_file = ChartFile {
+            difficulty: Difficulty::Easy,
+            difficulty_rating: 1,
+            timing_points: Vec::new(),
+            path_segments: Vec::new(),
+            notes: Vec::new(),
+            events: Vec::new(),
+            travel_beats,
+            look_ahead_beats,
+        };
+        let ron_str = serialize_chart(&chart_file).unwrap();
+        let parsed: ChartFile = ron::from_str(&ron_str).unwrap();
+        assert_eq!(parsed.travel_beats, 5.0);
+        assert_eq!(parsed.look_ahead_beats, 6.0);
+    }
+}
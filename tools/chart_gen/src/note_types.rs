@@ -1,9 +1,12 @@
 use crate::chart::{ChartNoteEntry, ChartNoteType, Difficulty, SlideDirection};
 use crate::difficulty::ScoredNote;
+use crate::onset::OnsetBand;
 
 /// Assign note types to scored notes based on difficulty and simple heuristics.
 ///
-/// Phase 7 uses basic rules. Phase 10 will add audio-feature-based assignment.
+/// Each difficulty unlocks more advanced types (see the `*_MIX` constants), so
+/// generated difficulties differ in variety and not only in density. Slides
+/// that follow closely on another slide turn at most one sector from it.
 pub fn assign_note_types(
     notes: &[ScoredNote],
     difficulty: Difficulty,
@@ -11,9 +14,20 @@ pub fn assign_note_types(
 ) -> Vec<ChartNoteEntry> {
     let mut entries = Vec::with_capacity(notes.len());
     let mut rng_state: u64 = 42; // Deterministic pseudo-random
+    let ms_per_beat = 60_000.0 / bpm;
+    let mut last_slide: Option<(f64, SlideDirection)> = None;
 
     for (i, note) in notes.iter().enumerate() {
         let note_type = pick_note_type(notes, i, difficulty, bpm, &mut rng_state);
+        let mut note_type = apply_band(note_type, note, difficulty, &mut rng_state);
+        if let ChartNoteType::Slide { direction } = &mut note_type {
+            if let Some((prev_beat, prev_dir)) = last_slide
+                && (note.beat - prev_beat) * ms_per_beat < SLIDE_FLOW_MS
+            {
+                *direction = neighbour_direction(prev_dir, &mut rng_state);
+            }
+            last_slide = Some((note.beat, *direction));
+        }
         entries.push(ChartNoteEntry {
             beat: note.beat,
             note_type,
@@ -23,6 +37,86 @@ pub fn assign_note_types(
     entries
 }
 
+/// How a difficulty mixes in advanced note types. Percentages are of the
+/// per-note roll; the off-phrase ones are checked in field order, so their sum
+/// must stay under 100.
+struct TypeMix {
+    /// Notes above this strength on a downbeat become holds or criticals.
+    downbeat_strength: f32,
+    /// Share of those strong downbeats that are criticals instead of holds.
+    downbeat_critical: u64,
+    /// Keep 16th-note pairs as taps so they stay readable.
+    protect_rapid_pairs: bool,
+    slide: u64,
+    beat: u64,
+    critical: u64,
+    dual_slide: u64,
+    scratch: u64,
+    /// Only taken by weak (strength < 0.3) notes.
+    rest: u64,
+}
+
+/// Easy: taps, with holds on the strongest downbeats.
+const EASY_MIX: TypeMix = TypeMix {
+    downbeat_strength: 0.8,
+    downbeat_critical: 0,
+    protect_rapid_pairs: false,
+    slide: 0,
+    beat: 0,
+    critical: 0,
+    dual_slide: 0,
+    scratch: 0,
+    rest: 0,
+};
+
+/// Normal adds slides.
+const NORMAL_MIX: TypeMix = TypeMix {
+    downbeat_strength: 0.8,
+    downbeat_critical: 0,
+    protect_rapid_pairs: false,
+    slide: 15,
+    beat: 0,
+    critical: 0,
+    dual_slide: 0,
+    scratch: 0,
+    rest: 0,
+};
+
+/// Hard adds beats and critical downbeats.
+const HARD_MIX: TypeMix = TypeMix {
+    downbeat_strength: 0.85,
+    downbeat_critical: 40,
+    protect_rapid_pairs: true,
+    slide: 20,
+    beat: 8,
+    critical: 0,
+    dual_slide: 0,
+    scratch: 0,
+    rest: 0,
+};
+
+/// Expert adds dual slides, scratches and rests on top of everything else.
+const EXPERT_MIX: TypeMix = TypeMix {
+    downbeat_strength: 0.9,
+    downbeat_critical: 30,
+    protect_rapid_pairs: true,
+    slide: 20,
+    beat: 6,
+    critical: 5,
+    dual_slide: 6,
+    scratch: 5,
+    rest: 5,
+};
+
+fn type_mix(difficulty: Difficulty) -> &'static TypeMix {
+    match difficulty {
+        Difficulty::Easy => &EASY_MIX,
+        Difficulty::Normal => &NORMAL_MIX,
+        Difficulty::Hard => &HARD_MIX,
+        Difficulty::Expert => &EXPERT_MIX,
+    }
+}
+
 fn pick_note_type(
     notes: &[ScoredNote],
     idx: usize,
@@ -31,97 +125,117 @@ fn pick_note_type(
     rng: &mut u64,
 ) -> ChartNoteType {
     let note = &notes[idx];
+    let mix = type_mix(difficulty);
+    let roll = pseudo_random(rng) % 100;
 
-    match difficulty {
-        Difficulty::Easy => {
-            // Easy: mostly taps, occasional holds on strong beats
-            if note.strength > 0.8 && is_downbeat(note.beat) {
-                // Strong downbeats get holds
-                let duration = if idx + 1 < notes.len() {
-                    let gap = notes[idx + 1].beat - note.beat;
-                    (gap * 0.75).min(2.0).max(0.5) // Hold for 75% of gap, max 2 beats
-                } else {
-                    1.0
-                };
-                ChartNoteType::Hold {
-                    duration_beats: duration,
-                }
-            } else {
-                ChartNoteType::Tap
-            }
+    if note.strength > mix.downbeat_strength && is_downbeat(note.beat) {
+        // Strong downbeats: hold or critical
+        if roll < mix.downbeat_critical {
+            return ChartNoteType::Critical;
         }
+        return ChartNoteType::Hold {
+            duration_beats: hold_duration(notes, idx),
+        };
+    }
+    if mix.protect_rapid_pairs && is_rapid_pair(notes, idx) {
+        return ChartNoteType::Tap;
+    }
 
-        Difficulty::Normal => {
-            let roll = pseudo_random(rng) % 100;
-
-            if note.strength > 0.8 && is_downbeat(note.beat) {
-                // Strong downbeats: hold
-                let duration = hold_duration(notes, idx);
-                ChartNoteType::Hold {
-                    duration_beats: duration,
-                }
-            } else if roll < 15 {
-                // 15% slides
-                let dir = pick_slide_direction(note.beat, rng);
-                ChartNoteType::Slide { direction: dir }
-            } else {
-                ChartNoteType::Tap
-            }
+    let mut threshold = mix.slide;
+    if roll < threshold {
+        let dir = pick_slide_direction(note.beat, rng);
+        return ChartNoteType::Slide { direction: dir };
+    }
+    threshold += mix.beat;
+    if roll < threshold {
+        return ChartNoteType::Beat;
+    }
+    threshold += mix.critical;
+    if roll < threshold {
+        return ChartNoteType::Critical;
+    }
+    threshold += mix.dual_slide;
+    if roll < threshold {
+        let left = pick_slide_direction(note.beat, rng);
+        let right = pick_slide_direction(note.beat + 0.5, rng);
+        return ChartNoteType::DualSlide { left, right };
+    }
+    threshold += mix.scratch;
+    if roll < threshold {
+        return ChartNoteType::Scratch;
+    }
+    threshold += mix.rest;
+    if roll < threshold && note.strength < 0.3 {
+        // Low-strength off-beat notes become rests
+        return ChartNoteType::Rest;
+    }
+    ChartNoteType::Tap
+}
+
+// --- Per-type spacing ---
+
+/// Minimum gap in milliseconds between notes of the same kind of gesture.
+/// Taps are already spaced by `--min-interval` at onset detection.
+#[derive(Debug, Clone, Copy)]
+pub struct TypeIntervals {
+    /// Start-to-start gap between slides, dual slides and scratches.
+    pub slide_ms: f64,
+    /// Gap from the end of one hold to the start of the next.
+    pub hold_ms: f64,
+}
+
+impl TypeIntervals {
+    pub fn for_difficulty(difficulty: Difficulty) -> Self {
+        match difficulty {
+            Difficulty::Easy => Self { slide_ms: 600.0, hold_ms: 500.0 },
+            Difficulty::Normal => Self { slide_ms: 400.0, hold_ms: 350.0 },
+            Difficulty::Hard => Self { slide_ms: 250.0, hold_ms: 250.0 },
+            Difficulty::Expert => Self { slide_ms: 150.0, hold_ms: 150.0 },
         }
+    }
+}
 
-        Difficulty::Hard => {
-            let roll = pseudo_random(rng) % 100;
-
-            if note.strength > 0.85 && is_downbeat(note.beat) {
-                // Strong downbeats: hold or critical
-                if roll < 40 {
-                    ChartNoteType::Critical
-                } else {
-                    let duration = hold_duration(notes, idx);
-                    ChartNoteType::Hold {
-                        duration_beats: duration,
-                    }
-                }
-            } else if is_rapid_pair(notes, idx) {
-                ChartNoteType::Tap
-            } else if roll < 25 {
-                let dir = pick_slide_direction(note.beat, rng);
-                ChartNoteType::Slide { direction: dir }
-            } else {
-                ChartNoteType::Tap
-            }
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Gesture {
+    Slide,
+    Hold,
+}
+
+/// Which spacing rule a note type follows, and how many beats it lasts.
+fn gesture(note_type: &ChartNoteType) -> Option<(Gesture, f64)> {
+    match note_type {
+        ChartNoteType::Slide { .. } | ChartNoteType::DualSlide { .. } | ChartNoteType::Scratch => {
+            Some((Gesture::Slide, 0.0))
         }
+        ChartNoteType::Hold { duration_beats }
+        | ChartNoteType::SlideHold { duration_beats, .. }
+        | ChartNoteType::CriticalHold { duration_beats } => Some((Gesture::Hold, *duration_beats)),
+        _ => None,
+    }
+}
 
-        Difficulty::Expert => {
-            let roll = pseudo_random(rng) % 100;
-
-            if note.strength > 0.9 && is_downbeat(note.beat) {
-                // Strongest downbeats: critical or hold
-                if roll < 30 {
-                    ChartNoteType::Critical
-                } else {
-                    let duration = hold_duration(notes, idx);
-                    ChartNoteType::Hold {
-                        duration_beats: duration,
-                    }
-                }
-            } else if is_rapid_pair(notes, idx) {
-                ChartNoteType::Tap
-            } else if roll < 20 {
-                let dir = pick_slide_direction(note.beat, rng);
-                ChartNoteType::Slide { direction: dir }
-            } else if roll < 28 {
-                let dir = pick_slide_direction(note.beat, rng);
-                ChartNoteType::Slide { direction: dir }
-            } else if roll < 33 {
-                ChartNoteType::Critical
-            } else if roll < 38 && note.strength < 0.3 {
-                // Low-strength off-beat notes become rests
-                ChartNoteType::Rest
-            } else {
-                ChartNoteType::Tap
-            }
+/// Downgrade slides and holds that come too soon after the previous one of
+/// the same kind to plain taps, keeping the rhythm but giving the gesture room.
+pub fn enforce_type_intervals(notes: &mut [ChartNoteEntry], intervals: TypeIntervals, bpm: f64) {
+    let ms_per_beat = 60_000.0 / bpm;
+    let mut last_slide: Option<f64> = None;
+    let mut last_hold_end: Option<f64> = None;
+
+    for note in notes.iter_mut() {
+        let Some((kind, duration)) = gesture(&note.note_type) else {
+            continue;
+        };
+        let (last, min_ms) = match kind {
+            Gesture::Slide => (&mut last_slide, intervals.slide_ms),
+            Gesture::Hold => (&mut last_hold_end, intervals.hold_ms),
+        };
+        if let Some(prev) = *last
+            && (note.beat - prev) * ms_per_beat < min_ms
+        {
+            note.note_type = ChartNoteType::Tap;
+            continue;
         }
+        *last = Some(note.beat + duration);
     }
 }
 
@@ -138,6 +252,28 @@ fn is_rapid_pair(notes: &[ScoredNote], idx: usize) -> bool {
     gap <= 0.25 + 0.01 // 16th note or closer
 }
 
+/// With band-split onsets, kicks play as taps and snares/hats as slides.
+///
+/// Holds, criticals, rests and the difficulty-gated types (beats, dual slides,
+/// scratches) keep their role; Easy stays slide-free.
+fn apply_band(
+    note_type: ChartNoteType,
+    note: &ScoredNote,
+    difficulty: Difficulty,
+    rng: &mut u64,
+) -> ChartNoteType {
+    if difficulty == Difficulty::Easy {
+        return note_type;
+    }
+    match (note.band, note_type) {
+        (OnsetBand::Low, ChartNoteType::Slide { .. }) => ChartNoteType::Tap,
+        (OnsetBand::High, ChartNoteType::Tap) => ChartNoteType::Slide {
+            direction: pick_slide_direction(note.beat, rng),
+        },
+        (_, other) => other,
+    }
+}
+
 fn hold_duration(notes: &[ScoredNote], idx: usize) -> f64 {
     if idx + 1 < notes.len() {
         let gap = notes[idx + 1].beat - notes[idx].beat;
@@ -147,6 +283,23 @@ fn hold_duration(notes: &[ScoredNote], idx: usize) -> f64 {
     }
 }
 
+/// Slides closer than this to the previous slide keep the hand moving the same
+/// way instead of whipping across the stick.
+const SLIDE_FLOW_MS: f64 = 300.0;
+
+fn sector(direction: SlideDirection) -> usize {
+    SlideDirection::ALL
+        .iter()
+        .position(|d| *d == direction)
+        .unwrap_or(0)
+}
+
+/// `prev` or one of the two directions 45° either side of it.
+fn neighbour_direction(prev: SlideDirection, rng: &mut u64) -> SlideDirection {
+    let step = (pseudo_random(rng) % 3) as usize; // 0 = left, 1 = same, 2 = right
+    SlideDirection::ALL[(sector(prev) + 7 + step) % 8]
+}
+
 fn pick_slide_direction(seed: f64, rng: &mut u64) -> SlideDirection {
     let combined = (seed * 1000.0) as u64 ^ pseudo_random(rng);
     SlideDirection::ALL[(combined % 8) as usize]
@@ -159,3 +312,92 @@ fn pseudo_random(state: &mut u64) -> u64 {
     *state ^= *state << 17;
     *state
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scored_notes() -> Vec<ScoredNote> {
+        (0..400)
+            .map(|i| ScoredNote {
+                beat: i as f64 * 0.5,
+                strength: [0.2, 0.5, 0.7, 0.95][i % 4],
+                importance: 0.5,
+                band: OnsetBand::Full,
+            })
+            .collect()
+    }
+
+    fn is_expert_only(note_type: &ChartNoteType) -> bool {
+        matches!(note_type, ChartNoteType::DualSlide { .. } | ChartNoteType::Scratch)
+    }
+
+    #[test]
+    fn advanced_types_are_gated_by_difficulty() {
+        let notes = scored_notes();
+
+        let easy = assign_note_types(&notes, Difficulty::Easy, 120.0);
+        assert!(!easy.iter().any(|n| is_expert_only(&n.note_type)));
+        assert!(!easy.iter().any(|n| matches!(n.note_type, ChartNoteType::Slide { .. })));
+
+        let expert = assign_note_types(&notes, Difficulty::Expert, 120.0);
+        assert!(expert.iter().any(|n| matches!(n.note_type, ChartNoteType::DualSlide { .. })));
+        assert!(expert.iter().any(|n| matches!(n.note_type, ChartNoteType::Scratch)));
+    }
+
+    #[test]
+    fn close_slides_turn_at_most_one_sector() {
+        // 0.2 beats at 120 BPM = 100 ms; high-band notes all become slides
+        let notes: Vec<ScoredNote> = (0..64)
+            .map(|i| ScoredNote {
+                beat: 0.1 + i as f64 * 0.2,
+                strength: 0.5,
+                importance: 0.5,
+                band: OnsetBand::High,
+            })
+            .collect();
+        let entries = assign_note_types(&notes, Difficulty::Expert, 120.0);
+
+        let slides: Vec<(f64, SlideDirection)> = entries
+            .iter()
+            .filter_map(|n| match n.note_type {
+                ChartNoteType::Slide { direction } => Some((n.beat, direction)),
+                _ => None,
+            })
+            .collect();
+        assert!(slides.len() > 32);
+        for pair in slides.windows(2) {
+            let (a_beat, a) = pair[0];
+            let (b_beat, b) = pair[1];
+            if (b_beat - a_beat) * 500.0 > 100.0 + 1e-6 {
+                continue;
+            }
+            let diff = (sector(a) + 8 - sector(b)) % 8;
+            assert!(diff.min(8 - diff) <= 1, "{a:?} -> {b:?} at beat {b_beat}");
+        }
+    }
+
+    #[test]
+    fn close_slides_are_thinned_to_taps() {
+        // 32nd-note slides at 120 BPM land 62.5 ms apart
+        let mut notes: Vec<ChartNoteEntry> = (0..32)
+            .map(|i| ChartNoteEntry {
+                beat: i as f64 * 0.125,
+                note_type: ChartNoteType::Slide {
+                    direction: SlideDirection::N,
+                },
+            })
+            .collect();
+        let intervals = TypeIntervals::for_difficulty(Difficulty::Hard);
+        enforce_type_intervals(&mut notes, intervals, 120.0);
+
+        let slide_beats: Vec<f64> = notes
+            .iter()
+            .filter(|n| matches!(n.note_type, ChartNoteType::Slide { .. }))
+            .map(|n| n.beat)
+            .collect();
+        assert_eq!(notes.len(), 32, "thinned slides stay as taps");
+        assert!(slide_beats.len() < 32 && !slide_beats.is_empty());
+        assert!(slide_beats.windows(2).all(|w| (w[1] - w[0]) * 500.0 >= intervals.slide_ms));
+    }
+}
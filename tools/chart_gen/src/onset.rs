@@ -1,5 +1,32 @@
 use crate::stft::Spectrogram;
 
+/// Kick drum range in Hz.
+const LOW_BAND_HZ: (f64, f64) = (20.0, 150.0);
+/// Snare crack and hi-hat range in Hz.
+const HIGH_BAND_HZ: (f64, f64) = (2000.0, 11000.0);
+
+/// Frequency band an onset was detected in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnsetBand {
+    /// Whole spectrum (band split disabled).
+    Full,
+    /// Kick range.
+    Low,
+    /// Snare/hat range.
+    High,
+}
+
+impl OnsetBand {
+    /// Frequency range in Hz, or None for the whole spectrum.
+    fn range_hz(self) -> Option<(f64, f64)> {
+        match self {
+            OnsetBand::Full => None,
+            OnsetBand::Low => Some(LOW_BAND_HZ),
+            OnsetBand::High => Some(HIGH_BAND_HZ),
+        }
+    }
+}
+
 /// A detected onset event.
 #[derive(Debug, Clone)]
 pub struct OnsetEvent {
@@ -9,49 +36,59 @@ pub struct OnsetEvent {
     pub strength: f32,
     /// Time in seconds.
     pub time_seconds: f64,
+    /// Band the onset was detected in.
+    pub band: OnsetBand,
 }
 
 /// Detect onsets using spectral flux with adaptive peak picking.
 ///
 /// - `sensitivity`: threshold multiplier (default 1.5). Higher = fewer onsets.
 /// - `min_interval_ms`: minimum time between onsets in milliseconds (default 50).
+/// - `window_ms`: span of the moving threshold window in milliseconds (default 500).
+/// - `band_split`: detect separately in the low and high bands and tag each onset.
 pub fn detect_onsets(
     spectrogram: &Spectrogram,
     sensitivity: f64,
     min_interval_ms: f64,
+    window_ms: f64,
+    band_split: bool,
 ) -> Vec<OnsetEvent> {
-    let num_frames = spectrogram.frames.len();
-    if num_frames < 2 {
-        return Vec::new();
+    if !band_split {
+        return detect_in_band(spectrogram, OnsetBand::Full, sensitivity, min_interval_ms, window_ms);
     }
 
-    // Stage 1: Compute spectral flux
-    let mut flux: Vec<f32> = Vec::with_capacity(num_frames);
-    flux.push(0.0); // First frame has no predecessor
+    let mut onsets = detect_in_band(spectrogram, OnsetBand::Low, sensitivity, min_interval_ms, window_ms);
+    onsets.extend(detect_in_band(spectrogram, OnsetBand::High, sensitivity, min_interval_ms, window_ms));
+    onsets.sort_by_key(|o| o.frame);
+    onsets
+}
 
-    for i in 1..num_frames {
-        let prev = &spectrogram.frames[i - 1];
-        let curr = &spectrogram.frames[i];
-        let sf: f32 = curr
-            .iter()
-            .zip(prev.iter())
-            .map(|(c, p)| (c - p).max(0.0))
-            .sum();
-        flux.push(sf);
+fn detect_in_band(
+    spectrogram: &Spectrogram,
+    band: OnsetBand,
+    sensitivity: f64,
+    min_interval_ms: f64,
+    window_ms: f64,
+) -> Vec<OnsetEvent> {
+    let num_frames = spectrogram.frames.len();
+    if num_frames < 2 {
+        return Vec::new();
     }
 
-    // Normalize flux to 0-1
-    let max_flux = flux.iter().cloned().fold(0.0f32, f32::max);
-    if max_flux > 0.0 {
-        for v in &mut flux {
-            *v /= max_flux;
+    let bins = match band.range_hz() {
+        Some((lo, hi)) => {
+            spectrogram.hz_to_bin(lo).max(1)..spectrogram.hz_to_bin(hi).min(spectrogram.num_bins() - 1) + 1
         }
-    }
+        None => 0..spectrogram.num_bins(),
+    };
 
-    // Stage 2: Adaptive peak picking
-    // Moving average window (~0.5 seconds)
-    let avg_window = (0.5 * spectrogram.sample_rate as f64 / spectrogram.hop_size as f64) as usize;
-    let avg_window = avg_window.max(3);
+    // Stage 1: Spectral flux over the band
+    let flux = spectral_flux(spectrogram, bins);
+
+    // Stage 2: Adaptive peak picking against a moving local threshold
+    let threshold_window = (window_ms / 1000.0 * spectrogram.sample_rate as f64
+        / spectrogram.hop_size as f64) as usize;
+    let threshold_window = threshold_window.max(3);
 
     let min_interval_frames =
         (min_interval_ms / 1000.0 * spectrogram.sample_rate as f64 / spectrogram.hop_size as f64)
@@ -65,18 +102,9 @@ pub fn detect_onsets(
     let mut last_onset_frame: Option<usize> = None;
 
     for i in 1..num_frames {
-        // Compute local mean and stddev
-        let window_start = i.saturating_sub(avg_window / 2);
-        let window_end = (i + avg_window / 2 + 1).min(num_frames);
-        let window_slice = &flux[window_start..window_end];
-
-        let n = window_slice.len() as f64;
-        let mean = window_slice.iter().map(|&v| v as f64).sum::<f64>() / n;
-        let variance =
-            window_slice.iter().map(|&v| (v as f64 - mean).powi(2)).sum::<f64>() / n;
-        let std_dev = variance.sqrt();
-
-        let threshold = mean + sensitivity * std_dev;
+        let window_start = i.saturating_sub(threshold_window / 2);
+        let window_end = (i + threshold_window / 2 + 1).min(num_frames);
+        let threshold = local_threshold(&flux[window_start..window_end], sensitivity);
 
         // Check if this frame is a peak above threshold
         if (flux[i] as f64) < threshold {
@@ -91,8 +119,11 @@ pub fn detect_onsets(
             continue;
         }
 
-        // Silence gate: check frame energy
-        let energy = spectrogram.frame_energy(i);
+        // Silence gate: check frame energy (within the band when split)
+        let energy = match band.range_hz() {
+            Some((lo, hi)) => spectrogram.band_energy(i, lo, hi),
+            None => spectrogram.frame_energy(i),
+        };
         if energy < silence_threshold {
             continue;
         }
@@ -109,9 +140,117 @@ pub fn detect_onsets(
             frame: i,
             strength: flux[i],
             time_seconds: time,
+            band,
         });
         last_onset_frame = Some(i);
     }
 
     onsets
 }
+
+/// Positive spectral flux per frame over `bins`, normalized to 0-1.
+fn spectral_flux(spectrogram: &Spectrogram, bins: std::ops::Range<usize>) -> Vec<f32> {
+    let num_frames = spectrogram.frames.len();
+    let mut flux: Vec<f32> = Vec::with_capacity(num_frames);
+    flux.push(0.0); // First frame has no predecessor
+
+    for i in 1..num_frames {
+        let prev = &spectrogram.frames[i - 1];
+        let curr = &spectrogram.frames[i];
+        let sf: f32 = curr
+            .iter()
+            .zip(prev.iter())
+            .skip(bins.start)
+            .take(bins.len())
+            .map(|(c, p)| (c - p).max(0.0))
+            .sum();
+        flux.push(sf);
+    }
+
+    let max_flux = flux.iter().cloned().fold(0.0f32, f32::max);
+    if max_flux > 0.0 {
+        for v in &mut flux {
+            *v /= max_flux;
+        }
+    }
+    flux
+}
+
+/// Peak threshold for one frame: the window's median plus `sensitivity`
+/// standard deviations. Both terms follow the local level, so quiet passages
+/// get a proportionally lower bar than loud ones.
+fn local_threshold(window: &[f32], sensitivity: f64) -> f64 {
+    let mut sorted: Vec<f64> = window.iter().map(|&v| v as f64).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let median = sorted[sorted.len() / 2];
+
+    let n = sorted.len() as f64;
+    let mean = sorted.iter().sum::<f64>() / n;
+    let variance = sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+
+    median + sensitivity * variance.sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stft::WINDOW_SIZE;
+
+    const SAMPLE_RATE: u32 = 44_100;
+
+    /// Kick-like bursts: energy only around 60-100 Hz, pulsing every 0.5s.
+    fn low_only_spectrogram() -> Spectrogram {
+        let mut spectrogram = Spectrogram {
+            frames: Vec::new(),
+            hop_size: 512,
+            sample_rate: SAMPLE_RATE,
+        };
+        let kick_bins = spectrogram.hz_to_bin(60.0)..=spectrogram.hz_to_bin(100.0);
+        let frames_per_beat = (0.5 * SAMPLE_RATE as f64 / 512.0) as usize;
+        spectrogram.frames = (0..frames_per_beat * 16)
+            .map(|i| {
+                let decay = (-((i % frames_per_beat) as f32) / 4.0).exp();
+                let mut frame = vec![0.0f32; WINDOW_SIZE / 2 + 1];
+                for bin in kick_bins.clone() {
+                    frame[bin] = decay;
+                }
+                frame
+            })
+            .collect();
+        spectrogram
+    }
+
+    #[test]
+    fn low_band_signal_only_yields_low_onsets() {
+        let spectrogram = low_only_spectrogram();
+
+        let onsets = detect_onsets(&spectrogram, 1.5, 50.0, 500.0, true);
+        assert!(onsets.len() >= 8, "expected kicks, got {}", onsets.len());
+        assert!(onsets.iter().all(|o| o.band == OnsetBand::Low));
+
+        let full = detect_onsets(&spectrogram, 1.5, 50.0, 500.0, false);
+        assert!(full.iter().all(|o| o.band == OnsetBand::Full));
+    }
+
+    #[test]
+    fn quiet_passage_still_triggers() {
+        // Eight loud kicks, then eight at 2% of the level
+        let mut spectrogram = low_only_spectrogram();
+        let half = spectrogram.frames.len() / 2;
+        for frame in &mut spectrogram.frames[half..] {
+            for m in frame.iter_mut() {
+                *m *= 0.02;
+            }
+        }
+        let quiet_start = spectrogram.frame_to_seconds(half);
+
+        // A fixed song-wide threshold sits above every quiet peak
+        let flux = spectral_flux(&spectrogram, 0..spectrogram.num_bins());
+        let global = local_threshold(&flux, 1.5);
+        assert!(flux[half..].iter().all(|&v| (v as f64) < global));
+
+        let onsets = detect_onsets(&spectrogram, 1.5, 50.0, 500.0, false);
+        let quiet = onsets.iter().filter(|o| o.time_seconds >= quiet_start).count();
+        assert!(quiet >= 7, "only {quiet} onsets in the quiet half");
+    }
+}
@@ -0,0 +1,162 @@
+//! Export to the osu! `.osu` text format (single-column mania).
+//!
+//! Only timing and rhythm carry over: every note becomes a circle or a hold
+//! in one column, and the path has no osu! equivalent.
+
+use std::fmt::Write;
+
+use crate::chart::{ChartFile, ChartNoteType, ChartTimingPoint, Difficulty, SongMetadata};
+
+/// Playfield x of the only column in a one-key mania map.
+const COLUMN_X: u32 = 256;
+/// Playfield y is ignored by mania but still required.
+const COLUMN_Y: u32 = 192;
+
+/// Render `chart` as a `.osu` file. Without metadata the song fields are left
+/// for the user to fill in.
+pub fn export_osu(chart: &ChartFile, metadata: Option<&SongMetadata>) -> String {
+    let mut out = String::new();
+    let (title, artist, creator, audio) = match metadata {
+        Some(m) => (m.title.as_str(), m.artist.as_str(), m.charter.as_str(), m.audio_file.as_str()),
+        None => ("Unknown", "Unknown", "chart_gen", ""),
+    };
+    let preview = metadata.map_or(-1, |m| m.preview_start_ms as i64);
+
+    out.push_str("osu file format v14\n\n");
+
+    out.push_str("[General]\n");
+    let _ = writeln!(out, "AudioFilename: {audio}");
+    out.push_str("AudioLeadIn: 0\n");
+    let _ = writeln!(out, "PreviewTime: {preview}");
+    out.push_str("Mode: 3\n\n");
+
+    out.push_str("[Metadata]\n");
+    let _ = writeln!(out, "Title:{title}");
+    let _ = writeln!(out, "Artist:{artist}");
+    let _ = writeln!(out, "Creator:{creator}");
+    let _ = writeln!(out, "Version:{}", version_name(chart.difficulty));
+    out.push_str("Source:\nTags:funktrack\n\n");
+
+    out.push_str("[Difficulty]\n");
+    out.push_str("HPDrainRate:5\n");
+    out.push_str("CircleSize:1\n");
+    let _ = writeln!(out, "OverallDifficulty:{}", chart.difficulty_rating.min(10));
+    out.push_str("ApproachRate:5\n");
+    out.push_str("SliderMultiplier:1.4\n");
+    out.push_str("SliderTickRate:1\n\n");
+
+    out.push_str("[TimingPoints]\n");
+    for tp in &chart.timing_points {
+        let time = beat_to_ms(&chart.timing_points, tp.beat).round() as i64;
+        let beat_length = 60_000.0 / tp.bpm;
+        let _ = writeln!(out, "{time},{beat_length},{},1,0,100,1,0", tp.time_signature.0);
+    }
+    out.push('\n');
+
+    out.push_str("[HitObjects]\n");
+    for note in &chart.notes {
+        let time = beat_to_ms(&chart.timing_points, note.beat).round() as i64;
+        match hold_beats(&note.note_type) {
+            Some(duration) => {
+                let end = beat_to_ms(&chart.timing_points, note.beat + duration).round() as i64;
+                let _ = writeln!(out, "{COLUMN_X},{COLUMN_Y},{time},128,0,{end}:0:0:0:0:");
+            }
+            None => {
+                // Rests have nothing to hit, and optional ad-libs would turn mandatory
+                if matches!(note.note_type, ChartNoteType::Rest | ChartNoteType::AdLib) {
+                    continue;
+                }
+                let _ = writeln!(out, "{COLUMN_X},{COLUMN_Y},{time},1,0,0:0:0:0:");
+            }
+        }
+    }
+
+    out
+}
+
+fn version_name(difficulty: Difficulty) -> &'static str {
+    match difficulty {
+        Difficulty::Easy => "Easy",
+        Difficulty::Normal => "Normal",
+        Difficulty::Hard => "Hard",
+        Difficulty::Expert => "Expert",
+    }
+}
+
+/// Length in beats of notes that become osu! holds.
+fn hold_beats(note_type: &ChartNoteType) -> Option<f64> {
+    match note_type {
+        ChartNoteType::Hold { duration_beats }
+        | ChartNoteType::SlideHold { duration_beats, .. }
+        | ChartNoteType::CriticalHold { duration_beats } => Some(*duration_beats),
+        _ => None,
+    }
+}
+
+/// Song time of `beat`, walking the tempo changes before it.
+fn beat_to_ms(timing_points: &[ChartTimingPoint], beat: f64) -> f64 {
+    let mut ms = 0.0;
+    let mut seg_beat = 0.0;
+    let mut bpm = timing_points.first().map_or(120.0, |tp| tp.bpm);
+    for tp in timing_points.iter().skip(1) {
+        if tp.beat > beat {
+            break;
+        }
+        ms += (tp.beat - seg_beat) * 60_000.0 / bpm;
+        seg_beat = tp.beat;
+        bpm = tp.bpm;
+    }
+    ms + (beat - seg_beat) * 60_000.0 / bpm
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chart::ChartNoteEntry;
+
+    /// Lines of one `[Section]` of an exported file.
+    fn section<'a>(osu: &'a str, name: &str) -> Vec<&'a str> {
+        osu.lines()
+            .skip_while(|line| *line != format!("[{name}]"))
+            .skip(1)
+            .take_while(|line| !line.is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn exported_file_parses_back() {
+        let chart = ChartFile {
+            difficulty: Difficulty::Hard,
+            difficulty_rating: 7,
+            timing_points: vec![ChartTimingPoint {
+                beat: 0.0,
+                bpm: 150.0,
+                time_signature: (4, 4),
+            }],
+            path_segments: Vec::new(),
+            notes: vec![
+                ChartNoteEntry { beat: 1.0, note_type: ChartNoteType::Tap },
+                ChartNoteEntry { beat: 2.0, note_type: ChartNoteType::Hold { duration_beats: 1.5 } },
+                ChartNoteEntry { beat: 4.0, note_type: ChartNoteType::Critical },
+                ChartNoteEntry { beat: 5.0, note_type: ChartNoteType::Rest },
+            ],
+            events: Vec::new(),
+            travel_beats: 3.0,
+            look_ahead_beats: 3.0,
+        };
+        let osu = export_osu(&chart, None);
+
+        let timing = section(&osu, "TimingPoints");
+        assert_eq!(timing.len(), 1);
+        let beat_length: f64 = timing[0].split(',').nth(1).unwrap().parse().unwrap();
+        assert!((60_000.0 / beat_length - 150.0).abs() < 1e-9);
+
+        // The rest has nothing to hit, so it is left out
+        let objects = section(&osu, "HitObjects");
+        assert_eq!(objects.len(), 3);
+        let hold: Vec<&str> = objects[1].split(',').collect();
+        assert_eq!(hold[2], "800");
+        assert_eq!(hold[3], "128");
+        assert!(hold[5].starts_with("1400:"));
+    }
+}
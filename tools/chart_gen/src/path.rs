@@ -1,6 +1,7 @@
 use noise::{NoiseFn, Perlin};
 
 use crate::chart::PathSegment;
+use crate::decode::AudioData;
 use crate::stft::Spectrogram;
 
 /// Screen bounds for path generation.
@@ -8,13 +9,25 @@ const SCREEN_HALF_WIDTH: f32 = 600.0;
 const SCREEN_HALF_HEIGHT: f32 = 360.0; // 720p / 2
 const Y_CLAMP: f32 = SCREEN_HALF_HEIGHT * 0.4; // ±40% of screen height
 
+/// Expressive path: horizontal swing at full stereo pan (px).
+const PAN_NUDGE: f32 = 80.0;
+/// Expressive path: per-beat vertical push at the centroid extremes (px).
+const CENTROID_NUDGE: f32 = 40.0;
+/// Centroid range mapped onto -1..1 (log scale), dark to bright.
+const CENTROID_RANGE_HZ: (f64, f64) = (250.0, 6000.0);
+/// How quickly the pan swing follows the balance (fraction per beat).
+const PAN_FOLLOW: f32 = 0.35;
+
 /// Generate an audio-reactive CatmullRom path from spectrogram data.
 ///
 /// Places one control point per beat, with Y driven by sub-band energy + Perlin noise.
+/// With `expressive` audio, X also swings with the stereo balance and Y rises
+/// with spectral brightness.
 pub fn generate_path(
     spectrogram: &Spectrogram,
     total_beats: f64,
     bpm: f64,
+    expressive: Option<&AudioData>,
 ) -> PathSegment {
     let num_points = (total_beats.ceil() as usize + 1).max(4);
     let perlin = Perlin::new(42);
@@ -28,6 +41,7 @@ pub fn generate_path(
 
     let mut points = Vec::with_capacity(num_points);
     let mut y = 0.0f32;
+    let mut pan_offset = 0.0f32;
 
     for i in 0..num_points {
         let beat = i as f64;
@@ -53,6 +67,19 @@ pub fn generate_path(
 
             // Combine components
             y += bass_sweep * 0.3 + high_oscillation + noise_component * 0.5;
+
+            if let Some(audio) = expressive {
+                // Brighter timbre climbs, darker sinks
+                y += centroid_position(spectrogram.spectral_centroid(frame)) * CENTROID_NUDGE;
+
+                // Drift toward the side the mix leans to over this beat
+                if let Some(ref stereo) = audio.stereo {
+                    let start = (time * audio.sample_rate as f64) as usize;
+                    let end = ((time + seconds_per_beat) * audio.sample_rate as f64) as usize;
+                    let target = stereo.balance(start, end) * PAN_NUDGE;
+                    pan_offset += (target - pan_offset) * PAN_FOLLOW;
+                }
+            }
         }
 
         // Mean-reversion spring
@@ -61,7 +88,7 @@ pub fn generate_path(
         // Soft sigmoid clamp
         y = soft_clamp(y, Y_CLAMP);
 
-        points.push((x, y));
+        points.push((x + pan_offset, y));
     }
 
     PathSegment::CatmullRom {
@@ -71,6 +98,16 @@ pub fn generate_path(
     }
 }
 
+/// Map a centroid in Hz onto -1..1 across `CENTROID_RANGE_HZ` (log scale).
+fn centroid_position(centroid_hz: f64) -> f32 {
+    if centroid_hz <= 0.0 {
+        return 0.0;
+    }
+    let (lo, hi) = CENTROID_RANGE_HZ;
+    let t = (centroid_hz.ln() - lo.ln()) / (hi.ln() - lo.ln());
+    (t.clamp(0.0, 1.0) * 2.0 - 1.0) as f32
+}
+
 /// Fractal Brownian Motion (Perlin noise with multiple octaves).
 fn fbm(perlin: &Perlin, x: f64, octaves: u32, persistence: f64, lacunarity: f64) -> f64 {
     let mut value = 0.0;
@@ -96,3 +133,48 @@ fn soft_clamp(value: f32, limit: f32) -> f32 {
     // tanh-based soft clamp: approaches ±limit asymptotically
     (value / limit).tanh() * limit
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::StereoSamples;
+
+    const SAMPLE_RATE: u32 = 44_100;
+    const HOP: usize = 512;
+
+    #[test]
+    fn hard_left_pan_pulls_path_left() {
+        // 16 beats at 120 BPM; the mix is centred, then hard left from beat 8
+        let bpm = 120.0;
+        let total_samples = SAMPLE_RATE as usize * 8;
+        let half = total_samples / 2;
+        let tone: Vec<f32> = (0..total_samples).map(|i| (i as f32 * 0.05).sin() * 0.5).collect();
+        let right: Vec<f32> = tone.iter().enumerate().map(|(i, &s)| if i < half { s } else { 0.0 }).collect();
+        let audio = AudioData {
+            samples: tone.clone(),
+            sample_rate: SAMPLE_RATE,
+            lead_offset_seconds: 0.0,
+            stereo: Some(StereoSamples { left: tone, right }),
+        };
+        let spectrogram = Spectrogram {
+            frames: vec![vec![0.1; 1025]; total_samples / HOP],
+            hop_size: HOP,
+            sample_rate: SAMPLE_RATE,
+        };
+
+        let points = |seg: PathSegment| match seg {
+            PathSegment::CatmullRom { points, .. } => points,
+            other => panic!("expected a CatmullRom path, got {other:?}"),
+        };
+        let plain = points(generate_path(&spectrogram, 16.0, bpm, None));
+        let expressive = points(generate_path(&spectrogram, 16.0, bpm, Some(&audio)));
+
+        // Centred section keeps its X; the hard-left section moves left
+        for i in 0..8 {
+            assert!((expressive[i].0 - plain[i].0).abs() < 1.0, "point {i} moved while centred");
+        }
+        for i in 10..16 {
+            assert!(expressive[i].0 < plain[i].0 - 20.0, "point {i} did not move left");
+        }
+    }
+}
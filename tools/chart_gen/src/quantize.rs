@@ -1,5 +1,5 @@
 use crate::beat::BeatGrid;
-use crate::onset::OnsetEvent;
+use crate::onset::{OnsetBand, OnsetEvent};
 
 /// A note quantized to the beat grid.
 #[derive(Debug, Clone)]
@@ -10,19 +10,27 @@ pub struct QuantizedNote {
     pub strength: f32,
     /// Original time in seconds (before quantization).
     pub original_time: f64,
+    /// Band the onset was detected in.
+    pub band: OnsetBand,
 }
 
 /// Quantize detected onsets to a beat grid at the given resolution.
 ///
 /// `grid_resolution`: subdivisions per beat (1 = whole, 2 = half, 4 = quarter, 8 = eighth).
+/// `strength`: how far each note moves from its detected beat toward the grid
+/// (0 = left as played, 1 = fully snapped). Onsets still share a grid slot,
+/// and so still dedupe, at any strength.
 pub fn quantize_onsets(
     onsets: &[OnsetEvent],
     beat_grid: &BeatGrid,
     grid_resolution: u32,
+    strength: f64,
 ) -> Vec<QuantizedNote> {
     let grid_step = 1.0 / grid_resolution as f64;
+    let strength = strength.clamp(0.0, 1.0);
 
-    let mut notes: Vec<QuantizedNote> = Vec::new();
+    // (grid slot, detected beat, note)
+    let mut slotted: Vec<(f64, f64, QuantizedNote)> = Vec::new();
 
     for onset in onsets {
         let raw_beat = beat_grid.time_to_beat(onset.time_seconds);
@@ -38,21 +46,28 @@ pub fn quantize_onsets(
         // Round to avoid floating point drift
         let snapped = (snapped * 10000.0).round() / 10000.0;
 
-        notes.push(QuantizedNote {
-            beat: snapped,
-            strength: onset.strength,
-            original_time: onset.time_seconds,
-        });
+        slotted.push((
+            snapped,
+            raw_beat,
+            QuantizedNote {
+                beat: snapped,
+                strength: onset.strength,
+                original_time: onset.time_seconds,
+                band: onset.band,
+            },
+        ));
     }
 
     // Deduplicate: if two onsets land on the same grid position, keep the stronger one
-    notes.sort_by(|a, b| a.beat.partial_cmp(&b.beat).unwrap());
-    notes.dedup_by(|b, a| {
-        if (a.beat - b.beat).abs() < 1e-6 {
+    slotted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    slotted.dedup_by(|b, a| {
+        if (a.0 - b.0).abs() < 1e-6 {
             // Keep the one with higher strength (stored in `a` after dedup)
-            if b.strength > a.strength {
-                a.strength = b.strength;
-                a.original_time = b.original_time;
+            if b.2.strength > a.2.strength {
+                a.1 = b.1;
+                a.2.strength = b.2.strength;
+                a.2.original_time = b.2.original_time;
+                a.2.band = b.2.band;
             }
             true
         } else {
@@ -60,5 +75,64 @@ pub fn quantize_onsets(
         }
     });
 
-    notes
+    slotted
+        .into_iter()
+        .map(|(snapped, raw, mut note)| {
+            if strength < 1.0 {
+                note.beat = raw + (snapped - raw) * strength;
+            }
+            note
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn onset(time_seconds: f64, strength: f32) -> OnsetEvent {
+        OnsetEvent {
+            frame: 0,
+            strength,
+            time_seconds,
+            band: OnsetBand::Full,
+        }
+    }
+
+    #[test]
+    fn half_strength_lands_halfway_to_the_grid() {
+        // 120 BPM: a beat every 0.5 s, so 0.6 s is beat 1.2
+        let grid = BeatGrid {
+            beats: (0..8).map(|b| b as f64 * 0.5).collect(),
+            bpm: 120.0,
+            downbeat_offset: 0,
+        };
+        let onsets = [onset(0.6, 0.8), onset(1.4, 0.5)];
+
+        let full = quantize_onsets(&onsets, &grid, 1, 1.0);
+        let beats: Vec<f64> = full.iter().map(|n| n.beat).collect();
+        assert_eq!(beats, vec![1.0, 3.0]);
+
+        let half = quantize_onsets(&onsets, &grid, 1, 0.5);
+        assert!((half[0].beat - 1.1).abs() < 1e-9, "beat {}", half[0].beat);
+        assert!((half[1].beat - 2.9).abs() < 1e-9, "beat {}", half[1].beat);
+
+        let raw = quantize_onsets(&onsets, &grid, 1, 0.0);
+        assert!((raw[0].beat - 1.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn shared_slot_keeps_the_stronger_onset_at_any_strength() {
+        let grid = BeatGrid {
+            beats: (0..8).map(|b| b as f64 * 0.5).collect(),
+            bpm: 120.0,
+            downbeat_offset: 0,
+        };
+        // Beats 0.9 and 1.1 both round to beat 1
+        let onsets = [onset(0.45, 0.3), onset(0.55, 0.9)];
+        let notes = quantize_onsets(&onsets, &grid, 1, 0.5);
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].strength, 0.9);
+        assert!((notes[0].beat - 1.05).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,198 @@
+//! Difficulty rating model shared by chart_gen and the game's editor.
+//!
+//! Depends only on std so the game crate can include this file directly.
+
+/// Weight of average notes per second.
+pub const WEIGHT_AVERAGE_NPS: f64 = 1.0;
+/// Weight of the densest `PEAK_WINDOW_SECONDS` stretch, in notes per second.
+pub const WEIGHT_PEAK_NPS: f64 = 0.5;
+/// Weight of note-type variety (normalized entropy, 0-1).
+pub const WEIGHT_VARIETY: f64 = 2.0;
+/// Weight of mean direction change between consecutive slides (0-1).
+pub const WEIGHT_MOVEMENT: f64 = 3.0;
+/// Weighted score at which the rating reaches ~63% of the scale.
+pub const RATING_SCALE: f64 = 8.0;
+/// Top of the rating scale.
+pub const MAX_RATING: u32 = 20;
+/// Window used for peak density.
+pub const PEAK_WINDOW_SECONDS: f64 = 2.0;
+
+/// What a note demands of the player, as far as rating is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RatedKind {
+    Tap,
+    Hold,
+    /// Slide with its direction as an index into the 8 compass points (N = 0, clockwise).
+    Slide(u8),
+    Critical,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RatedNote {
+    pub beat: f64,
+    pub kind: RatedKind,
+}
+
+/// Rating plus the densities that fed it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DifficultyRating {
+    /// 1 to `MAX_RATING`.
+    pub rating: u32,
+    pub average_nps: f64,
+    pub peak_nps: f64,
+}
+
+/// The `RatedKind` of a chart note, or `None` for Rests (timing anchors) and
+/// Ad-Libs (optional bonus). chart_gen and the game each have their own
+/// `ChartNoteType` with the same variants and compass-ordered `SlideDirection`,
+/// so this is a macro expanded against whichever is in scope at the call site.
+macro_rules! rated_kind {
+    ($note_type:expr) => {
+        match $note_type {
+            ChartNoteType::Tap | ChartNoteType::Beat | ChartNoteType::Scratch => {
+                Some(RatedKind::Tap)
+            }
+            ChartNoteType::Hold { .. } => Some(RatedKind::Hold),
+            ChartNoteType::Slide { direction }
+            | ChartNoteType::SlideHold { direction, .. }
+            | ChartNoteType::DualSlide { left: direction, .. } => {
+                Some(RatedKind::Slide(direction as u8))
+            }
+            ChartNoteType::Critical | ChartNoteType::CriticalHold { .. } => {
+                Some(RatedKind::Critical)
+            }
+            ChartNoteType::Rest | ChartNoteType::AdLib => None,
+        }
+    };
+}
+pub(crate) use rated_kind;
+
+/// Rate a chart: a weighted sum of density, variety and slide movement,
+/// eased onto 1..=`MAX_RATING` so ratings saturate rather than run away.
+pub fn rate(notes: &[RatedNote], bpm: f64) -> DifficultyRating {
+    let mut beats: Vec<f64> = notes.iter().map(|n| n.beat).collect();
+    beats.sort_by(|a, b| a.total_cmp(b));
+    let seconds: Vec<f64> = beats.iter().map(|b| b * 60.0 / bpm).collect();
+
+    let average_nps = match (seconds.first(), seconds.last()) {
+        (Some(first), Some(last)) if last > first => seconds.len() as f64 / (last - first),
+        _ => 0.0,
+    };
+    let peak_nps = peak_count(&seconds, PEAK_WINDOW_SECONDS) as f64 / PEAK_WINDOW_SECONDS;
+
+    let score = WEIGHT_AVERAGE_NPS * average_nps
+        + WEIGHT_PEAK_NPS * peak_nps
+        + WEIGHT_VARIETY * variety(notes)
+        + WEIGHT_MOVEMENT * movement(notes);
+    let eased = 1.0 + (MAX_RATING - 1) as f64 * (1.0 - (-score / RATING_SCALE).exp());
+
+    DifficultyRating {
+        rating: (eased.round() as u32).clamp(1, MAX_RATING),
+        average_nps,
+        peak_nps,
+    }
+}
+
+/// Most sorted times inside any half-open window.
+fn peak_count(sorted: &[f64], window: f64) -> usize {
+    let mut best = 0;
+    let mut start = 0;
+    for end in 0..sorted.len() {
+        while sorted[end] - sorted[start] >= window {
+            start += 1;
+        }
+        best = best.max(end - start + 1);
+    }
+    best
+}
+
+/// Shannon entropy of the kind mix, normalized to 0-1.
+fn variety(notes: &[RatedNote]) -> f64 {
+    if notes.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0usize; 4];
+    for note in notes {
+        let bucket = match note.kind {
+            RatedKind::Tap => 0,
+            RatedKind::Hold => 1,
+            RatedKind::Slide(_) => 2,
+            RatedKind::Critical => 3,
+        };
+        counts[bucket] += 1;
+    }
+    let total = notes.len() as f64;
+    let entropy: f64 = counts
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / total;
+            -p * p.ln()
+        })
+        .sum();
+    entropy / (counts.len() as f64).ln()
+}
+
+/// Mean direction change between consecutive slides (0 = same, 1 = opposite).
+fn movement(notes: &[RatedNote]) -> f64 {
+    let mut sorted: Vec<&RatedNote> = notes.iter().collect();
+    sorted.sort_by(|a, b| a.beat.total_cmp(&b.beat));
+    let directions: Vec<u8> = sorted
+        .iter()
+        .filter_map(|n| match n.kind {
+            RatedKind::Slide(dir) => Some(dir % 8),
+            _ => None,
+        })
+        .collect();
+    if directions.len() < 2 {
+        return 0.0;
+    }
+    let total: f64 = directions
+        .windows(2)
+        .map(|pair| {
+            let steps = pair[0].abs_diff(pair[1]);
+            steps.min(8 - steps) as f64 / 4.0
+        })
+        .sum();
+    total / (directions.len() - 1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sparse_taps_rate_low() {
+        // One tap every other beat at 120 BPM = 1 NPS
+        let notes: Vec<RatedNote> = (0..64)
+            .map(|i| RatedNote { beat: i as f64 * 2.0, kind: RatedKind::Tap })
+            .collect();
+        let rating = rate(&notes, 120.0);
+        assert!((rating.average_nps - 1.0).abs() < 0.05);
+        assert_eq!(rating.rating, 4);
+    }
+
+    #[test]
+    fn dense_varied_chart_rates_high() {
+        // 16ths at 150 BPM (10 NPS) cycling through every kind, slides zig-zagging
+        let notes: Vec<RatedNote> = (0..256)
+            .map(|i| RatedNote {
+                beat: i as f64 * 0.25,
+                kind: match i % 4 {
+                    0 => RatedKind::Tap,
+                    1 => RatedKind::Slide(if i % 8 == 1 { 2 } else { 6 }),
+                    2 => RatedKind::Critical,
+                    _ => RatedKind::Hold,
+                },
+            })
+            .collect();
+        let rating = rate(&notes, 150.0);
+        assert!(rating.peak_nps >= 10.0);
+        assert_eq!(rating.rating, 18);
+    }
+
+    #[test]
+    fn empty_chart_is_rating_one() {
+        assert_eq!(rate(&[], 120.0).rating, 1);
+    }
+}
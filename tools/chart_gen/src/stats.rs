@@ -0,0 +1,68 @@
+//! `--stats-json`: a machine-readable summary of one generation run, so chart
+//! packs can be tracked over time.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::chart::{ChartNoteEntry, ChartNoteType, Difficulty};
+use crate::rating::DifficultyRating;
+
+#[derive(Debug, Serialize)]
+pub struct GenerationStats {
+    pub onset_count: usize,
+    pub bpm: f64,
+    /// Fraction of grid beats that land on a detected onset (0..1).
+    pub bpm_confidence: f64,
+    pub difficulties: Vec<DifficultyStats>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DifficultyStats {
+    pub difficulty: Difficulty,
+    pub note_count: usize,
+    /// Notes per type name, e.g. `"Tap": 120`.
+    pub note_counts: BTreeMap<&'static str, usize>,
+    pub average_nps: f64,
+    pub peak_nps: f64,
+    pub rating: u32,
+}
+
+impl DifficultyStats {
+    pub fn new(difficulty: Difficulty, notes: &[ChartNoteEntry], rating: &DifficultyRating) -> Self {
+        let mut note_counts = BTreeMap::new();
+        for note in notes {
+            *note_counts.entry(note_type_name(&note.note_type)).or_insert(0) += 1;
+        }
+        Self {
+            difficulty,
+            note_count: notes.len(),
+            note_counts,
+            average_nps: rating.average_nps,
+            peak_nps: rating.peak_nps,
+            rating: rating.rating,
+        }
+    }
+}
+
+pub fn note_type_name(note_type: &ChartNoteType) -> &'static str {
+    match note_type {
+        ChartNoteType::Tap => "Tap",
+        ChartNoteType::Hold { .. } => "Hold",
+        ChartNoteType::Slide { .. } => "Slide",
+        ChartNoteType::SlideHold { .. } => "SlideHold",
+        ChartNoteType::Scratch => "Scratch",
+        ChartNoteType::Beat => "Beat",
+        ChartNoteType::Critical => "Critical",
+        ChartNoteType::CriticalHold { .. } => "CriticalHold",
+        ChartNoteType::DualSlide { .. } => "DualSlide",
+        ChartNoteType::AdLib => "AdLib",
+        ChartNoteType::Rest => "Rest",
+    }
+}
+
+pub fn write_stats_json(stats: &GenerationStats, path: &Path) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(stats).map_err(|e| format!("JSON serialize error: {e}"))?;
+    std::fs::write(path, json).map_err(|e| format!("Write error: {e}"))
+}
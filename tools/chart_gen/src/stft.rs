@@ -47,6 +47,21 @@ impl Spectrogram {
         (sum / (hi - lo + 1) as f32).sqrt()
     }
 
+    /// Magnitude-weighted mean frequency of a frame in Hz (0 for silence).
+    pub fn spectral_centroid(&self, frame: usize) -> f64 {
+        let magnitudes = &self.frames[frame];
+        let total: f64 = magnitudes.iter().map(|&m| m as f64).sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let weighted: f64 = magnitudes
+            .iter()
+            .enumerate()
+            .map(|(bin, &m)| self.bin_to_hz(bin) * m as f64)
+            .sum();
+        weighted / total
+    }
+
     /// Compute total RMS energy for a given frame.
     pub fn frame_energy(&self, frame: usize) -> f32 {
         let magnitudes = &self.frames[frame];
@@ -0,0 +1,198 @@
+//! `chart_gen verify`: check a song folder (or a folder of songs) the way the
+//! game would load it, without starting the game.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::chart::{ChartFile, ChartNoteType, PathSegment, SongMetadata};
+use crate::decode;
+
+/// One problem with one file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+/// Verify `root` as a single song if it has a `metadata.ron`, otherwise every
+/// song folder directly inside it.
+pub fn verify(root: &Path) -> Vec<Issue> {
+    if root.join("metadata.ron").exists() {
+        return verify_song(root);
+    }
+    let entries = match std::fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(e) => return vec![issue(root, format!("read error: {e}"))],
+    };
+    let mut song_dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join("metadata.ron").exists())
+        .collect();
+    if song_dirs.is_empty() {
+        return vec![issue(root, "no songs found".to_string())];
+    }
+    song_dirs.sort();
+    song_dirs.iter().flat_map(|dir| verify_song(dir)).collect()
+}
+
+/// Metadata, audio and every listed difficulty of one song.
+pub fn verify_song(song_dir: &Path) -> Vec<Issue> {
+    let meta_path = song_dir.join("metadata.ron");
+    let metadata: SongMetadata = match read_ron(&meta_path) {
+        Ok(metadata) => metadata,
+        Err(message) => return vec![issue(&meta_path, message)],
+    };
+
+    let mut issues = Vec::new();
+    let audio_path = song_dir.join(&metadata.audio_file);
+    if !audio_path.is_file() {
+        issues.push(issue(&audio_path, "audio file missing".to_string()));
+    } else if let Err(e) = decode::decode_audio(&audio_path, false) {
+        issues.push(issue(&audio_path, e));
+    }
+
+    if metadata.difficulties.is_empty() {
+        issues.push(issue(&meta_path, "no difficulties listed".to_string()));
+    }
+    for difficulty in &metadata.difficulties {
+        let chart_path = song_dir.join(difficulty.filename());
+        match read_ron::<ChartFile>(&chart_path) {
+            Ok(chart) => {
+                for message in validate_chart(&chart) {
+                    issues.push(issue(&chart_path, message));
+                }
+            }
+            Err(message) => issues.push(issue(&chart_path, message)),
+        }
+    }
+    issues
+}
+
+fn issue(path: &Path, message: String) -> Issue {
+    Issue {
+        path: path.to_path_buf(),
+        message,
+    }
+}
+
+fn read_ron<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| format!("read error: {e}"))?;
+    ron::from_str(&contents).map_err(|e| format!("parse error: {e}"))
+}
+
+// --- Mirror of the game's validate_chart (src/beatmap.rs) ---
+
+/// Control points a Catmull-Rom spline needs before it can be built.
+const MIN_PATH_POINTS: usize = 4;
+/// Samples taken along a non-CatmullRom segment when building the gameplay spline.
+const SEGMENT_SAMPLES: usize = 8;
+
+/// Every reason the game would refuse the chart, worded as the game words them.
+pub fn validate_chart(chart: &ChartFile) -> Vec<String> {
+    let mut errors = Vec::new();
+    if chart.timing_points.is_empty() {
+        errors.push("no timing points".to_string());
+    }
+    let count = spline_point_count(&chart.path_segments);
+    if count < MIN_PATH_POINTS {
+        errors.push(format!("path has {count} control points, need at least {MIN_PATH_POINTS}"));
+    }
+
+    let end_beat = chart
+        .path_segments
+        .iter()
+        .map(|seg| beat_range(seg).1)
+        .reduce(f64::max);
+    for (index, note) in chart.notes.iter().enumerate() {
+        let beat = note.beat;
+        if !beat.is_finite() {
+            errors.push(format!("note {index} has no valid beat"));
+            continue;
+        }
+        if beat < 0.0 {
+            errors.push(format!("note {index} is at negative beat {beat:.2}"));
+        }
+        let duration = match note.note_type {
+            ChartNoteType::Hold { duration_beats }
+            | ChartNoteType::SlideHold { duration_beats, .. }
+            | ChartNoteType::CriticalHold { duration_beats } => Some(duration_beats),
+            _ => None,
+        };
+        if let Some(duration) = duration.filter(|d| *d <= 0.0) {
+            errors.push(format!("note {index} at beat {beat:.2} has hold duration {duration:.2}"));
+        }
+        if let Some(end_beat) = end_beat.filter(|end| beat > *end) {
+            errors.push(format!(
+                "note {index} at beat {beat:.2} is past the path end at beat {end_beat:.2}"
+            ));
+        }
+    }
+    errors
+}
+
+fn beat_range(segment: &PathSegment) -> (f64, f64) {
+    match segment {
+        PathSegment::CatmullRom { start_beat, end_beat, .. }
+        | PathSegment::Bezier { start_beat, end_beat, .. }
+        | PathSegment::Arc { start_beat, end_beat, .. }
+        | PathSegment::Linear { start_beat, end_beat, .. } => (*start_beat, *end_beat),
+    }
+}
+
+/// Points the game's spline would be built from, dropping the duplicate where
+/// one segment ends exactly where the next begins.
+fn spline_point_count(segments: &[PathSegment]) -> usize {
+    let mut last: Option<(f32, f32)> = None;
+    let mut count = 0;
+    for point in segments.iter().flat_map(spline_points) {
+        if last.is_some_and(|l| (l.0 - point.0).hypot(l.1 - point.1) < 1e-3) {
+            continue;
+        }
+        last = Some(point);
+        count += 1;
+    }
+    count
+}
+
+fn spline_points(segment: &PathSegment) -> Vec<(f32, f32)> {
+    let samples = (0..=SEGMENT_SAMPLES).map(|i| i as f32 / SEGMENT_SAMPLES as f32);
+    match segment {
+        PathSegment::CatmullRom { points, .. } => points.clone(),
+        PathSegment::Linear { start, end, .. } => samples
+            .map(|t| (lerp(start.0, end.0, t), lerp(start.1, end.1, t)))
+            .collect(),
+        PathSegment::Bezier { control_points, .. } => {
+            if control_points.is_empty() {
+                return Vec::new();
+            }
+            samples.map(|t| de_casteljau(control_points, t)).collect()
+        }
+        PathSegment::Arc { center, radius, start_angle, end_angle, .. } => samples
+            .map(|t| {
+                let a = lerp(*start_angle, *end_angle, t);
+                (center.0 + radius * a.cos(), center.1 + radius * a.sin())
+            })
+            .collect(),
+    }
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn de_casteljau(points: &[(f32, f32)], t: f32) -> (f32, f32) {
+    let mut pts = points.to_vec();
+    for level in (1..pts.len()).rev() {
+        for i in 0..level {
+            pts[i] = (lerp(pts[i].0, pts[i + 1].0, t), lerp(pts[i].1, pts[i + 1].1, t));
+        }
+    }
+    pts[0]
+}
@@ -0,0 +1,84 @@
+use std::path::Path;
+use std::process::Command;
+
+const SAMPLE_RATE: u32 = 44_100;
+
+/// A mono 16-bit WAV of short clicks every half second (120 BPM).
+fn write_click_track(path: &Path, seconds: u32) {
+    let frames = (SAMPLE_RATE * seconds) as usize;
+    let period = SAMPLE_RATE as usize / 2;
+    let samples: Vec<i16> = (0..frames)
+        .map(|i| {
+            let t = i % period;
+            if t < 400 {
+                let decay = 1.0 - t as f32 / 400.0;
+                (decay * (t as f32 * 0.9).sin() * 24_000.0) as i16
+            } else {
+                0
+            }
+        })
+        .collect();
+
+    let data_len = (samples.len() * 2) as u32;
+    let mut bytes = Vec::new();
+    bytes.extend(b"RIFF");
+    bytes.extend((36 + data_len).to_le_bytes());
+    bytes.extend(b"WAVEfmt ");
+    bytes.extend(16u32.to_le_bytes());
+    bytes.extend(1u16.to_le_bytes());
+    bytes.extend(1u16.to_le_bytes());
+    bytes.extend(SAMPLE_RATE.to_le_bytes());
+    bytes.extend((SAMPLE_RATE * 2).to_le_bytes());
+    bytes.extend(2u16.to_le_bytes());
+    bytes.extend(16u16.to_le_bytes());
+    bytes.extend(b"data");
+    bytes.extend(data_len.to_le_bytes());
+    for s in samples {
+        bytes.extend(s.to_le_bytes());
+    }
+    std::fs::write(path, bytes).unwrap();
+}
+
+#[test]
+fn stats_json_summarises_each_difficulty() {
+    let dir = std::env::temp_dir().join(format!("chart_gen_stats_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let audio = dir.join("clicks.wav");
+    write_click_track(&audio, 12);
+    let stats_path = dir.join("stats.json");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_chart_gen"))
+        .arg(&audio)
+        .args(["--difficulty", "easy", "--bpm", "120"])
+        .arg("--output")
+        .arg(dir.join("easy.ron"))
+        .arg("--stats-json")
+        .arg(&stats_path)
+        .output()
+        .expect("failed to run chart_gen");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let stats: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&stats_path).unwrap()).unwrap();
+    for key in ["onset_count", "bpm", "bpm_confidence", "difficulties"] {
+        assert!(stats.get(key).is_some(), "missing {key}: {stats}");
+    }
+    assert_eq!(stats["bpm"], 120.0);
+    assert!(stats["onset_count"].as_u64().unwrap() > 0);
+    let confidence = stats["bpm_confidence"].as_f64().unwrap();
+    assert!((0.0..=1.0).contains(&confidence));
+
+    let difficulties = stats["difficulties"].as_array().unwrap();
+    assert_eq!(difficulties.len(), 1);
+    let easy = &difficulties[0];
+    assert_eq!(easy["difficulty"], "Easy");
+    for key in ["note_count", "note_counts", "average_nps", "peak_nps", "rating"] {
+        assert!(easy.get(key).is_some(), "missing {key}: {easy}");
+    }
+    // Per-type counts add up to the total
+    let by_type: u64 = easy["note_counts"].as_object().unwrap().values().map(|v| v.as_u64().unwrap()).sum();
+    assert_eq!(by_type, easy["note_count"].as_u64().unwrap());
+    assert!(by_type > 0);
+
+    std::fs::remove_dir_all(&dir).ok();
+}
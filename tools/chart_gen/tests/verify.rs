@@ -0,0 +1,23 @@
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn verify_reports_only_the_broken_chart() {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/verify_song");
+    let output = Command::new(env!("CARGO_BIN_EXE_chart_gen"))
+        .arg("verify")
+        .arg(&fixture)
+        .output()
+        .expect("failed to run chart_gen");
+
+    assert!(!output.status.success(), "a broken chart must fail verification");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    // easy.ron and the audio are fine; normal.ron has three problems
+    assert_eq!(lines.len(), 3, "{stdout}");
+    assert!(lines.iter().all(|line| line.contains("normal.ron: ")), "{stdout}");
+    assert!(lines[0].ends_with("path has 2 control points, need at least 4"));
+    assert!(lines[1].ends_with("note 1 at beat 8.00 has hold duration 0.00"));
+    assert!(lines[2].ends_with("note 2 at beat 20.00 is past the path end at beat 16.00"));
+}